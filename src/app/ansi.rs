@@ -0,0 +1,301 @@
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::Span,
+};
+
+// Beyond this many characters a line is cut, as no terminal shows more of it
+// and a huge minified line would otherwise be parsed on every frame
+const MAX_CHARS: usize = 2000;
+
+/// The line as spans styled by its ANSI SGR sequences, on top of `base`.
+/// Other escape sequences, like cursor movement and clears, and control
+/// characters are dropped.
+pub fn spans(text: &str, base: Style) -> Vec<Span<'static>> {
+    if !text.contains(|c: char| c.is_control() && c != '\t') && text.len() <= MAX_CHARS {
+        return vec![Span::styled(text.to_string(), base)];
+    }
+
+    let mut spans = Vec::new();
+    let mut current = String::new();
+    let mut style = base;
+    let mut shown = 0;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if shown >= MAX_CHARS {
+            break;
+        }
+        if c != '\u{1b}' {
+            if !c.is_control() || c == '\t' {
+                current.push(c);
+                shown += 1;
+            }
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters up to a final byte in @..~, of which only m
+            // sets the style
+            Some('[') => {
+                let mut params = String::new();
+                let mut last = None;
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        last = Some(c);
+                        break;
+                    }
+                    params.push(c);
+                }
+                if last != Some('m') {
+                    continue;
+                }
+                let next = apply_sgr(style, &params, base);
+                if next != style && !current.is_empty() {
+                    spans.push(Span::styled(std::mem::take(&mut current), style));
+                }
+                style = next;
+            }
+            // OSC: up to BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\u{7}' || (c == '\u{1b}' && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if !current.is_empty() {
+        spans.push(Span::styled(current, style));
+    }
+    spans
+}
+
+/// The style after an SGR sequence with the `;` separated parameters, where
+/// resets go back to `base`.
+fn apply_sgr(mut style: Style, params: &str, base: Style) -> Style {
+    // An empty sequence resets, like 0
+    if params.is_empty() {
+        return base;
+    }
+    // Colors like 38:2::255:0:0 separate with colons, leaving out the color
+    // space, and read the same as 38;2;255;0;0
+    let params = params.replace("::", ":").replace(':', ";");
+    let mut codes = params
+        .split(';')
+        .map(|code| code.parse::<u16>().unwrap_or(0));
+    while let Some(code) = codes.next() {
+        style = match code {
+            0 => base,
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            3 => style.add_modifier(Modifier::ITALIC),
+            4 => style.add_modifier(Modifier::UNDERLINED),
+            5 | 6 => style.add_modifier(Modifier::SLOW_BLINK),
+            7 => style.add_modifier(Modifier::REVERSED),
+            8 => style.add_modifier(Modifier::HIDDEN),
+            9 => style.add_modifier(Modifier::CROSSED_OUT),
+            21 | 22 => style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+            23 => style.remove_modifier(Modifier::ITALIC),
+            24 => style.remove_modifier(Modifier::UNDERLINED),
+            25 => style.remove_modifier(Modifier::SLOW_BLINK),
+            27 => style.remove_modifier(Modifier::REVERSED),
+            28 => style.remove_modifier(Modifier::HIDDEN),
+            29 => style.remove_modifier(Modifier::CROSSED_OUT),
+            30..=37 => style.fg(basic(code - 30)),
+            38 => match extended(&mut codes) {
+                Some(color) => style.fg(color),
+                None => style,
+            },
+            39 => Style {
+                fg: base.fg,
+                ..style
+            },
+            40..=47 => style.bg(basic(code - 40)),
+            48 => match extended(&mut codes) {
+                Some(color) => style.bg(color),
+                None => style,
+            },
+            49 => Style {
+                bg: base.bg,
+                ..style
+            },
+            90..=97 => style.fg(bright(code - 90)),
+            100..=107 => style.bg(bright(code - 100)),
+            _ => style,
+        };
+    }
+    style
+}
+
+// The color after 38 or 48: 5;n from the 256 colors, or 2;r;g;b
+fn extended(codes: &mut impl Iterator<Item = u16>) -> Option<Color> {
+    match codes.next()? {
+        5 => Some(Color::Indexed(codes.next()?.min(255) as u8)),
+        2 => {
+            let mut channel = || codes.next().map(|c| c.min(255) as u8);
+            Some(Color::Rgb(channel()?, channel()?, channel()?))
+        }
+        _ => None,
+    }
+}
+
+fn basic(index: u16) -> Color {
+    match index {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        _ => Color::Gray,
+    }
+}
+
+fn bright(index: u16) -> Color {
+    match index {
+        0 => Color::DarkGray,
+        1 => Color::LightRed,
+        2 => Color::LightGreen,
+        3 => Color::LightYellow,
+        4 => Color::LightBlue,
+        5 => Color::LightMagenta,
+        6 => Color::LightCyan,
+        _ => Color::White,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn styled(text: &str, base: Style) -> Vec<(String, Style)> {
+        spans(text, base)
+            .into_iter()
+            .map(|span| (span.content.into_owned(), span.style))
+            .collect()
+    }
+
+    #[test]
+    fn plain_text_is_one_span_in_the_base_style() {
+        let base = Style::default().fg(Color::Yellow);
+        assert_eq!(styled("plain\ttext", base), [("plain\ttext".into(), base)]);
+    }
+
+    #[test]
+    fn colors_and_resets_by_0_and_empty_parameters() {
+        let base = Style::default();
+        let red = base.fg(Color::Red);
+        let bold_green = base.fg(Color::Green).add_modifier(Modifier::BOLD);
+        assert_eq!(
+            styled("\x1b[31mred\x1b[0m plain \x1b[1;32mbold\x1b[m after", base),
+            [
+                ("red".into(), red),
+                (" plain ".into(), base),
+                ("bold".into(), bold_green),
+                (" after".into(), base),
+            ]
+        );
+    }
+
+    #[test]
+    fn resets_go_back_to_the_base_style() {
+        let base = Style::default().fg(Color::Yellow).bg(Color::Blue);
+        assert_eq!(
+            styled("\x1b[31;42mboth\x1b[39mbg only\x1b[49mbase", base),
+            [
+                ("both".into(), base.fg(Color::Red).bg(Color::Green)),
+                ("bg only".into(), base.bg(Color::Green)),
+                ("base".into(), base),
+            ]
+        );
+        assert_eq!(
+            apply_sgr(base.fg(Color::Red), "0", base),
+            apply_sgr(base.fg(Color::Red), "", base)
+        );
+    }
+
+    #[test]
+    fn nested_modifiers_are_removed_one_at_a_time() {
+        let base = Style::default();
+        let style = apply_sgr(base, "1;3;4", base);
+        assert_eq!(
+            style,
+            base.add_modifier(Modifier::BOLD | Modifier::ITALIC | Modifier::UNDERLINED)
+        );
+        let style = apply_sgr(style, "23", base);
+        assert_eq!(style.add_modifier, Modifier::BOLD | Modifier::UNDERLINED);
+        let style = apply_sgr(style, "22;24", base);
+        assert_eq!(style.add_modifier, Modifier::empty());
+    }
+
+    #[test]
+    fn extended_colors_with_semicolons_or_colons() {
+        let base = Style::default();
+        assert_eq!(
+            apply_sgr(base, "38;5;208", base),
+            base.fg(Color::Indexed(208))
+        );
+        assert_eq!(
+            apply_sgr(base, "48;5;17", base),
+            base.bg(Color::Indexed(17))
+        );
+        assert_eq!(
+            apply_sgr(base, "38;2;255;128;0", base),
+            base.fg(Color::Rgb(255, 128, 0))
+        );
+        assert_eq!(
+            apply_sgr(base, "38:5:208", base),
+            base.fg(Color::Indexed(208))
+        );
+        assert_eq!(
+            apply_sgr(base, "48:2::10:20:30", base),
+            base.bg(Color::Rgb(10, 20, 30))
+        );
+        // Cut short, the color is left as it was
+        assert_eq!(apply_sgr(base, "38;2;255", base), base);
+        // What follows a color still applies
+        assert_eq!(
+            apply_sgr(base, "38;5;1;1", base),
+            base.fg(Color::Indexed(1)).add_modifier(Modifier::BOLD)
+        );
+    }
+
+    #[test]
+    fn bright_colors() {
+        let base = Style::default();
+        assert_eq!(
+            apply_sgr(base, "91;104", base),
+            base.fg(Color::LightRed).bg(Color::LightBlue)
+        );
+    }
+
+    #[test]
+    fn other_sequences_and_control_characters_are_dropped() {
+        let base = Style::default();
+        assert_eq!(
+            styled("\x1b[2K\x1b[1Gprogress\r 50%\x1b[?25l", base),
+            [("progress 50%".into(), base)]
+        );
+        assert_eq!(
+            styled(
+                "\x1b]0;title\x07link \x1b]8;;http://x\x1b\\here\x1b]8;;\x1b\\",
+                base
+            ),
+            [("link here".into(), base)]
+        );
+    }
+
+    #[test]
+    fn long_lines_are_cut() {
+        let base = Style::default();
+        let long = "x".repeat(MAX_CHARS + 100);
+        let cut = styled(&long, base);
+        assert_eq!(cut, [("x".repeat(MAX_CHARS), base)]);
+
+        // Escape sequences don't count towards it
+        let colored = format!("\x1b[31m{}", long);
+        let cut = styled(&colored, base);
+        assert_eq!(cut, [("x".repeat(MAX_CHARS), base.fg(Color::Red))]);
+    }
+}
@@ -1,276 +1,12224 @@
 use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    ffi::OsString,
     fs,
-    io::{self},
-    path::Path,
-    process::Command,
-    time::Duration,
+    io::{self, BufWriter},
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus, Stdio},
+    thread,
+    time::{Duration, Instant},
 };
 
-use serde_json::Value;
-
-use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind},
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-    ExecutableCommand,
+use chrono::Local;
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent,
+    MouseEventKind,
 };
 
 use ratatui::{
-    backend::CrosstermBackend,
+    backend::{Backend, CrosstermBackend},
     crossterm,
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
-    text::{Span, Text},
-    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Position, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Tabs, Wrap},
     Terminal,
 };
 
-use crate::app::util::{find_sln_files, parse_sln_for_projects};
+use crate::app::{
+    ansi,
+    clean::{build_dirs, format_size, BuildDir, CleanEvent, DeepClean},
+    clipboard::{Clipboard, Copied},
+    compose::{self, StatusPoller},
+    config::{expand_tilde, BuildStrategy, Config, GroupMember, SolutionSort},
+    details::{read_details, DetailsCache, ProjectDetails},
+    devcerts,
+    diagnostics::{built_project, count_summary, Diagnostic, DiagnosticParser, Parsed, Problems},
+    docker::{self, Container},
+    editor::{editor_command, ide_command, ide_commands},
+    env::{self, EnvEditor, EnvSource, EnvVar},
+    error::{docker_spawn_error, dotnet_spawn_error, AppError, Result},
+    explorer::{parse_test_list, ListedTest, Node, Row, TestTree},
+    filter::fuzzy_match,
+    git::{GitCache, GitStatus},
+    graph::{GraphLoad, ProjectGraph},
+    group::{GroupRun, MemberState},
+    hooks::{Companion, HookJob, HookStage, Hooks},
+    input::TextInput,
+    jsonlog::{self, LogEvent},
+    keymap::{Action, Binding, Context, Keymap},
+    launch::{read_launch_profiles, LaunchProfile},
+    logs::{strip_ansi, Level, LevelFilter, LogEntry, LogPanel},
+    migrations::{self, Migration, INSTALL_COMMAND},
+    outputindex::OutputIndex,
+    packages::{self, Package, PackageFilter, Report},
+    palette::Palette,
+    ports::{find_conflicts, free_port, kill_process, with_port, PortConflict},
+    process::{
+        build_command, detached_summary, dotnet_version, listening_url, open_url, run_command,
+        DetachedProcess, Job, OutputLine, RunMode, RunningProcess,
+    },
+    publish::{default_publish_dir, publish_dir, RUNTIMES},
+    ready::{Probe, Readiness},
+    recording::{self, Recording},
+    restore::FeedAuthDetector,
+    rules::{self, Rule, RuleAction},
+    scan::{Scan, ScanEvent},
+    scrollback::Scrollback,
+    sdk::{self, GlobalJson},
+    secrets::{self, Secret},
+    spa::{dev_server_url, Spa},
+    state::{BuildRecord, PublishOptions, State, Verbosity},
+    status::StatusLine,
+    terminal::{self, screen, TerminalGuard},
+    testing::{read_results, rerun_filter, Outcome, TestResult, TestSummary},
+    util::{
+        is_solution_filter, parse_sln_configurations, parse_sln_for_projects, split_args,
+        target_frameworks, underlying_sln, IgnoreList, OutputKind, Project, SolutionFile,
+    },
+    vscode::{self, Export},
+};
 
-pub struct App {
-    pub exit: bool,
-    pub sln_files: Vec<String>,
-    pub selected_sln: String,
-    pub projects: Vec<String>,
-    list_state: ListState,
-    showing_projects: bool,
-    logs: Vec<String>,
+const SPINNER: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+const BUILD_ERROR_LINES: usize = 20;
+
+const MOUSE_SCROLL_LINES: usize = 3;
+
+// Pause between starting marked projects, so they don't all build at once
+const LAUNCH_STAGGER: Duration = Duration::from_millis(500);
+
+// How long a stopped process gets to shut down before it is killed
+const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+// The least time between two redraws, however fast input and output arrive
+const FRAME_INTERVAL: Duration = Duration::from_millis(33);
+
+// How long the loop waits for input when there is nothing left to draw
+const TICK: Duration = Duration::from_millis(100);
+
+// The least time between two bells of the alert rules
+const ALERT_INTERVAL: Duration = Duration::from_secs(2);
+
+// How long a project waits for its SPA dev server before it starts anyway
+const SPA_TIMEOUT: Duration = Duration::from_secs(60);
+
+// Output logged when a process crashes
+const CRASH_OUTPUT_LINES: usize = 20;
+
+// Wait before the first restart after a crash, doubled with each crash in a
+// row, and the uptime after which a crash no longer counts as one in a row
+const RESTART_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(60);
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+
+// Previous arguments kept per project
+const ARGS_HISTORY: usize = 20;
+
+const LOG_PANE_DEFAULT: u16 = 30;
+const LOG_PANE_MIN: u16 = 10;
+const LOG_PANE_MAX: u16 = 90;
+const LOG_PANE_STEP: u16 = 5;
+
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// What `sln-runner pick` asks for: Enter then picks it instead of opening
+/// the solution or running the project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pick {
+    Solution,
+    Project,
 }
 
-impl App {
-    pub fn new() -> io::Result<Self> {
-        let sln_files = find_sln_files()?;
-        let selected_sln = sln_files
-            .first()
-            .ok_or(io::Error::new(
-                io::ErrorKind::NotFound,
-                "No .sln files found",
-            ))?
-            .clone();
-        let projects = parse_sln_for_projects(&selected_sln)?;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum View {
+    Solutions,
+    Projects,
+    Output,
+    Processes,
+    Problems,
+    Tests,
+    Explorer,
+    Graph,
+}
 
-        Ok(Self {
-            exit: false,
-            sln_files,
-            selected_sln,
-            projects,
-            list_state: ListState::default().with_selected(Some(0)),
-            showing_projects: false,
-            logs: Vec::new(),
-        })
+#[derive(Debug, Clone)]
+struct RunTarget {
+    project: String,
+    project_path: PathBuf,
+    project_dir: PathBuf,
+    args: Vec<String>,
+    // Restart it when it crashes, besides the projects named in the config
+    auto_restart: bool,
+    // Which of several target frameworks to build and run
+    framework: Option<String>,
+}
+
+struct Build {
+    job: Job,
+    // First error lines of the output, repeated after the failure message
+    errors: Vec<String>,
+    diagnostics: DiagnosticParser,
+    target: RunTarget,
+    launch_profile: Option<String>,
+    configuration: String,
+    // Builds restore too, and fail the same way on feed credentials
+    feed_auth: FeedAuthDetector,
+    // Building the whole solution, which runs nothing after
+    solution: bool,
+    // Running the project once it is built, which libraries don't
+    run: bool,
+    // Written for the build, to be removed after
+    solution_filter: Option<PathBuf>,
+    // Projects the output reported as built
+    built: HashSet<String>,
+    // The project the last problem went to, for its continuation lines
+    last_problem: String,
+    binlog: Option<PathBuf>,
+}
+
+/// A `dotnet test` run of a test project, or of the whole solution.
+struct TestRun {
+    job: Job,
+    name: String,
+    path: PathBuf,
+    // Where the TRX files go, removed once they are read
+    results: PathBuf,
+    diagnostics: DiagnosticParser,
+    summary: TestSummary,
+}
+
+impl Drop for TestRun {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.results);
     }
+}
 
-    pub fn run(&mut self) -> io::Result<()> {
-        enable_raw_mode()?;
-        let mut stdout = io::stdout();
-        stdout.execute(EnterAlternateScreen)?;
-        let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
-
-        while !self.exit {
-            terminal.draw(|f| {
-                let chunks = Layout::default()
-                    .direction(Direction::Vertical)
-                    .constraints([
-                        Constraint::Percentage(70), // Main content takes 70% of height
-                        Constraint::Percentage(30), // Logs take 30% of height
-                    ])
-                    .split(f.area());
-
-                if self.showing_projects {
-                    self.draw_project_list(f);
-                } else {
-                    self.draw_solution_list(f);
-                }
+/// The test explorer of one test project.
+struct Explorer {
+    name: String,
+    path: PathBuf,
+    // Until the list is in
+    tree: Option<TestTree>,
+    // Why the list could not be had
+    error: Option<String>,
+    state: ListState,
+    search: Option<TextInput>,
+    query: String,
+}
 
-                self.draw_logs(f, chunks[1]);
-            })?;
+/// The references of one project at a time, moving to a neighbour with Enter.
+struct GraphView {
+    focus: usize,
+    // Showing the projects that reference the focus rather than its references
+    reverse: bool,
+    // The projects moved on from, for going back
+    history: Vec<usize>,
+    state: ListState,
+}
 
-            if event::poll(Duration::from_millis(100))? {
-                if let Event::Key(key) = event::read()? {
-                    if key.kind == KeyEventKind::Press {
-                        match key.code {
-                            KeyCode::Esc | KeyCode::Char('q') => self.exit = true,
-                            KeyCode::Up => self.move_selection(-1),
-                            KeyCode::Down => self.move_selection(1),
-                            KeyCode::Enter => {
-                                self.on_enter_key()?;
-
-                                self.add_log(format!(
-                                    "Selected item at index: {}",
-                                    self.list_state.selected().unwrap_or(0)
-                                ));
-                            }
-                            _ => {}
-                        }
-                    }
-                }
-            }
+/// A row of the solution or project list.
+struct ListRow {
+    entry: ListEntry,
+    // Characters of the name the filter matched
+    positions: Vec<usize>,
+    // How many solution folders deep it sits
+    depth: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ListEntry {
+    // Index into `sln_files` or `projects`
+    Item(usize),
+    // Index into `folders`
+    Folder(usize),
+}
+
+impl ListRow {
+    fn item(&self) -> Option<usize> {
+        match self.entry {
+            ListEntry::Item(index) => Some(index),
+            ListEntry::Folder(_) => None,
         }
+    }
+}
 
-        disable_raw_mode()?;
-        terminal.backend_mut().execute(LeaveAlternateScreen)?;
-        Ok(())
+enum GraphRow {
+    Project(usize),
+    // A referenced project file the solution doesn't list
+    Outside(String),
+}
+
+/// A `dotnet test --list-tests` in the background.
+struct TestListing {
+    job: Job,
+    path: PathBuf,
+    lines: Vec<String>,
+}
+
+/// The failed tests of the last run, and what it ran, to run them again.
+struct TestResults {
+    name: String,
+    path: PathBuf,
+    failures: Vec<TestResult>,
+}
+
+struct ArgsPrompt {
+    target: RunTarget,
+    input: TextInput,
+    // Previous arguments for the project, most recent last
+    history: Vec<String>,
+    // Position in `history` of the entry being shown, None for a new entry
+    history_index: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PortChoice {
+    Kill,
+    OtherPort,
+    Abort,
+}
+
+impl PortChoice {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Kill => "Kill the process using the port",
+            Self::OtherPort => "Run on a free port instead (--urls)",
+            Self::Abort => "Don't run",
+        }
     }
+}
+
+/// The whole JSON of an output line, with its exception apart as the stack
+/// trace is unreadable inside a JSON string.
+struct Payload {
+    lines: Vec<String>,
+    exception: Option<String>,
+    scroll: usize,
+}
 
-    pub fn add_log(&mut self, message: String) {
-        self.logs.push(message);
-        if self.logs.len() > 100 {
-            self.logs.remove(0);
+/// Where the output view is scrolled or paused, by the indices of lines in
+/// the process's output, which stay with their lines as more come in.
+#[derive(Clone, Copy)]
+struct OutputAnchor {
+    // The line at the top of the view
+    top: usize,
+    // The line Enter expands
+    line: usize,
+}
+
+/// Asks what to do when the launch profile's ports are taken.
+struct PortPrompt {
+    target: RunTarget,
+    launch_profile: Option<String>,
+    urls: Vec<String>,
+    conflicts: Vec<PortConflict>,
+    choices: Vec<PortChoice>,
+    state: ListState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DevCertChoice {
+    Trust,
+    Continue,
+    Abort,
+}
+
+impl DevCertChoice {
+    const ALL: [Self; 3] = [Self::Trust, Self::Continue, Self::Abort];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Trust => "t  Trust a development certificate (dotnet dev-certs https --trust)",
+            Self::Continue => "c  Run anyway",
+            Self::Abort => "Don't run",
         }
     }
+}
 
-    fn draw_solution_list(&mut self, f: &mut ratatui::Frame) {
-        let items = self.sln_files.iter().map(|path| {
-            let name = std::path::Path::new(path)
-                .file_name()
-                .unwrap_or_default()
-                .to_string_lossy();
-            ListItem::new(Span::styled(name, Style::default().fg(Color::Yellow)))
-        });
+/// Offers to trust a development certificate when a launch over HTTPS finds
+/// none.
+struct DevCertPrompt {
+    project: String,
+    urls: String,
+    then: AfterHooks,
+    state: ListState,
+}
 
-        let list = List::new(items)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title(" Solutions (↑/↓: navigate, Enter: select, q: quit) "),
-            )
-            .highlight_style(Style::default().bg(Color::DarkGray))
-            .highlight_symbol("➤ ");
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PublishField {
+    Configuration,
+    Runtime,
+    SelfContained,
+}
+
+impl PublishField {
+    const ALL: [Self; 3] = [Self::Configuration, Self::Runtime, Self::SelfContained];
 
-        f.render_stateful_widget(list, f.area(), &mut self.list_state);
+    fn label(self) -> &'static str {
+        match self {
+            Self::Configuration => "Configuration",
+            Self::Runtime => "Runtime",
+            Self::SelfContained => "Self-contained",
+        }
     }
+}
 
-    fn draw_logs(&self, f: &mut ratatui::Frame, area: Rect) {
-        let log_text = Text::from(self.logs.join("\n"));
-        let paragraph = Paragraph::new(log_text)
-            .block(Block::default().borders(Borders::ALL).title(" Logs "))
-            .style(Style::default().fg(Color::White));
-        f.render_widget(paragraph, area);
+struct PublishPrompt {
+    target: RunTarget,
+    field: PublishField,
+    configuration: String,
+    // 0 for none, then the common runtimes, then one typed in
+    runtime: usize,
+    custom_runtime: TextInput,
+    self_contained: bool,
+}
+
+impl PublishPrompt {
+    fn runtime(&self) -> Option<String> {
+        match self.runtime {
+            0 => None,
+            i if i <= RUNTIMES.len() => Some(RUNTIMES[i - 1].to_string()),
+            _ => Some(self.custom_runtime.value.trim().to_string()).filter(|r| !r.is_empty()),
+        }
     }
 
-    fn draw_project_list(&mut self, f: &mut ratatui::Frame) {
-        let items = self.projects.iter().map(|project| {
-            ListItem::new(Span::styled(
-                project.clone(),
-                Style::default().fg(Color::Yellow),
-            ))
-        });
+    fn runtime_label(&self) -> String {
+        match self.runtime {
+            0 => "none (portable)".to_string(),
+            i if i <= RUNTIMES.len() => RUNTIMES[i - 1].to_string(),
+            _ => format!("other: {}", self.custom_runtime.value),
+        }
+    }
+}
 
-        let list = List::new(items)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title(" Projects (↑/↓: navigate, Enter: select, q: quit) "),
-            )
-            .highlight_style(Style::default().bg(Color::DarkGray))
-            .highlight_symbol("➤ ");
+/// A `dotnet publish` under way.
+struct Publish {
+    job: Job,
+    target: RunTarget,
+    options: PublishOptions,
+    diagnostics: DiagnosticParser,
+    // Where it said it published to
+    output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CleanChoice {
+    Clean,
+    CleanSolution,
+    DeepClean,
+    DeepCleanSolution,
+    Cancel,
+}
+
+impl CleanChoice {
+    const ALL: [Self; 5] = [
+        Self::Clean,
+        Self::CleanSolution,
+        Self::DeepClean,
+        Self::DeepCleanSolution,
+        Self::Cancel,
+    ];
 
-        f.render_stateful_widget(list, f.area(), &mut self.list_state);
+    fn label(self, project: &str) -> String {
+        match self {
+            Self::Clean => format!("dotnet clean {}", project),
+            Self::CleanSolution => "dotnet clean the solution".to_string(),
+            Self::DeepClean => format!("Delete bin/ and obj/ of {}", project),
+            Self::DeepCleanSolution => "Delete bin/ and obj/ of every project".to_string(),
+            Self::Cancel => "Don't clean".to_string(),
+        }
     }
+}
 
-    fn move_selection(&mut self, delta: i32) {
-        if let Some(current) = self.list_state.selected() {
-            let max_len = if self.showing_projects {
-                self.projects.len()
-            } else {
-                self.sln_files.len()
-            };
+/// Asks how to clean the highlighted project or the solution.
+struct CleanMenu {
+    target: RunTarget,
+    state: ListState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DockerChoice {
+    Build,
+    Run,
+    BuildAndRun,
+    Cancel,
+}
+
+impl DockerChoice {
+    const ALL: [Self; 4] = [Self::Build, Self::Run, Self::BuildAndRun, Self::Cancel];
 
-            let new = current
-                .saturating_add_signed(delta as isize)
-                .min(max_len.saturating_sub(1));
-            self.list_state.select(Some(new));
+    fn label(self, image: &str) -> String {
+        match self {
+            Self::Build => format!("docker build --tag {}", image),
+            Self::Run => format!("docker run {}", image),
+            Self::BuildAndRun => format!("Build {} and run it", image),
+            Self::Cancel => "Don't do anything".to_string(),
         }
     }
+}
 
-    fn on_enter_key(&mut self) -> io::Result<()> {
-        if self.showing_projects {
-            self.run_selected_project()?;
-            return Ok(());
+/// Asks whether to build the project's image, run it or both.
+struct DockerMenu {
+    target: RunTarget,
+    image: String,
+    state: ListState,
+}
+
+/// A `docker build` under way, and whether the image runs after it.
+struct DockerBuild {
+    job: Job,
+    target: RunTarget,
+    image: String,
+    run: bool,
+}
+
+/// What the migrations panel knows of the migrations.
+enum MigrationsState {
+    Listing,
+    Listed(Vec<Migration>),
+    ToolMissing,
+    Failed(String),
+}
+
+/// The EF Core migrations of a project, to apply, add or roll back to.
+struct MigrationsPanel {
+    target: RunTarget,
+    startup_project: Option<PathBuf>,
+    migrations: MigrationsState,
+    // Below a first row that rolls all of them back
+    state: ListState,
+    name: Option<TextInput>,
+    // The migration to roll back to, until confirmed
+    confirm: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum EfStep {
+    List,
+    // To the migration, or to the latest
+    Update(Option<String>),
+    Add(String),
+    Install,
+}
+
+/// A `dotnet ef` command under way, or the install of the tool.
+struct EfJob {
+    job: Job,
+    project: String,
+    step: EfStep,
+    output: Vec<String>,
+}
+
+/// What the secrets view knows of the project's user secrets.
+enum SecretsState {
+    Listing,
+    Listed(Vec<Secret>),
+    // The project has no UserSecretsId yet
+    NoId,
+    Failed(String),
+}
+
+/// The user secrets of a project, masked until revealed.
+struct SecretsView {
+    target: RunTarget,
+    secrets: SecretsState,
+    state: ListState,
+    revealed: bool,
+    // `Key=value` being set
+    input: Option<TextInput>,
+    // The key to remove, until confirmed
+    confirm: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SecretsStep {
+    List,
+    Set { key: String, value: String },
+    Remove(String),
+    Init,
+}
+
+/// A `dotnet user-secrets` command under way.
+struct SecretsJob {
+    job: Job,
+    project: String,
+    step: SecretsStep,
+    output: Vec<String>,
+}
+
+/// The outdated and vulnerable NuGet packages of a project or the solution.
+struct PackagesView {
+    target: RunTarget,
+    solution: bool,
+    // None until `dotnet list package` reported them
+    outdated: Option<Vec<Package>>,
+    vulnerable: Option<Vec<Package>>,
+    error: Option<String>,
+    filter: PackageFilter,
+    state: ListState,
+    // Until the SDK turns out not to know `--format json`
+    json: bool,
+}
+
+impl PackagesView {
+    fn name(&self, sln: &str) -> String {
+        if self.solution {
+            solution_name(sln)
+        } else {
+            self.target.project.clone()
         }
+    }
 
-        self.select_solution()?;
-        Ok(())
+    fn rows(&self) -> Vec<Package> {
+        packages::merge(
+            self.outdated.as_deref().unwrap_or_default(),
+            self.vulnerable.as_deref().unwrap_or_default(),
+        )
+        .into_iter()
+        .filter(|package| self.filter.allows(package))
+        .collect()
     }
+}
 
-    fn detect_launch_profile(launch_settings_path: &Path) -> Option<String> {
-        if let Ok(contents) = fs::read_to_string(launch_settings_path) {
-            if let Ok(json) = serde_json::from_str::<Value>(&contents) {
-                if let Some(profiles) = json.get("profiles").and_then(|p| p.as_object()) {
-                    return profiles.keys().next().cloned(); // Get first profile name
-                }
-            }
+/// A `dotnet list package` under way for the packages view.
+struct PackagesJob {
+    job: Job,
+    name: String,
+    report: Report,
+    json: bool,
+    output: Vec<String>,
+}
+
+/// `dotnet dev-certs https --trust` under way, and the launch waiting on it.
+struct DevCertJob {
+    job: Job,
+    project: String,
+    then: AfterHooks,
+}
+
+/// A launch held back until the dev server of the project's single-page
+/// app listens.
+struct SpaWait {
+    project: String,
+    // The dev server's directory, which its process is listed by
+    root: PathBuf,
+    // Probes the port of SpaProxyServerUrl, else the address is read from
+    // the dev server's output
+    probe: Option<Probe>,
+    started: Instant,
+    then: AfterHooks,
+}
+
+/// The services of the compose file next to the solution, with what
+/// `docker compose ps` tells of them, to bring up or down.
+struct ComposePanel {
+    file: PathBuf,
+    services: Vec<String>,
+    marked: HashSet<String>,
+    status: StatusPoller,
+    state: ListState,
+}
+
+/// What a `docker compose` command runs for.
+enum ComposeStep {
+    /// Services brought up from the panel, attached.
+    Up,
+    Down,
+    /// The services the named group needs, brought up before it starts.
+    Wait(String),
+}
+
+struct ComposeJob {
+    job: Job,
+    file: PathBuf,
+    step: ComposeStep,
+}
+
+/// Asks before deleting the build directories, showing what they take up.
+struct DeepCleanPrompt {
+    name: String,
+    dirs: Vec<BuildDir>,
+    state: ListState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RestoreChoice {
+    Project,
+    Solution,
+    Cancel,
+}
+
+impl RestoreChoice {
+    const ALL: [Self; 3] = [Self::Project, Self::Solution, Self::Cancel];
+
+    fn label(self, project: &str) -> String {
+        match self {
+            Self::Project => format!("dotnet restore {}", project),
+            Self::Solution => "dotnet restore the solution".to_string(),
+            Self::Cancel => "Don't restore".to_string(),
         }
-        None
     }
+}
 
-    fn run_selected_project(&self) -> io::Result<()> {
-        if let Some(selected) = self.list_state.selected() {
-            let project = &self.projects[selected];
+/// Asks whether to restore the highlighted project or the solution.
+struct RestoreMenu {
+    target: RunTarget,
+    state: ListState,
+}
 
-            println!("Running project: {}", project);
+/// A `dotnet restore` under way, and the launch waiting for it when it runs
+/// ahead of the first build.
+struct Restore {
+    job: Job,
+    name: String,
+    path: PathBuf,
+    diagnostics: DiagnosticParser,
+    feed_auth: FeedAuthDetector,
+    then: Option<(RunTarget, Option<String>)>,
+}
 
-            let sln_dir = std::path::Path::new(&self.selected_sln)
-                .parent()
-                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Invalid solution path"))?;
+/// A `dotnet clean` under way.
+struct Clean {
+    job: Job,
+    name: String,
+}
 
-            let project_path = sln_dir.join(project);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum QuitChoice {
+    StopAll,
+    Detach,
+    Cancel,
+}
 
-            let project_dir = if project_path.is_file() {
-                project_path.parent().ok_or_else(|| {
-                    io::Error::new(
-                        io::ErrorKind::NotFound,
-                        "Cannot determine project directory",
-                    )
-                })?
-            } else {
-                &project_path
-            };
+impl QuitChoice {
+    const ALL: [Self; 3] = [Self::StopAll, Self::Detach, Self::Cancel];
 
-            let output = Command::new("dotnet")
-                .arg("build")
-                .arg("--configuration")
-                .arg("Debug")
-                .arg(&project_path)
-                .output()?;
+    fn label(self) -> &'static str {
+        match self {
+            Self::StopAll => "Stop them all and quit",
+            Self::Detach => "Quit and leave them running",
+            Self::Cancel => "Don't quit",
+        }
+    }
+}
+
+/// Asks what to do with the running processes on quit.
+struct QuitPrompt {
+    // One line per running process
+    processes: Vec<String>,
+    state: ListState,
+}
+
+struct UrlPicker {
+    urls: Vec<String>,
+    state: ListState,
+}
+
+/// What O opens, and the IDEs to open it in.
+struct IdePicker {
+    // The solution, and the solution or project file itself
+    sln: PathBuf,
+    path: PathBuf,
+    ides: Vec<(String, String)>,
+    state: ListState,
+}
+
+/// Asks before replacing VS Code configurations of the same name.
+struct VsCodePrompt {
+    export: Export,
+    replaced: Vec<String>,
+    state: ListState,
+}
+
+/// What goes ahead once the hooks before it have run.
+enum AfterHooks {
+    Build {
+        target: RunTarget,
+        launch_profile: Option<String>,
+        run: bool,
+    },
+    Launch {
+        target: RunTarget,
+        launch_profile: Option<String>,
+        configuration: String,
+        mode: RunMode,
+    },
+    Group(String),
+}
+
+impl AfterHooks {
+    fn name(&self) -> String {
+        match self {
+            Self::Build { target, .. } | Self::Launch { target, .. } => target.project.clone(),
+            Self::Group(name) => format!("group {}", name),
+        }
+    }
+}
+
+/// Shows the hooks of a project or group before they first run.
+struct HookPrompt {
+    owner: String,
+    commands: Vec<(HookStage, String)>,
+    // What confirming them is remembered by
+    key: String,
+    then: AfterHooks,
+    state: ListState,
+}
+
+/// A group from the config, checked against the open solution.
+struct GroupRow {
+    name: String,
+    members: usize,
+    missing: usize,
+}
+
+struct GroupPicker {
+    groups: Vec<GroupRow>,
+    // Offer to save the marked projects as a new group, as the last row
+    save: bool,
+    state: ListState,
+}
+
+struct ProfilePicker {
+    target: RunTarget,
+    profiles: Vec<LaunchProfile>,
+    state: ListState,
+}
+
+/// Asks which framework to run a multi-targeted project for.
+struct FrameworkPicker {
+    target: RunTarget,
+    frameworks: Vec<String>,
+    state: ListState,
+}
+
+/// How a project is held to an SDK other than the one dotnet picks.
+enum SdkPin {
+    // Through a global.json written next to the project
+    Sdk { version: String, file: PathBuf },
+    // DOTNET_ROLL_FORWARD=LatestMajor, onto the newest runtime installed
+    RollForward,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SdkChoice {
+    Default,
+    Sdk(String),
+    RollForward,
+}
 
-            if !output.status.success() {
-                let error_msg = String::from_utf8_lossy(&output.stderr);
-                eprintln!("Build failed: {}", error_msg);
-                return Err(io::Error::new(io::ErrorKind::Other, "Build failed"));
+impl SdkChoice {
+    fn label(&self) -> String {
+        match self {
+            Self::Default => "Default (as dotnet and global.json pick)".to_string(),
+            Self::Sdk(version) => format!(".NET SDK {}", version),
+            Self::RollForward => {
+                "Roll forward to the newest runtime (DOTNET_ROLL_FORWARD)".to_string()
             }
+        }
+    }
+}
+
+/// Asks which installed SDK to run a project with.
+struct SdkPicker {
+    target: RunTarget,
+    choices: Vec<SdkChoice>,
+    state: ListState,
+}
+
+pub struct App {
+    pub exit: bool,
+    pub pick: Option<Pick>,
+    // The solution or project file picked, once it is
+    pub picked: Option<PathBuf>,
+    pub roots: Vec<PathBuf>,
+    pub sln_files: Vec<SolutionFile>,
+    pub selected_sln: String,
+    // The .sln itself, which differs when a solution filter is selected
+    sln_file: PathBuf,
+    pub projects: Vec<Project>,
+    configurations: Vec<String>,
+    configuration: String,
+    config: Config,
+    scan: Option<Scan>,
+    rescan_results: Option<Vec<SolutionFile>>,
+    // The config's `ignore` patterns
+    ignore: IgnoreList,
+    // Hidden solutions listed, dimmed, until the next rescan without them
+    show_hidden: bool,
+    // The pattern of solutions to hide, starting as the highlighted one's path
+    hide_input: Option<TextInput>,
+    root_input: Option<TextInput>,
+    args_prompt: Option<ArgsPrompt>,
+    build_args_input: Option<TextInput>,
+    // MSBuild arguments for this session, as typed and as split
+    build_args_line: String,
+    build_args: Vec<String>,
+    // Builds write an MSBuild binary log
+    binlog: bool,
+    // The details panel beside the project list
+    show_details: bool,
+    details: DetailsCache,
+    // Branch and changes of the checkouts the solutions are in
+    git: GitCache,
+    clipboard: Clipboard,
+    env_editor: Option<EnvEditor>,
+    // Environment changes for this session only, per project file
+    env_overrides: HashMap<String, BTreeMap<String, Option<String>>>,
+    filter: Option<TextInput>,
+    error_popup: Option<AppError>,
+    status: StatusLine,
+    keymap: Keymap,
+    // Rows visible in the last rendered list, for paging
+    list_height: usize,
+    // Areas from the last draw, for mapping mouse events
+    list_area: Rect,
+    logs_area: Rect,
+    last_click: Option<(usize, Instant)>,
+    // Scroll offset of the help overlay while it is open
+    help_scroll: Option<usize>,
+    palette: Option<Palette>,
+    // SDK version from `dotnet --version`; None disables running projects
+    dotnet: Option<String>,
+    profile_picker: Option<ProfilePicker>,
+    framework_picker: Option<FrameworkPicker>,
+    sdk_picker: Option<SdkPicker>,
+    // From `dotnet --list-sdks`, asked once needed
+    sdks: Option<Vec<String>>,
+    // The solution's global.json, when no installed SDK satisfies it
+    global_json: Option<GlobalJson>,
+    // By project path, for the session
+    sdk_pins: HashMap<PathBuf, SdkPin>,
+    url_picker: Option<UrlPicker>,
+    ide_picker: Option<IdePicker>,
+    group_picker: Option<GroupPicker>,
+    // Name for the marked projects being saved as a group
+    group_name: Option<TextInput>,
+    port_prompt: Option<PortPrompt>,
+    quit_prompt: Option<QuitPrompt>,
+    // Quit once the processes being stopped have exited
+    quit_when_stopped: bool,
+    // Processes left running on quit
+    detached: Vec<DetachedProcess>,
+    state: State,
+    state_path: Option<PathBuf>,
+    // Last session's solution, selected as soon as the scan finds it
+    preselect: Option<String>,
+    build: Option<Build>,
+    tests: Option<TestRun>,
+    test_results: Option<TestResults>,
+    failure_state: ListState,
+    explorer: Option<Explorer>,
+    // References between the solution's projects, read when it is opened
+    graph: Option<ProjectGraph>,
+    graph_load: Option<GraphLoad>,
+    graph_view: Option<GraphView>,
+    publish_prompt: Option<PublishPrompt>,
+    publish: Option<Publish>,
+    // The folder of the last successful publish
+    publish_dir: Option<PathBuf>,
+    restore_menu: Option<RestoreMenu>,
+    restore: Option<Restore>,
+    // Solutions restored this session, for `auto_restore`
+    restored: HashSet<String>,
+    clean_menu: Option<CleanMenu>,
+    docker_menu: Option<DockerMenu>,
+    docker_build: Option<DockerBuild>,
+    compose_panel: Option<ComposePanel>,
+    compose_jobs: Vec<ComposeJob>,
+    // Groups whose compose services came up, to start next
+    compose_passed: HashSet<String>,
+    spa_wait: Option<SpaWait>,
+    migrations: Option<MigrationsPanel>,
+    ef_job: Option<EfJob>,
+    secrets: Option<SecretsView>,
+    secrets_job: Option<SecretsJob>,
+    packages: Option<PackagesView>,
+    packages_job: Option<PackagesJob>,
+    // Whether the HTTPS development certificate was checked this session
+    dev_cert_checked: bool,
+    dev_cert_prompt: Option<DevCertPrompt>,
+    dev_cert_job: Option<DevCertJob>,
+    deep_clean_prompt: Option<DeepCleanPrompt>,
+    vscode_prompt: Option<VsCodePrompt>,
+    hook_prompt: Option<HookPrompt>,
+    // The pre hook under way, and what waits for it
+    pre_hook: Option<(HookJob, AfterHooks)>,
+    // Pre hooks that succeeded for the launch waiting on them
+    hooks_passed: HashSet<(String, HookStage)>,
+    // post_start hooks running alongside, and on_stop hooks
+    hook_jobs: Vec<HookJob>,
+    // Groups with post_start or on_stop hooks, by the directories of their
+    // members, until all of those stopped
+    hooked_groups: Vec<(String, Vec<PathBuf>)>,
+    clean: Option<Clean>,
+    deep_clean: Option<DeepClean>,
+    test_listing: Option<TestListing>,
+    // Test lists per project file, until a build of it succeeds
+    test_lists: HashMap<PathBuf, Vec<ListedTest>>,
+    processes: Vec<RunningProcess>,
+    // Index into `processes` of the one shown in the output view
+    current_process: usize,
+    process_state: ListState,
+    // Errors and warnings of the latest build of each project
+    problems: Problems,
+    problem_state: ListState,
+    // A terminal program, like an editor, to hand the screen to
+    foreground: Option<Command>,
+    // Project files marked to run together
+    marked: HashSet<PathBuf>,
+    // Solution folders holding the projects, outermost names first, parents
+    // before their children
+    folders: Vec<Vec<String>>,
+    // Solution folders folded away, by their names joined with `/`
+    collapsed_folders: HashSet<String>,
+    // Marked projects still to be started, one at a time
+    launch_queue: VecDeque<RunTarget>,
+    // The group being started in dependency order, kept for its states after
+    group_run: Option<GroupRun>,
+    last_launch: Option<Instant>,
+    // Where the output view is while it doesn't follow the newest line
+    output_anchor: Option<OutputAnchor>,
+    // Lines the output view showed when last drawn
+    output_height: usize,
+    output_index: Option<OutputIndex>,
+    // Search input of the output view while it is being typed
+    output_search: Option<TextInput>,
+    output_query: String,
+    // The match last jumped to, by its index in the process's output
+    output_match: Option<usize>,
+    // While the output view is paused, how many lines the process had
+    // pushed when it was
+    output_paused: Option<usize>,
+    // Whether JSON log lines are shown as they are rather than prettified
+    raw_output: bool,
+    // The structured payload of an output line, while shown
+    payload: Option<Payload>,
+    // The output rules of the config, each switched on or off for the session
+    output_rules: Vec<Rule>,
+    // The popup switching the output rules, while open
+    rules_picker: Option<ListState>,
+    // When an alert rule last rang the bell, so a stack trace rings it once
+    last_alert: Option<Instant>,
+    sln_state: ListState,
+    project_state: ListState,
+    view: View,
+    logs: LogPanel,
+    // Keys go to the log panel instead of the list
+    log_focus: bool,
+    // Height of the log pane in percent, unless collapsed or zoomed to full screen
+    log_percent: u16,
+    logs_collapsed: bool,
+    logs_zoomed: bool,
+    build_strategy: BuildStrategy,
+    tick: usize,
+}
 
-            println!("Build successful! Running ...");
+impl App {
+    pub fn new(cli_root: Option<PathBuf>, fresh: bool) -> Result<Self> {
+        let mut logs = Vec::new();
 
-            let launch_settings_path = project_dir.join("Properties").join("launchSettings.json");
-            let launch_profile = Self::detect_launch_profile(&launch_settings_path);
+        let config = match Config::path() {
+            Some(path) => Config::load_or_create(&path).unwrap_or_else(|e| {
+                logs.push(LogEntry::new(
+                    Level::Warn,
+                    format!("Failed to load config {}: {}", path.display(), e),
+                ));
+                Config::default()
+            }),
+            None => Config::default(),
+        };
 
-            let mut command = Command::new("dotnet");
-            command.arg("run").current_dir(project_dir);
+        // --fresh ignores the state file entirely, so nothing is written back either
+        let state_path = State::path().filter(|_| !fresh);
+        let state = match &state_path {
+            Some(path) => State::load(path).unwrap_or_else(|e| {
+                logs.push(LogEntry::new(
+                    Level::Warn,
+                    format!("Failed to load state {}: {}", path.display(), e),
+                ));
+                State::default()
+            }),
+            None => State::default(),
+        };
+        Self::with(config, state, state_path, cli_root, logs)
+    }
 
-            if let Some(profile) = launch_profile {
-                println!("Detected launch profile: {}", profile);
-                command.arg("--launch-profile").arg(profile);
-            } else {
-                println!("No launch profile found, running normally...");
+    /// The app with a config and state already read, and what reading them
+    /// logged.
+    fn with(
+        config: Config,
+        state: State,
+        state_path: Option<PathBuf>,
+        cli_root: Option<PathBuf>,
+        mut logs: Vec<LogEntry>,
+    ) -> Result<Self> {
+        let roots = match cli_root {
+            Some(root) => vec![root],
+            None if !config.roots.is_empty() => config.roots.clone(),
+            None => vec![std::env::current_dir()?],
+        };
+        for root in roots.iter().filter(|root| !root.is_dir()) {
+            logs.push(LogEntry::new(
+                Level::Warn,
+                format!("Search root {} does not exist", root.display()),
+            ));
+        }
+
+        let preselect = state.last_solution.clone();
+        // Pinned solutions are listed right away, whether the scan finds them or not
+        let sln_files: Vec<SolutionFile> = state
+            .pinned
+            .iter()
+            .map(|path| pinned_solution(path))
+            .collect();
+        let last_pinned = preselect
+            .as_ref()
+            .and_then(|last| state.pinned.iter().position(|path| path == last));
+        let preselect = preselect.filter(|_| last_pinned.is_none());
+
+        let (keymap, warnings) = Keymap::with_overrides(&config.keys);
+        logs.extend(warnings.into_iter().map(|w| LogEntry::new(Level::Warn, w)));
+        let (output_rules, warnings) = rules::compile(&config.output_rules);
+        logs.extend(warnings.into_iter().map(|w| LogEntry::new(Level::Warn, w)));
+
+        let (dotnet, error_popup) = match dotnet_version() {
+            Ok(version) => (Some(version), None),
+            Err(e) => {
+                logs.push(LogEntry::new(Level::Error, e.to_string()));
+                (None, Some(e))
             }
+        };
 
-            command.spawn()?;
-            return Ok(());
+        let mut log_panel = LogPanel::new(
+            config.log_lines * state.verbosity.log_factor(),
+            config.scrollback_bytes(),
+        );
+        for entry in logs {
+            log_panel.push(entry);
         }
 
-        Err(io::Error::new(
-            io::ErrorKind::NotFound,
-            "No project selected",
-        ))
+        let ignore = IgnoreList::new(&config.ignore);
+        let scan = Scan::start(roots.clone(), config.exclude_dirs.clone(), ignore.clone());
+
+        Ok(Self {
+            exit: false,
+            pick: None,
+            picked: None,
+            roots,
+            sln_files,
+            selected_sln: String::new(),
+            sln_file: PathBuf::new(),
+            projects: Vec::new(),
+            configurations: Vec::new(),
+            configuration: "Debug".to_string(),
+            build_strategy: config.build,
+            config,
+            scan: Some(scan),
+            rescan_results: None,
+            ignore,
+            show_hidden: false,
+            hide_input: None,
+            root_input: None,
+            args_prompt: None,
+            build_args_input: None,
+            build_args_line: String::new(),
+            build_args: Vec::new(),
+            binlog: false,
+            show_details: false,
+            details: DetailsCache::new(),
+            git: GitCache::new(),
+            clipboard: Clipboard::new(),
+            env_editor: None,
+            env_overrides: HashMap::new(),
+            filter: None,
+            error_popup,
+            help_scroll: None,
+            palette: None,
+            list_height: 0,
+            list_area: Rect::default(),
+            logs_area: Rect::default(),
+            last_click: None,
+
+            status: StatusLine::default(),
+            keymap,
+
+            dotnet,
+            profile_picker: None,
+            framework_picker: None,
+            sdk_picker: None,
+            sdks: None,
+            global_json: None,
+            sdk_pins: HashMap::new(),
+            url_picker: None,
+            ide_picker: None,
+            group_picker: None,
+            group_name: None,
+            port_prompt: None,
+            quit_prompt: None,
+            quit_when_stopped: false,
+            detached: Vec::new(),
+            log_percent: state
+                .log_percent
+                .unwrap_or(LOG_PANE_DEFAULT)
+                .clamp(LOG_PANE_MIN, LOG_PANE_MAX),
+            logs_collapsed: state.logs_collapsed,
+            state,
+            state_path,
+            preselect,
+            build: None,
+            tests: None,
+            test_results: None,
+            failure_state: ListState::default().with_selected(Some(0)),
+            explorer: None,
+            graph: None,
+            graph_load: None,
+            graph_view: None,
+            publish_prompt: None,
+            publish: None,
+            publish_dir: None,
+            restore_menu: None,
+            restore: None,
+            restored: HashSet::new(),
+            clean_menu: None,
+            docker_menu: None,
+            docker_build: None,
+            compose_panel: None,
+            compose_jobs: Vec::new(),
+            compose_passed: HashSet::new(),
+            spa_wait: None,
+            migrations: None,
+            ef_job: None,
+            secrets: None,
+            secrets_job: None,
+            packages: None,
+            packages_job: None,
+            dev_cert_checked: false,
+            dev_cert_prompt: None,
+            dev_cert_job: None,
+            deep_clean_prompt: None,
+            vscode_prompt: None,
+            hook_prompt: None,
+            pre_hook: None,
+            hooks_passed: HashSet::new(),
+            hook_jobs: Vec::new(),
+            hooked_groups: Vec::new(),
+            clean: None,
+            deep_clean: None,
+            test_listing: None,
+            test_lists: HashMap::new(),
+            processes: Vec::new(),
+            current_process: 0,
+            process_state: ListState::default(),
+            problems: Problems::default(),
+            problem_state: ListState::default().with_selected(Some(0)),
+            foreground: None,
+            marked: HashSet::new(),
+            folders: Vec::new(),
+            collapsed_folders: HashSet::new(),
+            launch_queue: VecDeque::new(),
+            group_run: None,
+            last_launch: None,
+            output_anchor: None,
+            output_height: 0,
+            output_index: None,
+            output_search: None,
+            output_query: String::new(),
+            output_match: None,
+            output_paused: None,
+            raw_output: false,
+            payload: None,
+            output_rules,
+            rules_picker: None,
+            last_alert: None,
+            sln_state: ListState::default().with_selected(Some(last_pinned.unwrap_or(0))),
+            project_state: ListState::default().with_selected(Some(0)),
+            view: View::Solutions,
+            logs: log_panel,
+            log_focus: false,
+            logs_zoomed: false,
+            tick: 0,
+        })
+    }
+
+    fn roots_label(roots: &[PathBuf]) -> String {
+        roots
+            .iter()
+            .map(|root| root.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
     }
 
-    fn select_solution(&mut self) -> io::Result<()> {
-        if let Some(selected) = self.list_state.selected() {
-            self.selected_sln = self.sln_files[selected].clone();
-            self.projects = parse_sln_for_projects(&self.selected_sln)?;
-            self.showing_projects = true;
-            self.list_state = ListState::default().with_selected(Some(0));
+    pub fn run(&mut self) -> Result<()> {
+        // The shell captures stdout for the picked path
+        if self.pick.is_some() {
+            terminal::draw_on_stderr();
+        }
+        let guard = TerminalGuard::enter(self.config.mouse)?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(BufWriter::new(screen())))?;
+        let mut drawn_at: Option<Instant> = None;
+
+        while !self.exit && !terminal::closed() {
+            self.tick = self.tick.wrapping_add(1);
+            self.poll_scan();
+            self.poll_graph();
+            if let Err(e) = self.poll_build() {
+                self.show_error(e);
+            }
+            if let Err(e) = self.poll_tests() {
+                self.show_error(e);
+            }
+            if let Err(e) = self.poll_test_listing() {
+                self.show_error(e);
+            }
+            if let Err(e) = self.poll_publish() {
+                self.show_error(e);
+            }
+            if let Err(e) = self.poll_restore() {
+                self.show_error(e);
+            }
+            if let Err(e) = self.poll_clean() {
+                self.show_error(e);
+            }
+            if let Err(e) = self.poll_docker_build() {
+                self.show_error(e);
+            }
+            if let Err(e) = self.poll_compose() {
+                self.show_error(e);
+            }
+            if let Err(e) = self.poll_spa_wait() {
+                self.show_error(e);
+            }
+            if let Err(e) = self.poll_ef() {
+                self.show_error(e);
+            }
+            if let Err(e) = self.poll_secrets() {
+                self.show_error(e);
+            }
+            if let Err(e) = self.poll_packages() {
+                self.show_error(e);
+            }
+            if let Err(e) = self.poll_dev_cert() {
+                self.show_error(e);
+            }
+            self.poll_deep_clean();
+            if let Err(e) = self.poll_hooks() {
+                self.show_error(e);
+            }
+            for index in 0..self.processes.len() {
+                if let Err(e) = self.poll_process(index) {
+                    self.show_error(e);
+                }
+            }
+            self.advance_launch_queue();
+            self.advance_group();
+            if self.quit_when_stopped && self.processes.iter().all(|p| p.status.is_some()) {
+                self.exit = true;
+            }
+
+            // Changes between frames show in the next one
+            let since_draw = drawn_at.map(|at| at.elapsed());
+            let due = since_draw.is_none_or(|elapsed| elapsed >= FRAME_INTERVAL);
+            if due {
+                self.draw(&mut terminal)?;
+                drawn_at = Some(Instant::now());
+            }
+            let wait = match since_draw {
+                Some(elapsed) if !due => FRAME_INTERVAL - elapsed,
+                _ => TICK,
+            };
+
+            if event::poll(wait)? {
+                // Everything pending is handled before the next frame
+                loop {
+                    // Failed actions are reported in the log panel rather than ending the session
+                    let handled = match event::read()? {
+                        Event::Key(key) if key.kind == KeyEventKind::Press => self.handle_key(key),
+                        Event::Mouse(mouse) => self.handle_mouse(mouse),
+                        _ => Ok(()),
+                    };
+                    if let Err(e) = handled {
+                        self.show_error(e);
+                    }
+                    if self.exit || self.foreground.is_some() || !event::poll(Duration::ZERO)? {
+                        break;
+                    }
+                }
+            }
+
+            if let Some(mut command) = self.foreground.take() {
+                let result = guard.run_in_foreground(&mut command, self.config.mouse);
+                // Whatever the program left on the screen is drawn over in full
+                terminal.clear()?;
+                drawn_at = None;
+                match result {
+                    Ok(status) if !status.success() => self.log_warn(format!(
+                        "{} exited ({})",
+                        command.get_program().to_string_lossy(),
+                        status
+                    )),
+                    Ok(_) => {}
+                    Err(e) => self.show_error(AppError::Editor {
+                        message: format!("{}: {}", command.get_program().to_string_lossy(), e),
+                    }),
+                }
+            }
+        }
+
+        if let Some(mut scan) = self.scan.take() {
+            scan.cancel();
+        }
+        if let Some(mut build) = self.build.take() {
+            if let Some(filter) = &build.solution_filter {
+                let _ = fs::remove_file(filter);
+            }
+            let _ = build.job.kill();
+        }
+        if let Some(mut tests) = self.tests.take() {
+            let _ = tests.job.kill();
+        }
+        if let Some(mut listing) = self.test_listing.take() {
+            let _ = listing.job.kill();
+        }
+        if let Some(mut publish) = self.publish.take() {
+            let _ = publish.job.kill();
+        }
+        if let Some(mut restore) = self.restore.take() {
+            let _ = restore.job.kill();
+        }
+        if let Some(mut clean) = self.clean.take() {
+            let _ = clean.job.kill();
+        }
+        for process in &mut self.processes {
+            // Killing `docker run` leaves the container running
+            if let Some(container) = process
+                .container
+                .as_ref()
+                .filter(|_| process.status.is_none())
+            {
+                let _ = container.stop(STOP_TIMEOUT);
+            }
+            let _ = process.job.kill();
+        }
+        // Killing `docker compose up` leaves the services running
+        let mut compose_files = BTreeSet::new();
+        for mut compose in self.compose_jobs.drain(..) {
+            if matches!(compose.step, ComposeStep::Up) && !compose.job.has_exited() {
+                compose_files.insert(compose.file);
+            }
+            let _ = compose.job.kill();
+        }
+        for file in compose_files {
+            let _ = compose::down_command(&file)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status();
+        }
+        for pin in self.sdk_pins.values() {
+            if let SdkPin::Sdk { file, .. } = pin {
+                let _ = fs::remove_file(file);
+            }
+        }
+
+        Ok(())
+    }
+
+    // One frame of the whole screen with the popups open over it
+    fn draw<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
+        let (main, logs) = if self.logs_zoomed {
+            (Constraint::Length(0), Constraint::Min(0))
+        } else if self.logs_collapsed {
+            (Constraint::Min(0), Constraint::Length(0))
+        } else {
+            (
+                Constraint::Percentage(100 - self.log_percent),
+                Constraint::Percentage(self.log_percent),
+            )
+        };
+        terminal.draw(|f| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    main,
+                    logs,
+                    Constraint::Length(1), // Status bar
+                ])
+                .split(f.area());
+
+            match self.view {
+                View::Solutions => self.draw_solution_list(f, chunks[0]),
+                View::Projects if self.show_details => {
+                    let columns = Layout::default()
+                        .direction(Direction::Horizontal)
+                        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+                        .split(chunks[0]);
+                    self.draw_project_list(f, columns[0]);
+                    self.draw_project_details(f, columns[1]);
+                }
+                View::Projects => self.draw_project_list(f, chunks[0]),
+                View::Output => self.draw_output(f, chunks[0]),
+                View::Processes => self.draw_processes(f, chunks[0]),
+                View::Problems => self.draw_problems(f, chunks[0]),
+                View::Tests => self.draw_failed_tests(f, chunks[0]),
+                View::Explorer => self.draw_explorer(f, chunks[0]),
+                View::Graph => self.draw_graph(f, chunks[0]),
+            }
+            if let Some(filter) = &self.filter {
+                Self::draw_filter(f, chunks[0], " Filter (Enter: select, Esc: clear) ", filter);
+            }
+
+            self.logs_area = chunks[1];
+            self.draw_logs(f, chunks[1]);
+            if let Some(search) = &self.logs.search {
+                Self::draw_filter(
+                    f,
+                    chunks[1],
+                    " Search logs (Enter: keep, Esc: clear) ",
+                    search,
+                );
+            }
+            if let Some(search) = &self.output_search {
+                Self::draw_filter(
+                    f,
+                    chunks[0],
+                    " Search output (Enter: keep, Esc: clear) ",
+                    search,
+                );
+            }
+            self.draw_status(f, chunks[2]);
+
+            if let Some(input) = &self.root_input {
+                Self::draw_input_popup(f, " Search root (Enter: scan, Esc: cancel) ", input);
+            }
+            if let Some(input) = &self.hide_input {
+                Self::draw_input_popup(
+                    f,
+                    " Hide solutions matching a path or glob pattern (Enter: hide, Esc: cancel) ",
+                    input,
+                );
+            }
+            if let Some(prompt) = &self.args_prompt {
+                let title = format!(
+                    " Arguments for {} (Enter: run, ↑/↓: history, Esc: cancel) ",
+                    prompt.target.project
+                );
+                Self::draw_input_popup(f, &title, &prompt.input);
+            }
+            if let Some(input) = &self.build_args_input {
+                Self::draw_input_popup(
+                    f,
+                    " MSBuild arguments for every build, run and publish (Enter: set, Esc: cancel) ",
+                    input,
+                );
+            }
+            if self.env_editor.is_some() {
+                let vars = self.editor_vars();
+                if let Some(editor) = self.env_editor.as_mut() {
+                    Self::draw_env_editor(f, editor, &vars);
+                }
+            }
+            if let Some(picker) = self.framework_picker.as_mut() {
+                Self::draw_framework_picker(f, picker);
+            }
+            if let Some(picker) = self.sdk_picker.as_mut() {
+                Self::draw_sdk_picker(f, picker);
+            }
+            if let Some(picker) = self.profile_picker.as_mut() {
+                Self::draw_profile_picker(f, picker);
+            }
+            if let Some(picker) = self.url_picker.as_mut() {
+                Self::draw_url_picker(f, picker);
+            }
+            if let Some(picker) = self.ide_picker.as_mut() {
+                Self::draw_ide_picker(f, picker);
+            }
+            if let Some(picker) = self.group_picker.as_mut() {
+                Self::draw_group_picker(f, picker);
+            }
+            if let Some(input) = &self.group_name {
+                Self::draw_input_popup(f, " Group name (Enter: save, Esc: cancel) ", input);
+            }
+            if let Some(prompt) = self.port_prompt.as_mut() {
+                Self::draw_port_prompt(f, prompt);
+            }
+            if let Some(prompt) = self.dev_cert_prompt.as_mut() {
+                Self::draw_dev_cert_prompt(f, prompt);
+            }
+            if let Some(prompt) = self.quit_prompt.as_mut() {
+                Self::draw_quit_prompt(f, prompt);
+            }
+            if let Some(prompt) = &self.publish_prompt {
+                Self::draw_publish_prompt(f, prompt);
+            }
+            if let Some(menu) = self.restore_menu.as_mut() {
+                Self::draw_restore_menu(f, menu);
+            }
+            if let Some(menu) = self.clean_menu.as_mut() {
+                Self::draw_clean_menu(f, menu);
+            }
+            if let Some(menu) = self.docker_menu.as_mut() {
+                Self::draw_docker_menu(f, menu);
+            }
+            if let Some(panel) = self.compose_panel.as_mut() {
+                Self::draw_compose_panel(f, panel);
+            }
+            if let Some(panel) = self.migrations.as_mut() {
+                let running = self.ef_job.as_ref().map(|ef| &ef.step);
+                Self::draw_migrations_panel(f, panel, running);
+            }
+            if let Some(view) = self.secrets.as_mut() {
+                let running = self.secrets_job.as_ref().map(|job| &job.step);
+                Self::draw_secrets_view(f, view, running);
+            }
+            if let Some(view) = self.packages.as_mut() {
+                let running = self.packages_job.as_ref().map(|job| job.report);
+                Self::draw_packages_view(f, view, running, &self.selected_sln);
+            }
+            if let Some(prompt) = self.deep_clean_prompt.as_mut() {
+                Self::draw_deep_clean_prompt(f, prompt, &self.selected_sln);
+            }
+            if let Some(prompt) = self.vscode_prompt.as_mut() {
+                Self::draw_vscode_prompt(f, prompt);
+            }
+            if let Some(prompt) = self.hook_prompt.as_mut() {
+                Self::draw_hook_prompt(f, prompt);
+            }
+            if let Some(palette) = self.palette.as_mut() {
+                Self::draw_palette(f, palette);
+            }
+            if let Some(payload) = self.payload.as_mut() {
+                Self::draw_payload(f, payload);
+            }
+            if let Some(state) = self.rules_picker.as_mut() {
+                Self::draw_rules_picker(f, &self.output_rules, state);
+            }
+            if let Some(scroll) = self.help_scroll.as_mut() {
+                Self::draw_help(f, &self.keymap, scroll);
+            }
+            if let Some(error) = &self.error_popup {
+                Self::draw_error_popup(f, error);
+            }
+        })?;
+        Ok(())
+    }
+
+    /// What was left running on quit, to print once the terminal is restored.
+    pub fn detached_summary(&self) -> Option<String> {
+        (!self.detached.is_empty()).then(|| detached_summary(&self.detached))
+    }
+
+    /// Logs a recoverable error and shows it in a popup until the next key press.
+    fn show_error(&mut self, error: AppError) {
+        self.status.flash_error(error.to_string());
+        self.log_error(error.to_string());
+        for line in error.details() {
+            self.log_error(format!("  {}", line.trim()));
+        }
+        self.error_popup = Some(error);
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Result<()> {
+        if self.error_popup.take().is_some() {
+            return Ok(());
+        }
+        if self.help_scroll.is_some() {
+            self.handle_help_key(key);
+            return Ok(());
+        }
+        if self.payload.is_some() {
+            self.handle_payload_key(key);
+            return Ok(());
+        }
+        if self.rules_picker.is_some() {
+            self.handle_rules_picker_key(key);
+            return Ok(());
+        }
+        if self.palette.is_some() {
+            return self.handle_palette_key(key);
+        }
+        if self.root_input.is_some() {
+            self.handle_root_input_key(key);
+            return Ok(());
+        }
+        if self.hide_input.is_some() {
+            self.handle_hide_input_key(key);
+            return Ok(());
+        }
+        if self.build_args_input.is_some() {
+            self.handle_build_args_key(key);
+            return Ok(());
+        }
+        if self.args_prompt.is_some() {
+            return self.handle_args_prompt_key(key);
+        }
+        if self.env_editor.is_some() {
+            self.handle_env_editor_key(key);
+            return Ok(());
+        }
+        if self.logs.search.is_some() {
+            self.handle_log_search_key(key);
+            return Ok(());
+        }
+        if self.output_search.is_some() {
+            self.handle_output_search_key(key);
+            return Ok(());
+        }
+        if self.view == View::Explorer && self.explorer.as_ref().is_some_and(|e| e.search.is_some())
+        {
+            self.handle_explorer_search_key(key);
+            return Ok(());
+        }
+        if self.framework_picker.is_some() {
+            return self.handle_framework_picker_key(key);
+        }
+        if self.sdk_picker.is_some() {
+            return self.handle_sdk_picker_key(key);
+        }
+        if self.profile_picker.is_some() {
+            return self.handle_profile_picker_key(key);
+        }
+        if self.url_picker.is_some() {
+            self.handle_url_picker_key(key);
+            return Ok(());
+        }
+        if self.ide_picker.is_some() {
+            self.handle_ide_picker_key(key);
+            return Ok(());
+        }
+        if self.group_picker.is_some() {
+            return self.handle_group_picker_key(key);
+        }
+        if self.group_name.is_some() {
+            self.handle_group_name_key(key);
+            return Ok(());
+        }
+        if self.port_prompt.is_some() {
+            return self.handle_port_prompt_key(key);
+        }
+        if self.dev_cert_prompt.is_some() {
+            return self.handle_dev_cert_prompt_key(key);
+        }
+        if self.quit_prompt.is_some() {
+            return self.handle_quit_prompt_key(key);
+        }
+        if self.publish_prompt.is_some() {
+            return self.handle_publish_prompt_key(key);
+        }
+        if self.restore_menu.is_some() {
+            return self.handle_restore_menu_key(key);
+        }
+        if self.clean_menu.is_some() {
+            return self.handle_clean_menu_key(key);
+        }
+        if self.docker_menu.is_some() {
+            return self.handle_docker_menu_key(key);
+        }
+        if self.compose_panel.is_some() {
+            return self.handle_compose_panel_key(key);
+        }
+        if self.migrations.is_some() {
+            return self.handle_migrations_key(key);
+        }
+        if self.secrets.is_some() {
+            return self.handle_secrets_key(key);
+        }
+        if self.packages.is_some() {
+            return self.handle_packages_key(key);
+        }
+        if self.deep_clean_prompt.is_some() {
+            self.handle_deep_clean_prompt_key(key);
+            return Ok(());
+        }
+        if self.vscode_prompt.is_some() {
+            self.handle_vscode_prompt_key(key);
+            return Ok(());
+        }
+        if self.hook_prompt.is_some() {
+            return self.handle_hook_prompt_key(key);
+        }
+
+        if self.busy() && self.keymap.action(Context::Building, &key) == Some(Action::CancelBuild) {
+            self.cancel_tests()?;
+            self.cancel_publish()?;
+            self.cancel_restore()?;
+            self.cancel_clean()?;
+            self.cancel_docker_build()?;
+            self.cancel_compose_wait()?;
+            self.cancel_spa_wait();
+            self.cancel_ef_job()?;
+            self.cancel_secrets_job()?;
+            self.cancel_packages_job()?;
+            self.cancel_dev_cert_job()?;
+            self.cancel_pre_hook()?;
+            return self.cancel_build();
+        }
+
+        // Characters are typed into the filter rather than triggering global actions
+        let typing = self.filter.is_some()
+            && matches!(key.code, KeyCode::Char(_))
+            && !key.modifiers.contains(KeyModifiers::CONTROL);
+        let global = Some(key)
+            .filter(|_| !typing)
+            .and_then(|key| self.keymap.action(Context::Global, &key));
+
+        let page = self.log_page() as isize;
+        match global {
+            Some(Action::Help) => self.help_scroll = Some(0),
+            Some(Action::Palette) => self.palette = Some(Palette::new(self.palette_bindings())),
+            Some(Action::LogUp) => self.scroll_logs(1),
+            Some(Action::LogDown) => self.scroll_logs(-1),
+            Some(Action::LogPageUp) => self.scroll_logs(page),
+            Some(Action::LogPageDown) => self.scroll_logs(-page),
+            Some(Action::LogFollow) => self.logs.scroll = 0,
+            Some(Action::ToggleTimestamps) => self.logs.timestamps = !self.logs.timestamps,
+            Some(Action::GrowLogs) => self.resize_logs(LOG_PANE_STEP as i16),
+            Some(Action::ShrinkLogs) => self.resize_logs(-(LOG_PANE_STEP as i16)),
+            Some(Action::CollapseLogs) => {
+                self.logs_collapsed = !self.logs_collapsed;
+                self.logs_zoomed = false;
+                // Keys would otherwise go to a panel that isn't shown
+                self.log_focus &= !self.logs_collapsed;
+                self.state.logs_collapsed = self.logs_collapsed;
+                self.save_state();
+            }
+            Some(Action::ZoomLogs) => {
+                self.logs_zoomed = !self.logs_zoomed;
+                self.logs_collapsed = false;
+            }
+            Some(Action::FocusLogs) => self.log_focus = !self.log_focus,
+            Some(Action::ExportLogs) => self.export_logs()?,
+            Some(Action::ShowProcesses) => self.set_view(View::Processes),
+            Some(Action::ShowProblems) => self.set_view(View::Problems),
+            Some(Action::ShowFailedTests) => self.set_view(View::Tests),
+            _ if self.log_focus => return self.handle_logs_key(key),
+            _ => return self.handle_context_key(key),
+        }
+        Ok(())
+    }
+
+    /// The bindings the palette offers: the global ones and those of where
+    /// keys go now, leaving out moving around and what would do nothing.
+    fn palette_bindings(&self) -> Vec<Binding> {
+        let mut contexts = vec![Context::Global];
+        if self.busy() {
+            contexts.push(Context::Building);
+        }
+        contexts.push(self.key_context());
+        let filtering = self.filter.is_some();
+        self.keymap
+            .bindings()
+            .iter()
+            .filter(|binding| contexts.contains(&binding.context))
+            .filter(|binding| {
+                // Characters would be typed into the filter instead
+                binding.event().is_some_and(|event| {
+                    !filtering
+                        || !matches!(event.code, KeyCode::Char(_))
+                        || event.modifiers.contains(KeyModifiers::CONTROL)
+                })
+            })
+            .filter(|binding| self.palette_offers(binding.action))
+            .cloned()
+            .collect()
+    }
+
+    fn palette_offers(&self, action: Action) -> bool {
+        let project_selected = self.selected_index().is_some();
+        match action {
+            Action::Palette
+            | Action::Up
+            | Action::Down
+            | Action::PageUp
+            | Action::PageDown
+            | Action::Top
+            | Action::Bottom
+            | Action::LogUp
+            | Action::LogDown
+            | Action::LogPageUp
+            | Action::LogPageDown => false,
+            Action::Expand => matches!(
+                self.selected_row().map(|row| row.entry),
+                Some(ListEntry::Folder(_))
+            ),
+            Action::ForceRun
+            | Action::ToggleMark
+            | Action::RunWithArgs
+            | Action::ChooseProfile
+            | Action::ChooseFramework
+            | Action::EditEnv
+            | Action::Publish
+                if self.view == View::Projects =>
+            {
+                project_selected
+            }
+            Action::OpenPublishFolder => self.publish_dir.is_some(),
+            Action::ShowOutput
+            | Action::NextProcess
+            | Action::PreviousProcess
+            | Action::StopAll
+            | Action::OpenBrowser => !self.processes.is_empty(),
+            Action::Stop | Action::Restart | Action::Copy
+                if matches!(self.view, View::Output | View::Processes) =>
+            {
+                self.process().is_some()
+            }
+            Action::ClearExited => self.processes.iter().any(|p| p.status.is_some()),
+            Action::Compose => self.compose_file().is_some(),
+            _ => true,
+        }
+    }
+
+    /// Runs the picked command by pressing its key, so it does exactly what
+    /// the key does.
+    fn handle_palette_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(palette) = self.palette.as_mut() else {
+            return Ok(());
+        };
+        match key.code {
+            KeyCode::Esc => self.palette = None,
+            KeyCode::Up => palette.move_selection(-1),
+            KeyCode::Down => palette.move_selection(1),
+            KeyCode::PageUp => palette.move_selection(-10),
+            KeyCode::PageDown => palette.move_selection(10),
+            KeyCode::Enter => {
+                let event = palette.selected().and_then(Binding::event);
+                self.palette = None;
+                if let Some(event) = event {
+                    return self.handle_key(event);
+                }
+            }
+            _ => {
+                palette.handle_key(key);
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_logs_key(&mut self, key: KeyEvent) -> Result<()> {
+        let page = self.log_page();
+        match self.keymap.action(Context::Logs, &key) {
+            Some(Action::Quit) => self.request_quit(),
+            Some(Action::Back) => self.log_focus = false,
+            Some(Action::Up) => self.scroll_logs(1),
+            Some(Action::Down) => self.scroll_logs(-1),
+            Some(Action::PageUp) => self.scroll_logs(page as isize),
+            Some(Action::PageDown) => self.scroll_logs(-(page as isize)),
+            Some(Action::Follow) => self.logs.scroll = 0,
+            Some(Action::Filter) => {
+                self.logs.search = Some(TextInput::new(self.logs.query.clone()));
+            }
+            Some(Action::NextMatch) => self.logs.jump_to_match(true, page),
+            Some(Action::PreviousMatch) => self.logs.jump_to_match(false, page),
+            Some(Action::CycleLevel) => self.logs.cycle_level(),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_log_search_key(&mut self, key: KeyEvent) {
+        let page = self.log_page();
+        let Some(search) = self.logs.search.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => {
+                self.logs.search = None;
+                self.logs.current_match = None;
+                self.logs.jump_to_match(false, page);
+            }
+            KeyCode::Esc => {
+                self.logs.search = None;
+                self.logs.query.clear();
+                self.logs.current_match = None;
+            }
+            _ => {
+                // Matches update while typing
+                if search.handle_key(key) {
+                    self.logs.query = search.value.clone();
+                    self.logs.current_match = None;
+                }
+            }
+        }
+    }
+
+    fn handle_output_search_key(&mut self, key: KeyEvent) {
+        let Some(search) = self.output_search.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => self.output_search = None,
+            KeyCode::Esc => {
+                self.output_search = None;
+                self.output_query.clear();
+                self.output_match = None;
+            }
+            _ => {
+                // Jumps to the newest match while typing
+                if search.handle_key(key) {
+                    self.output_query = search.value.clone();
+                    self.output_match = None;
+                    self.jump_to_output_match(false);
+                }
+            }
+        }
+    }
+
+    fn resize_logs(&mut self, delta: i16) {
+        self.logs_collapsed = false;
+        self.logs_zoomed = false;
+        self.log_percent = self
+            .log_percent
+            .saturating_add_signed(delta)
+            .clamp(LOG_PANE_MIN, LOG_PANE_MAX);
+        self.state.log_percent = Some(self.log_percent);
+        self.state.logs_collapsed = false;
+        self.save_state();
+    }
+
+    fn export_logs(&mut self) -> Result<()> {
+        let dir = match &self.config.log_dir {
+            Some(dir) => dir.clone(),
+            None => std::env::current_dir()?,
+        };
+        let path = self.logs.export(&dir)?;
+        let message = format!("Saved log to {}", path.display());
+        self.status.flash(message.clone());
+        self.log_info(message);
+        Ok(())
+    }
+
+    /// Lines of log text visible in the panel as last drawn.
+    fn log_page(&self) -> usize {
+        self.logs_area.height.saturating_sub(2).max(1) as usize
+    }
+
+    fn handle_context_key(&mut self, key: KeyEvent) -> Result<()> {
+        if self.filter.is_some() {
+            return self.handle_filter_key(key);
+        }
+
+        if self.view == View::Output {
+            return self.handle_output_key(key);
+        }
+        if self.view == View::Processes {
+            return self.handle_processes_key(key);
+        }
+        if self.view == View::Problems {
+            return self.handle_problems_key(key);
+        }
+        if self.view == View::Tests {
+            return self.handle_failed_tests_key(key);
+        }
+        if self.view == View::Explorer {
+            return self.handle_explorer_key(key);
+        }
+        if self.view == View::Graph {
+            return self.handle_graph_key(key);
+        }
+
+        let context = match self.view {
+            View::Projects => Context::Projects,
+            _ => Context::Solutions,
+        };
+        match self.keymap.action(context, &key) {
+            Some(Action::Quit) => self.request_quit(),
+            Some(Action::Back) => self.set_view(View::Solutions),
+            Some(Action::Up) => self.move_selection(-1),
+            Some(Action::Down) => self.move_selection(1),
+            Some(Action::PageUp) => self.page_selection(-1),
+            Some(Action::PageDown) => self.page_selection(1),
+            Some(Action::Top) => self.select_row(0),
+            Some(Action::Bottom) => self.select_row(usize::MAX),
+            Some(Action::Filter) => {
+                self.filter = Some(TextInput::default());
+                self.list_state().select(Some(0));
+            }
+            Some(Action::ShowOutput) if !self.processes.is_empty() => self.set_view(View::Output),
+            Some(Action::ToggleMark) => {
+                if let Some(project) = self
+                    .selected_index()
+                    .and_then(|selected| self.projects.get(selected))
+                {
+                    if !self.marked.remove(&project.path) {
+                        self.marked.insert(project.path.clone());
+                    }
+                }
+                self.move_selection(1);
+            }
+            Some(Action::ChooseProfile) => self.choose_launch_profile()?,
+            Some(Action::ChooseFramework) => self.choose_framework()?,
+            Some(Action::Sdk) => self.open_sdk_picker()?,
+            Some(Action::ToggleDetails) => self.show_details = !self.show_details,
+            Some(Action::Expand) => {
+                if let Some(ListEntry::Folder(folder)) = self.selected_row().map(|row| row.entry) {
+                    self.collapsed_folders
+                        .remove(&self.folders[folder].join("/"));
+                }
+            }
+            Some(Action::Collapse) => self.collapse_selected(),
+            Some(Action::ToggleTree) => {
+                let selected = self.selected_index();
+                self.state.flat_projects = !self.state.flat_projects;
+                self.save_state();
+                match selected {
+                    Some(index) => self.select_original(index),
+                    None => self.clamp_selection(),
+                }
+                self.status.flash(if self.state.flat_projects {
+                    "Projects listed flat"
+                } else {
+                    "Projects listed in their solution folders"
+                });
+            }
+            Some(Action::ShowGraph) => self.open_graph()?,
+            Some(Action::BuildImpacted) => {
+                if let Some(selected) = self.selected_index() {
+                    self.build_impacted(selected)?;
+                }
+            }
+            Some(Action::ForceRun) => {
+                let Some(target) = self.selected_target()? else {
+                    return Err(AppError::NoProjectSelected);
+                };
+                self.run_target(target)?;
+            }
+            Some(Action::CycleConfiguration) => self.cycle_configuration(),
+            Some(Action::ToggleWatch) => self.toggle_watch()?,
+            Some(Action::ToggleSpa) => self.toggle_spa()?,
+            Some(Action::RunTests) => self.run_tests()?,
+            Some(Action::ExploreTests) => self.open_explorer()?,
+            Some(Action::Publish) => self.open_publish_prompt()?,
+            Some(Action::OpenPublishFolder) => self.open_publish_folder(),
+            Some(Action::OpenInIde) => self.open_selected_in_ide(),
+            Some(Action::ExportVsCode) => self.export_to_vscode()?,
+            Some(Action::Copy) => {
+                let path = if self.view == View::Solutions {
+                    self.highlighted_solution()
+                } else {
+                    Some(
+                        self.selected_index()
+                            .and_then(|selected| self.projects.get(selected))
+                            .map(|project| project.path.to_string_lossy().into_owned())
+                            .unwrap_or_else(|| self.selected_sln.clone()),
+                    )
+                };
+                if let Some(path) = path {
+                    self.copy_to_clipboard(&path);
+                }
+            }
+            Some(Action::BuildSolution) => self.build_solution()?,
+            Some(Action::Restore) => self.open_restore_menu()?,
+            Some(Action::Clean) => self.open_clean_menu()?,
+            Some(Action::Docker) => self.open_docker_menu()?,
+            Some(Action::Compose) => self.open_compose_panel(),
+            Some(Action::Migrations) => self.open_migrations()?,
+            Some(Action::Secrets) => self.open_secrets()?,
+            Some(Action::Packages) => self.open_packages()?,
+            Some(Action::RunWithArgs) => self.open_args_prompt()?,
+            Some(Action::BuildArgs) => {
+                self.build_args_input = Some(TextInput::new(self.build_args_line.clone()));
+            }
+            Some(Action::ClearBuildArgs) => self.clear_build_args(),
+            Some(Action::ChooseGroup) => self.open_group_picker(),
+            Some(Action::EditEnv) => self.open_env_editor()?,
+            Some(Action::CycleEnvironment) => self.cycle_environment()?,
+            Some(Action::OpenBrowser) => self.open_browser(),
+            Some(Action::CycleVerbosity) => {
+                let verbosity = self.state.verbosity.next();
+                self.state.verbosity = verbosity;
+                self.save_state();
+                self.logs.set_capacity(
+                    self.config.log_lines * verbosity.log_factor(),
+                    self.config.scrollback_bytes(),
+                );
+                let message = format!("Build verbosity: {}", verbosity.label());
+                self.status.flash(message.clone());
+                self.log_info(message);
+            }
+            Some(Action::ToggleBinlog) => {
+                self.binlog = !self.binlog;
+                let message = if self.binlog {
+                    "Builds write an MSBuild binary log"
+                } else {
+                    "Builds no longer write a binary log"
+                };
+                self.status.flash(message);
+                self.log_info(message);
+            }
+            Some(Action::ToggleBuildStrategy) => {
+                self.build_strategy = self.build_strategy.toggle();
+                let message = format!("Build strategy: {}", self.build_strategy.label());
+                self.status.flash(message.clone());
+                self.log_info(message);
+            }
+            Some(Action::Rescan) => self.rescan(),
+            Some(Action::CycleSort) => {
+                let sort = self.solution_sort().next();
+                self.state.solution_sort = Some(sort);
+                self.save_state();
+                self.sort_solutions();
+                self.status
+                    .flash(format!("Solutions sorted by {}", sort.label()));
+            }
+            Some(Action::TogglePin) => self.toggle_pin(),
+            Some(Action::Hide) => self.hide_selected_solution(),
+            Some(Action::ToggleHidden) => {
+                self.show_hidden = !self.show_hidden;
+                if self.show_hidden {
+                    // The scan left them out, so look again without skipping them
+                    if let Some(mut scan) = self.scan.take() {
+                        scan.cancel();
+                    }
+                    self.rescan();
+                    self.status.flash("Showing hidden solutions");
+                } else {
+                    self.drop_hidden_solutions();
+                    self.status.flash("Hiding hidden solutions");
+                }
+            }
+            Some(Action::EditRoot) => {
+                let current = self
+                    .roots
+                    .first()
+                    .map(|root| root.display().to_string())
+                    .unwrap_or_default();
+                self.root_input = Some(TextInput::new(current));
+            }
+            Some(Action::Select) => {
+                self.on_enter_key()?;
+
+                let index = self.list_state().selected().unwrap_or(0);
+                self.log_info(format!("Selected item at index: {}", index));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent) -> Result<()> {
+        let popup_open = self.error_popup.is_some()
+            || self.help_scroll.is_some()
+            || self.payload.is_some()
+            || self.rules_picker.is_some()
+            || self.palette.is_some()
+            || self.root_input.is_some()
+            || self.hide_input.is_some()
+            || self.args_prompt.is_some()
+            || self.build_args_input.is_some()
+            || self.env_editor.is_some()
+            || self.profile_picker.is_some()
+            || self.framework_picker.is_some()
+            || self.sdk_picker.is_some()
+            || self.url_picker.is_some()
+            || self.ide_picker.is_some()
+            || self.group_picker.is_some()
+            || self.group_name.is_some()
+            || self.port_prompt.is_some()
+            || self.dev_cert_prompt.is_some()
+            || self.quit_prompt.is_some()
+            || self.publish_prompt.is_some()
+            || self.restore_menu.is_some()
+            || self.clean_menu.is_some()
+            || self.docker_menu.is_some()
+            || self.compose_panel.is_some()
+            || self.migrations.is_some()
+            || self.secrets.is_some()
+            || self.packages.is_some()
+            || self.deep_clean_prompt.is_some()
+            || self.vscode_prompt.is_some()
+            || self.hook_prompt.is_some();
+        if popup_open {
+            return Ok(());
+        }
+
+        let position = Position::new(mouse.column, mouse.row);
+        let over_logs = self.logs_area.contains(position);
+        match mouse.kind {
+            MouseEventKind::ScrollUp if over_logs => {
+                self.scroll_logs(MOUSE_SCROLL_LINES as isize);
+            }
+            MouseEventKind::ScrollDown if over_logs => {
+                self.scroll_logs(-(MOUSE_SCROLL_LINES as isize));
+            }
+            MouseEventKind::ScrollUp if self.view == View::Output => {
+                self.scroll_output(MOUSE_SCROLL_LINES as isize);
+            }
+            MouseEventKind::ScrollDown if self.view == View::Output => {
+                self.scroll_output(-(MOUSE_SCROLL_LINES as isize));
+            }
+            MouseEventKind::ScrollUp if self.view == View::Problems => self.select_problem(-1),
+            MouseEventKind::ScrollDown if self.view == View::Problems => self.select_problem(1),
+            MouseEventKind::ScrollUp if self.view == View::Tests => self.select_failure(-1),
+            MouseEventKind::ScrollDown if self.view == View::Tests => self.select_failure(1),
+            MouseEventKind::ScrollUp if self.view == View::Explorer => self.select_test_row(-1),
+            MouseEventKind::ScrollDown if self.view == View::Explorer => self.select_test_row(1),
+            MouseEventKind::ScrollUp if self.view == View::Graph => self.select_graph_row(-1),
+            MouseEventKind::ScrollDown if self.view == View::Graph => self.select_graph_row(1),
+            MouseEventKind::ScrollUp if self.view == View::Processes => {
+                self.current_process = self.current_process.saturating_sub(1);
+            }
+            MouseEventKind::ScrollDown if self.view == View::Processes => {
+                self.select_process(self.current_process + 1);
+            }
+            MouseEventKind::ScrollUp => {
+                let current = self.list_state().selected().unwrap_or(0);
+                self.select_row(current.saturating_sub(1));
+            }
+            MouseEventKind::ScrollDown => {
+                let current = self.list_state().selected().unwrap_or(0);
+                self.select_row(current + 1);
+            }
+            MouseEventKind::Down(MouseButton::Left) if over_logs => self.log_focus = true,
+            MouseEventKind::Down(MouseButton::Left)
+                if matches!(self.view, View::Solutions | View::Projects) =>
+            {
+                self.log_focus = false;
+                self.click_list(position)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Selects the clicked list row; a second click on the same row acts like Enter.
+    fn click_list(&mut self, position: Position) -> Result<()> {
+        let area = self.list_area;
+        // Skip the borders
+        if position.y <= area.y || position.y + 1 >= area.bottom() {
+            return Ok(());
+        }
+        let row = (position.y - area.y - 1) as usize + self.list_state().offset();
+        if row >= self.visible_rows().len() {
+            return Ok(());
+        }
+
+        let double_click = self
+            .last_click
+            .is_some_and(|(last, at)| last == row && at.elapsed() < DOUBLE_CLICK);
+        self.select_row(row);
+        if !double_click {
+            self.last_click = Some((row, Instant::now()));
+            return Ok(());
+        }
+
+        self.last_click = None;
+        if self.filter.is_some() {
+            self.clear_filter();
+        }
+        self.on_enter_key()
+    }
+
+    fn handle_help_key(&mut self, key: KeyEvent) {
+        let Some(scroll) = self.help_scroll.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Up => *scroll = scroll.saturating_sub(1),
+            KeyCode::Down => *scroll = scroll.saturating_add(1),
+            KeyCode::PageUp => *scroll = scroll.saturating_sub(10),
+            KeyCode::PageDown => *scroll = scroll.saturating_add(10),
+            _ => self.help_scroll = None,
+        }
+    }
+
+    fn handle_payload_key(&mut self, key: KeyEvent) {
+        let Some(payload) = self.payload.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Up => payload.scroll = payload.scroll.saturating_sub(1),
+            KeyCode::Down => payload.scroll = payload.scroll.saturating_add(1),
+            KeyCode::PageUp => payload.scroll = payload.scroll.saturating_sub(10),
+            KeyCode::PageDown => payload.scroll = payload.scroll.saturating_add(10),
+            KeyCode::Char('y') => {
+                let json = payload.lines.join("\n");
+                self.copy_to_clipboard(&json);
+            }
+            _ => self.payload = None,
+        }
+    }
+
+    fn handle_rules_picker_key(&mut self, key: KeyEvent) {
+        let Some(state) = self.rules_picker.as_mut() else {
+            return;
+        };
+        let current = state.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.rules_picker = None,
+            KeyCode::Up => state.select(Some(current.saturating_sub(1))),
+            KeyCode::Down => {
+                let last = self.output_rules.len().saturating_sub(1);
+                state.select(Some((current + 1).min(last)));
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                if let Some(rule) = self.output_rules.get_mut(current) {
+                    rule.enabled = !rule.enabled;
+                    // Muting changes which lines there are to show
+                    self.output_index = None;
+                    self.output_match = None;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_filter_key(&mut self, key: KeyEvent) -> Result<()> {
+        match self.keymap.action(Context::Filter, &key) {
+            Some(Action::ClearFilter) => self.clear_filter(),
+            Some(Action::Up) => self.move_selection(-1),
+            Some(Action::Down) => self.move_selection(1),
+            Some(Action::PageUp) => self.page_selection(-1),
+            Some(Action::PageDown) => self.page_selection(1),
+            Some(Action::Top) => self.select_row(0),
+            Some(Action::Bottom) => self.select_row(usize::MAX),
+            Some(Action::Select) => {
+                self.clear_filter();
+                self.on_enter_key()?;
+            }
+            _ => {
+                if let Some(filter) = self.filter.as_mut() {
+                    if filter.handle_key(key) {
+                        // Typing re-ranks the list, so jump back to the top hit
+                        self.list_state().select(Some(0));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Closes the filter, keeping the highlighted item selected in the unfiltered list.
+    fn clear_filter(&mut self) {
+        let selected = self.selected_index();
+        self.filter = None;
+        self.select_original(selected.unwrap_or(0));
+    }
+
+    /// Folds the highlighted solution folder. Inside one that is folded
+    /// already, or on one of its projects, moves up to it instead, and at the
+    /// top of the tree goes back to the solutions.
+    fn collapse_selected(&mut self) {
+        let parent = match self.selected_row().map(|row| row.entry) {
+            Some(ListEntry::Folder(folder)) => {
+                let folder = &self.folders[folder];
+                if self.collapsed_folders.insert(folder.join("/")) {
+                    return;
+                }
+                folder[..folder.len() - 1].to_vec()
+            }
+            Some(ListEntry::Item(index)) if self.tree_shown() => {
+                self.projects[index].folder.clone()
+            }
+            _ => Vec::new(),
+        };
+        if parent.is_empty() {
+            self.set_view(View::Solutions);
+            return;
+        }
+        let row = self.visible_rows().iter().position(
+            |row| matches!(row.entry, ListEntry::Folder(folder) if self.folders[folder] == parent),
+        );
+        if let Some(row) = row {
+            self.select_row(row);
+        }
+    }
+
+    fn set_view(&mut self, view: View) {
+        self.filter = None;
+        self.view = view;
+        self.clamp_selection();
+    }
+
+    fn handle_problems_key(&mut self, key: KeyEvent) -> Result<()> {
+        let page = self.list_height.max(1) as isize;
+        match self.keymap.action(Context::Problems, &key) {
+            Some(Action::Quit) => self.request_quit(),
+            Some(Action::Back) if self.projects.is_empty() => self.set_view(View::Solutions),
+            Some(Action::Back) => self.set_view(View::Projects),
+            Some(Action::Up) => self.select_problem(-1),
+            Some(Action::Down) => self.select_problem(1),
+            Some(Action::PageUp) => self.select_problem(-page),
+            Some(Action::PageDown) => self.select_problem(page),
+            Some(Action::Top) => self.problem_state.select(Some(0)),
+            Some(Action::Bottom) => self.select_problem(isize::MAX),
+            Some(Action::Select) => self.open_problem()?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Opens the highlighted problem's file at its line in the editor.
+    fn open_problem(&mut self) -> Result<()> {
+        let Some(problem) = self
+            .problem_state
+            .selected()
+            .and_then(|selected| self.problems.get(selected))
+        else {
+            return Ok(());
+        };
+        let diagnostic = &problem.diagnostic;
+
+        // Relative paths are relative to the project that reported them
+        let origin = Path::new(&diagnostic.origin);
+        let project_dir = diagnostic
+            .project
+            .as_deref()
+            .map(Path::new)
+            .or_else(|| {
+                self.projects
+                    .iter()
+                    .find(|project| project.name == problem.build)
+                    .map(|project| project.path.as_path())
+            })
+            .and_then(Path::parent);
+        let file = match project_dir {
+            Some(dir) if origin.is_relative() => dir.join(origin),
+            _ => origin.to_path_buf(),
+        };
+        // Tools like CSC report without a file
+        if !file.is_file() {
+            let message = format!("{} is not a file to open", diagnostic.origin);
+            self.status.flash_error(message.clone());
+            self.log_warn(message);
+            return Ok(());
+        }
+
+        let location = diagnostic.short_location();
+        let (line, column) = (diagnostic.line.unwrap_or(1), diagnostic.column.unwrap_or(1));
+        self.open_in_editor(&file, line, column, &location)
+    }
+
+    /// Opens the file at the line in the configured editor, `what` saying
+    /// what it is for the log.
+    fn open_in_editor(&mut self, file: &Path, line: u32, column: u32, what: &str) -> Result<()> {
+        let editor = editor_command(self.config.editor.as_deref(), file, line, column)
+            .map_err(|message| AppError::Editor { message })?;
+        self.log_info(format!("Opening {} in {}", what, editor.program));
+        // `code` and others are batch files on Windows, which only cmd runs
+        let mut command = if cfg!(windows) {
+            let mut command = Command::new("cmd");
+            command.arg("/C").arg(&editor.program);
+            command
+        } else {
+            Command::new(&editor.program)
+        };
+        command.args(&editor.args);
+        if editor.in_terminal {
+            self.foreground = Some(command);
+            return Ok(());
+        }
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| AppError::Editor {
+                message: format!("{}: {}", editor.program, e),
+            })?;
+        // Reap it in the background so it doesn't linger as a zombie
+        thread::spawn(move || child.wait());
+        Ok(())
+    }
+
+    fn select_problem(&mut self, delta: isize) {
+        let last = self.problems.len().saturating_sub(1);
+        let current = self.problem_state.selected().unwrap_or(0);
+        let index = current.saturating_add_signed(delta).min(last);
+        self.problem_state.select(Some(index));
+    }
+
+    fn handle_failed_tests_key(&mut self, key: KeyEvent) -> Result<()> {
+        let page = self.list_height.max(1) as isize;
+        match self.keymap.action(Context::Tests, &key) {
+            Some(Action::Quit) => self.request_quit(),
+            Some(Action::Back) if self.projects.is_empty() => self.set_view(View::Solutions),
+            Some(Action::Back) => self.set_view(View::Projects),
+            Some(Action::Up) => self.select_failure(-1),
+            Some(Action::Down) => self.select_failure(1),
+            Some(Action::PageUp) => self.select_failure(-page),
+            Some(Action::PageDown) => self.select_failure(page),
+            Some(Action::Top) => self.failure_state.select(Some(0)),
+            Some(Action::Bottom) => self.select_failure(isize::MAX),
+            Some(Action::RerunFailed) => self.rerun_failed_tests()?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn select_failure(&mut self, delta: isize) {
+        let len = self.test_results.as_ref().map_or(0, |r| r.failures.len());
+        let current = self.failure_state.selected().unwrap_or(0);
+        let index = current
+            .saturating_add_signed(delta)
+            .min(len.saturating_sub(1));
+        self.failure_state.select(Some(index));
+    }
+
+    fn handle_explorer_key(&mut self, key: KeyEvent) -> Result<()> {
+        let page = self.list_height.max(1) as isize;
+        match self.keymap.action(Context::Explorer, &key) {
+            Some(Action::Quit) => self.request_quit(),
+            Some(Action::Back) => self.set_view(View::Projects),
+            Some(Action::Up) => self.select_test_row(-1),
+            Some(Action::Down) => self.select_test_row(1),
+            Some(Action::PageUp) => self.select_test_row(-page),
+            Some(Action::PageDown) => self.select_test_row(page),
+            Some(Action::Top) => self.select_test_row(isize::MIN),
+            Some(Action::Bottom) => self.select_test_row(isize::MAX),
+            Some(Action::Filter) => {
+                if let Some(explorer) = self.explorer.as_mut() {
+                    explorer.search = Some(TextInput::new(explorer.query.clone()));
+                }
+            }
+            Some(Action::ToggleGroup) => {
+                if let Some((explorer, row)) = self.selected_test_row() {
+                    if let Some(tree) = explorer.tree.as_mut() {
+                        tree.toggle(&row.node);
+                    }
+                }
+            }
+            Some(Action::Rescan) => {
+                if let Some(explorer) = self.explorer.as_ref() {
+                    let path = explorer.path.clone();
+                    self.test_lists.remove(&path);
+                    self.start_test_listing(path)?;
+                }
+            }
+            Some(Action::Select) => self.run_selected_tests()?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_explorer_search_key(&mut self, key: KeyEvent) {
+        let Some(explorer) = self.explorer.as_mut() else {
+            return;
+        };
+        let Some(search) = explorer.search.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => explorer.search = None,
+            KeyCode::Esc => {
+                explorer.search = None;
+                explorer.query.clear();
+            }
+            _ => {
+                // The tree narrows down while typing
+                if search.handle_key(key) {
+                    explorer.query = search.value.clone();
+                    explorer.state.select(Some(0));
+                }
+            }
+        }
+    }
+
+    fn select_test_row(&mut self, delta: isize) {
+        let Some(explorer) = self.explorer.as_mut() else {
+            return;
+        };
+        let len = explorer
+            .tree
+            .as_ref()
+            .map_or(0, |tree| tree.rows(&explorer.query).len());
+        let current = explorer.state.selected().unwrap_or(0);
+        let index = current
+            .saturating_add_signed(delta)
+            .min(len.saturating_sub(1));
+        explorer.state.select(Some(index));
+    }
+
+    /// The explorer with its highlighted row.
+    fn selected_test_row(&mut self) -> Option<(&mut Explorer, Row)> {
+        let explorer = self.explorer.as_mut()?;
+        let selected = explorer.state.selected()?;
+        let row = explorer
+            .tree
+            .as_ref()?
+            .rows(&explorer.query)
+            .into_iter()
+            .nth(selected)?;
+        Some((explorer, row))
+    }
+
+    /// Opens the test explorer on the highlighted test project, listing its
+    /// tests unless they are known from before.
+    fn open_explorer(&mut self) -> Result<()> {
+        let Some(project) = self
+            .selected_index()
+            .and_then(|selected| self.projects.get(selected))
+        else {
+            return Err(AppError::NoProjectSelected);
+        };
+        if project.output != OutputKind::Test {
+            self.log_warn(format!("{} is not a test project", project.name));
+            return Ok(());
+        }
+        let (name, path) = (project.name.clone(), project.path.clone());
+
+        // The same project keeps its folds, filter and outcomes
+        if self.explorer.as_ref().is_none_or(|e| e.path != path) {
+            self.explorer = Some(Explorer {
+                name,
+                path: path.clone(),
+                tree: self.test_lists.get(&path).cloned().map(TestTree::new),
+                error: None,
+                state: ListState::default().with_selected(Some(0)),
+                search: None,
+                query: String::new(),
+            });
+        }
+        self.set_view(View::Explorer);
+        if !self.test_lists.contains_key(&path) {
+            self.start_test_listing(path)?;
+        }
+        Ok(())
+    }
+
+    fn start_test_listing(&mut self, path: PathBuf) -> Result<()> {
+        if self.test_listing.as_ref().is_some_and(|l| l.path == path) {
+            return Ok(());
+        }
+        if let Some(mut listing) = self.test_listing.take() {
+            listing.job.kill()?;
+        }
+        if self.dotnet.is_none() {
+            return Err(AppError::DotnetMissing);
+        }
+        let job = Job::spawn(
+            Command::new("dotnet")
+                .arg("test")
+                .arg(&path)
+                .arg("--list-tests")
+                .arg("--no-build")
+                .arg("--configuration")
+                .arg(&self.configuration),
+        )
+        .map_err(dotnet_spawn_error)?;
+        if let Some(explorer) = self.explorer.as_mut().filter(|e| e.path == path) {
+            explorer.error = None;
+        }
+        self.test_listing = Some(TestListing {
+            job,
+            path,
+            lines: Vec::new(),
+        });
+        Ok(())
+    }
+
+    fn poll_test_listing(&mut self) -> Result<()> {
+        let Some(listing) = self.test_listing.as_mut() else {
+            return Ok(());
+        };
+        listing
+            .lines
+            .extend(listing.job.drain().into_iter().map(|line| line.text));
+        let Some(status) = listing.job.poll_exit()? else {
+            return Ok(());
+        };
+        let Some(listing) = self.test_listing.take() else {
+            return Ok(());
+        };
+
+        if !status.success() {
+            // Without a build there is no test assembly to list
+            let message = format!(
+                "Could not list the tests ({}), build the project first with t",
+                status
+            );
+            self.log_error(format!("{}: {}", listing.path.display(), message));
+            if let Some(explorer) = self.explorer.as_mut().filter(|e| e.path == listing.path) {
+                explorer.error = Some(message);
+            }
+            return Ok(());
+        }
+        let tests = parse_test_list(&listing.lines);
+        if let Some(explorer) = self.explorer.as_mut().filter(|e| e.path == listing.path) {
+            match explorer.tree.as_mut() {
+                Some(tree) => tree.replace_tests(tests.clone()),
+                None => explorer.tree = Some(TestTree::new(tests.clone())),
+            }
+        }
+        self.test_lists.insert(listing.path, tests);
+        Ok(())
+    }
+
+    /// Runs the highlighted test, or every test of the class or namespace.
+    fn run_selected_tests(&mut self) -> Result<()> {
+        let Some((explorer, row)) = self.selected_test_row() else {
+            return Ok(());
+        };
+        let Some(tree) = explorer.tree.as_ref() else {
+            return Ok(());
+        };
+        let filter = tree.filter(&row.node);
+        let description = format!("Testing {} in {}", tree.name(&row.node), explorer.name);
+        let (name, path) = (explorer.name.clone(), explorer.path.clone());
+        self.start_tests(name, path, Some(filter), description)
+    }
+
+    /// Forgets the test lists a build may have changed, and lists the tests
+    /// in the explorer again if it shows one of them.
+    fn invalidate_test_lists(&mut self, path: &Path) -> Result<()> {
+        // A solution builds every project in it, and a filter of it several
+        if path
+            .extension()
+            .is_some_and(|ext| ext == "sln" || ext == "slnx" || ext == "slnf")
+        {
+            self.test_lists.clear();
+        } else {
+            self.test_lists.remove(path);
+        }
+        let Some(explorer) = self.explorer.as_ref() else {
+            return Ok(());
+        };
+        if !self.test_lists.contains_key(&explorer.path) {
+            let path = explorer.path.clone();
+            self.start_test_listing(path)?;
+        }
+        Ok(())
+    }
+
+    fn handle_graph_key(&mut self, key: KeyEvent) -> Result<()> {
+        let page = self.list_height.max(1) as isize;
+        match self.keymap.action(Context::Graph, &key) {
+            Some(Action::Quit) => self.request_quit(),
+            Some(Action::Back) => {
+                let previous = self.graph_view.as_mut().and_then(|view| view.history.pop());
+                match previous {
+                    Some(previous) => self.focus_graph(previous),
+                    None => self.set_view(View::Projects),
+                }
+            }
+            Some(Action::Up) => self.select_graph_row(-1),
+            Some(Action::Down) => self.select_graph_row(1),
+            Some(Action::PageUp) => self.select_graph_row(-page),
+            Some(Action::PageDown) => self.select_graph_row(page),
+            Some(Action::Top) => self.select_graph_row(isize::MIN),
+            Some(Action::Bottom) => self.select_graph_row(isize::MAX),
+            Some(Action::ToggleReverse) => {
+                if let Some(view) = self.graph_view.as_mut() {
+                    view.reverse = !view.reverse;
+                    view.state.select(Some(0));
+                }
+            }
+            Some(Action::Select) => {
+                let row = self.graph_view.as_ref().and_then(|view| {
+                    let selected = view.state.selected()?;
+                    self.graph_rows(view).into_iter().nth(selected)
+                });
+                match row {
+                    Some(GraphRow::Project(index)) => {
+                        if let Some(view) = self.graph_view.as_mut() {
+                            view.history.push(view.focus);
+                        }
+                        self.focus_graph(index);
+                    }
+                    Some(GraphRow::Outside(name)) => {
+                        self.status
+                            .flash(format!("{} is not in the solution", name));
+                    }
+                    None => {}
+                }
+            }
+            Some(Action::BuildImpacted) => {
+                if let Some(focus) = self.graph_view.as_ref().map(|view| view.focus) {
+                    self.build_impacted(focus)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Reads the references of the solution's projects in the background,
+    /// unless what was read is still current.
+    fn load_graph(&mut self) {
+        let paths: Vec<PathBuf> = self.projects.iter().map(|p| p.path.clone()).collect();
+        let current = self
+            .graph
+            .as_ref()
+            .is_some_and(|graph| graph.is_current(&self.selected_sln, &paths));
+        if !current && self.graph_load.is_none() {
+            self.graph_load = Some(GraphLoad::start(self.selected_sln.clone(), paths));
+        }
+    }
+
+    fn poll_graph(&mut self) {
+        let Some(graph) = self.graph_load.as_ref().and_then(GraphLoad::poll) else {
+            return;
+        };
+        self.graph_load = None;
+        // The solution may have changed while it was read
+        if graph.solution != self.selected_sln || graph.references.len() != self.projects.len() {
+            self.load_graph();
+            return;
+        }
+
+        // Each cycle once, whichever of its projects it is found from
+        let mut reported: HashSet<usize> = HashSet::new();
+        let mut cycles: Vec<String> = Vec::new();
+        let mut cyclic: Vec<usize> = graph.cyclic.iter().copied().collect();
+        cyclic.sort_unstable();
+        for index in cyclic {
+            if reported.contains(&index) {
+                continue;
+            }
+            let Some(cycle) = graph.cycle(index) else {
+                continue;
+            };
+            reported.extend(&cycle);
+            let names: Vec<&str> = cycle
+                .iter()
+                .map(|&i| self.projects[i].name.as_str())
+                .collect();
+            cycles.push(names.join(" → "));
+        }
+        self.graph = Some(graph);
+        for cycle in cycles {
+            self.log_warn(format!("Circular project reference: {}", cycle));
+        }
+    }
+
+    fn open_graph(&mut self) -> Result<()> {
+        let Some(selected) = self.selected_index() else {
+            return Err(AppError::NoProjectSelected);
+        };
+        self.load_graph();
+        self.graph_view = Some(GraphView {
+            focus: selected,
+            reverse: false,
+            history: Vec::new(),
+            state: ListState::default().with_selected(Some(0)),
+        });
+        self.set_view(View::Graph);
+        Ok(())
+    }
+
+    fn focus_graph(&mut self, index: usize) {
+        if let Some(view) = self.graph_view.as_mut() {
+            view.focus = index;
+            view.state.select(Some(0));
+        }
+    }
+
+    /// The neighbours of the focused project the view lists, with references
+    /// to projects outside the solution after the rest.
+    fn graph_rows(&self, view: &GraphView) -> Vec<GraphRow> {
+        let Some(graph) = self.graph.as_ref() else {
+            return Vec::new();
+        };
+        let Some(references) = graph.references.get(view.focus) else {
+            return Vec::new();
+        };
+        if view.reverse {
+            let mut dependents = graph.referenced_by[view.focus].clone();
+            dependents.sort_by(|a, b| self.projects[*a].name.cmp(&self.projects[*b].name));
+            return dependents.into_iter().map(GraphRow::Project).collect();
+        }
+        let mut references = references.clone();
+        references.sort_by(|a, b| self.projects[*a].name.cmp(&self.projects[*b].name));
+        references
+            .into_iter()
+            .map(GraphRow::Project)
+            .chain(
+                graph.outside[view.focus]
+                    .iter()
+                    .cloned()
+                    .map(GraphRow::Outside),
+            )
+            .collect()
+    }
+
+    fn select_graph_row(&mut self, delta: isize) {
+        let Some(view) = self.graph_view.as_ref() else {
+            return;
+        };
+        let last = self.graph_rows(view).len().saturating_sub(1);
+        if let Some(view) = self.graph_view.as_mut() {
+            let current = view.state.selected().unwrap_or(0);
+            let index = current.saturating_add_signed(delta).min(last);
+            view.state.select(Some(index));
+        }
+    }
+
+    fn handle_processes_key(&mut self, key: KeyEvent) -> Result<()> {
+        match self.keymap.action(Context::Processes, &key) {
+            Some(Action::Quit) => self.request_quit(),
+            Some(Action::Back) if self.projects.is_empty() => self.set_view(View::Solutions),
+            Some(Action::Back) => self.set_view(View::Projects),
+            Some(Action::Up) => self.current_process = self.current_process.saturating_sub(1),
+            Some(Action::Down) => self.select_process(self.current_process + 1),
+            Some(Action::Select) if !self.processes.is_empty() => {
+                self.output_anchor = None;
+                self.output_match = None;
+                self.output_paused = None;
+                self.set_view(View::Output);
+            }
+            Some(Action::Stop) => self.stop_process(self.current_process)?,
+            Some(Action::StopAll) => self.stop_all()?,
+            Some(Action::Restart) => self.restart_process()?,
+            Some(Action::ClearExited) => self.clear_exited(),
+            Some(Action::OpenRecording) => self.open_recording()?,
+            Some(Action::OpenBrowser) => self.open_browser(),
+            Some(Action::Copy) => self.copy_url(),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Opens the file the highlighted process's output is saved to, at its
+    /// end.
+    fn open_recording(&mut self) -> Result<()> {
+        let Some(process) = self.processes.get_mut(self.current_process) else {
+            return Ok(());
+        };
+        let Some(recording) = process.recording.as_mut() else {
+            let message = if self.config.record_output {
+                format!("{} has no recording", process.project)
+            } else {
+                "Output is not recorded, set record_output in the config".to_string()
+            };
+            self.status.flash(message);
+            return Ok(());
+        };
+        recording.flush()?;
+        let path = recording.path.clone();
+        let line = recording.lines.max(1);
+        let what = format!("the recording of {}", process.project);
+        self.open_in_editor(&path, line, 1, &what)
+    }
+
+    fn handle_output_key(&mut self, key: KeyEvent) -> Result<()> {
+        match self.keymap.action(Context::Output, &key) {
+            Some(Action::Quit) => self.request_quit(),
+            Some(Action::Back) => self.set_view(View::Projects),
+            Some(Action::Up) => self.scroll_output(1),
+            Some(Action::Down) => self.scroll_output(-1),
+            Some(Action::PageUp) => self.scroll_output(10),
+            Some(Action::PageDown) => self.scroll_output(-10),
+            Some(Action::Top) => {
+                self.update_output_index();
+                self.scroll_output_to(0);
+            }
+            Some(Action::Follow) => {
+                self.output_anchor = None;
+                self.output_paused = None;
+            }
+            Some(Action::PauseOutput) => self.toggle_output_pause(),
+            Some(Action::Filter) => {
+                self.output_search = Some(TextInput::new(self.output_query.clone()));
+            }
+            Some(Action::NextMatch) => self.jump_to_output_match(true),
+            Some(Action::PreviousMatch) => self.jump_to_output_match(false),
+            Some(Action::RawOutput) => {
+                self.raw_output = !self.raw_output;
+                self.status.flash(if self.raw_output {
+                    "JSON log lines shown raw"
+                } else {
+                    "JSON log lines prettified"
+                });
+            }
+            Some(Action::Select) => self.open_payload(),
+            Some(Action::OutputRules) => self.open_rules_picker(),
+
+            Some(Action::OpenBrowser) => self.open_browser(),
+            Some(Action::Copy) => self.copy_url(),
+            Some(Action::Stop) => self.stop_process(self.current_process)?,
+            Some(Action::StopAll) => self.stop_all()?,
+            Some(Action::Restart) => self.restart_process()?,
+            Some(Action::NextProcess) => self.switch_process(1),
+            Some(Action::PreviousProcess) => self.switch_process(-1),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // Paused, the output view stays on the lines it shows while the process
+    // keeps adding to its scrollback; resuming jumps to the newest
+    fn toggle_output_pause(&mut self) {
+        if self.output_paused.take().is_some() {
+            self.output_anchor = None;
+            return;
+        }
+        self.update_output_index();
+        let (Some(process), Some(index)) = (
+            self.processes.get(self.current_process),
+            self.output_index.as_ref(),
+        ) else {
+            return;
+        };
+        let Some(last) = index.lines.len().checked_sub(1) else {
+            self.status.flash("No output to pause");
+            return;
+        };
+        self.output_paused = Some(process.output.pushed());
+        if self.output_anchor.is_none() {
+            self.scroll_output_to(last);
+        }
+    }
+
+    fn handle_root_input_key(&mut self, key: KeyEvent) {
+        let Some(input) = self.root_input.as_mut() else {
+            return;
+        };
+
+        match key.code {
+            KeyCode::Esc => self.root_input = None,
+            KeyCode::Enter => {
+                let root = expand_tilde(Path::new(input.value.trim()));
+                if !root.is_dir() {
+                    self.log_warn(format!("{} is not a directory", root.display()));
+                    return;
+                }
+                self.root_input = None;
+                self.set_root(std::path::absolute(&root).unwrap_or(root));
+            }
+            _ => {
+                input.handle_key(key);
+            }
+        }
+    }
+
+    fn open_args_prompt(&mut self) -> Result<()> {
+        let Some(target) = self.selected_target()? else {
+            return Err(AppError::NoProjectSelected);
+        };
+        let key = target.project_path.to_string_lossy().into_owned();
+        let history = self.state.run_args.get(&key).cloned().unwrap_or_default();
+        self.args_prompt = Some(ArgsPrompt {
+            target,
+            input: TextInput::new(history.last().cloned().unwrap_or_default()),
+            history_index: history.len().checked_sub(1),
+            history,
+        });
+        Ok(())
+    }
+
+    fn handle_args_prompt_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(prompt) = self.args_prompt.as_mut() else {
+            return Ok(());
+        };
+
+        match key.code {
+            KeyCode::Esc => self.args_prompt = None,
+            KeyCode::Up if !prompt.history.is_empty() => {
+                let index = prompt
+                    .history_index
+                    .map_or(prompt.history.len() - 1, |i| i.saturating_sub(1));
+                prompt.history_index = Some(index);
+                prompt.input = TextInput::new(prompt.history[index].clone());
+            }
+            KeyCode::Down => {
+                let index = prompt
+                    .history_index
+                    .map(|i| i + 1)
+                    .filter(|&i| i < prompt.history.len());
+                prompt.history_index = index;
+                prompt.input = TextInput::new(index.map_or("", |i| &prompt.history[i]));
+            }
+            KeyCode::Enter => {
+                let line = prompt.input.value.trim().to_string();
+                let Some(args) = split_args(&line) else {
+                    self.log_warn("Unterminated quote in arguments");
+                    return Ok(());
+                };
+                let Some(mut prompt) = self.args_prompt.take() else {
+                    return Ok(());
+                };
+                if !line.is_empty() {
+                    let key = prompt.target.project_path.to_string_lossy().into_owned();
+                    let history = self.state.run_args.entry(key).or_default();
+                    history.retain(|previous| *previous != line);
+                    history.push(line);
+                    if history.len() > ARGS_HISTORY {
+                        history.remove(0);
+                    }
+                    self.save_state();
+                }
+                prompt.target.args = args;
+                self.run_target(prompt.target)?;
+            }
+            _ => {
+                prompt.input.handle_key(key);
+            }
+        }
+        Ok(())
+    }
+
+    /// The profile the next run of the project will use, as far as it is
+    /// known yet.
+    fn next_profile<'a>(
+        &self,
+        key: &str,
+        profiles: &'a [LaunchProfile],
+    ) -> Option<&'a LaunchProfile> {
+        let mut supported = profiles.iter().filter(|p| p.is_supported());
+        match self.state.launch_profiles.get(key) {
+            Some(choice) => choice
+                .as_ref()
+                .and_then(|name| profiles.iter().find(|p| &p.name == name)),
+            None => supported.next().filter(|_| supported.next().is_none()),
+        }
+    }
+
+    fn open_env_editor(&mut self) -> Result<()> {
+        let Some(target) = self.selected_target()? else {
+            return Err(AppError::NoProjectSelected);
+        };
+        let key = target.project_path.to_string_lossy().into_owned();
+        let profiles = read_launch_profiles(&target.project_dir);
+        let profile = self.next_profile(&key, &profiles);
+        self.env_editor = Some(EnvEditor::new(
+            target.project,
+            key,
+            profile.map(|p| p.name.clone()),
+            profile
+                .map(|p| p.environment_variables.clone())
+                .unwrap_or_default(),
+        ));
+        Ok(())
+    }
+
+    /// Steps the selected project through the configured environments and then
+    /// back to whatever its launch profile sets.
+    fn cycle_environment(&mut self) -> Result<()> {
+        let Some(target) = self.selected_target()? else {
+            return Ok(());
+        };
+        let key = target.project_path.to_string_lossy().into_owned();
+        let candidates = &self.config.environments;
+        let next = match self.state.environments.get(&key) {
+            Some(current) => candidates
+                .iter()
+                .position(|c| c == current)
+                .and_then(|i| candidates.get(i + 1)),
+            None => candidates.first(),
+        };
+        let message = match next.cloned() {
+            Some(environment) => {
+                let message = format!("{} environment: {}", target.project, environment);
+                self.state.environments.insert(key, environment);
+                message
+            }
+            None => {
+                self.state.environments.remove(&key);
+                format!("{} environment: from launch profile", target.project)
+            }
+        };
+        self.save_state();
+        self.status.flash(message.clone());
+        self.log_info(message);
+        Ok(())
+    }
+
+    fn editor_vars(&self) -> Vec<EnvVar> {
+        let Some(editor) = &self.env_editor else {
+            return Vec::new();
+        };
+        self.env_vars(&editor.project, &editor.project_path, &editor.profile_vars)
+    }
+
+    fn env_vars(
+        &self,
+        project: &str,
+        project_path: &str,
+        profile_vars: &BTreeMap<String, String>,
+    ) -> Vec<EnvVar> {
+        env::merge(
+            profile_vars,
+            &self.config.env.get(project).cloned().unwrap_or_default(),
+            &self
+                .env_overrides
+                .get(project_path)
+                .cloned()
+                .unwrap_or_default(),
+        )
+    }
+
+    fn handle_env_editor_key(&mut self, key: KeyEvent) {
+        let vars = self.editor_vars();
+        let Some(editor) = self.env_editor.as_mut() else {
+            return;
+        };
+
+        if let Some(input) = editor.input.as_mut() {
+            match key.code {
+                KeyCode::Esc => editor.input = None,
+                KeyCode::Enter => match env::parse_assignment(&input.value) {
+                    Some((name, value)) => {
+                        editor.input = None;
+                        let path = editor.project_path.clone();
+                        self.env_overrides
+                            .entry(path)
+                            .or_default()
+                            .insert(name, Some(value));
+                    }
+                    None => self.log_warn("Expected NAME=value"),
+                },
+                _ => {
+                    input.handle_key(key);
+                }
+            }
+            return;
+        }
+
+        let selected = editor.state.selected().and_then(|i| vars.get(i));
+        match key.code {
+            KeyCode::Esc => self.env_editor = None,
+            KeyCode::Up | KeyCode::Char('k') => editor.state.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => {
+                let last = vars.len().saturating_sub(1);
+                let next = editor.state.selected().map_or(0, |i| (i + 1).min(last));
+                editor.state.select(Some(next));
+            }
+            KeyCode::Char('a') => editor.input = Some(TextInput::default()),
+            KeyCode::Enter | KeyCode::Char('e') => {
+                if let Some(var) = selected {
+                    let value = var.value.as_deref().unwrap_or_default();
+                    editor.input = Some(TextInput::new(format!("{}={}", var.name, value)));
+                }
+            }
+            KeyCode::Char('d') | KeyCode::Delete => {
+                let Some(var) = selected else {
+                    return;
+                };
+                let overrides = self
+                    .env_overrides
+                    .entry(editor.project_path.clone())
+                    .or_default();
+                match var.source {
+                    // Falls back to the config or profile value, if any
+                    EnvSource::Session => {
+                        overrides.remove(&var.name);
+                    }
+                    EnvSource::Config => {
+                        overrides.insert(var.name.clone(), None);
+                    }
+                    EnvSource::Profile => self.log_warn(format!(
+                        "{} comes from the launch profile, edit launchSettings.json to remove it",
+                        var.name
+                    )),
+                }
+            }
+            KeyCode::Char('s') => self.save_env_overrides(),
+            _ => {}
+        }
+    }
+
+    /// Writes the session overrides of the open editor's project to the config file.
+    fn save_env_overrides(&mut self) {
+        let Some(editor) = &self.env_editor else {
+            return;
+        };
+        let project = editor.project.clone();
+        let Some(overrides) = self.env_overrides.remove(&editor.project_path) else {
+            return;
+        };
+        let mut vars = self.config.env.get(&project).cloned().unwrap_or_default();
+        for (name, value) in &overrides {
+            match value {
+                Some(value) => vars.insert(name.clone(), value.clone()),
+                None => vars.remove(name),
+            };
+        }
+
+        let saved = match Config::path() {
+            Some(path) => Config::save_env(&path, &project, &vars),
+            None => Err(AppError::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                "no config directory",
+            ))),
+        };
+        match saved {
+            Ok(()) => {
+                let message = format!("Saved the environment of {} to the config", project);
+                self.status.flash(message.clone());
+                self.log_info(message);
+                self.config.env.insert(project, vars);
+            }
+            Err(e) => {
+                // Keep the overrides so nothing typed is lost
+                let path = self.env_editor.as_ref().map(|e| e.project_path.clone());
+                self.env_overrides
+                    .extend(path.map(|path| (path, overrides)));
+                self.status
+                    .flash_error(format!("Failed to save environment: {}", e));
+                self.log_error(format!("Failed to save environment of {}: {}", project, e));
+            }
+        }
+    }
+
+    fn set_root(&mut self, root: PathBuf) {
+        if let Some(mut scan) = self.scan.take() {
+            scan.cancel();
+        }
+
+        self.log_info(format!("Scanning {}...", root.display()));
+        self.roots = vec![root];
+        self.sln_files.clear();
+        self.rescan_results = None;
+        self.sln_state.select(Some(0));
+        self.scan = Some(self.start_scan());
+    }
+
+    fn start_scan(&self) -> Scan {
+        let ignore = if self.show_hidden {
+            IgnoreList::default()
+        } else {
+            self.ignore.clone()
+        };
+        Scan::start(self.roots.clone(), self.config.exclude_dirs.clone(), ignore)
+    }
+
+    /// Asks for a pattern to hide the highlighted solution by, or shows it
+    /// again when it is hidden already.
+    fn hide_selected_solution(&mut self) {
+        let Some(path) = self.highlighted_solution() else {
+            return;
+        };
+        let matching: Vec<String> = self
+            .ignore
+            .matching(Path::new(&path))
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        if matching.is_empty() {
+            self.hide_input = Some(TextInput::new(path));
+            return;
+        }
+
+        let mut patterns = self.config.ignore.clone();
+        patterns.retain(|pattern| !matching.contains(pattern));
+        if self.save_ignore(patterns) {
+            let message = format!(
+                "Showing {} again, dropped {} from the ignore list",
+                solution_name(&path),
+                matching.join(", ")
+            );
+            self.status.flash(message.clone());
+            self.log_info(message);
+        }
+    }
+
+    fn handle_hide_input_key(&mut self, key: KeyEvent) {
+        let Some(input) = self.hide_input.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.hide_input = None,
+            KeyCode::Enter => {
+                let pattern = input.value.trim().to_string();
+                self.hide_input = None;
+                if pattern.is_empty() || self.config.ignore.contains(&pattern) {
+                    return;
+                }
+                let mut patterns = self.config.ignore.clone();
+                patterns.push(pattern.clone());
+                if self.save_ignore(patterns) {
+                    let before = self.sln_files.len();
+                    if !self.show_hidden {
+                        self.drop_hidden_solutions();
+                    }
+                    let message = format!(
+                        "Hid {} solutions matching {}",
+                        before - self.sln_files.len(),
+                        pattern
+                    );
+                    self.status.flash(message.clone());
+                    self.log_info(message);
+                }
+            }
+            _ => {
+                input.handle_key(key);
+            }
+        }
+    }
+
+    /// Writes the ignore patterns to the config and goes by them from now on.
+    fn save_ignore(&mut self, patterns: Vec<String>) -> bool {
+        let saved = match Config::path() {
+            Some(path) => Config::save_ignore(&path, &patterns),
+            None => Err(AppError::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                "no config directory",
+            ))),
+        };
+        match saved {
+            Ok(()) => {
+                self.ignore = IgnoreList::new(&patterns);
+                self.config.ignore = patterns;
+                true
+            }
+            Err(e) => {
+                self.status
+                    .flash_error(format!("Failed to save the ignore list: {}", e));
+                self.log_error(format!("Failed to save the ignore list: {}", e));
+                false
+            }
+        }
+    }
+
+    /// Takes the hidden solutions out of the list, but for pinned ones.
+    fn drop_hidden_solutions(&mut self) {
+        let highlighted = self.highlighted_solution();
+        let mut sln_files = std::mem::take(&mut self.sln_files);
+        sln_files.retain(|sln| {
+            self.pin_position(&sln.path).is_some() || !self.ignore.is_ignored(Path::new(&sln.path))
+        });
+        self.sln_files = sln_files;
+        if !highlighted.is_some_and(|path| self.highlight_solution(&path)) {
+            self.clamp_selection();
+        }
+    }
+
+    fn rescan(&mut self) {
+        if self.scan.is_some() {
+            return;
+        }
+
+        self.log_info("Rescanning for solutions...");
+        self.rescan_results = Some(Vec::new());
+        self.scan = Some(self.start_scan());
+    }
+
+    fn solution_sort(&self) -> SolutionSort {
+        self.state.solution_sort.unwrap_or(self.config.sort)
+    }
+
+    fn compare_solutions(&self, a: &SolutionFile, b: &SolutionFile) -> Ordering {
+        let by_name = || {
+            solution_name(&a.path)
+                .to_lowercase()
+                .cmp(&solution_name(&b.path).to_lowercase())
+                .then_with(|| a.path.cmp(&b.path))
+        };
+        match self.solution_sort() {
+            SolutionSort::Name => by_name(),
+            SolutionSort::Path => a
+                .path
+                .to_lowercase()
+                .cmp(&b.path.to_lowercase())
+                .then_with(|| a.path.cmp(&b.path)),
+            // The newest first, and those never run last
+            SolutionSort::Modified => b.modified.cmp(&a.modified).then_with(by_name),
+            SolutionSort::Recent => {
+                let last_run = |sln: &SolutionFile| self.state.last_runs.get(&sln.path).copied();
+                last_run(b).cmp(&last_run(a)).then_with(by_name)
+            }
+        }
+    }
+
+    /// Puts the solutions in the chosen order, keeping the highlighted one highlighted.
+    fn sort_solutions(&mut self) {
+        let highlighted = self.highlighted_solution();
+        let mut sln_files = std::mem::take(&mut self.sln_files);
+        sln_files.sort_by(|a, b| self.compare_solutions(a, b));
+        self.sln_files = sln_files;
+        if let Some(path) = highlighted {
+            self.highlight_solution(&path);
+        }
+    }
+
+    /// Where the solution is among the pinned ones, unless it isn't pinned.
+    fn pin_position(&self, path: &str) -> Option<usize> {
+        self.state.pinned.iter().position(|pinned| pinned == path)
+    }
+
+    /// Pins the highlighted solution to the top of the list, or unpins it,
+    /// dropping it from the list when its file is gone.
+    fn toggle_pin(&mut self) {
+        let Some(index) = self.selected_index() else {
+            return;
+        };
+        let path = self.sln_files[index].path.clone();
+        let name = solution_name(&path);
+        match self.pin_position(&path) {
+            Some(position) => {
+                self.state.pinned.remove(position);
+                if !Path::new(&path).is_file() {
+                    self.sln_files.remove(index);
+                }
+                self.status.flash(format!("Unpinned {}", name));
+            }
+            None => {
+                self.state.pinned.push(path.clone());
+                self.status.flash(format!("Pinned {}", name));
+            }
+        }
+        self.save_state();
+        if !self.highlight_solution(&path) {
+            self.clamp_selection();
+        }
+    }
+
+    /// Path of the solution highlighted in the solution list.
+    fn highlighted_solution(&self) -> Option<String> {
+        self.rows(View::Solutions)
+            .get(self.sln_state.selected().unwrap_or(0))
+            .and_then(ListRow::item)
+            .and_then(|i| self.sln_files.get(i))
+            .map(|sln| sln.path.clone())
+    }
+
+    /// Highlights the solution in the solution list, unless it is gone.
+    fn highlight_solution(&mut self, path: &str) -> bool {
+        let row = self
+            .sln_files
+            .iter()
+            .position(|sln| sln.path == path)
+            .and_then(|index| {
+                self.rows(View::Solutions)
+                    .iter()
+                    .position(|row| row.item() == Some(index))
+            });
+        if let Some(row) = row {
+            self.sln_state.select(Some(row));
+        }
+        row.is_some()
+    }
+
+    fn apply_rescan(&mut self, mut results: Vec<SolutionFile>) {
+        for path in &self.state.pinned {
+            if !results.iter().any(|sln| &sln.path == path) {
+                results.push(pinned_solution(path));
+            }
+        }
+        let old: HashSet<&str> = self.sln_files.iter().map(|s| s.path.as_str()).collect();
+        let new: HashSet<&str> = results.iter().map(|s| s.path.as_str()).collect();
+        let added = new.difference(&old).count();
+        let removed = old.difference(&new).count();
+
+        let previous_index = self.sln_state.selected().unwrap_or(0);
+        let selected_path = self.highlighted_solution();
+
+        results.sort_by(|a, b| self.compare_solutions(a, b));
+        self.sln_files = results;
+        // The scan can finish after the user moved on to the project list
+        if !selected_path.is_some_and(|path| self.highlight_solution(&path)) {
+            self.sln_state.select(Some(previous_index));
+        }
+        self.clamp_selection();
+
+        self.log_info(format!(
+            "Rescan complete: {} solutions ({} added, {} removed)",
+            self.sln_files.len(),
+            added,
+            removed
+        ));
+    }
+
+    fn poll_scan(&mut self) {
+        let Some(scan) = self.scan.as_mut() else {
+            return;
+        };
+
+        let events = scan.drain();
+        let started = scan.started;
+        for event in events {
+            match event {
+                ScanEvent::Found(sln) => match self.rescan_results.as_mut() {
+                    Some(results) => results.push(sln),
+                    None => {
+                        // A pinned solution is listed already
+                        if let Some(listed) = self
+                            .sln_files
+                            .iter_mut()
+                            .find(|listed| listed.path == sln.path)
+                        {
+                            *listed = sln;
+                            continue;
+                        }
+                        // Solutions turn up in whatever order the file system
+                        // lists them, so each goes where the order puts it
+                        let is_last_used = self.preselect.as_deref() == Some(sln.path.as_str());
+                        let highlighted = self.highlighted_solution().filter(|path| {
+                            self.sln_state.selected().unwrap_or(0) > 0
+                                || self.state.last_solution.as_ref() == Some(path)
+                        });
+                        let path = sln.path.clone();
+                        let at = self
+                            .sln_files
+                            .partition_point(|other| self.compare_solutions(other, &sln).is_le());
+                        self.sln_files.insert(at, sln);
+                        if is_last_used && self.view == View::Solutions {
+                            self.preselect = None;
+                            self.highlight_solution(&path);
+                        } else if let Some(highlighted) = highlighted {
+                            self.highlight_solution(&highlighted);
+                        }
+                    }
+                },
+                ScanEvent::Done => {
+                    let elapsed = started.elapsed();
+                    self.scan = None;
+                    self.preselect = None;
+                    if let Some(results) = self.rescan_results.take() {
+                        self.apply_rescan(results);
+                    } else if self.sln_files.is_empty() {
+                        let root = Self::roots_label(&self.roots);
+                        self.log_warn(AppError::NoSolutionsFound { root }.to_string());
+                    } else {
+                        self.log_info(format!(
+                            "Found {} solutions in {:.1}s",
+                            self.sln_files.len(),
+                            elapsed.as_secs_f32()
+                        ));
+                    }
+                    return;
+                }
+            }
+        }
+    }
+
+    pub fn log_info(&mut self, message: impl Into<String>) {
+        self.log(Level::Info, message);
+    }
+
+    pub fn log_warn(&mut self, message: impl Into<String>) {
+        self.log(Level::Warn, message);
+    }
+
+    pub fn log_error(&mut self, message: impl Into<String>) {
+        self.log(Level::Error, message);
+    }
+
+    /// Logs a line of build output, at error level when it reports an error.
+    fn log_output(&mut self, line: OutputLine) {
+        let level = if line.is_error() {
+            Level::Error
+        } else {
+            Level::Info
+        };
+        self.log(level, line.text);
+    }
+
+    fn log(&mut self, level: Level, message: impl Into<String>) {
+        self.logs.push(LogEntry::new(level, message));
+    }
+
+    /// Scrolls the log panel by `delta` lines; positive values go back in history.
+    fn scroll_logs(&mut self, delta: isize) {
+        let page = self.log_page();
+        self.logs.scroll_by(delta, page);
+    }
+
+    fn draw_solution_list(&mut self, f: &mut ratatui::Frame, area: Rect) {
+        let rows: Vec<(usize, Vec<usize>)> = self
+            .visible_rows()
+            .into_iter()
+            .filter_map(|row| Some((row.item()?, row.positions)))
+            .collect();
+        let git: Vec<Option<GitStatus>> = rows
+            .iter()
+            .map(|(index, _)| {
+                let dir = Path::new(&self.sln_files[*index].path)
+                    .parent()
+                    .unwrap_or(Path::new("."));
+                self.git.get(dir).cloned()
+            })
+            .collect();
+        let items: Vec<ListItem> = rows
+            .into_iter()
+            .zip(git)
+            .map(|((index, positions), git)| {
+                let sln = &self.sln_files[index];
+                let pinned = self.pin_position(&sln.path).is_some();
+                let missing = pinned && !Path::new(&sln.path).is_file();
+                let hidden = self.show_hidden && self.ignore.is_ignored(Path::new(&sln.path));
+                let mut spans = Vec::new();
+                if !self.state.pinned.is_empty() {
+                    let star = if pinned { "★ " } else { "  " };
+                    spans.push(Span::styled(star, Style::default().fg(Color::Yellow)));
+                }
+                let name_style = if missing || hidden {
+                    Style::default()
+                        .fg(Color::DarkGray)
+                        .add_modifier(Modifier::DIM)
+                } else {
+                    Style::default().fg(Color::Yellow)
+                };
+                spans.extend(highlight_matches(
+                    &solution_name(&sln.path),
+                    &positions,
+                    name_style,
+                ));
+                if missing {
+                    spans.push(Span::styled(" (missing)", name_style));
+                }
+                if hidden {
+                    spans.push(Span::styled(" hidden", name_style));
+                }
+                if let Some(git) = git {
+                    let dirty = if git.dirty == Some(true) { "*" } else { "" };
+                    spans.push(Span::styled(
+                        format!(" {}{}", git.branch, dirty),
+                        Style::default()
+                            .fg(Color::DarkGray)
+                            .add_modifier(Modifier::DIM),
+                    ));
+                }
+                let running = self
+                    .processes
+                    .iter()
+                    .any(|p| p.status.is_none() && p.solution == sln.path);
+                if running {
+                    spans.push(Span::styled(" ●", Style::default().fg(Color::Green)));
+                }
+                if is_solution_filter(&sln.path) {
+                    spans.push(Span::styled(" filter", Style::default().fg(Color::Cyan)));
+                }
+                spans.push(Span::styled(
+                    format!("  {}", sln.root.display()),
+                    Style::default().fg(Color::DarkGray),
+                ));
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let title = match &self.scan {
+            Some(scan) => format!(
+                " {}… {} {} found in {} (q: quit) ",
+                if self.rescan_results.is_some() {
+                    "Rescanning"
+                } else {
+                    "Scanning"
+                },
+                SPINNER[self.tick % SPINNER.len()],
+                scan.found,
+                Self::roots_label(&self.roots)
+            ),
+            None => format!(
+                " Solutions in {} by {} (↑/↓: navigate, Enter: select, /: filter, o: order, *: pin, h: hide, r: rescan, q: quit) ",
+                Self::roots_label(&self.roots),
+                self.solution_sort().label()
+            ),
+        };
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(Style::default().bg(Color::DarkGray))
+            .highlight_symbol("➤ ");
+
+        self.list_area = area;
+        self.list_height = area.height.saturating_sub(2) as usize;
+        f.render_stateful_widget(list, area, &mut self.sln_state);
+
+        if self.sln_files.is_empty() && self.scan.is_none() {
+            let placeholder = format!(
+                "No solutions found under {} — press r to rescan or e to edit the search root",
+                Self::roots_label(&self.roots)
+            );
+            let area = centered_rect(area, 80, 3);
+            f.render_widget(
+                Paragraph::new(placeholder)
+                    .alignment(Alignment::Center)
+                    .wrap(Wrap { trim: true })
+                    .style(Style::default().fg(Color::DarkGray)),
+                area,
+            );
+        }
+    }
+
+    fn draw_input_popup(f: &mut ratatui::Frame, title: &str, input: &TextInput) {
+        let area = centered_rect(f.area(), 70, 3);
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(input.value.as_str())
+                .block(Block::default().borders(Borders::ALL).title(title)),
+            area,
+        );
+        f.set_cursor_position((area.x + 1 + input.cursor() as u16, area.y + 1));
+    }
+
+    fn draw_env_editor(f: &mut ratatui::Frame, editor: &mut EnvEditor, vars: &[EnvVar]) {
+        let dim = Style::default().fg(Color::DarkGray);
+        let items: Vec<ListItem> = vars
+            .iter()
+            .map(|var| {
+                let value = match &var.value {
+                    None => Span::styled(" (removed)", dim.add_modifier(Modifier::CROSSED_OUT)),
+                    Some(_) if var.is_secret() => Span::raw("=••••••••"),
+                    Some(value) => Span::raw(format!("={}", value)),
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(var.name.clone(), Style::default().fg(Color::Yellow)),
+                    value,
+                    Span::styled(format!("  {}", var.source.label()), dim),
+                ]))
+            })
+            .collect();
+        let empty = items.is_empty();
+
+        let title = match &editor.profile {
+            Some(profile) => format!(" Environment for {} (profile {}) ", editor.project, profile),
+            None => format!(" Environment for {} ", editor.project),
+        };
+        let height = (vars.len().max(1) as u16 + 2).min(f.area().height.saturating_sub(2));
+        let area = centered_rect(f.area(), 70, height);
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(title)
+                    .title_bottom(" a: add, e: edit, d: delete, s: save to config, Esc: close "),
+            )
+            .highlight_style(Style::default().bg(Color::DarkGray))
+            .highlight_symbol("➤ ");
+
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(list, area, &mut editor.state);
+        if empty {
+            f.render_widget(
+                Paragraph::new("No variables — press a to add one").style(dim),
+                area.inner(Margin::new(2, 1)),
+            );
+        }
+        if let Some(input) = &editor.input {
+            Self::draw_input_popup(f, " NAME=value (Enter: set, Esc: cancel) ", input);
+        }
+    }
+
+    fn draw_port_prompt(f: &mut ratatui::Frame, prompt: &mut PortPrompt) {
+        let conflicts: Vec<Line> = prompt
+            .conflicts
+            .iter()
+            .map(|conflict| Line::styled(conflict.describe(), Style::default().fg(Color::Red)))
+            .collect();
+        let items: Vec<ListItem> = prompt
+            .choices
+            .iter()
+            .map(|choice| ListItem::new(choice.label()))
+            .collect();
+
+        let height = (conflicts.len() + items.len() + 3) as u16;
+        let area = centered_rect(f.area(), 60, height);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" Port in use — {} ", prompt.target.project));
+        let [message_area, list_area] = Layout::vertical([
+            Constraint::Length(conflicts.len() as u16 + 1),
+            Constraint::Min(0),
+        ])
+        .areas(block.inner(area));
+
+        f.render_widget(Clear, area);
+        f.render_widget(block, area);
+        f.render_widget(Paragraph::new(conflicts), message_area);
+        f.render_stateful_widget(
+            List::new(items)
+                .highlight_style(Style::default().bg(Color::DarkGray))
+                .highlight_symbol("➤ "),
+            list_area,
+            &mut prompt.state,
+        );
+    }
+
+    fn draw_dev_cert_prompt(f: &mut ratatui::Frame, prompt: &mut DevCertPrompt) {
+        let mut message = vec![
+            Line::styled(
+                "No valid ASP.NET Core HTTPS development certificate is installed,",
+                Style::default().fg(Color::Red),
+            ),
+            Line::styled(
+                format!("so Kestrel cannot listen on {}", prompt.urls),
+                Style::default().fg(Color::Red),
+            ),
+        ];
+        if let Some(hint) = devcerts::elevation_hint() {
+            message.push(Line::styled(
+                format!("Trusting one: {}", hint),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        let items: Vec<ListItem> = DevCertChoice::ALL
+            .iter()
+            .map(|choice| ListItem::new(choice.label()))
+            .collect();
+
+        let height = (message.len() + items.len() + 3) as u16;
+        let area = centered_rect(f.area(), 80, height);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" No HTTPS certificate — {} ", prompt.project));
+        let [message_area, list_area] = Layout::vertical([
+            Constraint::Length(message.len() as u16 + 1),
+            Constraint::Min(0),
+        ])
+        .areas(block.inner(area));
+
+        f.render_widget(Clear, area);
+        f.render_widget(block, area);
+        f.render_widget(Paragraph::new(message), message_area);
+        f.render_stateful_widget(
+            List::new(items)
+                .highlight_style(Style::default().bg(Color::DarkGray))
+                .highlight_symbol("➤ "),
+            list_area,
+            &mut prompt.state,
+        );
+    }
+
+    fn draw_publish_prompt(f: &mut ratatui::Frame, prompt: &PublishPrompt) {
+        let area = centered_rect(f.area(), 60, 5);
+        let block = Block::default().borders(Borders::ALL).title(format!(
+            " Publish {} (↑/↓: option, ←/→: change, Enter: publish, Esc: cancel) ",
+            prompt.target.project
+        ));
+        let inner = block.inner(area);
+        let lines: Vec<Line> = PublishField::ALL
+            .iter()
+            .map(|&field| {
+                let value = match field {
+                    PublishField::Configuration => prompt.configuration.clone(),
+                    PublishField::Runtime => prompt.runtime_label(),
+                    PublishField::SelfContained => {
+                        if prompt.self_contained { "yes" } else { "no" }.to_string()
+                    }
+                };
+                let selected = field == prompt.field;
+                let style = if selected {
+                    Style::default().bg(Color::DarkGray)
+                } else {
+                    Style::default()
+                };
+                Line::from(vec![
+                    Span::raw(if selected { "➤ " } else { "  " }),
+                    Span::styled(format!("{:<16}", field.label()), style.fg(Color::Yellow)),
+                    Span::styled(format!("◂ {} ▸", value), style),
+                ])
+            })
+            .collect();
+
+        f.render_widget(Clear, area);
+        f.render_widget(block, area);
+        f.render_widget(Paragraph::new(lines), inner);
+        if prompt.field == PublishField::Runtime && prompt.runtime > RUNTIMES.len() {
+            // After "➤ ", the label and "◂ other: "
+            let x = inner.x + 2 + 16 + 9 + prompt.custom_runtime.cursor() as u16;
+            f.set_cursor_position((x, inner.y + 1));
+        }
+    }
+
+    fn draw_restore_menu(f: &mut ratatui::Frame, menu: &mut RestoreMenu) {
+        let items: Vec<ListItem> = RestoreChoice::ALL
+            .iter()
+            .map(|choice| ListItem::new(choice.label(&menu.target.project)))
+            .collect();
+        let area = centered_rect(f.area(), 50, items.len() as u16 + 2);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Restore (↑/↓: choose, Enter: confirm, Esc: cancel) ");
+
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(
+            List::new(items)
+                .block(block)
+                .highlight_style(Style::default().bg(Color::DarkGray))
+                .highlight_symbol("➤ "),
+            area,
+            &mut menu.state,
+        );
+    }
+
+    fn draw_clean_menu(f: &mut ratatui::Frame, menu: &mut CleanMenu) {
+        let items: Vec<ListItem> = CleanChoice::ALL
+            .iter()
+            .map(|choice| ListItem::new(choice.label(&menu.target.project)))
+            .collect();
+        let area = centered_rect(f.area(), 50, items.len() as u16 + 2);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Clean (↑/↓: choose, Enter: confirm, Esc: cancel) ");
+
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(
+            List::new(items)
+                .block(block)
+                .highlight_style(Style::default().bg(Color::DarkGray))
+                .highlight_symbol("➤ "),
+            area,
+            &mut menu.state,
+        );
+    }
+
+    fn draw_docker_menu(f: &mut ratatui::Frame, menu: &mut DockerMenu) {
+        let items: Vec<ListItem> = DockerChoice::ALL
+            .iter()
+            .map(|choice| ListItem::new(choice.label(&menu.image)))
+            .collect();
+        let area = centered_rect(f.area(), 50, items.len() as u16 + 2);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Docker (↑/↓: choose, Enter: confirm, Esc: cancel) ");
+
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(
+            List::new(items)
+                .block(block)
+                .highlight_style(Style::default().bg(Color::DarkGray))
+                .highlight_symbol("➤ "),
+            area,
+            &mut menu.state,
+        );
+    }
+
+    fn draw_compose_panel(f: &mut ratatui::Frame, panel: &mut ComposePanel) {
+        let width = panel.services.iter().map(|s| s.len()).max().unwrap_or(0);
+        let items: Vec<ListItem> = panel
+            .services
+            .iter()
+            .map(|service| {
+                let mark = if panel.marked.contains(service) {
+                    "✓ "
+                } else {
+                    "  "
+                };
+                let status = match panel.status.status(service) {
+                    Some(status) => {
+                        Span::styled(status.label(), Style::default().fg(status.color()))
+                    }
+                    None if panel.status.statuses.is_some() => {
+                        Span::styled("not created", Style::default().fg(Color::DarkGray))
+                    }
+                    None => Span::styled("…", Style::default().fg(Color::DarkGray)),
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(mark, Style::default().fg(Color::Green)),
+                    Span::styled(
+                        format!("{:width$}  ", service, width = width),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    status,
+                ]))
+            })
+            .collect();
+        let height = items.len() as u16 + 2 + panel.status.failed as u16;
+        let area = centered_rect(f.area(), 60, height);
+        let block = Block::default().borders(Borders::ALL).title(format!(
+            " {} (Space: mark, u: up, d: down, Esc: close) ",
+            solution_name(&panel.file.to_string_lossy())
+        ));
+        let [list_area, message_area] = Layout::vertical([
+            Constraint::Length(panel.services.len() as u16),
+            Constraint::Min(0),
+        ])
+        .areas(block.inner(area));
+
+        f.render_widget(Clear, area);
+        f.render_widget(block, area);
+        f.render_stateful_widget(
+            List::new(items)
+                .highlight_style(Style::default().bg(Color::DarkGray))
+                .highlight_symbol("➤ "),
+            list_area,
+            &mut panel.state,
+        );
+        if panel.status.failed {
+            f.render_widget(
+                Paragraph::new(Span::styled(
+                    "docker compose ps failed",
+                    Style::default().fg(Color::Red),
+                )),
+                message_area,
+            );
+        }
+    }
+
+    fn draw_migrations_panel(
+        f: &mut ratatui::Frame,
+        panel: &mut MigrationsPanel,
+        running: Option<&EfStep>,
+    ) {
+        let dim = Style::default().fg(Color::DarkGray);
+        let mut items = vec![ListItem::new(Span::styled(
+            "  (none: roll back all migrations)",
+            dim,
+        ))];
+        if let MigrationsState::Listed(migrations) = &panel.migrations {
+            items.extend(migrations.iter().map(|migration| {
+                let (mark, label, color) = match migration.applied {
+                    Some(true) => ("✓ ", "applied", Color::Green),
+                    Some(false) => ("○ ", "pending", Color::Yellow),
+                    None => ("? ", "database unreachable", Color::DarkGray),
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(mark, Style::default().fg(color)),
+                    Span::raw(migration.id.clone()),
+                    Span::styled(format!("  {}", label), Style::default().fg(color)),
+                ]))
+            }));
+        }
+        let message = match (running, &panel.migrations) {
+            (Some(step), _) => Line::styled(
+                match step {
+                    EfStep::List => "Listing the migrations…".to_string(),
+                    EfStep::Update(None) => "Running dotnet ef database update…".to_string(),
+                    EfStep::Update(Some(target)) => {
+                        format!("Running dotnet ef database update {}…", target)
+                    }
+                    EfStep::Add(name) => format!("Running dotnet ef migrations add {}…", name),
+                    EfStep::Install => format!("Running {}…", INSTALL_COMMAND),
+                },
+                Style::default().fg(Color::Yellow),
+            ),
+            (None, _) if panel.confirm.is_some() => Line::styled(
+                format!(
+                    "Roll the database back to {}? (y: yes, n: no)",
+                    panel.confirm.as_deref().unwrap_or_default()
+                ),
+                Style::default().fg(Color::Red),
+            ),
+            (None, MigrationsState::ToolMissing) => Line::styled(
+                format!("dotnet-ef is not installed. i: {}", INSTALL_COMMAND),
+                Style::default().fg(Color::Red),
+            ),
+            (None, MigrationsState::Failed(error)) => {
+                Line::styled(error.as_str(), Style::default().fg(Color::Red))
+            }
+            (None, MigrationsState::Listed(migrations)) if migrations.is_empty() => {
+                Line::styled("No migrations yet, a: add the first", dim)
+            }
+            (None, _) => Line::default(),
+        };
+
+        let height = items.len() as u16 + 4;
+        let area = centered_rect(f.area(), 90, height);
+        let block = Block::default().borders(Borders::ALL).title(format!(
+            " Migrations of {} (u: update, a: add, Enter: roll back to, r: refresh, Esc: close) ",
+            panel.target.project
+        ));
+        let [list_area, message_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(2)]).areas(block.inner(area));
+
+        f.render_widget(Clear, area);
+        f.render_widget(block, area);
+        f.render_stateful_widget(
+            List::new(items)
+                .highlight_style(Style::default().bg(Color::DarkGray))
+                .highlight_symbol("➤ "),
+            list_area,
+            &mut panel.state,
+        );
+        f.render_widget(Paragraph::new(vec![Line::default(), message]), message_area);
+        if let Some(input) = &panel.name {
+            Self::draw_input_popup(f, " Migration name (Enter: add, Esc: cancel) ", input);
+        }
+    }
+
+    fn draw_secrets_view(
+        f: &mut ratatui::Frame,
+        view: &mut SecretsView,
+        running: Option<&SecretsStep>,
+    ) {
+        let dim = Style::default().fg(Color::DarkGray);
+        let listed = match &view.secrets {
+            SecretsState::Listed(secrets) => secrets.as_slice(),
+            _ => &[],
+        };
+        let items: Vec<ListItem> = listed
+            .iter()
+            .map(|secret| {
+                let value = if view.revealed {
+                    format!(" = {}", secret.value)
+                } else {
+                    " = ••••••••".to_string()
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(secret.key.clone(), Style::default().fg(Color::Yellow)),
+                    Span::raw(value),
+                ]))
+            })
+            .collect();
+        let message = match (running, &view.secrets) {
+            (Some(step), _) => Line::styled(
+                match step {
+                    SecretsStep::List => "Listing the user secrets…".to_string(),
+                    SecretsStep::Set { key, .. } => format!("Setting {}…", key),
+                    SecretsStep::Remove(key) => format!("Removing {}…", key),
+                    SecretsStep::Init => "Running dotnet user-secrets init…".to_string(),
+                },
+                Style::default().fg(Color::Yellow),
+            ),
+            (None, _) if view.confirm.is_some() => Line::styled(
+                format!(
+                    "Remove {}? (y: yes, n: no)",
+                    view.confirm.as_deref().unwrap_or_default()
+                ),
+                Style::default().fg(Color::Red),
+            ),
+            (None, SecretsState::NoId) => Line::styled(
+                "The project has no UserSecretsId. i: run dotnet user-secrets init",
+                Style::default().fg(Color::Yellow),
+            ),
+            (None, SecretsState::Failed(error)) => {
+                Line::styled(error.as_str(), Style::default().fg(Color::Red))
+            }
+            (None, SecretsState::Listed(secrets)) if secrets.is_empty() => {
+                Line::styled("No secrets yet, a: add one", dim)
+            }
+            (None, _) => Line::default(),
+        };
+
+        let height = (items.len() as u16 + 4).min(f.area().height.saturating_sub(2));
+        let area = centered_rect(f.area(), 80, height);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" User secrets of {} ", view.target.project))
+            .title_bottom(" a: add, e: edit, d: remove, v: reveal, r: refresh, Esc: close ");
+        let [list_area, message_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(2)]).areas(block.inner(area));
+
+        f.render_widget(Clear, area);
+        f.render_widget(block, area);
+        f.render_stateful_widget(
+            List::new(items)
+                .highlight_style(Style::default().bg(Color::DarkGray))
+                .highlight_symbol("➤ "),
+            list_area,
+            &mut view.state,
+        );
+        f.render_widget(Paragraph::new(vec![Line::default(), message]), message_area);
+        if let Some(input) = &view.input {
+            Self::draw_input_popup(f, " Key=value (Enter: set, Esc: cancel) ", input);
+        }
+    }
+
+    fn draw_packages_view(
+        f: &mut ratatui::Frame,
+        view: &mut PackagesView,
+        running: Option<Report>,
+        sln: &str,
+    ) {
+        let dim = Style::default().fg(Color::DarkGray);
+        let rows = view.rows();
+        let width = |column: fn(&Package) -> usize, title: &str| {
+            rows.iter().map(column).max().unwrap_or(0).max(title.len())
+        };
+        let project_width = width(|p| p.project.len(), "Project");
+        let id_width = width(|p| p.id.len(), "Package");
+        let requested_width = width(|p| p.requested.len(), "Requested");
+        let resolved_width = width(|p| p.resolved.len(), "Resolved");
+        let latest_width = width(|p| p.latest.as_ref().map_or(0, String::len), "Latest");
+
+        let project = |text: &str| {
+            if view.solution {
+                format!("{:w$}  ", text, w = project_width)
+            } else {
+                String::new()
+            }
+        };
+        let header = Line::styled(
+            format!(
+                "  {}{:id$}  {:req$}  {:res$}  {:lat$}  Severity",
+                project("Project"),
+                "Package",
+                "Requested",
+                "Resolved",
+                "Latest",
+                id = id_width,
+                req = requested_width,
+                res = resolved_width,
+                lat = latest_width,
+            ),
+            dim.add_modifier(Modifier::BOLD),
+        );
+        let items: Vec<ListItem> = rows
+            .iter()
+            .map(|package| {
+                let latest_color = if package.major_behind() {
+                    Color::Red
+                } else {
+                    Color::Yellow
+                };
+                let severity = match &package.severity {
+                    Some(severity) => Span::styled(
+                        severity.clone(),
+                        Style::default().fg(packages::severity_color(severity)),
+                    ),
+                    None => Span::raw(""),
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(project(&package.project), dim),
+                    Span::raw(format!("{:w$}  ", package.id, w = id_width)),
+                    Span::raw(format!("{:w$}  ", package.requested, w = requested_width)),
+                    Span::raw(format!("{:w$}  ", package.resolved, w = resolved_width)),
+                    Span::styled(
+                        format!(
+                            "{:w$}  ",
+                            package.latest.as_deref().unwrap_or_default(),
+                            w = latest_width
+                        ),
+                        Style::default().fg(latest_color),
+                    ),
+                    severity,
+                ]))
+            })
+            .collect();
+        let message = match (running, &view.error) {
+            (Some(Report::Outdated), _) => Line::styled(
+                "Running dotnet list package --outdated…",
+                Style::default().fg(Color::Yellow),
+            ),
+            (Some(Report::Vulnerable), _) => Line::styled(
+                "Running dotnet list package --vulnerable…",
+                Style::default().fg(Color::Yellow),
+            ),
+            (None, Some(error)) => Line::styled(error.as_str(), Style::default().fg(Color::Red)),
+            (None, None) if rows.is_empty() => match view.filter {
+                PackageFilter::All => Line::styled("All packages are up to date", dim),
+                filter => Line::styled(format!("No packages {}", filter.label()), dim),
+            },
+            (None, None) => Line::default(),
+        };
+
+        let height = (items.len() as u16 + 5).min(f.area().height.saturating_sub(2));
+        let area = centered_rect(f.area(), 90, height);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(
+                " Packages of {} ({}) ",
+                view.name(sln),
+                view.filter.label()
+            ))
+            .title_bottom(" f: filter, s: project or solution, y: copy update, o: open advisory, r: refresh, Esc: close ");
+        let [header_area, list_area, message_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(2),
+        ])
+        .areas(block.inner(area));
+
+        f.render_widget(Clear, area);
+        f.render_widget(block, area);
+        f.render_widget(Paragraph::new(header), header_area);
+        f.render_stateful_widget(
+            List::new(items)
+                .highlight_style(Style::default().bg(Color::DarkGray))
+                .highlight_symbol("➤ "),
+            list_area,
+            &mut view.state,
+        );
+        f.render_widget(Paragraph::new(vec![Line::default(), message]), message_area);
+    }
+
+    fn draw_deep_clean_prompt(f: &mut ratatui::Frame, prompt: &mut DeepCleanPrompt, sln: &str) {
+        const SHOWN: usize = 10;
+        let root = Path::new(sln)
+            .parent()
+            .and_then(|root| root.canonicalize().ok())
+            .unwrap_or_default();
+        let mut dirs: Vec<Line> = prompt
+            .dirs
+            .iter()
+            .take(SHOWN)
+            .map(|dir| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{:>10}  ", format_size(dir.size)),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    Span::raw(
+                        dir.path
+                            .strip_prefix(&root)
+                            .unwrap_or(&dir.path)
+                            .display()
+                            .to_string(),
+                    ),
+                ])
+            })
+            .collect();
+        if prompt.dirs.len() > SHOWN {
+            dirs.push(Line::styled(
+                format!("  … and {} more", prompt.dirs.len() - SHOWN),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        let items = vec![ListItem::new("Delete them"), ListItem::new("Don't delete")];
+
+        let size: u64 = prompt.dirs.iter().map(|dir| dir.size).sum();
+        let height = (dirs.len() + items.len() + 3) as u16;
+        let area = centered_rect(f.area(), 60, height);
+        let block = Block::default().borders(Borders::ALL).title(format!(
+            " Delete bin/ and obj/ of {}, {} in {} directories? ",
+            prompt.name,
+            format_size(size),
+            prompt.dirs.len()
+        ));
+        let [message_area, list_area] = Layout::vertical([
+            Constraint::Length(dirs.len() as u16 + 1),
+            Constraint::Min(0),
+        ])
+        .areas(block.inner(area));
+
+        f.render_widget(Clear, area);
+        f.render_widget(block, area);
+        f.render_widget(Paragraph::new(dirs), message_area);
+        f.render_stateful_widget(
+            List::new(items)
+                .highlight_style(Style::default().bg(Color::DarkGray))
+                .highlight_symbol("➤ "),
+            list_area,
+            &mut prompt.state,
+        );
+    }
+
+    fn draw_vscode_prompt(f: &mut ratatui::Frame, prompt: &mut VsCodePrompt) {
+        let replaced: Vec<Line> = prompt
+            .replaced
+            .iter()
+            .map(|entry| Line::styled(entry.as_str(), Style::default().fg(Color::Yellow)))
+            .collect();
+        let items = vec![
+            ListItem::new("Replace them"),
+            ListItem::new("Don't replace"),
+        ];
+
+        let height = (replaced.len() + items.len() + 3) as u16;
+        let area = centered_rect(f.area(), 60, height);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Replace the existing VS Code configurations? ");
+        let [message_area, list_area] = Layout::vertical([
+            Constraint::Length(replaced.len() as u16 + 1),
+            Constraint::Min(0),
+        ])
+        .areas(block.inner(area));
+
+        f.render_widget(Clear, area);
+        f.render_widget(block, area);
+        f.render_widget(Paragraph::new(replaced), message_area);
+        f.render_stateful_widget(
+            List::new(items)
+                .highlight_style(Style::default().bg(Color::DarkGray))
+                .highlight_symbol("➤ "),
+            list_area,
+            &mut prompt.state,
+        );
+    }
+
+    fn draw_hook_prompt(f: &mut ratatui::Frame, prompt: &mut HookPrompt) {
+        let commands: Vec<Line> = prompt
+            .commands
+            .iter()
+            .map(|(stage, command)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{:<11} ", stage.label()),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(command.as_str(), Style::default().fg(Color::Yellow)),
+                ])
+            })
+            .collect();
+        let items = vec![ListItem::new("Run them"), ListItem::new("Don't run them")];
+
+        let height = (commands.len() + items.len() + 3) as u16;
+        let area = centered_rect(f.area(), 70, height);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" Run the hooks of {}? ", prompt.owner));
+        let [message_area, list_area] = Layout::vertical([
+            Constraint::Length(commands.len() as u16 + 1),
+            Constraint::Min(0),
+        ])
+        .areas(block.inner(area));
+
+        f.render_widget(Clear, area);
+        f.render_widget(block, area);
+        f.render_widget(Paragraph::new(commands), message_area);
+        f.render_stateful_widget(
+            List::new(items)
+                .highlight_style(Style::default().bg(Color::DarkGray))
+                .highlight_symbol("➤ "),
+            list_area,
+            &mut prompt.state,
+        );
+    }
+
+    fn draw_quit_prompt(f: &mut ratatui::Frame, prompt: &mut QuitPrompt) {
+        let processes: Vec<Line> = prompt
+            .processes
+            .iter()
+            .map(|process| Line::styled(process.as_str(), Style::default().fg(Color::Green)))
+            .collect();
+        let items: Vec<ListItem> = QuitChoice::ALL
+            .iter()
+            .map(|choice| ListItem::new(choice.label()))
+            .collect();
+
+        let height = (processes.len() + items.len() + 3) as u16;
+        let area = centered_rect(f.area(), 60, height);
+        let block = Block::default().borders(Borders::ALL).title(format!(
+            " Quit with {} still running? ",
+            if processes.len() == 1 {
+                "a process".to_string()
+            } else {
+                format!("{} processes", processes.len())
+            }
+        ));
+        let [message_area, list_area] = Layout::vertical([
+            Constraint::Length(processes.len() as u16 + 1),
+            Constraint::Min(0),
+        ])
+        .areas(block.inner(area));
+
+        f.render_widget(Clear, area);
+        f.render_widget(block, area);
+        f.render_widget(Paragraph::new(processes), message_area);
+        f.render_stateful_widget(
+            List::new(items)
+                .highlight_style(Style::default().bg(Color::DarkGray))
+                .highlight_symbol("➤ "),
+            list_area,
+            &mut prompt.state,
+        );
+    }
+
+    fn draw_group_picker(f: &mut ratatui::Frame, picker: &mut GroupPicker) {
+        let mut items: Vec<ListItem> = picker
+            .groups
+            .iter()
+            .map(|group| {
+                let mut spans = vec![
+                    Span::styled(group.name.as_str(), Style::default().fg(Color::Yellow)),
+                    Span::styled(
+                        format!("  {} projects", group.members),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ];
+                if group.missing > 0 {
+                    spans.push(Span::styled(
+                        format!(" ({} missing)", group.missing),
+                        Style::default().fg(Color::Red),
+                    ));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        if picker.save {
+            items.push(ListItem::new(Span::styled(
+                "+ Save marked projects as a group",
+                Style::default().fg(Color::Green),
+            )));
+        }
+        let area = centered_rect(f.area(), 50, items.len() as u16 + 2);
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" Groups (Enter: run, Esc: cancel) "),
+            )
+            .highlight_style(Style::default().bg(Color::DarkGray))
+            .highlight_symbol("➤ ");
+
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(list, area, &mut picker.state);
+    }
+
+    fn draw_url_picker(f: &mut ratatui::Frame, picker: &mut UrlPicker) {
+        let items: Vec<ListItem> = picker
+            .urls
+            .iter()
+            .map(|url| ListItem::new(url.as_str()))
+            .collect();
+        let area = centered_rect(f.area(), 50, items.len() as u16 + 2);
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" Open in browser (Enter: open, Esc: cancel) "),
+            )
+            .highlight_style(Style::default().bg(Color::DarkGray))
+            .highlight_symbol("➤ ");
+
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(list, area, &mut picker.state);
+    }
+
+    fn draw_ide_picker(f: &mut ratatui::Frame, picker: &mut IdePicker) {
+        let items: Vec<ListItem> = picker
+            .ides
+            .iter()
+            .map(|(name, template)| {
+                ListItem::new(Line::from(vec![
+                    Span::raw(name.as_str()),
+                    Span::styled(
+                        format!("  {}", template),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]))
+            })
+            .collect();
+        let area = centered_rect(f.area(), 50, items.len() as u16 + 2);
+        let name = picker
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(" Open {} in ", name)),
+            )
+            .highlight_style(Style::default().bg(Color::DarkGray))
+            .highlight_symbol("➤ ");
+
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(list, area, &mut picker.state);
+    }
+
+    fn draw_framework_picker(f: &mut ratatui::Frame, picker: &mut FrameworkPicker) {
+        let items: Vec<ListItem> = picker
+            .frameworks
+            .iter()
+            .map(|framework| ListItem::new(framework.as_str()))
+            .collect();
+        let area = centered_rect(f.area(), 40, items.len() as u16 + 2);
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(" Target framework for {} ", picker.target.project)),
+            )
+            .highlight_style(Style::default().bg(Color::DarkGray))
+            .highlight_symbol("➤ ");
+
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(list, area, &mut picker.state);
+    }
+
+    fn draw_sdk_picker(f: &mut ratatui::Frame, picker: &mut SdkPicker) {
+        let items: Vec<ListItem> = picker
+            .choices
+            .iter()
+            .map(|choice| ListItem::new(choice.label()))
+            .collect();
+        let area = centered_rect(f.area(), 60, items.len() as u16 + 2);
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(" SDK for {} ", picker.target.project)),
+            )
+            .highlight_style(Style::default().bg(Color::DarkGray))
+            .highlight_symbol("➤ ");
+
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(list, area, &mut picker.state);
+    }
+
+    fn draw_profile_picker(f: &mut ratatui::Frame, picker: &mut ProfilePicker) {
+        let mut items: Vec<ListItem> = picker
+            .profiles
+            .iter()
+            .map(|profile| {
+                let mut spans = vec![Span::raw(profile.name.clone())];
+                if let Some(url) = &profile.application_url {
+                    spans.push(Span::styled(
+                        format!("  {}", url),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                if !profile.is_supported() {
+                    spans = vec![Span::styled(
+                        format!(
+                            "{} (unsupported: {})",
+                            profile.name,
+                            profile.command_name.as_deref().unwrap_or("unknown")
+                        ),
+                        Style::default().fg(Color::DarkGray),
+                    )];
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        items.push(ListItem::new(Span::styled(
+            "(none — run without --launch-profile)",
+            Style::default().fg(Color::Gray),
+        )));
+
+        let details = match picker.state.selected().and_then(|i| picker.profiles.get(i)) {
+            Some(profile) => profile_details(profile),
+            None => vec![Line::styled(
+                "dotnet run without a launch profile",
+                Style::default().fg(Color::DarkGray),
+            )],
+        };
+        let list_height = items.len() as u16 + 2;
+        let height = (list_height + details.len() as u16 + 2).min(f.area().height);
+        let area = centered_rect(f.area(), 60, height);
+        let [list_area, details_area] =
+            Layout::vertical([Constraint::Length(list_height), Constraint::Min(0)]).areas(area);
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(" Launch profile for {} ", picker.target.project)),
+            )
+            .highlight_style(Style::default().bg(Color::DarkGray))
+            .highlight_symbol("➤ ");
+
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(list, list_area, &mut picker.state);
+        f.render_widget(
+            Paragraph::new(details)
+                .wrap(Wrap { trim: false })
+                .block(Block::default().borders(Borders::ALL).title(" Applies ")),
+            details_area,
+        );
+    }
+
+    fn draw_palette(f: &mut ratatui::Frame, palette: &mut Palette) {
+        let key_width = palette
+            .rows()
+            .map(|(binding, _)| binding.keys_label().chars().count())
+            .max()
+            .unwrap_or(0);
+        let items: Vec<ListItem> = palette
+            .rows()
+            .map(|(binding, positions)| {
+                let mut spans = vec![Span::styled(
+                    format!("{:<width$}  ", binding.keys_label(), width = key_width),
+                    Style::default().fg(Color::Cyan),
+                )];
+                spans.extend(highlight_matches(
+                    binding.description,
+                    positions,
+                    Style::default(),
+                ));
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let height = (items.len().max(1) as u16 + 4).min(f.area().height.saturating_sub(2));
+        let area = centered_rect(f.area(), 70, height);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Commands (Enter: run, Esc: close) ");
+        let inner = block.inner(area);
+        let [input_area, _, list_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+        ])
+        .areas(inner);
+
+        f.render_widget(Clear, area);
+        f.render_widget(block, area);
+        f.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled("> ", Style::default().fg(Color::DarkGray)),
+                Span::raw(palette.input.value.as_str()),
+            ])),
+            input_area,
+        );
+        if items.is_empty() {
+            f.render_widget(
+                Paragraph::new("No matching command").style(Style::default().fg(Color::DarkGray)),
+                list_area,
+            );
+        } else {
+            let list = List::new(items)
+                .highlight_style(Style::default().bg(Color::DarkGray))
+                .highlight_symbol("➤ ");
+            f.render_stateful_widget(list, list_area, &mut palette.state);
+        }
+        f.set_cursor_position((
+            input_area.x + 2 + palette.input.cursor() as u16,
+            input_area.y,
+        ));
+    }
+
+    fn draw_help(f: &mut ratatui::Frame, keymap: &Keymap, scroll: &mut usize) {
+        let key_width = keymap
+            .bindings()
+            .iter()
+            .map(|binding| binding.keys_label().chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut lines = Vec::new();
+        let mut context = None;
+        for binding in keymap.bindings() {
+            if context != Some(binding.context) {
+                if context.is_some() {
+                    lines.push(Line::default());
+                }
+                context = Some(binding.context);
+                lines.push(Line::styled(
+                    binding.context.title(),
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("  {:<width$}  ", binding.keys_label(), width = key_width),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::raw(binding.description),
+            ]));
+        }
+
+        let area = f.area();
+        let width = (key_width as u16 + 40).min(area.width.saturating_sub(2));
+        let height = (lines.len() as u16 + 2).min(area.height.saturating_sub(2));
+        let popup = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+
+        // Clamp here so scrolling past the end does not accumulate
+        let visible = height.saturating_sub(2) as usize;
+        *scroll = (*scroll).min(lines.len().saturating_sub(visible));
+        let title = if lines.len() > visible {
+            " Keys (↑/↓ to scroll, any other key to close) "
+        } else {
+            " Keys (any key to close) "
+        };
+
+        f.render_widget(Clear, popup);
+        f.render_widget(
+            Paragraph::new(lines)
+                .scroll((*scroll as u16, 0))
+                .block(Block::default().borders(Borders::ALL).title(title)),
+            popup,
+        );
+    }
+
+    fn draw_error_popup(f: &mut ratatui::Frame, error: &AppError) {
+        let mut lines = vec![Line::styled(
+            error.to_string(),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )];
+        lines.extend(
+            error
+                .details()
+                .iter()
+                .map(|line| Line::raw(line.trim().to_string())),
+        );
+
+        let height = (lines.len() as u16 + 2).min(f.area().height.saturating_sub(2));
+        let area = centered_rect(f.area(), 80, height.max(3));
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Red))
+                    .title(" Error (press any key) "),
+            ),
+            area,
+        );
+    }
+
+    fn draw_status(&self, f: &mut ratatui::Frame, area: Rect) {
+        let dim = Style::default().fg(Color::DarkGray);
+        let mut spans = Vec::new();
+
+        if !self.selected_sln.is_empty() {
+            spans.push(Span::styled(
+                format!(" {} ", solution_name(&self.selected_sln)),
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+            spans.push(Span::styled(
+                format!("[{}] ", self.configuration),
+                Style::default().fg(Color::Cyan),
+            ));
+            spans.push(Span::styled(
+                format!("[{}] ", self.build_strategy.label()),
+                dim,
+            ));
+            if self.state.verbosity != Verbosity::Minimal {
+                spans.push(Span::styled(
+                    format!("[{}] ", self.state.verbosity.label()),
+                    dim,
+                ));
+            }
+            if self.binlog {
+                spans.push(Span::styled(
+                    "[binlog] ",
+                    Style::default().fg(Color::Magenta),
+                ));
+            }
+        }
+        let project = self
+            .selected_index()
+            .and_then(|selected| self.projects.get(selected))
+            .filter(|_| self.view == View::Projects);
+        if let Some(project) = project {
+            if self.is_watched(&project.path) {
+                spans.push(Span::styled("[watch] ", Style::default().fg(Color::Green)));
+            }
+            if self.runs_spa(&project.path) {
+                spans.push(Span::styled("[spa] ", Style::default().fg(Color::Green)));
+            }
+            match self.sdk_pins.get(&project.path) {
+                Some(SdkPin::Sdk { version, .. }) => spans.push(Span::styled(
+                    format!("[sdk {}] ", version),
+                    Style::default().fg(Color::Yellow),
+                )),
+                Some(SdkPin::RollForward) => spans.push(Span::styled(
+                    "[roll forward] ",
+                    Style::default().fg(Color::Yellow),
+                )),
+                None => {}
+            }
+            let key = project.path.to_string_lossy();
+            if let Some(environment) = self.state.environments.get(key.as_ref()) {
+                spans.push(Span::styled(
+                    format!("[{}] ", environment),
+                    environment_style(environment),
+                ));
+            }
+        }
+        // Easy to forget once set, and they change every build
+        if !self.selected_sln.is_empty() {
+            let build_args = self.build_args_for(project.map(|p| p.name.as_str()));
+            if !build_args.is_empty() {
+                spans.push(Span::styled(
+                    format!("[{}] ", build_args.join(" ")),
+                    Style::default().fg(Color::Magenta),
+                ));
+            }
+        }
+
+        let running = self.processes.iter().filter(|p| p.status.is_none()).count();
+        // Stays up until the crashed process is restarted or cleared
+        let crashed = self
+            .processes
+            .iter()
+            .find(|p| p.crashed)
+            .map(|p| (format!("✗ {} {}", p.project, p.state_label()), Color::Red));
+        let activity = match (self.busy_activity(), self.process()) {
+            (Some(activity), _) => Some((activity, Color::Yellow)),
+            (None, Some(process)) if process.stopping.is_some() => Some((
+                format!(
+                    "{} {} {}",
+                    SPINNER[self.tick % SPINNER.len()],
+                    if process.restarting {
+                        "Restarting"
+                    } else {
+                        "Stopping"
+                    },
+                    process.project
+                ),
+                Color::Yellow,
+            )),
+            (None, _) if crashed.is_some() => crashed,
+            (None, _) if running > 1 => Some((format!("▶ {} running", running), Color::Green)),
+            (None, Some(process)) if process.status.is_none() => Some((
+                format!(
+                    "▶ {} {} {}s",
+                    if process.mode == RunMode::Watch {
+                        "Watching"
+                    } else {
+                        "Running"
+                    },
+                    process.project,
+                    process.job.started.elapsed().as_secs()
+                ),
+                Color::Green,
+            )),
+            _ => None,
+        };
+        if let Some((text, color)) = activity {
+            spans.push(Span::styled(
+                format!(" {} ", text),
+                Style::default().fg(color),
+            ));
+        }
+        if let Some(process) = self.process().filter(|p| p.status.is_none()) {
+            for url in &process.urls {
+                spans.push(Span::styled(
+                    format!("{} ", url),
+                    Style::default()
+                        .fg(Color::Blue)
+                        .add_modifier(Modifier::UNDERLINED),
+                ));
+            }
+        }
+
+        spans.push(Span::styled("│ ", dim));
+        match self.status.current() {
+            Some((message, true)) => spans.push(Span::styled(
+                message.to_string(),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )),
+            Some((message, false)) => spans.push(Span::raw(message.to_string())),
+            None => {
+                let context = self.hint_context();
+                let mut bindings = self.keymap.find(context, self.hint_actions());
+                // `?` is typed into the filter rather than opening help
+                if context != Context::Filter {
+                    bindings.extend(self.keymap.find(Context::Global, &[Action::Help]));
+                }
+                for binding in bindings {
+                    spans.push(Span::styled(
+                        binding.keys_label(),
+                        Style::default().fg(Color::Cyan),
+                    ));
+                    spans.push(Span::styled(format!(" {}  ", binding.description), dim));
+                }
+            }
+        }
+
+        f.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+
+    fn hint_context(&self) -> Context {
+        if self.busy() {
+            return Context::Building;
+        }
+        self.key_context()
+    }
+
+    /// Where keys go apart from the global ones and cancelling a build.
+    fn key_context(&self) -> Context {
+        match self.view {
+            _ if self.log_focus => Context::Logs,
+            _ if self.filter.is_some() => Context::Filter,
+            View::Solutions => Context::Solutions,
+            View::Projects => Context::Projects,
+            View::Output => Context::Output,
+            View::Processes => Context::Processes,
+            View::Problems => Context::Problems,
+            View::Tests => Context::Tests,
+            View::Explorer => Context::Explorer,
+            View::Graph => Context::Graph,
+        }
+    }
+
+    fn hint_actions(&self) -> &'static [Action] {
+        match self.hint_context() {
+            Context::Building => &[Action::CancelBuild],
+            Context::Filter => &[Action::Select, Action::ClearFilter],
+            Context::Solutions => &[Action::Select, Action::Filter, Action::Rescan, Action::Quit],
+            Context::Projects => &[
+                Action::Select,
+                Action::ToggleMark,
+                Action::CycleConfiguration,
+                Action::ToggleBuildStrategy,
+                Action::ToggleWatch,
+                Action::RunTests,
+                Action::Back,
+                Action::Quit,
+            ],
+            Context::Logs => &[
+                Action::Filter,
+                Action::NextMatch,
+                Action::CycleLevel,
+                Action::Back,
+            ],
+            Context::Processes => &[
+                Action::Select,
+                Action::Stop,
+                Action::Restart,
+                Action::ClearExited,
+                Action::Back,
+            ],
+            Context::Problems => &[Action::Select, Action::Up, Action::Down, Action::Back],
+            Context::Tests => &[Action::RerunFailed, Action::Up, Action::Down, Action::Back],
+            Context::Explorer => &[
+                Action::Select,
+                Action::ToggleGroup,
+                Action::Filter,
+                Action::Rescan,
+                Action::Back,
+            ],
+            Context::Graph => &[
+                Action::Select,
+                Action::ToggleReverse,
+                Action::BuildImpacted,
+                Action::Back,
+            ],
+            Context::Output | Context::Global => &[
+                Action::Stop,
+                Action::StopAll,
+                Action::Restart,
+                Action::NextProcess,
+                Action::Back,
+            ],
+        }
+    }
+
+    fn draw_logs(&self, f: &mut ratatui::Frame, area: Rect) {
+        let entries = self.logs.visible();
+        let matches = self.logs.matches();
+
+        // Keep the newest lines visible while output is streaming in
+        let visible = area.height.saturating_sub(2) as usize;
+        let len = entries.len();
+        let end = len.saturating_sub(self.logs.scroll).max(visible.min(len));
+        let start = end.saturating_sub(visible);
+        let query = self.logs.query.to_lowercase();
+        let log_text = Text::from_iter((start..end).map(|i| {
+            let entry = entries[i];
+            let style = match entry.level {
+                Level::Info => Style::default(),
+                Level::Success => Style::default().fg(Color::Green),
+                Level::Warn => Style::default().fg(Color::Yellow),
+                Level::Error => Style::default().fg(Color::Red),
+            };
+            let mut spans = Vec::new();
+            if self.logs.timestamps {
+                spans.push(Span::styled(
+                    format!("{} ", entry.timestamp()),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            if matches.binary_search(&i).is_ok() {
+                let highlight = if self.logs.current_match == Some(i) {
+                    Style::default().fg(Color::Black).bg(Color::LightYellow)
+                } else {
+                    Style::default().fg(Color::Black).bg(Color::Yellow)
+                };
+                spans.extend(highlight_substrings(
+                    &strip_ansi(&entry.message),
+                    &query,
+                    style,
+                    highlight,
+                ));
+            } else {
+                spans.extend(ansi::spans(&entry.message, style));
+            }
+            Line::from(spans)
+        }));
+
+        let follow = match self.logs.scroll {
+            0 => Line::styled(" following ", Style::default().fg(Color::DarkGray)),
+            _ => {
+                let resume = self
+                    .keymap
+                    .find(Context::Global, &[Action::LogFollow])
+                    .first()
+                    .map(|binding| binding.keys_label())
+                    .unwrap_or_default();
+                Line::styled(
+                    format!(" scrolled ({} to resume) ", resume),
+                    Style::default().fg(Color::Yellow),
+                )
+            }
+        };
+        let mut view = Vec::new();
+        if self.logs.level != LevelFilter::All {
+            view.push(format!("level: {}", self.logs.level.label()));
+        }
+        if !self.logs.query.is_empty() {
+            let position = self
+                .logs
+                .current_match
+                .and_then(|current| matches.iter().position(|&i| i == current))
+                .map(|n| format!("{}/", n + 1))
+                .unwrap_or_default();
+            view.push(format!(
+                "\"{}\": {}{} matches",
+                self.logs.query,
+                position,
+                matches.len()
+            ));
+        }
+        let view = (!view.is_empty()).then(|| {
+            Line::styled(
+                format!(" {} ", view.join(", ")),
+                Style::default().fg(Color::Cyan),
+            )
+        });
+        let title = match self.busy_activity() {
+            Some(activity) => format!(" Logs — {} (x to cancel) ", activity),
+            None => " Logs ".to_string(),
+        };
+        let sdk = match &self.dotnet {
+            Some(version) => Line::from(format!(" .NET SDK {} ", version)),
+            None => Line::styled(" .NET SDK not found ", Style::default().fg(Color::Red)),
+        };
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .title(follow)
+            .title(sdk.right_aligned());
+        if let Some(view) = view {
+            block = block.title(view);
+        }
+        if self.log_focus {
+            block = block.border_style(Style::default().fg(Color::Cyan));
+        }
+        let paragraph = Paragraph::new(log_text)
+            .block(block)
+            .style(Style::default().fg(Color::White));
+        f.render_widget(paragraph, area);
+    }
+
+    fn draw_project_list(&mut self, f: &mut ratatui::Frame, area: Rect) {
+        let missing = self.projects.iter().filter(|p| p.missing).count();
+        let count = match missing {
+            0 => format!("{} projects", self.projects.len()),
+            _ => format!("{} projects ({} missing)", self.projects.len(), missing),
+        };
+        let tree = self.tree_shown() && self.filter.is_none();
+        let title = format!(
+            " {} [{}] (↑/↓: navigate, {}Enter: run, Space: mark, G: groups, /: filter, c: configuration, l: profile, {}Esc: back, q: quit) ",
+            count,
+            self.configuration,
+            if tree { "←/→: fold, " } else { "" },
+            if self.processes.is_empty() { "" } else { "v: output, " }
+        );
+
+        // Borders plus the highlight symbol
+        let width = area.width.saturating_sub(4) as usize;
+        let marking = !self.marked.is_empty();
+        let running: HashSet<&Path> = self
+            .processes
+            .iter()
+            .filter(|p| p.status.is_none())
+            .map(|p| p.project_dir.as_path())
+            .collect();
+        let now = Local::now().timestamp();
+        let rows = self.visible_rows();
+        let items = rows.into_iter().map(|row| {
+            let index = match row.entry {
+                ListEntry::Item(index) => index,
+                ListEntry::Folder(folder) => {
+                    let folder = &self.folders[folder];
+                    let count = self
+                        .projects
+                        .iter()
+                        .filter(|p| p.folder.starts_with(folder))
+                        .count();
+                    let fold = if self.collapsed_folders.contains(&folder.join("/")) {
+                        "▸ "
+                    } else {
+                        "▾ "
+                    };
+                    let dim = Style::default().fg(Color::DarkGray);
+                    return ListItem::new(Line::from(vec![
+                        Span::raw(if marking { "  " } else { "" }),
+                        Span::raw("  ".repeat(row.depth)),
+                        Span::styled(fold, dim),
+                        Span::styled(
+                            folder.last().cloned().unwrap_or_default(),
+                            Style::default().fg(Color::Cyan),
+                        ),
+                        Span::styled(format!(" ({})", count), dim),
+                    ]));
+                }
+            };
+            let project = &self.projects[index];
+            // Under its folder, past where the folders show whether they are folded
+            let indent = if tree {
+                "  ".repeat(row.depth + 1)
+            } else {
+                String::new()
+            };
+            let kind = format!("  {}", project.kind.label());
+            let output = format!(" {}", project.output.label());
+            // The whale takes two columns
+            let docker = if project.docker { " 🐳" } else { "" };
+            let suffix = if project.missing { " (missing)" } else { "" };
+            let is_running = project
+                .path
+                .parent()
+                .is_some_and(|dir| running.contains(dir));
+            let marker = if is_running { " ●" } else { "" };
+            let environment = self
+                .state
+                .environments
+                .get(project.path.to_string_lossy().as_ref())
+                .map(|environment| format!(" {} ", environment));
+            let environment_width = environment.as_ref().map_or(0, |e| e.chars().count() + 1);
+            let last_build = self
+                .state
+                .builds
+                .get(project.path.to_string_lossy().as_ref())
+                .map(|build| {
+                    let ago = format_ago(now - build.finished);
+                    if build.succeeded {
+                        (format!("  ✓ {}", ago), Color::Green)
+                    } else {
+                        (format!("  ✗ failed {}", ago), Color::Red)
+                    }
+                });
+            let build_width = last_build.as_ref().map_or(0, |(b, _)| b.chars().count());
+            // The one chosen to run stands out when there are several
+            let chosen = self
+                .state
+                .frameworks
+                .get(project.path.to_string_lossy().as_ref())
+                .filter(|_| project.frameworks.len() > 1);
+            let frameworks_width = match project.frameworks.len() {
+                0 => 0,
+                _ => project.frameworks.join(";").chars().count() + 1,
+            };
+            let path_width = width.saturating_sub(
+                indent.len()
+                    + project.name.chars().count()
+                    + marker.chars().count()
+                    + suffix.len()
+                    + kind.chars().count()
+                    + frameworks_width
+                    + output.len()
+                    + if project.docker { 3 } else { 0 }
+                    + build_width
+                    + environment_width
+                    + if marking { 4 } else { 2 },
+            );
+            let path = format!("  {}", truncate_left(&project.rel_path, path_width));
+
+            let (name_style, path_style) = if project.missing {
+                let dim = Style::default()
+                    .fg(Color::DarkGray)
+                    .add_modifier(Modifier::DIM);
+                (dim, dim)
+            } else {
+                (
+                    Style::default().fg(Color::Yellow),
+                    Style::default().fg(Color::DarkGray),
+                )
+            };
+            let mut spans = Vec::new();
+            if marking {
+                let mark = if self.marked.contains(&project.path) {
+                    "✓ "
+                } else {
+                    "  "
+                };
+                spans.push(Span::styled(mark, Style::default().fg(Color::Green)));
+            }
+            spans.push(Span::raw(indent));
+            spans.extend(highlight_matches(&project.name, &row.positions, name_style));
+            spans.push(Span::styled(marker, Style::default().fg(Color::Green)));
+            spans.push(Span::styled(suffix, name_style));
+            spans.push(Span::styled(path, path_style));
+            spans.push(Span::styled(kind, Style::default().fg(Color::DarkGray)));
+            for (i, framework) in project.frameworks.iter().enumerate() {
+                let separator = if i == 0 { " " } else { ";" };
+                spans.push(Span::styled(
+                    separator,
+                    Style::default().fg(Color::DarkGray),
+                ));
+                let color = if chosen == Some(framework) {
+                    Color::Cyan
+                } else {
+                    Color::DarkGray
+                };
+                spans.push(Span::styled(framework.as_str(), Style::default().fg(color)));
+            }
+            spans.push(Span::styled(output, output_style(project.output)));
+            spans.push(Span::raw(docker));
+            if let Some((last_build, color)) = last_build {
+                spans.push(Span::styled(
+                    last_build,
+                    Style::default().fg(color).add_modifier(Modifier::DIM),
+                ));
+            }
+            if let Some(environment) = environment {
+                let style = environment_style(environment.trim());
+                spans.push(Span::raw(" "));
+                spans.push(Span::styled(environment, style));
+            }
+            ListItem::new(Line::from(spans))
+        });
+
+        let mut block = Block::default().borders(Borders::ALL).title(title);
+        if let Some(global_json) = &self.global_json {
+            block = block.title_bottom(Line::styled(
+                format!(
+                    " ⚠ global.json asks for .NET SDK {}, which is not installed (ctrl+k: choose one) ",
+                    global_json.requirement()
+                ),
+                Style::default().fg(Color::White).bg(Color::Red),
+            ));
+        }
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().bg(Color::DarkGray))
+            .highlight_symbol("➤ ");
+
+        self.list_area = area;
+        self.list_height = area.height.saturating_sub(2) as usize;
+        f.render_stateful_widget(list, area, &mut self.project_state);
+    }
+
+    fn draw_graph(&mut self, f: &mut ratatui::Frame, area: Rect) {
+        let Some(view) = self.graph_view.as_ref() else {
+            return;
+        };
+        let Some(project) = self.projects.get(view.focus) else {
+            return;
+        };
+        let dim = Style::default().fg(Color::DarkGray);
+        let direction = if view.reverse {
+            "Projects referencing"
+        } else {
+            "References of"
+        };
+        let title = format!(
+            " {} {} (Enter: open, r: {}, b: build impacted, Esc: back) ",
+            direction,
+            project.name,
+            if view.reverse {
+                "references"
+            } else {
+                "dependents"
+            }
+        );
+        let block = Block::default().borders(Borders::ALL).title(title);
+
+        let graph = self
+            .graph
+            .as_ref()
+            .filter(|graph| graph.solution == self.selected_sln);
+        let Some(graph) = graph else {
+            let spinner = SPINNER[self.tick % SPINNER.len()];
+            let inner = block.inner(area);
+            f.render_widget(block, area);
+            f.render_widget(
+                Paragraph::new(format!("{} Reading project references…", spinner)).style(dim),
+                inner,
+            );
+            return;
+        };
+
+        let mut header = vec![Line::from(vec![
+            Span::styled(project.name.clone(), Style::default().fg(Color::Yellow)),
+            Span::styled(format!("  {}", project.rel_path), dim),
+            Span::styled(
+                format!(
+                    "  {} references, {} dependents",
+                    graph.references[view.focus].len(),
+                    graph.dependents(view.focus).len()
+                ),
+                dim,
+            ),
+        ])];
+        if let Some(cycle) = graph.cycle(view.focus) {
+            let names: Vec<&str> = cycle
+                .iter()
+                .map(|&i| self.projects[i].name.as_str())
+                .collect();
+            header.push(Line::styled(
+                format!("Circular reference: {}", names.join(" → ")),
+                Style::default().fg(Color::Red),
+            ));
+        }
+
+        let rows = self.graph_rows(view);
+        let items: Vec<ListItem> = rows
+            .iter()
+            .map(|row| match row {
+                GraphRow::Project(index) => {
+                    let neighbour = &self.projects[*index];
+                    let mut spans = vec![
+                        Span::raw(neighbour.name.clone()),
+                        Span::styled(format!("  {}", neighbour.rel_path), dim),
+                        Span::styled(
+                            format!(" {}", neighbour.output.label()),
+                            output_style(neighbour.output),
+                        ),
+                    ];
+                    let count = match view.reverse {
+                        true => graph.referenced_by[*index].len(),
+                        false => graph.references[*index].len(),
+                    };
+                    if count > 0 {
+                        spans.push(Span::styled(format!("  ({} more)", count), dim));
+                    }
+                    if graph.cyclic.contains(index) {
+                        spans.push(Span::styled("  ⟳ cycle", Style::default().fg(Color::Red)));
+                    }
+                    ListItem::new(Line::from(spans))
+                }
+                GraphRow::Outside(name) => ListItem::new(Line::from(vec![
+                    Span::styled(name.clone(), dim),
+                    Span::styled("  not in the solution", dim),
+                ])),
+            })
+            .collect();
+        let empty = items.is_empty();
+
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(header.len() as u16 + 1),
+                Constraint::Min(0),
+            ])
+            .split(inner);
+        f.render_widget(Paragraph::new(header), chunks[0]);
+        if empty {
+            let text = match view.reverse {
+                true => "No project references it",
+                false => "It references no other project",
+            };
+            f.render_widget(Paragraph::new(text).style(dim), chunks[1]);
+            return;
+        }
+        self.list_height = chunks[1].height as usize;
+        if let Some(view) = self.graph_view.as_mut() {
+            f.render_stateful_widget(
+                List::new(items)
+                    .highlight_style(Style::default().bg(Color::DarkGray))
+                    .highlight_symbol("➤ "),
+                chunks[1],
+                &mut view.state,
+            );
+        }
+    }
+
+    fn draw_project_details(&mut self, f: &mut ratatui::Frame, area: Rect) {
+        let Some(project) = self
+            .selected_index()
+            .and_then(|selected| self.projects.get(selected))
+        else {
+            let block = Block::default().borders(Borders::ALL).title(" Details ");
+            f.render_widget(block, area);
+            return;
+        };
+        let title = format!(" {} (i: hide) ", project.name);
+        let (name, path) = (project.name.clone(), project.path.clone());
+        let lines = match self.details.get(&name, &path) {
+            Some(details) => details_lines(details),
+            None => vec![Line::styled(
+                "Reading the project…",
+                Style::default().fg(Color::DarkGray),
+            )],
+        };
+        let details = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .wrap(Wrap { trim: false });
+        f.render_widget(details, area);
+    }
+
+    fn draw_processes(&mut self, f: &mut ratatui::Frame, area: Rect) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Processes (Enter: output, s: stop, R: restart, c: clear exited, v: recording, Esc: back) ");
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+        if self.processes.is_empty() {
+            f.render_widget(
+                Paragraph::new("No processes yet — run a project with Enter")
+                    .style(Style::default().fg(Color::DarkGray)),
+                inner,
+            );
+            return;
+        }
+
+        let [header_area, list_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(inner);
+        let dim = Style::default().fg(Color::DarkGray);
+        f.render_widget(
+            Paragraph::new(format!(
+                "    {:<24} {:>8}  {:>9}  {:<8}  {:<22}  {:<20}  {}",
+                "Project", "PID", "Uptime", "Config", "State", "Output", "URLs"
+            ))
+            .style(dim.add_modifier(Modifier::BOLD)),
+            header_area,
+        );
+
+        let items: Vec<ListItem> = self
+            .processes
+            .iter()
+            .map(|process| {
+                let (symbol, color) = match process.status {
+                    None if process.stopping.is_some() => ("●", Color::Yellow),
+                    None => ("●", Color::Green),
+                    Some(status) if status.success() => ("○", Color::DarkGray),
+                    Some(_) => ("✗", Color::Red),
+                };
+                let state = process.state_label();
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{} ", symbol), Style::default().fg(color)),
+                    Span::styled(
+                        format!("{:<24} ", process.project),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    Span::styled(format!("{:>8}  ", process.job.id()), dim),
+                    Span::raw(format!("{:>9}  ", format_uptime(process.job.uptime()))),
+                    Span::styled(
+                        format!(
+                            "{:<8}  ",
+                            match (&process.container, &process.spa) {
+                                (Some(_), _) => "docker",
+                                (_, Some(spa)) => spa.tool(),
+                                _ => &process.configuration,
+                            }
+                        ),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::styled(format!("{:<22}  ", state), Style::default().fg(color)),
+                    // What the output view holds of it, within the scrollback limits
+                    Span::styled(
+                        format!(
+                            "{:<20}  ",
+                            format!(
+                                "{} lines, {}",
+                                process.output.kept(),
+                                format_size(process.output.bytes() as u64)
+                            )
+                        ),
+                        dim,
+                    ),
+                    Span::styled(
+                        process
+                            .container
+                            .as_ref()
+                            .and_then(|c| c.id.as_deref())
+                            .map(|id| format!("container {}  ", id))
+                            .unwrap_or_default(),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::styled(process.known_urls().join(" "), dim),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_style(Style::default().bg(Color::DarkGray))
+            .highlight_symbol("➤ ");
+
+        self.process_state.select(Some(self.current_process));
+        f.render_stateful_widget(list, list_area, &mut self.process_state);
+    }
+
+    fn draw_problems(&mut self, f: &mut ratatui::Frame, area: Rect) {
+        let block = Block::default().borders(Borders::ALL).title(format!(
+            " Problems — {} (↑/↓: select, Enter: open in editor, Esc: back) ",
+            self.problems.summary(None)
+        ));
+        if self.problems.is_empty() {
+            let inner = block.inner(area);
+            f.render_widget(block, area);
+            f.render_widget(
+                Paragraph::new("No errors or warnings from the latest builds")
+                    .style(Style::default().fg(Color::DarkGray)),
+                inner,
+            );
+            return;
+        }
+        let selected = self
+            .problem_state
+            .selected()
+            .unwrap_or(0)
+            .min(self.problems.len() - 1);
+        self.problem_state.select(Some(selected));
+
+        let dim = Style::default().fg(Color::DarkGray);
+        let build_width = self
+            .problems
+            .iter()
+            .map(|problem| problem.build.chars().count())
+            .max()
+            .unwrap_or(0);
+        let items: Vec<ListItem> = self
+            .problems
+            .iter()
+            .map(|problem| {
+                let diagnostic = &problem.diagnostic;
+                let severity = diagnostic.severity;
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{:<width$}  ", problem.build, width = build_width),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    Span::styled(
+                        format!("{:<8}", severity.label()),
+                        Style::default().fg(severity.color()),
+                    ),
+                    Span::styled(
+                        format!("{:<8} ", diagnostic.code.as_deref().unwrap_or("")),
+                        dim,
+                    ),
+                    Span::styled(
+                        format!("{} ", diagnostic.short_location()),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::raw(
+                        diagnostic
+                            .message
+                            .lines()
+                            .next()
+                            .unwrap_or_default()
+                            .to_string(),
+                    ),
+                ]))
+            })
+            .collect();
+
+        // The full message of the highlighted problem below the list
+        let Some(problem) = self.problems.get(selected) else {
+            return;
+        };
+        let diagnostic = &problem.diagnostic;
+        let mut details = vec![Line::styled(
+            diagnostic.location(),
+            Style::default().fg(Color::Cyan),
+        )];
+        if let Some(project) = &diagnostic.project {
+            details.push(Line::styled(format!("in {}", project), dim));
+        }
+        details.extend(diagnostic.message.lines().map(Line::raw));
+        let detail_height = (details.len() as u16 + 2).clamp(5, area.height / 2);
+        let [list_area, detail_area] =
+            Layout::vertical([Constraint::Min(3), Constraint::Length(detail_height)]).areas(area);
+
+        self.list_height = list_area.height.saturating_sub(2) as usize;
+        f.render_stateful_widget(
+            List::new(items)
+                .block(block)
+                .highlight_style(Style::default().bg(Color::DarkGray))
+                .highlight_symbol("➤ "),
+            list_area,
+            &mut self.problem_state,
+        );
+        let severity = diagnostic.severity;
+        let title = match &diagnostic.code {
+            Some(code) => format!(" {} {} ", severity.label(), code),
+            None => format!(" {} ", severity.label()),
+        };
+        f.render_widget(
+            Paragraph::new(details).wrap(Wrap { trim: false }).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(severity.color()))
+                    .title(title),
+            ),
+            detail_area,
+        );
+    }
+
+    fn draw_explorer(&mut self, f: &mut ratatui::Frame, area: Rect) {
+        let listing = self.test_listing.is_some();
+        let spinner = SPINNER[self.tick % SPINNER.len()];
+        let Some(explorer) = self.explorer.as_mut() else {
+            return;
+        };
+        let count = explorer.tree.as_ref().map_or(0, |tree| tree.tests.len());
+        let mut title = format!(" Tests of {} — {}", explorer.name, count);
+        if !explorer.query.is_empty() {
+            title.push_str(&format!(" [filter: {}]", explorer.query));
+        }
+        if listing {
+            title.push_str(&format!(" {} listing…", spinner));
+        }
+        title.push_str(" (Enter: run, Space: fold, /: filter, r: reload, Esc: back) ");
+        let block = Block::default().borders(Borders::ALL).title(title);
+
+        let Some(tree) = explorer.tree.as_ref() else {
+            let (text, color) = match &explorer.error {
+                Some(error) => (error.clone(), Color::Red),
+                None => (format!("{} Listing the tests…", spinner), Color::DarkGray),
+            };
+            let inner = block.inner(area);
+            f.render_widget(block, area);
+            f.render_widget(
+                Paragraph::new(text).style(Style::default().fg(color)),
+                inner,
+            );
+            return;
+        };
+
+        let dim = Style::default().fg(Color::DarkGray);
+        let items: Vec<ListItem> = tree
+            .rows(&explorer.query)
+            .into_iter()
+            .map(|row| {
+                let fold = match row.node {
+                    Node::Test(_) => "  ",
+                    _ if row.collapsed => "▸ ",
+                    _ => "▾ ",
+                };
+                let style = match row.node {
+                    Node::Namespace(_) => Style::default().fg(Color::Cyan),
+                    Node::Class(_) => Style::default().fg(Color::Yellow),
+                    Node::Test(_) => Style::default(),
+                };
+                let mut spans = vec![
+                    Span::raw("  ".repeat(row.depth)),
+                    Span::styled(fold, dim),
+                    Span::styled(row.label.clone(), style),
+                ];
+                if let Some(outcome) = tree.outcome(&row.node) {
+                    let (symbol, color) = match outcome.outcome {
+                        Outcome::Passed => ("✓", Color::Green),
+                        Outcome::Failed => ("✗", Color::Red),
+                        Outcome::Skipped => ("○", Color::DarkGray),
+                    };
+                    spans.push(Span::styled(
+                        format!("  {}", symbol),
+                        Style::default().fg(color),
+                    ));
+                    spans.push(Span::styled(
+                        format!(" {}", format_test_duration(outcome.duration)),
+                        dim,
+                    ));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        self.list_height = area.height.saturating_sub(2) as usize;
+        f.render_stateful_widget(
+            List::new(items)
+                .block(block)
+                .highlight_style(Style::default().bg(Color::DarkGray))
+                .highlight_symbol("➤ "),
+            area,
+            &mut explorer.state,
+        );
+        if let Some(search) = &explorer.search {
+            Self::draw_filter(f, area, " Filter tests (Enter: keep, Esc: clear) ", search);
+        }
+    }
+
+    fn draw_failed_tests(&mut self, f: &mut ratatui::Frame, area: Rect) {
+        let failures = self
+            .test_results
+            .as_ref()
+            .map_or(&[][..], |results| results.failures.as_slice());
+        let title = match &self.test_results {
+            Some(results) => format!(
+                " Failed tests — {} in {} (↑/↓: select, r: rerun failed, Esc: back) ",
+                failures.len(),
+                results.name
+            ),
+            None => " Failed tests (Esc: back) ".to_string(),
+        };
+        let block = Block::default().borders(Borders::ALL).title(title);
+        if failures.is_empty() {
+            let inner = block.inner(area);
+            f.render_widget(block, area);
+            f.render_widget(
+                Paragraph::new("No failed tests — run tests with t in the project list")
+                    .style(Style::default().fg(Color::DarkGray)),
+                inner,
+            );
+            return;
+        }
+        let selected = self
+            .failure_state
+            .selected()
+            .unwrap_or(0)
+            .min(failures.len() - 1);
+        self.failure_state.select(Some(selected));
+
+        let dim = Style::default().fg(Color::DarkGray);
+        let items: Vec<ListItem> = failures
+            .iter()
+            .map(|test| {
+                ListItem::new(Line::from(vec![
+                    Span::styled("✗ ", Style::default().fg(Color::Red)),
+                    Span::raw(format!("{} ", test.name)),
+                    Span::styled(
+                        test.message.lines().next().unwrap_or_default().to_string(),
+                        dim,
+                    ),
+                ]))
+            })
+            .collect();
+
+        // The message and stack trace of the highlighted test below the list
+        let test = &failures[selected];
+        let mut details: Vec<Line> = test.message.lines().map(Line::raw).collect();
+        if !test.stack_trace.is_empty() {
+            details.push(Line::raw(""));
+            details.extend(test.stack_trace.lines().map(|line| Line::styled(line, dim)));
+        }
+        let detail_height = (details.len() as u16 + 2).clamp(5, area.height / 2);
+        let [list_area, detail_area] =
+            Layout::vertical([Constraint::Min(3), Constraint::Length(detail_height)]).areas(area);
+
+        self.list_height = list_area.height.saturating_sub(2) as usize;
+        f.render_stateful_widget(
+            List::new(items)
+                .block(block)
+                .highlight_style(Style::default().bg(Color::DarkGray))
+                .highlight_symbol("➤ "),
+            list_area,
+            &mut self.failure_state,
+        );
+        f.render_widget(
+            Paragraph::new(details).wrap(Wrap { trim: false }).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Red))
+                    .title(format!(" {} ", test.full_name)),
+            ),
+            detail_area,
+        );
+    }
+
+    fn draw_output(&mut self, f: &mut ratatui::Frame, area: Rect) {
+        self.update_output_index();
+        let (Some(process), Some(index)) = (
+            self.processes.get(self.current_process),
+            self.output_index.as_ref(),
+        ) else {
+            return;
+        };
+
+        // One tab per process, with its state at a glance
+        let [tabs_area, area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
+        let titles = self.processes.iter().map(|process| {
+            let (symbol, color) = match process.status {
+                None => ("●", Color::Green),
+                Some(status) if status.success() => ("○", Color::DarkGray),
+                Some(_) => ("✗", Color::Red),
+            };
+            Line::from(vec![
+                Span::styled(symbol, Style::default().fg(color)),
+                Span::raw(format!(" {}", process.project)),
+            ])
+        });
+        f.render_widget(
+            Tabs::new(titles)
+                .select(self.current_process)
+                .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+            tabs_area,
+        );
+
+        // Startup progress of the group, including members without a process yet
+        let area = match &self.group_run {
+            Some(run) => {
+                let [group_area, area] =
+                    Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
+                let mut spans = vec![Span::styled(
+                    format!(" {}:", run.name),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )];
+                for member in &run.members {
+                    spans.push(Span::raw(format!("  {} ", member.name)));
+                    spans.push(Span::styled(
+                        member.state.label(),
+                        Style::default().fg(member.state.color()),
+                    ));
+                }
+                f.render_widget(Paragraph::new(Line::from(spans)), group_area);
+                area
+            }
+            None => area,
+        };
+
+        let end = self.output_end(index);
+        let matches = index.matches.range(..self.output_match_count(index));
+        let mut state = process.state_label();
+        let muted = process.output.len() - index.lines.len();
+        if muted > 0 {
+            state.push_str(&format!(", {} muted", muted));
+        }
+        if !self.output_query.is_empty() {
+            let position = self
+                .output_match
+                .and_then(|current| index.matches.binary_search(&current).ok())
+                .filter(|&n| n < matches.len());
+            state.push_str(&match position {
+                Some(n) => format!(
+                    ", \"{}\": match {}/{}",
+                    self.output_query,
+                    n + 1,
+                    matches.len()
+                ),
+                None => format!(", \"{}\": {} matches", self.output_query, matches.len()),
+            });
+        }
+        let title = format!(
+            " {} — {} (s: stop, S: stop all, R: restart, [/]: switch, ↑/↓: scroll, Space: pause, /: search, J: {} JSON, Enter: payload, m: rules, Esc: back) ",
+            process.project,
+            state,
+            if self.raw_output { "pretty" } else { "raw" }
+        );
+
+        let visible = area.height.saturating_sub(2) as usize;
+        let start = match self.output_anchor {
+            Some(anchor) => index.position(anchor.top),
+            None => end.saturating_sub(visible),
+        };
+        let query = self.output_query.to_lowercase();
+        let mut lines: Vec<Line> = index
+            .lines
+            .range(start..end.max(start))
+            .take(visible)
+            .filter_map(|&i| Some((i, process.output.get(i)?)))
+            .map(|(i, text)| {
+                let event = if self.raw_output {
+                    None
+                } else {
+                    jsonlog::parse(text)
+                };
+                let style = match rules::find(&self.output_rules, &process.project, text) {
+                    Some(rule) => Style::default().fg(rule.color),
+                    None => Style::default(),
+                };
+                let highlight = if self.output_match == Some(i) {
+                    Style::default().fg(Color::Black).bg(Color::LightYellow)
+                } else {
+                    Style::default().fg(Color::Black).bg(Color::Yellow)
+                };
+                let found = index.matches.binary_search(&i).is_ok();
+                let mut line = match event {
+                    // The match may be in what the prettified line leaves out
+                    Some(event) if found => Self::event_line(&event).patch_style(highlight),
+                    Some(event) => Self::event_line(&event).patch_style(style),
+                    None if found => Line::from(highlight_substrings(
+                        &strip_ansi(text),
+                        &query,
+                        style,
+                        highlight,
+                    )),
+                    None => Line::from(ansi::spans(text, style)),
+                };
+                // The line Enter expands, marked once scrolled away from the newest
+                if self.output_anchor.is_some_and(|anchor| anchor.line == i) {
+                    line = line.patch_style(Style::default().bg(Color::DarkGray));
+                }
+                line
+            })
+            .collect();
+        // Rather than moving on to lines it never showed
+        if self
+            .output_anchor
+            .is_some_and(|anchor| anchor.top < process.output.first())
+        {
+            lines = vec![Line::styled(
+                if self.output_paused.is_some() {
+                    "The paused lines were dropped from the scrollback (Space: resume)"
+                } else {
+                    "The lines scrolled to were dropped from the scrollback (G: follow)"
+                },
+                Style::default().fg(Color::DarkGray),
+            )];
+        }
+
+        let mut block = Block::default().borders(Borders::ALL);
+        if let Some(paused_at) = self.output_paused {
+            block = block.title(Span::styled(
+                format!(
+                    " paused — +{} new lines ",
+                    process.output.pushed().saturating_sub(paused_at)
+                ),
+                Style::default().fg(Color::Black).bg(Color::Yellow),
+            ));
+        }
+        let paragraph = Paragraph::new(lines).block(block.title(title));
+        f.render_widget(paragraph, area);
+        self.output_height = visible;
+    }
+
+    /// Brings the index of the lines the output view shows up to date with
+    /// the process shown.
+    fn update_output_index(&mut self) {
+        let Some(process) = self.processes.get(self.current_process) else {
+            self.output_index = None;
+            return;
+        };
+        let pid = process.job.id();
+        let index = match &mut self.output_index {
+            Some(index) if index.is_for(pid) => index,
+            index => index.insert(OutputIndex::new(pid)),
+        };
+        index.update(&process.output, &self.output_rules, &process.project);
+        index.search(&process.output, &self.output_query);
+    }
+
+    /// How many of the matches of the search the output view can show.
+    fn output_match_count(&self, index: &OutputIndex) -> usize {
+        match self.output_paused {
+            Some(paused_at) => index.matches.partition_point(|&line| line < paused_at),
+            None => index.matches.len(),
+        }
+    }
+
+    /// The position in the index past the lines the output view can show,
+    /// which are those from before it was paused.
+    fn output_end(&self, index: &OutputIndex) -> usize {
+        match self.output_paused {
+            Some(paused_at) => index.position(paused_at),
+            None => index.lines.len(),
+        }
+    }
+
+    /// Scrolls the output view so the line at the position in the index is
+    /// the one Enter expands, at the bottom unless near the top. At the
+    /// newest line it follows new output again, unless paused.
+    fn scroll_output_to(&mut self, position: usize) {
+        let Some(index) = &self.output_index else {
+            return;
+        };
+        let Some(last) = self.output_end(index).checked_sub(1) else {
+            return;
+        };
+        let position = position.min(last);
+        self.output_anchor = if position == last && self.output_paused.is_none() {
+            None
+        } else {
+            let top = position.saturating_sub(self.output_height.saturating_sub(1));
+            Some(OutputAnchor {
+                top: index.lines[top],
+                line: index.lines[position],
+            })
+        };
+    }
+
+    /// Scrolls the output view up by `delta` lines, or down for a negative one.
+    fn scroll_output(&mut self, delta: isize) {
+        self.update_output_index();
+        let Some(index) = &self.output_index else {
+            return;
+        };
+        let current = match self.output_anchor {
+            Some(anchor) => index.position(anchor.line),
+            None => self.output_end(index).saturating_sub(1),
+        };
+        self.scroll_output_to(current.saturating_add_signed(-delta));
+    }
+
+    /// Moves to the next (newer) or previous (older) output line matching
+    /// the search, wrapping around, and scrolls it into view, which stops
+    /// following new output.
+    fn jump_to_output_match(&mut self, forward: bool) {
+        self.update_output_index();
+        let Some(index) = &self.output_index else {
+            return;
+        };
+        let matches = index.matches.range(..self.output_match_count(index));
+        let (Some(&first), Some(&last)) = (matches.clone().next(), matches.clone().next_back())
+        else {
+            return;
+        };
+        let target = match self.output_match {
+            None => last,
+            Some(current) if forward => matches.copied().find(|&i| i > current).unwrap_or(first),
+            Some(current) => matches
+                .rev()
+                .copied()
+                .find(|&i| i < current)
+                .unwrap_or(last),
+        };
+        self.output_match = Some(target);
+        self.scroll_output_to(index.position(target));
+    }
+
+    /// A JSON log line the way the console logger would print it, like
+    /// `14:03:07.512 info Microsoft.Hosting.Lifetime: Now listening on…`.
+    fn event_line(event: &LogEvent) -> Line<'static> {
+        let dim = Style::default().fg(Color::DarkGray);
+        let mut spans = Vec::new();
+        if let Some(timestamp) = &event.timestamp {
+            spans.push(Span::styled(
+                format!("{} ", jsonlog::time_of_day(timestamp)),
+                dim,
+            ));
+        }
+        spans.push(Span::styled(
+            event.level_label(),
+            Style::default()
+                .fg(event.level_color())
+                .add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::raw(" "));
+        if let Some(category) = &event.category {
+            spans.push(Span::styled(
+                format!("{}: ", category),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
+        spans.push(Span::raw(event.message.clone()));
+        // The rest of the stack trace is in the payload
+        if let Some(exception) = event.exception.as_ref().and_then(|e| e.lines().next()) {
+            spans.push(Span::styled(
+                format!("  {}", exception),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        Line::from(spans)
+    }
+
+    /// Shows the whole JSON of the highlighted output line, or the newest.
+    fn open_payload(&mut self) {
+        self.update_output_index();
+        let (Some(process), Some(index)) = (
+            self.processes.get(self.current_process),
+            self.output_index.as_ref(),
+        ) else {
+            return;
+        };
+        let line = match self.output_anchor {
+            Some(anchor) => Some(anchor.line),
+            None => self
+                .output_end(index)
+                .checked_sub(1)
+                .map(|last| index.lines[last]),
+        };
+        let Some(line) = line.and_then(|line| process.output.get(line)) else {
+            return;
+        };
+        let Some(lines) = jsonlog::pretty(line) else {
+            self.status.flash("Not a JSON line");
+            return;
+        };
+        let exception = jsonlog::parse(line).and_then(|event| event.exception);
+        self.payload = Some(Payload {
+            lines,
+            exception,
+            scroll: 0,
+        });
+    }
+
+    fn open_rules_picker(&mut self) {
+        if self.output_rules.is_empty() {
+            self.status
+                .flash("No output rules, add [[output_rules]] to the config");
+            return;
+        }
+        self.rules_picker = Some(ListState::default().with_selected(Some(0)));
+    }
+
+    fn draw_rules_picker(f: &mut ratatui::Frame, rules: &[Rule], state: &mut ListState) {
+        let items: Vec<ListItem> = rules
+            .iter()
+            .map(|rule| {
+                let mut spans = vec![
+                    Span::raw(if rule.enabled { "[x] " } else { "[ ] " }),
+                    Span::styled(
+                        format!("{:<10}", rule.config.action.label()),
+                        Style::default().fg(rule.color),
+                    ),
+                    Span::raw(rule.config.pattern.as_str()),
+                ];
+                if let Some(project) = &rule.config.project {
+                    spans.push(Span::styled(
+                        format!("  {}", project),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let area = centered_rect(f.area(), 60, items.len() as u16 + 2);
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" Output rules (Space: switch on/off, Esc: close) "),
+            )
+            .highlight_style(Style::default().bg(Color::DarkGray))
+            .highlight_symbol("➤ ");
+
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(list, area, state);
+    }
+
+    fn draw_payload(f: &mut ratatui::Frame, payload: &mut Payload) {
+        let mut lines: Vec<Line> = payload
+            .lines
+            .iter()
+            .map(|line| Line::raw(line.as_str()))
+            .collect();
+        if let Some(exception) = &payload.exception {
+            lines.push(Line::default());
+            lines.extend(
+                exception
+                    .lines()
+                    .map(|line| Line::styled(line, Style::default().fg(Color::Red))),
+            );
+        }
+
+        let area = f.area();
+        let height = (lines.len() as u16 + 2).min(area.height.saturating_sub(2));
+        let area = centered_rect(area, 90, height.max(3));
+        // Clamp here so scrolling past the end does not accumulate
+        let visible = height.saturating_sub(2) as usize;
+        payload.scroll = payload.scroll.min(lines.len().saturating_sub(visible));
+        let title = if lines.len() > visible {
+            " Payload (↑/↓ to scroll, y: copy, any other key to close) "
+        } else {
+            " Payload (y: copy, any other key to close) "
+        };
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(lines)
+                .scroll((payload.scroll as u16, 0))
+                .block(Block::default().borders(Borders::ALL).title(title)),
+            area,
+        );
+    }
+
+    fn draw_filter(f: &mut ratatui::Frame, area: Rect, title: &str, filter: &TextInput) {
+        let area = Rect {
+            y: area.bottom().saturating_sub(3),
+            height: area.height.min(3),
+            ..area
+        };
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(filter.value.as_str())
+                .block(Block::default().borders(Borders::ALL).title(title)),
+            area,
+        );
+        f.set_cursor_position((area.x + 1 + filter.cursor() as u16, area.y + 1));
+    }
+
+    /// Rows of the current list, ordered by match score while a filter is active.
+    fn visible_rows(&self) -> Vec<ListRow> {
+        self.rows(self.view)
+    }
+
+    /// Rows of the list shown in `view`; the filter only applies to the current
+    /// view, and looks through the projects of folded folders too.
+    fn rows(&self, view: View) -> Vec<ListRow> {
+        let names: Vec<String> = match view {
+            View::Projects => self.projects.iter().map(|p| p.name.clone()).collect(),
+            _ => self
+                .sln_files
+                .iter()
+                .map(|sln| solution_name(&sln.path))
+                .collect(),
+        };
+
+        let Some(filter) = self.filter.as_ref().filter(|_| view == self.view) else {
+            if view == View::Projects && self.tree_shown() {
+                let mut rows = Vec::new();
+                self.push_tree_rows(&[], &mut rows);
+                return rows;
+            }
+            let mut indices: Vec<usize> = (0..names.len()).collect();
+            if view == View::Solutions && !self.state.pinned.is_empty() {
+                // The pinned ones first, in the order they were pinned
+                indices.sort_by_key(|&i| {
+                    self.pin_position(&self.sln_files[i].path)
+                        .unwrap_or(usize::MAX)
+                });
+            }
+            return indices
+                .into_iter()
+                .map(|i| ListRow {
+                    entry: ListEntry::Item(i),
+                    positions: Vec::new(),
+                    depth: 0,
+                })
+                .collect();
+        };
+
+        let mut rows: Vec<(i32, usize, Vec<usize>)> = names
+            .iter()
+            .enumerate()
+            .filter_map(|(i, name)| {
+                fuzzy_match(&filter.value, name).map(|m| (m.score, i, m.positions))
+            })
+            .collect();
+        rows.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        rows.into_iter()
+            .map(|(_, i, positions)| ListRow {
+                entry: ListEntry::Item(i),
+                positions,
+                depth: 0,
+            })
+            .collect()
+    }
+
+    /// Whether the projects are listed in their solution folders.
+    fn tree_shown(&self) -> bool {
+        !self.state.flat_projects && !self.folders.is_empty()
+    }
+
+    /// Rows of the folders and projects in `parent`, folders first, leaving
+    /// out what folded folders hold.
+    fn push_tree_rows(&self, parent: &[String], rows: &mut Vec<ListRow>) {
+        let depth = parent.len();
+        for (i, folder) in self.folders.iter().enumerate() {
+            if folder.len() != depth + 1 || !folder.starts_with(parent) {
+                continue;
+            }
+            rows.push(ListRow {
+                entry: ListEntry::Folder(i),
+                positions: Vec::new(),
+                depth,
+            });
+            if !self.collapsed_folders.contains(&folder.join("/")) {
+                self.push_tree_rows(folder, rows);
+            }
+        }
+        for (i, project) in self.projects.iter().enumerate() {
+            if project.folder == parent {
+                rows.push(ListRow {
+                    entry: ListEntry::Item(i),
+                    positions: Vec::new(),
+                    depth,
+                });
+            }
+        }
+    }
+
+    fn selected_row(&self) -> Option<ListRow> {
+        let state = if self.view == View::Projects {
+            &self.project_state
+        } else {
+            &self.sln_state
+        };
+        let selected = state.selected()?;
+        self.visible_rows().into_iter().nth(selected)
+    }
+
+    /// Index into `sln_files` or `projects` of the highlighted row, unless it
+    /// is a solution folder.
+    fn selected_index(&self) -> Option<usize> {
+        self.selected_row().and_then(|row| row.item())
+    }
+
+    /// Highlights the row of the solution or project, unfolding the folders
+    /// a project is in.
+    fn select_original(&mut self, index: usize) {
+        if self.view == View::Projects {
+            if let Some(project) = self.projects.get(index) {
+                for depth in 1..=project.folder.len() {
+                    self.collapsed_folders
+                        .remove(&project.folder[..depth].join("/"));
+                }
+            }
+        }
+        let row = self
+            .visible_rows()
+            .iter()
+            .position(|row| row.item() == Some(index))
+            .unwrap_or(0);
+        self.list_state().select(Some(row));
+    }
+
+    /// Keeps both highlights within their lists after the underlying vectors changed.
+    fn clamp_selection(&mut self) {
+        let solutions = self.rows(View::Solutions).len();
+        let projects = self.rows(View::Projects).len();
+        for (state, len) in [
+            (&mut self.sln_state, solutions),
+            (&mut self.project_state, projects),
+        ] {
+            let selected = state.selected().unwrap_or(0);
+            state.select(Some(selected.min(len.saturating_sub(1))));
+        }
+    }
+
+    fn list_state(&mut self) -> &mut ListState {
+        if self.view == View::Projects {
+            &mut self.project_state
+        } else {
+            &mut self.sln_state
+        }
+    }
+
+    /// Moves the highlight by one row, wrapping around at either end.
+    fn move_selection(&mut self, delta: isize) {
+        let len = self.visible_rows().len() as isize;
+        if len == 0 {
+            return;
+        }
+        let current = self.list_state().selected().unwrap_or(0) as isize;
+        self.select_row((current + delta).rem_euclid(len) as usize);
+    }
+
+    /// Moves the highlight by whole pages of the list viewport, stopping at the ends.
+    fn page_selection(&mut self, pages: isize) {
+        let current = self.list_state().selected().unwrap_or(0);
+        let delta = pages * self.list_height.max(1) as isize;
+        self.select_row(current.saturating_add_signed(delta));
+    }
+
+    fn select_row(&mut self, row: usize) {
+        self.preselect = None;
+        let last = self.visible_rows().len().saturating_sub(1);
+        self.list_state().select(Some(row.min(last)));
+    }
+
+    fn on_enter_key(&mut self) -> Result<()> {
+        if self.view == View::Projects {
+            if let Some(ListEntry::Folder(folder)) = self.selected_row().map(|row| row.entry) {
+                let key = self.folders[folder].join("/");
+                if !self.collapsed_folders.remove(&key) {
+                    self.collapsed_folders.insert(key);
+                }
+                return Ok(());
+            }
+            if self.pick == Some(Pick::Project) {
+                self.picked = self
+                    .selected_index()
+                    .and_then(|selected| self.projects.get(selected))
+                    .map(|project| project.path.clone());
+                self.exit = self.picked.is_some();
+                return Ok(());
+            }
+            self.run_selected_project()?;
+            return Ok(());
+        }
+
+        if self.pick == Some(Pick::Solution) {
+            self.picked = self.highlighted_solution().map(PathBuf::from);
+            self.exit = self.picked.is_some();
+            return Ok(());
+        }
+        self.select_solution()?;
+        Ok(())
+    }
+
+    fn selected_target(&self) -> Result<Option<RunTarget>> {
+        self.selected_index()
+            .and_then(|selected| self.projects.get(selected))
+            .map(Self::run_target_for)
+            .transpose()
+    }
+
+    fn run_target_for(project: &Project) -> Result<RunTarget> {
+        let project_path = project.path.clone();
+        let project_dir = project_path
+            .parent()
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!(
+                        "cannot determine project directory of {}",
+                        project_path.display()
+                    ),
+                )
+            })?
+            .to_path_buf();
+
+        Ok(RunTarget {
+            project: project.name.clone(),
+            project_path,
+            project_dir,
+            args: Vec::new(),
+            auto_restart: false,
+            framework: None,
+        })
+    }
+
+    /// Runs what can run. Libraries are only built and test projects tested,
+    /// unless forced to run with `R`.
+    fn run_selected_project(&mut self) -> Result<()> {
+        if !self.marked.is_empty() {
+            let (runnable, skipped): (Vec<&Project>, Vec<&Project>) = self
+                .projects
+                .iter()
+                .filter(|p| self.marked.contains(&p.path))
+                .partition(|p| p.output.is_executable());
+            let targets = runnable
+                .into_iter()
+                .map(Self::run_target_for)
+                .collect::<Result<Vec<_>>>()?;
+            let skipped: Vec<String> = skipped.iter().map(|p| p.name.clone()).collect();
+            self.marked.clear();
+            if !skipped.is_empty() {
+                self.log_warn(format!(
+                    "Not starting {}, which can't run on their own",
+                    skipped.join(", ")
+                ));
+            }
+            if targets.is_empty() {
+                return Ok(());
+            }
+            self.log_info(format!("Starting {} projects", targets.len()));
+            self.launch_queue.extend(targets);
+            self.advance_launch_queue();
+            return Ok(());
+        }
+
+        let Some(output) = self
+            .selected_index()
+            .and_then(|selected| self.projects.get(selected))
+            .map(|project| project.output)
+        else {
+            return Err(AppError::NoProjectSelected);
+        };
+        let Some(target) = self.selected_target()? else {
+            return Err(AppError::NoProjectSelected);
+        };
+        match output {
+            OutputKind::Test => self.run_tests(),
+            OutputKind::Library => self.build_project(target),
+            _ => self.run_target(target),
+        }
+    }
+
+    fn run_target(&mut self, mut target: RunTarget) -> Result<()> {
+        if self.dotnet.is_none() {
+            return Err(AppError::DotnetMissing);
+        }
+
+        if self.build.is_some() {
+            self.log_warn("A build is already running");
+            return Ok(());
+        }
+
+        if !target.project_path.is_file() {
+            self.log_error(format!(
+                "Project file not found at {}",
+                target.project_path.display()
+            ));
+            return Ok(());
+        }
+
+        let key = target.project_path.to_string_lossy().into_owned();
+        let frameworks = target_frameworks(&target.project_path);
+        if frameworks.len() > 1 && target.framework.is_none() {
+            let remembered = self
+                .state
+                .frameworks
+                .get(&key)
+                .filter(|framework| frameworks.contains(framework));
+            match remembered {
+                Some(framework) => target.framework = Some(framework.clone()),
+                None => {
+                    self.open_framework_picker(target, frameworks);
+                    return Ok(());
+                }
+            }
+        }
+
+        let profiles = read_launch_profiles(&target.project_dir);
+        let remembered = self
+            .state
+            .launch_profiles
+            .get(&key)
+            .filter(|choice| match choice {
+                Some(name) => profiles.iter().any(|p| &p.name == name && p.is_supported()),
+                None => true,
+            })
+            .cloned();
+
+        let supported: Vec<&LaunchProfile> = profiles.iter().filter(|p| p.is_supported()).collect();
+        match (remembered, supported.as_slice()) {
+            (Some(choice), _) => self.start_build(target, choice),
+            (None, []) => self.start_build(target, None),
+            (None, [profile]) => {
+                let name = profile.name.clone();
+                self.start_build(target, Some(name))
+            }
+            (None, _) => {
+                self.open_profile_picker(target, profiles);
+                Ok(())
+            }
+        }
+    }
+
+    fn choose_launch_profile(&mut self) -> Result<()> {
+        if let Some(target) = self.selected_target()? {
+            let profiles = read_launch_profiles(&target.project_dir);
+            if profiles.is_empty() {
+                self.log_info(format!("{} has no launch profiles", target.project));
+                return Ok(());
+            }
+            self.open_profile_picker(target, profiles);
+        }
+        Ok(())
+    }
+
+    fn choose_framework(&mut self) -> Result<()> {
+        if let Some(target) = self.selected_target()? {
+            let frameworks = target_frameworks(&target.project_path);
+            if frameworks.len() < 2 {
+                self.log_info(format!(
+                    "{} targets {} only",
+                    target.project,
+                    frameworks.first().map_or("one framework", String::as_str)
+                ));
+                return Ok(());
+            }
+            self.open_framework_picker(target, frameworks);
+        }
+        Ok(())
+    }
+
+    fn open_framework_picker(&mut self, target: RunTarget, frameworks: Vec<String>) {
+        let key = target.project_path.to_string_lossy();
+        let selected = self
+            .state
+            .frameworks
+            .get(key.as_ref())
+            .and_then(|chosen| frameworks.iter().position(|f| f == chosen))
+            .unwrap_or(0);
+        self.framework_picker = Some(FrameworkPicker {
+            target,
+            frameworks,
+            state: ListState::default().with_selected(Some(selected)),
+        });
+    }
+
+    fn handle_framework_picker_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(picker) = self.framework_picker.as_mut() else {
+            return Ok(());
+        };
+        let current = picker.state.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Esc => self.framework_picker = None,
+            KeyCode::Up => picker.state.select(Some(current.saturating_sub(1))),
+            KeyCode::Down => {
+                let last = picker.frameworks.len() - 1;
+                picker.state.select(Some((current + 1).min(last)));
+            }
+            KeyCode::Enter => {
+                let Some(mut picker) = self.framework_picker.take() else {
+                    return Ok(());
+                };
+                let framework = picker.frameworks.swap_remove(current);
+                let key = picker.target.project_path.to_string_lossy().into_owned();
+                self.state.frameworks.insert(key, framework.clone());
+                self.save_state();
+                picker.target.framework = Some(framework);
+                self.run_target(picker.target)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// The installed SDKs, asked of dotnet the first time.
+    fn installed_sdks(&mut self) -> Result<&[String]> {
+        if self.sdks.is_none() {
+            self.sdks = Some(sdk::list_sdks()?);
+        }
+        Ok(self.sdks.as_deref().unwrap_or_default())
+    }
+
+    /// Looks for the global.json that applies to the solution and warns when
+    /// none of the installed SDKs satisfies it.
+    fn check_global_json(&mut self) {
+        let dir = Path::new(&self.selected_sln)
+            .parent()
+            .map(Path::to_path_buf);
+        let global_json = dir.as_deref().and_then(GlobalJson::find);
+        let unsatisfied = match global_json {
+            // `dotnet --version` itself fails where no SDK satisfies it
+            Some(global_json) => match self.installed_sdks() {
+                Ok(sdks) if !sdks.iter().any(|sdk| global_json.allows(sdk)) => Some(global_json),
+                Ok(_) => None,
+                Err(AppError::DotnetMissing) => None,
+                Err(e) => {
+                    self.log_warn(format!("Could not list the installed SDKs: {}", e));
+                    None
+                }
+            },
+            None => None,
+        };
+        if let Some(global_json) = unsatisfied
+            .as_ref()
+            .filter(|g| self.global_json.as_ref() != Some(*g))
+        {
+            let installed = self.sdks.as_deref().unwrap_or_default();
+            self.log_warn(format!(
+                "{} asks for .NET SDK {}, which is not installed (installed: {})",
+                global_json.path.display(),
+                global_json.requirement(),
+                if installed.is_empty() {
+                    "none".to_string()
+                } else {
+                    installed.join(", ")
+                }
+            ));
+        }
+        self.global_json = unsatisfied;
+    }
+
+    fn open_sdk_picker(&mut self) -> Result<()> {
+        let Some(target) = self.selected_target()? else {
+            return Err(AppError::NoProjectSelected);
+        };
+        let mut choices = vec![SdkChoice::Default];
+        choices.extend(
+            self.installed_sdks()?
+                .iter()
+                .rev()
+                .cloned()
+                .map(SdkChoice::Sdk),
+        );
+        choices.push(SdkChoice::RollForward);
+        let current = match self.sdk_pins.get(&target.project_path) {
+            Some(SdkPin::Sdk { version, .. }) => SdkChoice::Sdk(version.clone()),
+            Some(SdkPin::RollForward) => SdkChoice::RollForward,
+            None => SdkChoice::Default,
+        };
+        let selected = choices.iter().position(|c| *c == current).unwrap_or(0);
+        self.sdk_picker = Some(SdkPicker {
+            target,
+            choices,
+            state: ListState::default().with_selected(Some(selected)),
+        });
+        Ok(())
+    }
+
+    fn handle_sdk_picker_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(picker) = self.sdk_picker.as_mut() else {
+            return Ok(());
+        };
+        let current = picker.state.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Esc => self.sdk_picker = None,
+            KeyCode::Up => picker.state.select(Some(current.saturating_sub(1))),
+            KeyCode::Down => {
+                let last = picker.choices.len() - 1;
+                picker.state.select(Some((current + 1).min(last)));
+            }
+            KeyCode::Enter => {
+                let Some(mut picker) = self.sdk_picker.take() else {
+                    return Ok(());
+                };
+                let choice = picker.choices.swap_remove(current);
+                self.pin_sdk(&picker.target, choice);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Holds the project's runs to the chosen SDK from now on, undoing the
+    /// previous choice.
+    fn pin_sdk(&mut self, target: &RunTarget, choice: SdkChoice) {
+        if let Some(SdkPin::Sdk { file, .. }) = self.sdk_pins.remove(&target.project_path) {
+            if let Err(e) = fs::remove_file(&file) {
+                self.log_warn(format!("Failed to delete {}: {}", file.display(), e));
+            }
+        }
+        let pin = match choice {
+            SdkChoice::Default => None,
+            SdkChoice::Sdk(version) => match sdk::write_pin(&target.project_dir, &version) {
+                Ok(file) => Some(SdkPin::Sdk { version, file }),
+                Err(e) => {
+                    self.log_error(format!(
+                        "Cannot pin {} to .NET SDK {}: {}",
+                        target.project, version, e
+                    ));
+                    return;
+                }
+            },
+            SdkChoice::RollForward => Some(SdkPin::RollForward),
+        };
+        let message = match &pin {
+            None => format!("{} runs with the default SDK", target.project),
+            Some(SdkPin::Sdk { version, file }) => format!(
+                "{} runs with .NET SDK {}, through {} until sln-runner quits",
+                target.project,
+                version,
+                file.display()
+            ),
+            Some(SdkPin::RollForward) => format!(
+                "{} runs with DOTNET_ROLL_FORWARD=LatestMajor",
+                target.project
+            ),
+        };
+        if let Some(pin) = pin {
+            self.sdk_pins.insert(target.project_path.clone(), pin);
+        }
+        self.status.flash(message.clone());
+        self.log_info(message);
+    }
+
+    fn open_profile_picker(&mut self, target: RunTarget, profiles: Vec<LaunchProfile>) {
+        let selected = profiles.iter().position(|p| p.is_supported()).unwrap_or(0);
+        self.profile_picker = Some(ProfilePicker {
+            target,
+            profiles,
+            state: ListState::default().with_selected(Some(selected)),
+        });
+    }
+
+    fn handle_profile_picker_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(picker) = self.profile_picker.as_mut() else {
+            return Ok(());
+        };
+
+        // The last row is the "no profile" entry
+        let len = picker.profiles.len() + 1;
+        let current = picker.state.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Esc => self.profile_picker = None,
+            KeyCode::Up => picker.state.select(Some(current.saturating_sub(1))),
+            KeyCode::Down => picker.state.select(Some((current + 1).min(len - 1))),
+            KeyCode::Enter => {
+                let choice = match picker.profiles.get(current) {
+                    Some(profile) if !profile.is_supported() => {
+                        let message = format!(
+                            "Profile '{}' uses commandName '{}', which dotnet run does not support",
+                            profile.name,
+                            profile.command_name.as_deref().unwrap_or("?")
+                        );
+                        self.log_info(message);
+                        return Ok(());
+                    }
+                    Some(profile) => Some(profile.name.clone()),
+                    None => None,
+                };
+
+                let Some(picker) = self.profile_picker.take() else {
+                    return Ok(());
+                };
+                let key = picker.target.project_path.to_string_lossy().into_owned();
+                self.state.launch_profiles.insert(key, choice.clone());
+                self.save_state();
+                self.start_build(picker.target, choice)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn cycle_configuration(&mut self) {
+        if self.configurations.is_empty() {
+            return;
+        }
+        let next = self
+            .configurations
+            .iter()
+            .position(|c| *c == self.configuration)
+            .map_or(0, |i| (i + 1) % self.configurations.len());
+        self.configuration = self.configurations[next].clone();
+        self.state
+            .configurations
+            .insert(self.selected_sln.clone(), self.configuration.clone());
+        self.save_state();
+        let message = format!("Configuration: {}", self.configuration);
+        self.status.flash(message.clone());
+        self.log_info(message);
+    }
+
+    fn is_watched(&self, project_path: &Path) -> bool {
+        self.state
+            .watch
+            .contains(project_path.to_string_lossy().as_ref())
+    }
+
+    fn toggle_watch(&mut self) -> Result<()> {
+        let Some(target) = self.selected_target()? else {
+            return Ok(());
+        };
+        let key = target.project_path.to_string_lossy().into_owned();
+        let watched = !self.state.watch.remove(&key);
+        if watched {
+            self.state.watch.insert(key);
+        }
+        self.save_state();
+        let message = format!(
+            "dotnet watch {} for {}",
+            if watched { "on" } else { "off" },
+            target.project
+        );
+        self.status.flash(message.clone());
+        self.log_info(message);
+        Ok(())
+    }
+
+    fn runs_spa(&self, project_path: &Path) -> bool {
+        self.state
+            .spa
+            .contains(project_path.to_string_lossy().as_ref())
+    }
+
+    fn toggle_spa(&mut self) -> Result<()> {
+        let Some(target) = self.selected_target()? else {
+            return Ok(());
+        };
+        let key = target.project_path.to_string_lossy().into_owned();
+        if self.state.spa.remove(&key) {
+            self.save_state();
+            let message = format!("SPA dev server off for {}", target.project);
+            self.status.flash(message.clone());
+            self.log_info(message);
+            return Ok(());
+        }
+        let Some(spa) = Spa::detect(&target.project_path) else {
+            self.log_warn(format!(
+                "No SPA found for {}: no SpaRoot in the project file and no package.json with a dev or start script",
+                target.project
+            ));
+            return Ok(());
+        };
+        self.state.spa.insert(key);
+        self.save_state();
+        let message = format!(
+            "SPA dev server on for {}: {} in {}",
+            target.project,
+            spa.command,
+            spa.root.display()
+        );
+        self.status.flash(message.clone());
+        self.log_info(message);
+        Ok(())
+    }
+
+    fn save_state(&mut self) {
+        let Some(path) = &self.state_path else {
+            return;
+        };
+        if let Err(e) = self.state.save(path) {
+            self.log_error(format!("Failed to save state {}: {}", path.display(), e));
+        }
+    }
+
+    /// Remembers how the build went, with what it reported, for the project list.
+    fn record_build(&mut self, project: &str, path: &Path, succeeded: bool, duration: f32) {
+        let (errors, warnings) = self.problems.counts(Some(project));
+        let key = path.to_string_lossy().into_owned();
+        self.state.builds.insert(
+            key,
+            BuildRecord {
+                succeeded,
+                duration,
+                errors,
+                warnings,
+                finished: Local::now().timestamp(),
+            },
+        );
+        self.save_state();
+    }
+
+    fn start_build(&mut self, target: RunTarget, launch_profile: Option<String>) -> Result<()> {
+        let urls = self.profile_urls(&target, launch_profile.as_deref());
+        let conflicts = find_conflicts(&urls);
+        if conflicts.is_empty() {
+            return self.build_and_run(target, launch_profile);
+        }
+
+        for conflict in &conflicts {
+            self.log_warn(conflict.describe());
+        }
+        let mut choices = vec![PortChoice::OtherPort, PortChoice::Abort];
+        if conflicts.iter().any(|c| c.owner.is_some()) {
+            choices.insert(0, PortChoice::Kill);
+        }
+        self.port_prompt = Some(PortPrompt {
+            target,
+            launch_profile,
+            urls,
+            conflicts,
+            choices,
+            state: ListState::default().with_selected(Some(0)),
+        });
+        Ok(())
+    }
+
+    /// The addresses the launch profile will listen on, for checking them
+    /// before the run. Empty when the check does not apply.
+    fn profile_urls(&self, target: &RunTarget, launch_profile: Option<&str>) -> Vec<String> {
+        // Our own process of the project is stopped before the new one starts,
+        // and explicit --urls replace the profile's
+        let running = self
+            .processes
+            .iter()
+            .any(|p| p.status.is_none() && p.project_dir == target.project_dir);
+        if running || target.args.iter().any(|arg| arg == "--urls") {
+            return Vec::new();
+        }
+        let Some(name) = launch_profile else {
+            return Vec::new();
+        };
+        read_launch_profiles(&target.project_dir)
+            .into_iter()
+            .find(|p| p.name == name)
+            .and_then(|p| p.application_url)
+            .map(|urls| urls.split(';').map(|url| url.trim().to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    fn handle_port_prompt_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(prompt) = self.port_prompt.as_mut() else {
+            return Ok(());
+        };
+        let current = prompt.state.selected().unwrap_or(0);
+        let choice = match key.code {
+            KeyCode::Up => {
+                prompt.state.select(Some(current.saturating_sub(1)));
+                return Ok(());
+            }
+            KeyCode::Down => {
+                let last = prompt.choices.len() - 1;
+                prompt.state.select(Some((current + 1).min(last)));
+                return Ok(());
+            }
+            KeyCode::Enter => prompt.choices[current],
+            KeyCode::Esc => PortChoice::Abort,
+            _ => return Ok(()),
+        };
+        let Some(mut prompt) = self.port_prompt.take() else {
+            return Ok(());
+        };
+
+        match choice {
+            PortChoice::Kill => {
+                for conflict in &prompt.conflicts {
+                    let Some(owner) = &conflict.owner else {
+                        continue;
+                    };
+                    match kill_process(owner.pid) {
+                        Ok(()) => self.log_info(format!(
+                            "Killed PID {} to free port {}",
+                            owner.pid, conflict.port
+                        )),
+                        Err(e) => {
+                            self.log_error(format!("Failed to kill PID {}: {}", owner.pid, e))
+                        }
+                    }
+                }
+            }
+            PortChoice::OtherPort => {
+                let mut urls = Vec::new();
+                for url in &prompt.urls {
+                    let taken = prompt.conflicts.iter().any(|c| &c.url == url);
+                    urls.push(if taken {
+                        with_port(url, free_port()?)
+                    } else {
+                        url.clone()
+                    });
+                }
+                let urls = urls.join(";");
+                self.log_info(format!("Running {} on {}", prompt.target.project, urls));
+                prompt.target.args.extend(["--urls".to_string(), urls]);
+            }
+            PortChoice::Abort => {
+                self.log_info(format!(
+                    "Not running {}: port in use",
+                    prompt.target.project
+                ));
+                return Ok(());
+            }
+        }
+        self.build_and_run(prompt.target, prompt.launch_profile)
+    }
+
+    /// Quits, first asking what to do with processes that are still running.
+    /// Quitting again while they are being stopped kills them instead.
+    fn request_quit(&mut self) {
+        let processes: Vec<String> = self
+            .processes
+            .iter()
+            .filter(|p| p.status.is_none())
+            .map(|p| {
+                let line = format!(
+                    "{}  PID {}  {}",
+                    p.project,
+                    p.job.id(),
+                    p.known_urls().join(" ")
+                );
+                line.trim_end().to_string()
+            })
+            .collect();
+        if processes.is_empty() || self.quit_when_stopped {
+            self.exit = true;
+            return;
+        }
+        self.quit_prompt = Some(QuitPrompt {
+            processes,
+            state: ListState::default().with_selected(Some(0)),
+        });
+    }
+
+    fn handle_quit_prompt_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(prompt) = self.quit_prompt.as_mut() else {
+            return Ok(());
+        };
+        let current = prompt.state.selected().unwrap_or(0);
+        let choice = match key.code {
+            KeyCode::Up => {
+                prompt.state.select(Some(current.saturating_sub(1)));
+                return Ok(());
+            }
+            KeyCode::Down => {
+                let last = QuitChoice::ALL.len() - 1;
+                prompt.state.select(Some((current + 1).min(last)));
+                return Ok(());
+            }
+            KeyCode::Enter => QuitChoice::ALL[current],
+            KeyCode::Esc => QuitChoice::Cancel,
+            _ => return Ok(()),
+        };
+        self.quit_prompt = None;
+
+        match choice {
+            QuitChoice::StopAll => {
+                self.stop_all()?;
+                self.quit_when_stopped = true;
+                self.status
+                    .flash("Stopping all processes before quitting, quit again to kill them");
+            }
+            QuitChoice::Detach => {
+                for hook in self.hook_jobs.iter_mut().filter(|h| h.companion.is_some()) {
+                    hook.job.detach();
+                }
+                // Dropped detached, so that the services are not brought down
+                for mut compose in self.compose_jobs.drain(..) {
+                    compose.job.detach();
+                }
+                for mut process in self.processes.drain(..).filter(|p| p.status.is_none()) {
+                    process.job.detach();
+                    self.detached.push(DetachedProcess {
+                        project: process.project.clone(),
+                        pid: process.job.id(),
+                        urls: process.known_urls().to_vec(),
+                        container: process.container.as_ref().map(|c| c.name.clone()),
+                    });
+                }
+                self.exit = true;
+            }
+            QuitChoice::Cancel => {}
+        }
+        Ok(())
+    }
+
+    fn build_and_run(&mut self, target: RunTarget, launch_profile: Option<String>) -> Result<()> {
+        if self.config.auto_restore && !self.restored.contains(&self.selected_sln) {
+            let name = solution_name(&self.selected_sln);
+            let path = PathBuf::from(&self.selected_sln);
+            return self.start_restore(name, path, Some((target, launch_profile)));
+        }
+        self.state.last_projects.insert(
+            self.selected_sln.clone(),
+            target.project_path.to_string_lossy().into_owned(),
+        );
+        self.state
+            .last_runs
+            .insert(self.selected_sln.clone(), Local::now().timestamp());
+        self.save_state();
+        if self.solution_sort() == SolutionSort::Recent {
+            self.sort_solutions();
+        }
+
+        let configuration = self.configuration.clone();
+        // Watch builds on its own and keeps rebuilding, so there is nothing to wait for
+        if self.is_watched(&target.project_path) {
+            self.log_info(format!("Watching project: {}", target.project));
+            return self.launch_project(target, launch_profile, configuration, RunMode::Watch);
+        }
+        if self.build_strategy == BuildStrategy::Run {
+            self.log_info(format!("Building and running project: {}", target.project));
+            return self.launch_project(target, launch_profile, configuration, RunMode::Run);
+        }
+        self.log_info(format!("Building project: {}", target.project));
+        self.start_project_build(target, launch_profile, true)
+    }
+
+    /// Builds a project that has nothing to run, like a class library.
+    fn build_project(&mut self, target: RunTarget) -> Result<()> {
+        if self.dotnet.is_none() {
+            return Err(AppError::DotnetMissing);
+        }
+        if self.busy() {
+            self.log_warn("A build is already running");
+            return Ok(());
+        }
+        if !target.project_path.is_file() {
+            self.log_error(format!(
+                "Project file not found at {}",
+                target.project_path.display()
+            ));
+            return Ok(());
+        }
+        self.log_info(format!(
+            "Building library: {} (R runs it anyway)",
+            target.project
+        ));
+        self.start_project_build(target, None, false)
+    }
+
+    fn start_project_build(
+        &mut self,
+        target: RunTarget,
+        launch_profile: Option<String>,
+        run: bool,
+    ) -> Result<()> {
+        let hooks = self.project_hooks(&target.project);
+        let held = self.hold_for_hooks(
+            &target.project,
+            &hooks,
+            &target.project_dir,
+            &[HookStage::PreBuild],
+            || AfterHooks::Build {
+                target: target.clone(),
+                launch_profile: launch_profile.clone(),
+                run,
+            },
+        )?;
+        if held {
+            return Ok(());
+        }
+        let binlog = self.binlog_path(&target.project_path, &target.project);
+        let job = self.spawn_build(
+            &target.project_path,
+            Some(&target.project),
+            target.framework.as_deref(),
+            binlog.as_deref(),
+        )?;
+        self.problems.clear_build(&target.project);
+        self.build = Some(Build {
+            job,
+            errors: Vec::new(),
+            diagnostics: DiagnosticParser::default(),
+            last_problem: target.project.clone(),
+            target,
+            launch_profile,
+            configuration: self.configuration.clone(),
+            feed_auth: FeedAuthDetector::default(),
+            solution: false,
+            run,
+            solution_filter: None,
+            built: HashSet::new(),
+            binlog,
+        });
+        Ok(())
+    }
+
+    /// Builds every project of the solution in one `dotnet build`, to see
+    /// that it all compiles. Nothing runs afterwards.
+    fn build_solution(&mut self) -> Result<()> {
+        if self.dotnet.is_none() {
+            return Err(AppError::DotnetMissing);
+        }
+        if self.busy() {
+            self.log_warn("A build is already running");
+            return Ok(());
+        }
+        let target = RunTarget {
+            project: solution_name(&self.selected_sln),
+            project_dir: self
+                .sln_file
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default(),
+            project_path: PathBuf::from(&self.selected_sln),
+            args: Vec::new(),
+            auto_restart: false,
+            framework: None,
+        };
+        self.log_info(format!(
+            "Building solution: {} ({})",
+            target.project, self.configuration
+        ));
+        let projects: Vec<String> = self.projects.iter().map(|p| p.name.clone()).collect();
+        self.start_solution_build(target, &projects, None)
+    }
+
+    /// Builds the project and every project that references it, directly or
+    /// through others, through a solution filter of just those projects.
+    fn build_impacted(&mut self, index: usize) -> Result<()> {
+        if self.dotnet.is_none() {
+            return Err(AppError::DotnetMissing);
+        }
+        if self.busy() {
+            self.log_warn("A build is already running");
+            return Ok(());
+        }
+        self.load_graph();
+        if self.graph_load.is_some() {
+            self.status
+                .flash("Still reading the project references, try again in a moment");
+            return Ok(());
+        }
+        let Some(graph) = self.graph.as_ref() else {
+            return Ok(());
+        };
+        let dependents = graph.dependents(index);
+        let name = self.projects[index].name.clone();
+        if dependents.is_empty() {
+            let target = Self::run_target_for(&self.projects[index])?;
+            self.log_info(format!("Nothing references {}", name));
+            return self.build_project(target);
+        }
+
+        let mut names: Vec<String> = dependents
+            .iter()
+            .map(|&i| self.projects[i].name.clone())
+            .collect();
+        names.sort();
+        self.log_info(format!(
+            "Building {} and the projects that depend on it: {} ({})",
+            name,
+            names.join(", "),
+            self.configuration
+        ));
+        let filter = std::env::temp_dir().join(format!("sln-runner-{}.slnf", std::process::id()));
+        let solution = self
+            .sln_file
+            .canonicalize()
+            .unwrap_or_else(|_| self.sln_file.clone());
+        let contents = serde_json::json!({
+            "solution": {
+                "path": solution,
+                "projects": std::iter::once(index)
+                    .chain(dependents.iter().copied())
+                    .map(|i| self.projects[i].rel_path.as_str())
+                    .collect::<Vec<_>>(),
+            }
+        });
+        fs::write(&filter, contents.to_string())?;
+
+        let target = RunTarget {
+            project: format!("{} and {} dependents", name, dependents.len()),
+            project_path: filter.clone(),
+            project_dir: solution.parent().map(Path::to_path_buf).unwrap_or_default(),
+            args: Vec::new(),
+            auto_restart: false,
+            framework: None,
+        };
+        names.push(name);
+        self.start_solution_build(target, &names, Some(filter))
+    }
+
+    /// Builds a solution, or the part of it a solution filter picks, giving
+    /// each of `projects` the problems it reported.
+    fn start_solution_build(
+        &mut self,
+        target: RunTarget,
+        projects: &[String],
+        solution_filter: Option<PathBuf>,
+    ) -> Result<()> {
+        let solution = PathBuf::from(&self.selected_sln);
+        let binlog = self.binlog_path(&solution, &target.project);
+        let job = self.spawn_build(&target.project_path, None, None, binlog.as_deref())?;
+        self.problems.clear_build(&target.project);
+        for project in projects {
+            self.problems.clear_build(project);
+        }
+        self.build = Some(Build {
+            job,
+            errors: Vec::new(),
+            diagnostics: DiagnosticParser::default(),
+            last_problem: target.project.clone(),
+            target,
+            launch_profile: None,
+            configuration: self.configuration.clone(),
+            feed_auth: FeedAuthDetector::default(),
+            solution: true,
+            run: false,
+            solution_filter,
+            built: HashSet::new(),
+            binlog,
+        });
+        Ok(())
+    }
+
+    fn spawn_build(
+        &self,
+        path: &Path,
+        project: Option<&str>,
+        framework: Option<&str>,
+        binlog: Option<&Path>,
+    ) -> Result<Job> {
+        let mut command = build_command(path, &self.configuration, &self.build_args_for(project));
+        command.arg("--verbosity").arg(self.state.verbosity.label());
+        // dotnet looks for the pinning global.json from where it runs
+        if let (Some(SdkPin::Sdk { .. }), Some(dir)) = (self.sdk_pins.get(path), path.parent()) {
+            command.current_dir(dir);
+        }
+        if let Some(framework) = framework {
+            command.arg("--framework").arg(framework);
+        }
+        if let Some(binlog) = binlog {
+            let mut arg = OsString::from("-bl:");
+            arg.push(binlog);
+            command.arg(arg);
+        }
+        Job::spawn(&mut command).map_err(dotnet_spawn_error)
+    }
+
+    /// Where the build of the project or solution at `path` writes its binary
+    /// log, when that is switched on.
+    fn binlog_path(&self, path: &Path, name: &str) -> Option<PathBuf> {
+        if !self.binlog {
+            return None;
+        }
+        match &self.config.binlog_dir {
+            Some(dir) => Some(dir.join(format!("{}.binlog", name))),
+            None => path.parent().map(|dir| dir.join("msbuild.binlog")),
+        }
+    }
+
+    fn cancel_build(&mut self) -> Result<()> {
+        if let Some(mut build) = self.build.take() {
+            if let Some(filter) = &build.solution_filter {
+                let _ = fs::remove_file(filter);
+            }
+            if let Err(e) = build.job.kill() {
+                self.log_error(format!("Failed to kill the build: {}", e));
+            }
+            for line in build.job.drain() {
+                self.log_output(line);
+            }
+            self.log_warn(format!("Build of {} cancelled", build.target.project));
+        }
+        Ok(())
+    }
+
+    /// Whether a build, test run, publish or clean is under way; one at a time.
+    fn busy(&self) -> bool {
+        self.build.is_some()
+            || self.tests.is_some()
+            || self.publish.is_some()
+            || self.restore.is_some()
+            || self.clean.is_some()
+            || self.deep_clean.is_some()
+            || self.docker_build.is_some()
+            || self.compose_wait().is_some()
+            || self.spa_wait.is_some()
+            || self.ef_job.is_some()
+            || self.secrets_job.is_some()
+            || self.packages_job.is_some()
+            || self.dev_cert_job.is_some()
+            || self.pre_hook.is_some()
+    }
+
+    /// What is building, like "⠋ Testing Api.Tests… 3s".
+    fn busy_activity(&self) -> Option<String> {
+        let (verb, name, started) = if let Some(build) = &self.build {
+            ("Building", &build.target.project, build.job.started)
+        } else if let Some(tests) = &self.tests {
+            ("Testing", &tests.name, tests.job.started)
+        } else if let Some(publish) = &self.publish {
+            ("Publishing", &publish.target.project, publish.job.started)
+        } else if let Some(restore) = &self.restore {
+            ("Restoring", &restore.name, restore.job.started)
+        } else if let Some(clean) = &self.clean {
+            ("Cleaning", &clean.name, clean.job.started)
+        } else if let Some(deep_clean) = &self.deep_clean {
+            (
+                "Deleting bin/ and obj/ of",
+                &deep_clean.name,
+                deep_clean.started,
+            )
+        } else if let Some(build) = &self.docker_build {
+            (
+                "Building the image of",
+                &build.target.project,
+                build.job.started,
+            )
+        } else if let Some((group, started)) = self.compose_wait() {
+            ("Bringing up the compose services of", group, started)
+        } else if let Some(wait) = &self.spa_wait {
+            (
+                "Waiting for the SPA dev server of",
+                &wait.project,
+                wait.started,
+            )
+        } else if let Some(ef) = &self.ef_job {
+            let verb = match ef.step {
+                EfStep::List => "Listing the migrations of",
+                EfStep::Update(None) => "Updating the database of",
+                EfStep::Update(Some(_)) => "Rolling back the database of",
+                EfStep::Add(_) => "Adding a migration to",
+                EfStep::Install => "Installing dotnet-ef for",
+            };
+            (verb, &ef.project, ef.job.started)
+        } else if let Some(secrets) = &self.secrets_job {
+            let verb = match secrets.step {
+                SecretsStep::List => "Listing the user secrets of",
+                SecretsStep::Set { .. } => "Setting a user secret of",
+                SecretsStep::Remove(_) => "Removing a user secret of",
+                SecretsStep::Init => "Initializing the user secrets of",
+            };
+            (verb, &secrets.project, secrets.job.started)
+        } else if let Some(packages) = &self.packages_job {
+            let verb = match packages.report {
+                Report::Outdated => "Looking for outdated packages of",
+                Report::Vulnerable => "Looking for vulnerable packages of",
+            };
+            (verb, &packages.name, packages.job.started)
+        } else if let Some(dev_cert) = &self.dev_cert_job {
+            (
+                "Trusting the HTTPS development certificate for",
+                &dev_cert.project,
+                dev_cert.job.started,
+            )
+        } else if let Some((hook, _)) = &self.pre_hook {
+            let verb = match hook.stage {
+                HookStage::PreBuild => "Running pre_build hook of",
+                _ => "Running pre_run hook of",
+            };
+            (verb, &hook.owner, hook.job.started)
+        } else {
+            return None;
+        };
+        Some(format!(
+            "{} {} {}… {}s",
+            SPINNER[self.tick % SPINNER.len()],
+            verb,
+            name,
+            started.elapsed().as_secs()
+        ))
+    }
+
+    fn open_restore_menu(&mut self) -> Result<()> {
+        let Some(target) = self.selected_target()? else {
+            return Err(AppError::NoProjectSelected);
+        };
+        self.restore_menu = Some(RestoreMenu {
+            target,
+            state: ListState::default().with_selected(Some(0)),
+        });
+        Ok(())
+    }
+
+    fn handle_restore_menu_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(menu) = self.restore_menu.as_mut() else {
+            return Ok(());
+        };
+        let current = menu.state.selected().unwrap_or(0);
+        let choice = match key.code {
+            KeyCode::Up => {
+                menu.state.select(Some(current.saturating_sub(1)));
+                return Ok(());
+            }
+            KeyCode::Down => {
+                let last = RestoreChoice::ALL.len() - 1;
+                menu.state.select(Some((current + 1).min(last)));
+                return Ok(());
+            }
+            KeyCode::Enter => RestoreChoice::ALL[current],
+            KeyCode::Esc => RestoreChoice::Cancel,
+            _ => return Ok(()),
+        };
+        let Some(menu) = self.restore_menu.take() else {
+            return Ok(());
+        };
+
+        match choice {
+            RestoreChoice::Project => {
+                self.start_restore(menu.target.project, menu.target.project_path, None)
+            }
+            RestoreChoice::Solution => self.start_restore(
+                solution_name(&self.selected_sln),
+                PathBuf::from(&self.selected_sln),
+                None,
+            ),
+            RestoreChoice::Cancel => Ok(()),
+        }
+    }
+
+    /// Runs `dotnet restore`, then the launch in `then` once it succeeds.
+    fn start_restore(
+        &mut self,
+        name: String,
+        path: PathBuf,
+        then: Option<(RunTarget, Option<String>)>,
+    ) -> Result<()> {
+        if self.dotnet.is_none() {
+            return Err(AppError::DotnetMissing);
+        }
+        if self.busy() {
+            self.log_warn("Wait for the running build to finish before restoring");
+            return Ok(());
+        }
+        let job = Job::spawn(
+            Command::new("dotnet")
+                .arg("restore")
+                .arg(&path)
+                .arg("-nodeReuse:false")
+                .env("MSBUILDDISABLENODEREUSE", "1"),
+        )
+        .map_err(dotnet_spawn_error)?;
+        self.log_info(format!("Restoring {}", name));
+        self.problems.clear_build(&name);
+        self.restore = Some(Restore {
+            job,
+            name,
+            path,
+            diagnostics: DiagnosticParser::default(),
+            feed_auth: FeedAuthDetector::default(),
+            then,
+        });
+        Ok(())
+    }
+
+    fn cancel_restore(&mut self) -> Result<()> {
+        if let Some(mut restore) = self.restore.take() {
+            restore.job.kill()?;
+            for line in restore.job.drain() {
+                self.log_output(line);
+            }
+            self.log_warn(format!("Restore of {} cancelled", restore.name));
+        }
+        Ok(())
+    }
+
+    fn poll_restore(&mut self) -> Result<()> {
+        let Some(restore) = self.restore.as_mut() else {
+            return Ok(());
+        };
+        let lines = restore.job.drain();
+        let status = restore.job.poll_exit()?;
+        for line in &lines {
+            restore.feed_auth.feed(&line.text);
+            if let Some(parsed) = restore.diagnostics.feed(&line.text) {
+                self.problems.add(&restore.name, parsed);
+            }
+        }
+        for line in lines {
+            self.log_output(line);
+        }
+        let Some(status) = status else {
+            return Ok(());
+        };
+        let Some(restore) = self.restore.take() else {
+            return Ok(());
+        };
+
+        let elapsed = restore.job.started.elapsed().as_secs_f32();
+        if !status.success() {
+            let (errors, warnings) = self.problems.counts(Some(&restore.name));
+            let mut counts = count_summary(errors, warnings);
+            if errors + warnings > 0 {
+                counts.push_str(", press E to list them");
+            }
+            let message = format!(
+                "Restore of {} failed after {:.1}s ({}) — {}",
+                restore.name, elapsed, status, counts
+            );
+            self.status.flash_error(message.clone());
+            self.log_error(message);
+            if let Some(error) = &restore.feed_auth.error {
+                self.log_warn(error.hint());
+            }
+            if let Some((target, _)) = restore.then {
+                self.log_info(format!("Not running {}: restore failed", target.project));
+            }
+            return Ok(());
+        }
+
+        if restore.path == Path::new(&self.selected_sln) {
+            self.restored.insert(self.selected_sln.clone());
+        }
+        self.log(
+            Level::Success,
+            format!("Restored {} in {:.1}s", restore.name, elapsed),
+        );
+        match restore.then {
+            Some((target, launch_profile)) => self.build_and_run(target, launch_profile),
+            None => Ok(()),
+        }
+    }
+
+    fn open_clean_menu(&mut self) -> Result<()> {
+        let Some(target) = self.selected_target()? else {
+            return Err(AppError::NoProjectSelected);
+        };
+        self.clean_menu = Some(CleanMenu {
+            target,
+            state: ListState::default().with_selected(Some(0)),
+        });
+        Ok(())
+    }
+
+    fn handle_clean_menu_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(menu) = self.clean_menu.as_mut() else {
+            return Ok(());
+        };
+        let current = menu.state.selected().unwrap_or(0);
+        let choice = match key.code {
+            KeyCode::Up => {
+                menu.state.select(Some(current.saturating_sub(1)));
+                return Ok(());
+            }
+            KeyCode::Down => {
+                let last = CleanChoice::ALL.len() - 1;
+                menu.state.select(Some((current + 1).min(last)));
+                return Ok(());
+            }
+            KeyCode::Enter => CleanChoice::ALL[current],
+            KeyCode::Esc => CleanChoice::Cancel,
+            _ => return Ok(()),
+        };
+        let Some(menu) = self.clean_menu.take() else {
+            return Ok(());
+        };
+
+        let solution = solution_name(&self.selected_sln);
+        match choice {
+            CleanChoice::Clean => self.start_clean(menu.target.project, menu.target.project_path),
+            CleanChoice::CleanSolution => {
+                self.start_clean(solution, PathBuf::from(&self.selected_sln))
+            }
+            CleanChoice::DeepClean => {
+                self.ask_deep_clean(menu.target.project, &[menu.target.project_dir]);
+                Ok(())
+            }
+            CleanChoice::DeepCleanSolution => {
+                let project_dirs: Vec<PathBuf> = self
+                    .projects
+                    .iter()
+                    .filter_map(|p| p.path.parent().map(Path::to_path_buf))
+                    .collect();
+                self.ask_deep_clean(solution, &project_dirs);
+                Ok(())
+            }
+            CleanChoice::Cancel => Ok(()),
+        }
+    }
+
+    fn start_clean(&mut self, name: String, path: PathBuf) -> Result<()> {
+        if self.dotnet.is_none() {
+            return Err(AppError::DotnetMissing);
+        }
+        if self.busy() {
+            self.log_warn("Wait for the running build to finish before cleaning");
+            return Ok(());
+        }
+        let job = Job::spawn(
+            Command::new("dotnet")
+                .arg("clean")
+                .arg(&path)
+                .arg("--configuration")
+                .arg(&self.configuration)
+                .arg("-nodeReuse:false")
+                .env("MSBUILDDISABLENODEREUSE", "1"),
+        )
+        .map_err(dotnet_spawn_error)?;
+        self.log_info(format!("Cleaning {} ({})", name, self.configuration));
+        self.clean = Some(Clean { job, name });
+        Ok(())
+    }
+
+    fn cancel_clean(&mut self) -> Result<()> {
+        if let Some(mut clean) = self.clean.take() {
+            clean.job.kill()?;
+            for line in clean.job.drain() {
+                self.log_output(line);
+            }
+            self.log_warn(format!("Clean of {} cancelled", clean.name));
+        }
+        if let Some(mut deep_clean) = self.deep_clean.take() {
+            deep_clean.cancel();
+            self.log_warn(format!(
+                "Stopped deleting the build directories of {}, {} freed so far",
+                deep_clean.name,
+                format_size(deep_clean.freed)
+            ));
+        }
+        Ok(())
+    }
+
+    fn poll_clean(&mut self) -> Result<()> {
+        let Some(clean) = self.clean.as_mut() else {
+            return Ok(());
+        };
+        let lines = clean.job.drain();
+        let status = clean.job.poll_exit()?;
+        for line in lines {
+            self.log_output(line);
+        }
+        let Some(status) = status else {
+            return Ok(());
+        };
+        let Some(clean) = self.clean.take() else {
+            return Ok(());
+        };
+        let elapsed = clean.job.started.elapsed().as_secs_f32();
+        if status.success() {
+            self.log(
+                Level::Success,
+                format!("Cleaned {} in {:.1}s", clean.name, elapsed),
+            );
+        } else {
+            let message = format!("Clean of {} failed ({})", clean.name, status);
+            self.status.flash_error(message.clone());
+            self.log_error(message);
+        }
+        Ok(())
+    }
+
+    fn compose_file(&self) -> Option<PathBuf> {
+        compose::find_compose_file(self.sln_file.parent()?)
+    }
+
+    fn open_compose_panel(&mut self) {
+        let Some(file) = self.compose_file() else {
+            self.status
+                .flash("No compose.yaml or docker-compose.yml next to the solution");
+            return;
+        };
+        let services = compose::services(&file);
+        if services.is_empty() {
+            self.log_warn(format!("No services found in {}", file.display()));
+            return;
+        }
+        self.compose_panel = Some(ComposePanel {
+            status: StatusPoller::new(&file),
+            file,
+            services,
+            marked: HashSet::new(),
+            state: ListState::default().with_selected(Some(0)),
+        });
+    }
+
+    fn handle_compose_panel_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(panel) = self.compose_panel.as_mut() else {
+            return Ok(());
+        };
+        let current = panel.state.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Esc => self.compose_panel = None,
+            KeyCode::Up => panel.state.select(Some(current.saturating_sub(1))),
+            KeyCode::Down => {
+                let last = panel.services.len().saturating_sub(1);
+                panel.state.select(Some((current + 1).min(last)));
+            }
+            KeyCode::Char(' ') => {
+                if let Some(service) = panel.services.get(current) {
+                    if !panel.marked.remove(service) {
+                        panel.marked.insert(service.clone());
+                    }
+                }
+            }
+            KeyCode::Enter | KeyCode::Char('u') => {
+                // The marked services in the file's order, or all of them
+                let services: Vec<String> = panel
+                    .services
+                    .iter()
+                    .filter(|service| panel.marked.contains(*service))
+                    .cloned()
+                    .collect();
+                let file = panel.file.clone();
+                self.compose_up(&file, &services)?;
+            }
+            KeyCode::Char('d') => {
+                let file = panel.file.clone();
+                self.compose_down(&file)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Brings up the services attached, streaming their output into the log.
+    fn compose_up(&mut self, file: &Path, services: &[String]) -> Result<()> {
+        let job =
+            Job::spawn(&mut compose::up_command(file, services)).map_err(docker_spawn_error)?;
+        let which = if services.is_empty() {
+            "all services".to_string()
+        } else {
+            services.join(", ")
+        };
+        self.log_info(format!("Bringing up {} of {}", which, file.display()));
+        self.compose_jobs.push(ComposeJob {
+            job,
+            file: file.to_path_buf(),
+            step: ComposeStep::Up,
+        });
+        Ok(())
+    }
+
+    /// Stops and removes the services, which ends the attached `up` runs too.
+    fn compose_down(&mut self, file: &Path) -> Result<()> {
+        let job = Job::spawn(&mut compose::down_command(file)).map_err(docker_spawn_error)?;
+        self.log_info(format!("Bringing down the services of {}", file.display()));
+        self.compose_jobs.push(ComposeJob {
+            job,
+            file: file.to_path_buf(),
+            step: ComposeStep::Down,
+        });
+        Ok(())
+    }
+
+    /// The group whose compose services are being brought up, and since when.
+    fn compose_wait(&self) -> Option<(&String, Instant)> {
+        self.compose_jobs
+            .iter()
+            .find_map(|compose| match &compose.step {
+                ComposeStep::Wait(group) => Some((group, compose.job.started)),
+                _ => None,
+            })
+    }
+
+    /// Brings up the compose services the group needs and waits for them to
+    /// be healthy, unless they came up for this run of it. Returns whether the group
+    /// is held back; `poll_compose` starts it again.
+    fn hold_for_compose(&mut self, group: &str) -> Result<bool> {
+        let services = self
+            .config
+            .group_services
+            .get(group)
+            .cloned()
+            .unwrap_or_default();
+        if services.is_empty() || self.compose_passed.contains(group) {
+            return Ok(false);
+        }
+        let Some(file) = self.compose_file() else {
+            return Err(AppError::NoComposeFile {
+                group: group.to_string(),
+            });
+        };
+        let defined = compose::services(&file);
+        let missing: Vec<String> = services
+            .iter()
+            .filter(|service| !defined.contains(service))
+            .cloned()
+            .collect();
+        if !missing.is_empty() {
+            return Err(AppError::ComposeServicesMissing {
+                group: group.to_string(),
+                file: file.display().to_string(),
+                services: missing,
+            });
+        }
+        let job =
+            Job::spawn(&mut compose::wait_command(&file, &services)).map_err(docker_spawn_error)?;
+        self.log_info(format!(
+            "Bringing up {} for group {}",
+            services.join(", "),
+            group
+        ));
+        self.compose_jobs.push(ComposeJob {
+            job,
+            file,
+            step: ComposeStep::Wait(group.to_string()),
+        });
+        Ok(true)
+    }
+
+    fn cancel_compose_wait(&mut self) -> Result<()> {
+        let mut index = 0;
+        while index < self.compose_jobs.len() {
+            let ComposeStep::Wait(group) = &self.compose_jobs[index].step else {
+                index += 1;
+                continue;
+            };
+            let group = group.clone();
+            let mut compose = self.compose_jobs.remove(index);
+            compose.job.kill()?;
+            self.log_warn(format!(
+                "Stopped waiting for the compose services of group {}, not running it",
+                group
+            ));
+        }
+        Ok(())
+    }
+
+    /// Logs the output of the `docker compose` commands, refreshes the
+    /// panel's statuses and starts the groups whose services came up.
+    fn poll_compose(&mut self) -> Result<()> {
+        if let Some(panel) = self.compose_panel.as_mut() {
+            panel.status.poll();
+        }
+        let mut index = 0;
+        while index < self.compose_jobs.len() {
+            let compose = &mut self.compose_jobs[index];
+            let lines = compose.job.drain();
+            let status = compose.job.poll_exit()?;
+            // Compose writes its progress to stderr, so only its errors count as such
+            for line in lines {
+                self.log_output(OutputLine {
+                    text: format!("[compose] {}", line.text),
+                    is_stderr: line.is_stderr && line.text.to_lowercase().contains("error"),
+                });
+            }
+            let Some(status) = status else {
+                index += 1;
+                continue;
+            };
+            let compose = self.compose_jobs.remove(index);
+            match compose.step {
+                ComposeStep::Wait(group) if status.success() => {
+                    self.log(
+                        Level::Success,
+                        format!("Compose services of group {} are up", group),
+                    );
+                    self.compose_passed.insert(group.clone());
+                    self.run_group(&group)?;
+                }
+                ComposeStep::Wait(group) => {
+                    let message = format!(
+                        "Compose services of group {} did not come up ({}), not running it",
+                        group, status
+                    );
+                    self.status.flash_error(message.clone());
+                    self.log_error(message);
+                }
+                ComposeStep::Up | ComposeStep::Down => {
+                    let command = match compose.step {
+                        ComposeStep::Down => "down",
+                        _ => "up",
+                    };
+                    let message = format!("docker compose {} exited ({})", command, status);
+                    if status.success() {
+                        self.log_info(message);
+                    } else {
+                        self.log_warn(message);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn open_migrations(&mut self) -> Result<()> {
+        let Some(target) = self.selected_target()? else {
+            return Err(AppError::NoProjectSelected);
+        };
+        if !migrations::uses_ef(&target.project_path) {
+            self.log_warn(format!(
+                "{} does not reference Microsoft.EntityFrameworkCore.Design",
+                target.project
+            ));
+            return Ok(());
+        }
+        let startup_project = match self.config.ef_startup_projects.get(&target.project) {
+            Some(path) => {
+                let path = path.replace('\\', "/");
+                let project = self
+                    .projects
+                    .iter()
+                    .find(|p| !p.missing && p.rel_path.replace('\\', "/") == path);
+                match project {
+                    Some(project) => Some(project.path.clone()),
+                    None => {
+                        self.log_error(format!(
+                            "Startup project {} of {} is not in the solution",
+                            path, target.project
+                        ));
+                        return Ok(());
+                    }
+                }
+            }
+            None => None,
+        };
+        self.migrations = Some(MigrationsPanel {
+            target,
+            startup_project,
+            migrations: MigrationsState::Listing,
+            state: ListState::default().with_selected(Some(0)),
+            name: None,
+            confirm: None,
+        });
+        self.start_ef(EfStep::List)
+    }
+
+    fn handle_migrations_key(&mut self, key: KeyEvent) -> Result<()> {
+        let idle = self.ef_job.is_none();
+        let Some(panel) = self.migrations.as_mut() else {
+            return Ok(());
+        };
+        if let Some(input) = panel.name.as_mut() {
+            match key.code {
+                KeyCode::Esc => panel.name = None,
+                KeyCode::Enter => {
+                    let name = input.value.trim().to_string();
+                    if !name.is_empty() {
+                        panel.name = None;
+                        return self.start_ef(EfStep::Add(name));
+                    }
+                }
+                _ => {
+                    input.handle_key(key);
+                }
+            }
+            return Ok(());
+        }
+        if let Some(target) = panel.confirm.take() {
+            if matches!(key.code, KeyCode::Char('y') | KeyCode::Enter) {
+                return self.start_ef(EfStep::Update(Some(target)));
+            }
+            return Ok(());
+        }
+
+        let listed = match &panel.migrations {
+            MigrationsState::Listed(migrations) => migrations.as_slice(),
+            _ => &[],
+        };
+        let current = panel.state.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Esc => self.migrations = None,
+            KeyCode::Up | KeyCode::Char('k') => {
+                panel.state.select(Some(current.saturating_sub(1)));
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                panel.state.select(Some((current + 1).min(listed.len())));
+            }
+            KeyCode::Enter if idle && !listed.is_empty() => {
+                // The first row rolls back to before the first migration
+                panel.confirm = Some(match current {
+                    0 => "0".to_string(),
+                    i => listed[i - 1].id.clone(),
+                });
+            }
+            KeyCode::Char('u') => self.start_ef(EfStep::Update(None))?,
+            KeyCode::Char('a') if idle => panel.name = Some(TextInput::new(String::new())),
+            KeyCode::Char('r') => self.start_ef(EfStep::List)?,
+            KeyCode::Char('i') if matches!(panel.migrations, MigrationsState::ToolMissing) => {
+                self.start_ef(EfStep::Install)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Runs `dotnet ef` for the project of the migrations panel.
+    fn start_ef(&mut self, step: EfStep) -> Result<()> {
+        if self.ef_job.is_some() {
+            self.status.flash("Wait for dotnet ef to finish");
+            return Ok(());
+        }
+        if self.busy() {
+            self.log_warn("Wait for the running build to finish before running dotnet ef");
+            return Ok(());
+        }
+        let Some(panel) = self.migrations.as_mut() else {
+            return Ok(());
+        };
+        let project_path = &panel.target.project_path;
+        let startup_project = panel.startup_project.as_deref();
+        let mut command = match &step {
+            EfStep::List => {
+                panel.migrations = MigrationsState::Listing;
+                migrations::ef_command(
+                    project_path,
+                    startup_project,
+                    &self.configuration,
+                    &["migrations", "list"],
+                )
+            }
+            EfStep::Update(target) => {
+                let mut args = vec!["database", "update"];
+                args.extend(target.as_deref());
+                migrations::ef_command(project_path, startup_project, &self.configuration, &args)
+            }
+            EfStep::Add(name) => migrations::ef_command(
+                project_path,
+                startup_project,
+                &self.configuration,
+                &["migrations", "add", name],
+            ),
+            EfStep::Install => migrations::install_command(),
+        };
+        let project = panel.target.project.clone();
+        let job = Job::spawn(&mut command).map_err(dotnet_spawn_error)?;
+        match &step {
+            EfStep::List => {}
+            EfStep::Update(None) => self.log_info(format!("Updating the database of {}", project)),
+            EfStep::Update(Some(target)) => self.log_info(format!(
+                "Rolling back the database of {} to {}",
+                project, target
+            )),
+            EfStep::Add(name) => self.log_info(format!("Adding migration {} to {}", name, project)),
+            EfStep::Install => self.log_info(INSTALL_COMMAND),
+        }
+        self.ef_job = Some(EfJob {
+            job,
+            project,
+            step,
+            output: Vec::new(),
+        });
+        Ok(())
+    }
+
+    fn cancel_ef_job(&mut self) -> Result<()> {
+        if let Some(mut ef) = self.ef_job.take() {
+            ef.job.kill()?;
+            self.log_warn(format!("dotnet ef for {} cancelled", ef.project));
+            if let Some(panel) = self.migrations.as_mut() {
+                if matches!(panel.migrations, MigrationsState::Listing) {
+                    panel.migrations = MigrationsState::Failed("Cancelled".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Logs the output of `dotnet ef`, but for the listing, and lists the
+    /// migrations again once they changed.
+    fn poll_ef(&mut self) -> Result<()> {
+        let Some(ef) = self.ef_job.as_mut() else {
+            return Ok(());
+        };
+        let lines = ef.job.drain();
+        let status = ef.job.poll_exit()?;
+        let listing = ef.step == EfStep::List;
+        ef.output.extend(lines.iter().map(|line| line.text.clone()));
+        if !listing {
+            for line in lines {
+                self.log_output(OutputLine {
+                    text: format!("[ef] {}", line.text),
+                    is_stderr: line.is_stderr,
+                });
+            }
+        }
+        let Some(status) = status else {
+            return Ok(());
+        };
+        let Some(ef) = self.ef_job.take() else {
+            return Ok(());
+        };
+
+        if ef.step == EfStep::List {
+            let migrations = if status.success() {
+                MigrationsState::Listed(migrations::parse_migrations(&ef.output))
+            } else if migrations::tool_missing(&ef.output) {
+                MigrationsState::ToolMissing
+            } else {
+                for line in &ef.output {
+                    self.log_output(OutputLine {
+                        text: format!("[ef] {}", line),
+                        is_stderr: true,
+                    });
+                }
+                MigrationsState::Failed(format!(
+                    "dotnet ef migrations list failed ({}), see the logs",
+                    status
+                ))
+            };
+            if let Some(panel) = self.migrations.as_mut() {
+                // On the latest migration
+                if let MigrationsState::Listed(listed) = &migrations {
+                    panel.state.select(Some(listed.len()));
+                }
+                panel.migrations = migrations;
+            }
+            return Ok(());
+        }
+
+        if status.success() {
+            let message = match &ef.step {
+                EfStep::Update(None) => format!("Updated the database of {}", ef.project),
+                EfStep::Update(Some(target)) => {
+                    format!("Rolled back the database of {} to {}", ef.project, target)
+                }
+                EfStep::Add(name) => format!("Added migration {} to {}", name, ef.project),
+                _ => "Installed dotnet-ef".to_string(),
+            };
+            self.status.flash(message.clone());
+            self.log(Level::Success, message);
+        } else {
+            let message = format!("dotnet ef failed for {} ({})", ef.project, status);
+            self.status.flash_error(message.clone());
+            self.log_error(message);
+        }
+        self.start_ef(EfStep::List)
+    }
+
+    fn open_secrets(&mut self) -> Result<()> {
+        let Some(target) = self.selected_target()? else {
+            return Err(AppError::NoProjectSelected);
+        };
+        let has_id = secrets::user_secrets_id(&target.project_path).is_some();
+        self.secrets = Some(SecretsView {
+            target,
+            secrets: SecretsState::NoId,
+            state: ListState::default().with_selected(Some(0)),
+            revealed: false,
+            input: None,
+            confirm: None,
+        });
+        if has_id {
+            self.start_secrets(SecretsStep::List)?;
+        }
+        Ok(())
+    }
+
+    fn handle_secrets_key(&mut self, key: KeyEvent) -> Result<()> {
+        let idle = self.secrets_job.is_none();
+        let Some(view) = self.secrets.as_mut() else {
+            return Ok(());
+        };
+        if let Some(input) = view.input.as_mut() {
+            match key.code {
+                KeyCode::Esc => view.input = None,
+                KeyCode::Enter => match env::parse_assignment(&input.value) {
+                    Some((key, value)) => {
+                        view.input = None;
+                        self.logs.mask(&value);
+                        return self.start_secrets(SecretsStep::Set { key, value });
+                    }
+                    None => self.log_warn("Expected Key=value"),
+                },
+                _ => {
+                    input.handle_key(key);
+                }
+            }
+            return Ok(());
+        }
+        if let Some(secret) = view.confirm.take() {
+            if matches!(key.code, KeyCode::Char('y') | KeyCode::Enter) {
+                return self.start_secrets(SecretsStep::Remove(secret));
+            }
+            return Ok(());
+        }
+
+        let listed = match &view.secrets {
+            SecretsState::Listed(secrets) => secrets.as_slice(),
+            _ => &[],
+        };
+        let selected = view.state.selected().and_then(|i| listed.get(i));
+        match key.code {
+            KeyCode::Esc => self.secrets = None,
+            KeyCode::Up | KeyCode::Char('k') => view.state.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => {
+                let last = listed.len().saturating_sub(1);
+                let next = view.state.selected().map_or(0, |i| (i + 1).min(last));
+                view.state.select(Some(next));
+            }
+            KeyCode::Char('v') | KeyCode::Char(' ') => view.revealed = !view.revealed,
+            KeyCode::Char('a') if idle && matches!(view.secrets, SecretsState::Listed(_)) => {
+                view.input = Some(TextInput::default());
+            }
+            KeyCode::Enter | KeyCode::Char('e') if idle => {
+                if let Some(secret) = selected {
+                    view.input = Some(TextInput::new(format!("{}={}", secret.key, secret.value)));
+                }
+            }
+            KeyCode::Char('d') | KeyCode::Delete if idle => {
+                view.confirm = selected.map(|secret| secret.key.clone());
+            }
+            KeyCode::Char('r') => self.start_secrets(SecretsStep::List)?,
+            KeyCode::Char('i') if matches!(view.secrets, SecretsState::NoId) => {
+                self.start_secrets(SecretsStep::Init)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Runs `dotnet user-secrets` for the project of the secrets view.
+    fn start_secrets(&mut self, step: SecretsStep) -> Result<()> {
+        if self.secrets_job.is_some() {
+            self.status.flash("Wait for dotnet user-secrets to finish");
+            return Ok(());
+        }
+        if self.busy() {
+            self.log_warn("Wait for the running build to finish before changing user secrets");
+            return Ok(());
+        }
+        let Some(view) = self.secrets.as_mut() else {
+            return Ok(());
+        };
+        let project_path = &view.target.project_path;
+        let mut command = match &step {
+            SecretsStep::List => {
+                view.secrets = SecretsState::Listing;
+                secrets::list_command(project_path)
+            }
+            SecretsStep::Set { key, value } => secrets::set_command(project_path, key, value),
+            SecretsStep::Remove(key) => secrets::remove_command(project_path, key),
+            SecretsStep::Init => secrets::init_command(project_path),
+        };
+        let project = view.target.project.clone();
+        let job = Job::spawn(&mut command).map_err(dotnet_spawn_error)?;
+        self.secrets_job = Some(SecretsJob {
+            job,
+            project,
+            step,
+            output: Vec::new(),
+        });
+        Ok(())
+    }
+
+    fn cancel_secrets_job(&mut self) -> Result<()> {
+        if let Some(mut secrets) = self.secrets_job.take() {
+            secrets.job.kill()?;
+            self.log_warn(format!(
+                "dotnet user-secrets for {} cancelled",
+                secrets.project
+            ));
+            if let Some(view) = self.secrets.as_mut() {
+                if matches!(view.secrets, SecretsState::Listing) {
+                    view.secrets = SecretsState::Failed("Cancelled".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Collects the output of `dotnet user-secrets`, which is only logged when
+    /// it fails, as it holds the values, and lists the secrets again once
+    /// they changed.
+    fn poll_secrets(&mut self) -> Result<()> {
+        let Some(secrets) = self.secrets_job.as_mut() else {
+            return Ok(());
+        };
+        let lines = secrets.job.drain();
+        secrets
+            .output
+            .extend(lines.into_iter().map(|line| line.text));
+        let Some(status) = secrets.job.poll_exit()? else {
+            return Ok(());
+        };
+        let Some(secrets) = self.secrets_job.take() else {
+            return Ok(());
+        };
+
+        if !status.success() {
+            let message = match &secrets.step {
+                SecretsStep::List => {
+                    format!("Failed to list the user secrets of {}", secrets.project)
+                }
+                SecretsStep::Set { key, .. } => {
+                    format!("Failed to set {} for {}", key, secrets.project)
+                }
+                SecretsStep::Remove(key) => {
+                    format!("Failed to remove {} from {}", key, secrets.project)
+                }
+                SecretsStep::Init => {
+                    format!(
+                        "Failed to initialize the user secrets of {}",
+                        secrets.project
+                    )
+                }
+            };
+            for line in &secrets.output {
+                self.log_output(OutputLine {
+                    text: format!("[user-secrets] {}", line),
+                    is_stderr: true,
+                });
+            }
+            self.status.flash_error(message.clone());
+            self.log_error(format!("{} ({})", message, status));
+            if let Some(view) = self.secrets.as_mut() {
+                if matches!(view.secrets, SecretsState::Listing) {
+                    view.secrets = SecretsState::Failed(format!("{}, see the logs", message));
+                }
+            }
+            return Ok(());
+        }
+
+        let message = match &secrets.step {
+            SecretsStep::List => {
+                let listed = secrets::parse_secrets(&secrets.output);
+                for secret in &listed {
+                    self.logs.mask(&secret.value);
+                }
+                if let Some(view) = self.secrets.as_mut() {
+                    let last = listed.len().saturating_sub(1);
+                    view.state
+                        .select(Some(view.state.selected().unwrap_or(0).min(last)));
+                    view.secrets = SecretsState::Listed(listed);
+                }
+                return Ok(());
+            }
+            SecretsStep::Set { key, .. } => {
+                format!("Set user secret {} of {}", key, secrets.project)
+            }
+            SecretsStep::Remove(key) => {
+                format!("Removed user secret {} of {}", key, secrets.project)
+            }
+            SecretsStep::Init => format!("Initialized the user secrets of {}", secrets.project),
+        };
+        self.status.flash(message.clone());
+        self.log(Level::Success, message);
+        self.start_secrets(SecretsStep::List)
+    }
+
+    fn open_packages(&mut self) -> Result<()> {
+        let Some(target) = self.selected_target()? else {
+            return Err(AppError::NoProjectSelected);
+        };
+        self.packages = Some(PackagesView {
+            target,
+            solution: false,
+            outdated: None,
+            vulnerable: None,
+            error: None,
+            filter: PackageFilter::All,
+            state: ListState::default().with_selected(Some(0)),
+            json: true,
+        });
+        self.start_packages(Report::Outdated)
+    }
+
+    fn handle_packages_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(view) = self.packages.as_mut() else {
+            return Ok(());
+        };
+        let rows = view.rows();
+        match key.code {
+            KeyCode::Esc => self.packages = None,
+            KeyCode::Up | KeyCode::Char('k') => view.state.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => {
+                let last = rows.len().saturating_sub(1);
+                let next = view.state.selected().map_or(0, |i| (i + 1).min(last));
+                view.state.select(Some(next));
+            }
+            KeyCode::Char('f') => {
+                view.filter = view.filter.next();
+                view.state.select(Some(0));
+            }
+            KeyCode::Char('y') => {
+                if let Some(package) = view.state.selected().and_then(|i| rows.get(i)) {
+                    self.copy_to_clipboard(&package.add_command());
+                }
+            }
+            KeyCode::Char('o') => {
+                let package = view.state.selected().and_then(|i| rows.get(i));
+                if let Some(url) = package.and_then(|p| p.advisory.clone()) {
+                    let result = open_url(&url);
+                    self.report_open(&url, result);
+                }
+            }
+            KeyCode::Char('s') if self.packages_job.is_none() => {
+                view.solution = !view.solution;
+                self.start_packages(Report::Outdated)?;
+            }
+            KeyCode::Char('r') => self.start_packages(Report::Outdated)?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Runs `dotnet list package` for the report. The outdated packages come
+    /// first, and asking for them clears what the view listed.
+    fn start_packages(&mut self, report: Report) -> Result<()> {
+        if self.packages_job.is_some() {
+            self.status.flash("Wait for dotnet list package to finish");
+            return Ok(());
+        }
+        if self.busy() {
+            self.log_warn("Wait for the running build to finish before listing packages");
+            return Ok(());
+        }
+        let Some(view) = self.packages.as_mut() else {
+            return Ok(());
+        };
+        if report == Report::Outdated {
+            view.outdated = None;
+            view.vulnerable = None;
+            view.error = None;
+        }
+        let path = if view.solution {
+            PathBuf::from(&self.selected_sln)
+        } else {
+            view.target.project_path.clone()
+        };
+        let name = view.name(&self.selected_sln);
+        let json = view.json;
+        let job = Job::spawn(&mut packages::list_command(&path, report, json))
+            .map_err(dotnet_spawn_error)?;
+        self.packages_job = Some(PackagesJob {
+            job,
+            name,
+            report,
+            json,
+            output: Vec::new(),
+        });
+        Ok(())
+    }
+
+    fn cancel_packages_job(&mut self) -> Result<()> {
+        if let Some(mut packages) = self.packages_job.take() {
+            packages.job.kill()?;
+            self.log_warn(format!(
+                "Listing the packages of {} cancelled",
+                packages.name
+            ));
+            if let Some(view) = self.packages.as_mut() {
+                view.error = Some("Cancelled".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the report of `dotnet list package` into the packages view, then
+    /// asks for the vulnerable packages after the outdated ones. SDKs without
+    /// `--format json` are asked again for the text report.
+    fn poll_packages(&mut self) -> Result<()> {
+        let Some(packages) = self.packages_job.as_mut() else {
+            return Ok(());
+        };
+        let lines = packages.job.drain();
+        packages
+            .output
+            .extend(lines.into_iter().map(|line| line.text));
+        let Some(status) = packages.job.poll_exit()? else {
+            return Ok(());
+        };
+        let Some(packages) = self.packages_job.take() else {
+            return Ok(());
+        };
+
+        let parsed = if packages.json {
+            packages::parse_json(&packages.output)
+        } else if status.success() {
+            Some(packages::parse_text(&packages.output, packages.report))
+        } else {
+            None
+        };
+        let Some(parsed) = parsed else {
+            if packages.json && packages::format_unsupported(&packages.output) {
+                if let Some(view) = self.packages.as_mut() {
+                    view.json = false;
+                }
+                return self.start_packages(packages.report);
+            }
+            for line in &packages.output {
+                self.log_output(OutputLine {
+                    text: format!("[packages] {}", line),
+                    is_stderr: true,
+                });
+            }
+            let message = format!(
+                "dotnet list package failed for {} ({})",
+                packages.name, status
+            );
+            self.log_error(message.clone());
+            if let Some(view) = self.packages.as_mut() {
+                view.error = Some(format!("{}, see the logs", message));
+            }
+            return Ok(());
+        };
+        let Some(view) = self.packages.as_mut() else {
+            return Ok(());
+        };
+        match packages.report {
+            Report::Outdated => {
+                view.outdated = Some(parsed);
+                self.start_packages(Report::Vulnerable)
+            }
+            Report::Vulnerable => {
+                view.vulnerable = Some(parsed);
+                let rows = view.rows();
+                let vulnerable = rows.iter().filter(|p| p.severity.is_some()).count();
+                let outdated = rows.iter().filter(|p| p.latest.is_some()).count();
+                self.log_info(format!(
+                    "{}: {} outdated and {} vulnerable packages",
+                    packages.name, outdated, vulnerable
+                ));
+                Ok(())
+            }
+        }
+    }
+
+    fn open_docker_menu(&mut self) -> Result<()> {
+        let Some(target) = self.selected_target()? else {
+            return Err(AppError::NoProjectSelected);
+        };
+        if !docker::has_docker(&target.project_dir) {
+            self.log_warn(format!(
+                "{} has no Dockerfile or Docker launch profile",
+                target.project
+            ));
+            return Ok(());
+        }
+        self.docker_menu = Some(DockerMenu {
+            image: docker::image_tag(&target.project),
+            target,
+            state: ListState::default().with_selected(Some(0)),
+        });
+        Ok(())
+    }
+
+    fn handle_docker_menu_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(menu) = self.docker_menu.as_mut() else {
+            return Ok(());
+        };
+        let current = menu.state.selected().unwrap_or(0);
+        let choice = match key.code {
+            KeyCode::Up => {
+                menu.state.select(Some(current.saturating_sub(1)));
+                return Ok(());
+            }
+            KeyCode::Down => {
+                let last = DockerChoice::ALL.len() - 1;
+                menu.state.select(Some((current + 1).min(last)));
+                return Ok(());
+            }
+            KeyCode::Enter => DockerChoice::ALL[current],
+            KeyCode::Esc => DockerChoice::Cancel,
+            _ => return Ok(()),
+        };
+        let Some(menu) = self.docker_menu.take() else {
+            return Ok(());
+        };
+
+        match choice {
+            DockerChoice::Build => self.start_docker_build(menu.target, menu.image, false),
+            DockerChoice::Run => self.run_container(menu.target, menu.image),
+            DockerChoice::BuildAndRun => self.start_docker_build(menu.target, menu.image, true),
+            DockerChoice::Cancel => Ok(()),
+        }
+    }
+
+    fn start_docker_build(&mut self, target: RunTarget, image: String, run: bool) -> Result<()> {
+        if self.busy() {
+            self.log_warn("Wait for the running build to finish before building the image");
+            return Ok(());
+        }
+        let sln_dir = self
+            .sln_file
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let mut command = match docker::build_command(&target.project_path, &sln_dir, &image) {
+            Ok(command) => command,
+            Err(message) => {
+                self.log_error(format!(
+                    "Can't build the image of {}: {}",
+                    target.project, message
+                ));
+                return Ok(());
+            }
+        };
+        let job = Job::spawn(&mut command).map_err(docker_spawn_error)?;
+        self.log_info(format!("Building image {} of {}", image, target.project));
+        self.docker_build = Some(DockerBuild {
+            job,
+            target,
+            image,
+            run,
+        });
+        Ok(())
+    }
+
+    fn cancel_docker_build(&mut self) -> Result<()> {
+        if let Some(mut build) = self.docker_build.take() {
+            if let Err(e) = build.job.kill() {
+                self.log_error(format!("Failed to kill the image build: {}", e));
+            }
+            for line in build.job.drain() {
+                self.log_output(line);
+            }
+            self.log_warn(format!("Build of image {} cancelled", build.image));
+        }
+        Ok(())
+    }
+
+    fn poll_docker_build(&mut self) -> Result<()> {
+        let Some(build) = self.docker_build.as_mut() else {
+            return Ok(());
+        };
+        let lines = build.job.drain();
+        let status = build.job.poll_exit()?;
+        // BuildKit writes its progress to stderr, so only its errors count as such
+        for line in lines {
+            self.log_output(OutputLine {
+                is_stderr: line.text.contains("ERROR"),
+                ..line
+            });
+        }
+        let Some(status) = status else {
+            return Ok(());
+        };
+        let Some(build) = self.docker_build.take() else {
+            return Ok(());
+        };
+        let elapsed = build.job.started.elapsed().as_secs_f32();
+        if !status.success() {
+            let message = format!("Build of image {} failed ({})", build.image, status);
+            self.status.flash_error(message.clone());
+            self.log_error(message);
+            return Ok(());
+        }
+        self.log(
+            Level::Success,
+            format!("Built image {} in {:.1}s", build.image, elapsed),
+        );
+        if build.run {
+            return self.run_container(build.target, build.image);
+        }
+        Ok(())
+    }
+
+    /// Runs the project's image in a container, attached so that it is
+    /// listed and shown like any other process. The ports of the launch
+    /// profile are published onto those the image exposes.
+    fn run_container(&mut self, target: RunTarget, image: String) -> Result<()> {
+        let key = target.project_path.to_string_lossy().into_owned();
+        let profiles = read_launch_profiles(&target.project_dir);
+        let profile = self
+            .next_profile(&key, &profiles)
+            .filter(|p| p.is_supported());
+        let ports = docker::port_mappings(&target.project_dir, profile);
+        let launch_profile = profile.map(|p| p.name.clone());
+        let profile_vars = profile
+            .map(|p| p.environment_variables.clone())
+            .unwrap_or_default();
+        let mut env = env::resolve(&self.env_vars(&target.project, &key, &profile_vars));
+        if let Some(environment) = self.state.environments.get(&key) {
+            for name in ["ASPNETCORE_ENVIRONMENT", "DOTNET_ENVIRONMENT"] {
+                env.insert(name.to_string(), environment.clone());
+            }
+        }
+
+        let previous = self.replace_run(&target.project_dir)?;
+        let container = Container::new(&image, ports);
+        let job = Job::spawn(&mut container.run_command(&env)).map_err(docker_spawn_error)?;
+        let published: Vec<String> = container
+            .ports
+            .iter()
+            .map(|(host, port)| format!("{}→{}", host, port))
+            .collect();
+        match published.as_slice() {
+            [] => self.log_info(format!("Running image {} of {}", image, target.project)),
+            ports => self.log_info(format!(
+                "Running image {} of {}, publishing ports {}",
+                image,
+                target.project,
+                ports.join(", ")
+            )),
+        }
+        self.process_started(&target.project, &target.project_dir);
+
+        let process = RunningProcess {
+            job,
+            project: target.project,
+            solution: self.selected_sln.clone(),
+            project_dir: target.project_dir,
+            launch_profile,
+            configuration: self.configuration.clone(),
+            mode: RunMode::NoBuild,
+            build_args: Vec::new(),
+            args: Vec::new(),
+            env,
+            urls: Vec::new(),
+            profile_urls: container.urls(),
+            build_errors: Vec::new(),
+            diagnostics: DiagnosticParser::default(),
+            output: self.new_output(),
+            status: None,
+            stopping: None,
+            restarting: false,
+            crashed: false,
+            auto_restart: false,
+            crashes: 0,
+            restart_at: None,
+            container: Some(container),
+            spa: None,
+            recording: None,
+        };
+        self.add_process(previous, process);
+        Ok(())
+    }
+
+    /// Looks up the build directories and asks before deleting them. Only ones
+    /// under the solution's directory are offered.
+    fn ask_deep_clean(&mut self, name: String, project_dirs: &[PathBuf]) {
+        if self.busy() {
+            self.log_warn("Wait for the running build to finish before cleaning");
+            return;
+        }
+        let Some(root) = self.sln_file.parent() else {
+            return;
+        };
+        let dirs = build_dirs(project_dirs, root);
+        if dirs.is_empty() {
+            self.log_info(format!("{} has no bin/ or obj/ to delete", name));
+            return;
+        }
+        self.deep_clean_prompt = Some(DeepCleanPrompt {
+            name,
+            dirs,
+            state: ListState::default().with_selected(Some(1)),
+        });
+    }
+
+    fn handle_deep_clean_prompt_key(&mut self, key: KeyEvent) {
+        let Some(prompt) = self.deep_clean_prompt.as_mut() else {
+            return;
+        };
+        let current = prompt.state.selected().unwrap_or(1);
+        let delete = match key.code {
+            KeyCode::Up => {
+                prompt.state.select(Some(0));
+                return;
+            }
+            KeyCode::Down => {
+                prompt.state.select(Some(1));
+                return;
+            }
+            KeyCode::Enter => current == 0,
+            KeyCode::Esc => false,
+            _ => return,
+        };
+        let Some(prompt) = self.deep_clean_prompt.take() else {
+            return;
+        };
+        if !delete {
+            return;
+        }
+        let size: u64 = prompt.dirs.iter().map(|dir| dir.size).sum();
+        self.log_info(format!(
+            "Deleting {} build directories of {} ({})",
+            prompt.dirs.len(),
+            prompt.name,
+            format_size(size)
+        ));
+        self.deep_clean = Some(DeepClean::start(prompt.name, prompt.dirs));
+    }
+
+    fn poll_deep_clean(&mut self) {
+        let Some(deep_clean) = self.deep_clean.as_mut() else {
+            return;
+        };
+        let events = deep_clean.drain();
+        let root = self
+            .sln_file
+            .parent()
+            .and_then(|root| root.canonicalize().ok())
+            .unwrap_or_default();
+        let mut done = false;
+        for event in events {
+            match event {
+                CleanEvent::Removed {
+                    path,
+                    freed,
+                    skipped,
+                } => {
+                    let path = path.strip_prefix(&root).unwrap_or(&path).display();
+                    if skipped.is_empty() {
+                        self.log_info(format!("Deleted {} ({})", path, format_size(freed)));
+                        continue;
+                    }
+                    self.log_warn(format!(
+                        "Deleted {} ({}) but for {} files in use",
+                        path,
+                        format_size(freed),
+                        skipped.len()
+                    ));
+                    for (file, error) in skipped {
+                        self.log_warn(format!("  Skipped {}: {}", file.display(), error));
+                    }
+                }
+                CleanEvent::Done => done = true,
+            }
+        }
+        if !done {
+            return;
+        }
+        let Some(deep_clean) = self.deep_clean.take() else {
+            return;
+        };
+        let message = format!(
+            "Deleted the build directories of {}, {} freed",
+            deep_clean.name,
+            format_size(deep_clean.freed)
+        );
+        if deep_clean.skipped > 0 {
+            self.log_warn(format!(
+                "{}, {} files skipped, stop what holds them and clean again",
+                message, deep_clean.skipped
+            ));
+        } else {
+            self.log(Level::Success, message);
+        }
+    }
+
+    /// Asks how to publish the highlighted project, starting from the options
+    /// it was published with last time.
+    fn open_publish_prompt(&mut self) -> Result<()> {
+        let Some(target) = self.selected_target()? else {
+            return Err(AppError::NoProjectSelected);
+        };
+        let key = target.project_path.to_string_lossy().into_owned();
+        let options = self
+            .state
+            .publish
+            .get(&key)
+            .cloned()
+            .unwrap_or_else(|| PublishOptions {
+                configuration: self.configuration.clone(),
+                runtime: None,
+                self_contained: false,
+            });
+        let (runtime, custom) = match &options.runtime {
+            None => (0, String::new()),
+            Some(runtime) => match RUNTIMES.iter().position(|r| r == runtime) {
+                Some(index) => (index + 1, String::new()),
+                None => (RUNTIMES.len() + 1, runtime.clone()),
+            },
+        };
+        self.publish_prompt = Some(PublishPrompt {
+            target,
+            field: PublishField::Configuration,
+            configuration: options.configuration,
+            runtime,
+            custom_runtime: TextInput::new(custom),
+            self_contained: options.self_contained,
+        });
+        Ok(())
+    }
+
+    fn handle_publish_prompt_key(&mut self, key: KeyEvent) -> Result<()> {
+        let configurations = self.configurations.clone();
+        let Some(prompt) = self.publish_prompt.as_mut() else {
+            return Ok(());
+        };
+        let field = PublishField::ALL
+            .iter()
+            .position(|f| *f == prompt.field)
+            .unwrap_or(0);
+        let step = match key.code {
+            KeyCode::Left => -1,
+            KeyCode::Right => 1,
+            _ => 0,
+        };
+        match key.code {
+            KeyCode::Esc => self.publish_prompt = None,
+            KeyCode::Up | KeyCode::BackTab => {
+                prompt.field = PublishField::ALL[field.saturating_sub(1)];
+            }
+            KeyCode::Down | KeyCode::Tab => {
+                prompt.field = PublishField::ALL[(field + 1).min(PublishField::ALL.len() - 1)];
+            }
+            KeyCode::Enter => return self.start_publish(),
+            KeyCode::Left | KeyCode::Right => match prompt.field {
+                PublishField::Configuration if !configurations.is_empty() => {
+                    let current = configurations
+                        .iter()
+                        .position(|c| *c == prompt.configuration)
+                        .unwrap_or(0);
+                    let next = (current as isize + step).rem_euclid(configurations.len() as isize);
+                    prompt.configuration = configurations[next as usize].clone();
+                }
+                PublishField::Configuration => {}
+                PublishField::Runtime => {
+                    let count = RUNTIMES.len() as isize + 2;
+                    prompt.runtime = (prompt.runtime as isize + step).rem_euclid(count) as usize;
+                }
+                PublishField::SelfContained => prompt.self_contained = !prompt.self_contained,
+            },
+            KeyCode::Char(' ') if prompt.field == PublishField::SelfContained => {
+                prompt.self_contained = !prompt.self_contained;
+            }
+            // Typing goes to the runtime identifier of its own
+            _ if prompt.field == PublishField::Runtime && prompt.runtime > RUNTIMES.len() => {
+                prompt.custom_runtime.handle_key(key);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn start_publish(&mut self) -> Result<()> {
+        let Some(prompt) = self.publish_prompt.as_ref() else {
+            return Ok(());
+        };
+        let options = PublishOptions {
+            configuration: prompt.configuration.clone(),
+            runtime: prompt.runtime(),
+            self_contained: prompt.self_contained,
+        };
+        if options.self_contained && options.runtime.is_none() {
+            self.status
+                .flash_error("A self-contained publish needs a runtime identifier");
+            return Ok(());
+        }
+        if self.dotnet.is_none() {
+            return Err(AppError::DotnetMissing);
+        }
+        if self.busy() {
+            self.log_warn("Wait for the running build to finish before publishing");
+            return Ok(());
+        }
+        let Some(prompt) = self.publish_prompt.take() else {
+            return Ok(());
+        };
+        let target = prompt.target;
+
+        let key = target.project_path.to_string_lossy().into_owned();
+        self.state.publish.insert(key, options.clone());
+        self.save_state();
+
+        let mut command = Command::new("dotnet");
+        command
+            .arg("publish")
+            .arg(&target.project_path)
+            .arg("--configuration")
+            .arg(&options.configuration);
+        if let Some(runtime) = &options.runtime {
+            command.arg("--runtime").arg(runtime);
+        }
+        command
+            .arg("--self-contained")
+            .arg(options.self_contained.to_string())
+            .arg("--verbosity")
+            .arg(self.state.verbosity.label())
+            .arg("-nodeReuse:false")
+            .args(self.build_args_for(Some(&target.project)))
+            .env("MSBUILDDISABLENODEREUSE", "1");
+        let job = Job::spawn(&mut command).map_err(dotnet_spawn_error)?;
+
+        self.log_info(format!(
+            "Publishing {} ({}, {}, {})",
+            target.project,
+            options.configuration,
+            options.runtime.as_deref().unwrap_or("portable"),
+            if options.self_contained {
+                "self-contained"
+            } else {
+                "framework-dependent"
+            }
+        ));
+        self.problems.clear_build(&target.project);
+        self.publish = Some(Publish {
+            job,
+            target,
+            options,
+            diagnostics: DiagnosticParser::default(),
+            output: None,
+        });
+        Ok(())
+    }
+
+    fn cancel_publish(&mut self) -> Result<()> {
+        if let Some(mut publish) = self.publish.take() {
+            publish.job.kill()?;
+            for line in publish.job.drain() {
+                self.log_output(line);
+            }
+            self.log_warn(format!("Publish of {} cancelled", publish.target.project));
+        }
+        Ok(())
+    }
+
+    fn poll_publish(&mut self) -> Result<()> {
+        let Some(publish) = self.publish.as_mut() else {
+            return Ok(());
+        };
+
+        let lines = publish.job.drain();
+        let status = publish.job.poll_exit()?;
+        for line in &lines {
+            if let Some(dir) = publish_dir(&line.text) {
+                publish.output = Some(dir);
+            }
+            if let Some(parsed) = publish.diagnostics.feed(&line.text) {
+                self.problems.add(&publish.target.project, parsed);
+            }
+        }
+        for line in lines {
+            self.log_output(line);
+        }
+
+        let Some(status) = status else {
+            return Ok(());
+        };
+        let Some(publish) = self.publish.take() else {
+            return Ok(());
+        };
+
+        let project = &publish.target.project;
+        let elapsed = publish.job.started.elapsed().as_secs_f32();
+        if !status.success() {
+            let message = format!(
+                "Publish of {} failed after {:.1}s ({}) — {}",
+                project,
+                elapsed,
+                status,
+                self.problems.summary(Some(project))
+            );
+            self.status.flash_error(message.clone());
+            self.log_error(message);
+            return Ok(());
+        }
+
+        let dir = publish.output.clone().or_else(|| {
+            default_publish_dir(
+                &publish.target.project_path,
+                &publish.options.configuration,
+                publish.options.runtime.as_deref(),
+            )
+        });
+        let message = match &dir {
+            Some(dir) => format!(
+                "Published {} in {:.1}s to {}, press U to open it",
+                project,
+                elapsed,
+                dir.display()
+            ),
+            None => format!("Published {} in {:.1}s", project, elapsed),
+        };
+        self.status.flash(message.clone());
+        self.log(Level::Success, message);
+        self.publish_dir = dir;
+        Ok(())
+    }
+
+    /// Shows the folder of the last publish in the file manager.
+    fn open_publish_folder(&mut self) {
+        let Some(dir) = self.publish_dir.clone() else {
+            self.log_warn("Nothing published yet, press u to publish a project");
+            return;
+        };
+        match open_url(&dir.to_string_lossy()) {
+            Ok(()) => self.log_info(format!("Opened {}", dir.display())),
+            Err(e) => self.log_error(format!("Failed to open {}: {}", dir.display(), e)),
+        }
+    }
+
+    /// Runs the selected test project with `dotnet test`, or every test project
+    /// of the solution when the selected project has no tests.
+    fn run_tests(&mut self) -> Result<()> {
+        let project = self
+            .selected_index()
+            .and_then(|selected| self.projects.get(selected))
+            .filter(|project| project.output == OutputKind::Test);
+        let (name, path) = match project {
+            Some(project) => (project.name.clone(), project.path.clone()),
+            None => (
+                solution_name(&self.selected_sln),
+                PathBuf::from(&self.selected_sln),
+            ),
+        };
+        let description = format!("Testing {}", name);
+        self.start_tests(name, path, None, description)
+    }
+
+    /// Runs only the tests that failed last time.
+    fn rerun_failed_tests(&mut self) -> Result<()> {
+        let Some(results) = self
+            .test_results
+            .as_ref()
+            .filter(|r| !r.failures.is_empty())
+        else {
+            return Ok(());
+        };
+        let (name, path) = (results.name.clone(), results.path.clone());
+        let filter = rerun_filter(&results.failures);
+        let description = format!("Rerunning the failed tests of {}", name);
+        self.start_tests(name, path, Some(filter), description)
+    }
+
+    fn start_tests(
+        &mut self,
+        name: String,
+        path: PathBuf,
+        filter: Option<String>,
+        description: String,
+    ) -> Result<()> {
+        if self.dotnet.is_none() {
+            return Err(AppError::DotnetMissing);
+        }
+        if self.build.is_some() || self.publish.is_some() {
+            self.log_warn("A build is already running");
+            return Ok(());
+        }
+        if self.tests.is_some() {
+            self.log_warn("Tests are already running");
+            return Ok(());
+        }
+        self.log_info(description);
+
+        // A directory of its own, since a TRX file is named after the machine
+        // and the time rather than anything we choose
+        let results = std::env::temp_dir().join(format!(
+            "sln-runner-tests-{}-{}",
+            std::process::id(),
+            Local::now().timestamp_millis()
+        ));
+        let mut command = Command::new("dotnet");
+        command
+            .arg("test")
+            .arg(&path)
+            .arg("--configuration")
+            .arg(&self.configuration)
+            .arg("--logger")
+            .arg("trx")
+            .arg("--results-directory")
+            .arg(&results)
+            .arg("-nodeReuse:false")
+            .env("MSBUILDDISABLENODEREUSE", "1");
+        if let Some(filter) = &filter {
+            command.arg("--filter").arg(filter);
+        }
+        let job = Job::spawn(&mut command).map_err(dotnet_spawn_error)?;
+
+        self.problems.clear_build(&name);
+        self.tests = Some(TestRun {
+            job,
+            name,
+            path,
+            results,
+            diagnostics: DiagnosticParser::default(),
+            summary: TestSummary::default(),
+        });
+        Ok(())
+    }
+
+    fn cancel_tests(&mut self) -> Result<()> {
+        if let Some(mut tests) = self.tests.take() {
+            tests.job.kill()?;
+            for line in tests.job.drain() {
+                self.log_output(line);
+            }
+            self.log_warn(format!("Tests of {} cancelled", tests.name));
+        }
+        Ok(())
+    }
+
+    fn poll_tests(&mut self) -> Result<()> {
+        let Some(tests) = self.tests.as_mut() else {
+            return Ok(());
+        };
+
+        let lines = tests.job.drain();
+        let status = tests.job.poll_exit()?;
+        for line in &lines {
+            tests.summary.feed(&line.text);
+            if let Some(parsed) = tests.diagnostics.feed(&line.text) {
+                self.problems.add(&tests.name, parsed);
+            }
+        }
+        for line in lines {
+            self.log_output(line);
+        }
+
+        let Some(status) = status else {
+            return Ok(());
+        };
+        let Some(tests) = self.tests.take() else {
+            return Ok(());
+        };
+
+        let summary = tests.summary;
+        let elapsed = summary
+            .duration
+            .unwrap_or_else(|| tests.job.started.elapsed())
+            .as_secs_f32();
+        if summary.assemblies == 0 {
+            // Nothing ran, most likely because the build failed
+            let (errors, _) = self.problems.counts(Some(&tests.name));
+            let message = match errors {
+                0 if status.success() => {
+                    self.log_warn(format!("No tests found in {}", tests.name));
+                    return Ok(());
+                }
+                0 => format!("Tests of {} failed to run ({})", tests.name, status),
+                _ => format!(
+                    "Tests of {} failed to build — {}, press E to list them",
+                    tests.name,
+                    self.problems.summary(Some(&tests.name))
+                ),
+            };
+            self.status.flash_error(message.clone());
+            self.log_error(message);
+            return Ok(());
+        }
+
+        let results = read_results(&tests.results);
+        if let Some(tree) = self.explorer.as_mut().and_then(|e| e.tree.as_mut()) {
+            tree.record(&results);
+        }
+        // `dotnet test` built the project first
+        self.invalidate_test_lists(&tests.path)?;
+        let failures: Vec<TestResult> = results
+            .into_iter()
+            .filter(|result| result.outcome == Outcome::Failed)
+            .collect();
+        let hint = if failures.is_empty() {
+            ""
+        } else {
+            ", press F to list the failures"
+        };
+        self.failure_state.select(Some(0));
+        self.test_results = Some(TestResults {
+            name: tests.name.clone(),
+            path: tests.path.clone(),
+            failures,
+        });
+        if summary.failed > 0 || !status.success() {
+            let message = format!(
+                "Tests of {} failed in {:.1}s — {}{}",
+                tests.name,
+                elapsed,
+                summary.counts(),
+                hint
+            );
+            self.status.flash_error(message.clone());
+            self.log_error(message);
+        } else {
+            let message = format!(
+                "Tests of {} passed in {:.1}s — {}",
+                tests.name,
+                elapsed,
+                summary.counts()
+            );
+            self.status.flash(message.clone());
+            self.log(Level::Success, message);
+        }
+        Ok(())
+    }
+
+    fn poll_build(&mut self) -> Result<()> {
+        let Some(build) = self.build.as_mut() else {
+            return Ok(());
+        };
+
+        let lines = build.job.drain();
+        let status = build.job.poll_exit()?;
+        for line in &lines {
+            if line.is_error() && build.errors.len() < BUILD_ERROR_LINES {
+                build.errors.push(line.text.clone());
+            }
+            build.feed_auth.feed(&line.text);
+            if !build.solution {
+                if let Some(parsed) = build.diagnostics.feed(&line.text) {
+                    self.problems.add(&build.target.project, parsed);
+                }
+                continue;
+            }
+            if let Some(project) = built_project(&line.text) {
+                build.built.insert(project);
+            }
+            // A solution's problems go to the project that reported them
+            if let Some(parsed) = build.diagnostics.feed(&line.text) {
+                if let Parsed::Diagnostic(diagnostic) = &parsed {
+                    build.last_problem = reporting_project(&self.projects, diagnostic)
+                        .unwrap_or_else(|| build.target.project.clone());
+                }
+                self.problems.add(&build.last_problem, parsed);
+            }
+        }
+        for line in lines {
+            self.log_output(line);
+        }
+
+        let Some(status) = status else {
+            return Ok(());
+        };
+        let Some(build) = self.build.take() else {
+            return Ok(());
+        };
+        if let Some(filter) = &build.solution_filter {
+            let _ = fs::remove_file(filter);
+        }
+
+        let project = build.target.project.clone();
+        let elapsed = build.job.started.elapsed().as_secs_f32();
+        if let Some(binlog) = build.binlog.as_ref().filter(|binlog| binlog.is_file()) {
+            self.log_info(format!(
+                "Binary log written to {}, open it in the MSBuild Structured Log Viewer",
+                binlog.display()
+            ));
+        }
+        if build.solution {
+            return self.finish_solution_build(build, status, elapsed);
+        }
+        let (errors, warnings) = self.problems.counts(Some(&project));
+        let mut counts = count_summary(errors, warnings);
+        if errors + warnings > 0 {
+            counts.push_str(", press E to list them");
+        }
+        self.record_build(
+            &project,
+            &build.target.project_path,
+            status.success(),
+            elapsed,
+        );
+        if status.success() {
+            self.invalidate_test_lists(&build.target.project_path)?;
+        }
+        if !status.success() {
+            self.log_error(format!(
+                "Build of {} failed after {:.1}s ({}) — {}",
+                project, elapsed, status, counts
+            ));
+            if let Some(error) = &build.feed_auth.error {
+                self.log_warn(error.hint());
+            }
+            return Err(AppError::BuildFailed {
+                project,
+                stderr: build.errors,
+            });
+        }
+
+        let message = format!(
+            "Build of {} succeeded in {:.1}s — {}",
+            project, elapsed, counts
+        );
+        self.status.flash(message.clone());
+        if warnings > 0 {
+            self.log_warn(message);
+        } else {
+            self.log_info(message);
+        }
+        if !build.run {
+            return Ok(());
+        }
+        if let Err(e) = self.launch_project(
+            build.target,
+            build.launch_profile,
+            build.configuration,
+            RunMode::NoBuild,
+        ) {
+            self.log_error(format!("Failed to start {}: {}", project, e));
+        }
+        Ok(())
+    }
+
+    /// Reports a solution build and updates the build status of every project
+    /// it built or found problems in.
+    fn finish_solution_build(
+        &mut self,
+        build: Build,
+        status: ExitStatus,
+        elapsed: f32,
+    ) -> Result<()> {
+        let reported: Vec<(String, PathBuf)> = self
+            .projects
+            .iter()
+            .filter(|p| {
+                build.built.contains(&p.name) || self.problems.counts(Some(&p.name)) != (0, 0)
+            })
+            .map(|p| (p.name.clone(), p.path.clone()))
+            .collect();
+        let mut failed = Vec::new();
+        for (name, path) in &reported {
+            let (errors, _) = self.problems.counts(Some(name));
+            let succeeded = errors == 0 && build.built.contains(name);
+            if !succeeded {
+                failed.push(name.clone());
+            }
+            self.record_build(name, path, succeeded, elapsed);
+        }
+
+        let solution = build.target.project;
+        let (errors, warnings) = self.problems.counts(None);
+        let mut counts = count_summary(errors, warnings);
+        if errors + warnings > 0 {
+            counts.push_str(", press E to list them");
+        }
+        if !status.success() {
+            let failed = match failed.as_slice() {
+                [] => String::new(),
+                names => format!(" in {}", names.join(", ")),
+            };
+            self.log_error(format!(
+                "Build of {} failed after {:.1}s ({}){} — {}",
+                solution, elapsed, status, failed, counts
+            ));
+            return Err(AppError::BuildFailed {
+                project: solution,
+                stderr: build.errors,
+            });
+        }
+
+        self.invalidate_test_lists(&build.target.project_path)?;
+        let message = format!(
+            "Build of {} succeeded in {:.1}s, {} projects built — {}",
+            solution,
+            elapsed,
+            build.built.len(),
+            counts
+        );
+        self.status.flash(message.clone());
+        if warnings > 0 {
+            self.log_warn(message);
+        } else {
+            self.log(Level::Success, message);
+        }
+        Ok(())
+    }
+
+    fn launch_project(
+        &mut self,
+        target: RunTarget,
+        launch_profile: Option<String>,
+        configuration: String,
+        mode: RunMode,
+    ) -> Result<()> {
+        let held =
+            self.hold_for_dev_cert(&target, launch_profile.as_deref(), || AfterHooks::Launch {
+                target: target.clone(),
+                launch_profile: launch_profile.clone(),
+                configuration: configuration.clone(),
+                mode,
+            })?;
+        if held {
+            return Ok(());
+        }
+        let held = self.hold_for_spa(&target, || AfterHooks::Launch {
+            target: target.clone(),
+            launch_profile: launch_profile.clone(),
+            configuration: configuration.clone(),
+            mode,
+        })?;
+        if held {
+            return Ok(());
+        }
+        let hooks = self.project_hooks(&target.project);
+        let stages: &[HookStage] = if mode == RunMode::NoBuild {
+            &[HookStage::PreRun]
+        } else {
+            &[HookStage::PreBuild, HookStage::PreRun]
+        };
+        let held =
+            self.hold_for_hooks(&target.project, &hooks, &target.project_dir, stages, || {
+                AfterHooks::Launch {
+                    target: target.clone(),
+                    launch_profile: launch_profile.clone(),
+                    configuration: configuration.clone(),
+                    mode,
+                }
+            })?;
+        if held {
+            return Ok(());
+        }
+
+        let previous = self.replace_run(&target.project_dir)?;
+
+        let profile = launch_profile.as_ref().and_then(|name| {
+            read_launch_profiles(&target.project_dir)
+                .into_iter()
+                .find(|p| &p.name == name)
+        });
+        // dotnet run rejects a missing or non-Project profile, so run without one instead
+        let profile = match (profile, &launch_profile) {
+            (Some(profile), _) if profile.is_supported() => Some(profile),
+            (_, Some(name)) => {
+                self.log_warn(format!(
+                    "Launch profile '{}' is missing or not runnable with dotnet run, running without it",
+                    name
+                ));
+                None
+            }
+            (_, None) => None,
+        };
+        let launch_profile = profile.as_ref().map(|p| p.name.clone());
+
+        match &profile {
+            Some(profile) => {
+                self.log_info(format!("Using launch profile: {}", profile.name));
+                if let Some(url) = &profile.application_url {
+                    self.log_info(format!("  applicationUrl: {}", url));
+                }
+                match &profile.command_line_args {
+                    Some(args) if !target.args.is_empty() => self.log_warn(format!(
+                        "  commandLineArgs '{}' are replaced by the given arguments",
+                        args
+                    )),
+                    Some(args) => self.log_info(format!("  commandLineArgs: {}", args)),
+                    None => {}
+                }
+            }
+            None => self.log_info("Running without a launch profile"),
+        }
+
+        // applicationUrl separates several addresses with `;`
+        let profile_urls = profile
+            .as_ref()
+            .and_then(|p| p.application_url.as_deref())
+            .map(|urls| urls.split(';').map(|url| url.trim().to_string()).collect())
+            .unwrap_or_default();
+        let profile_vars = profile.map(|p| p.environment_variables).unwrap_or_default();
+        let key = target.project_path.to_string_lossy().into_owned();
+        let mut env = env::resolve(&self.env_vars(&target.project, &key, &profile_vars));
+        if let Some(environment) = self.state.environments.get(&key).cloned() {
+            for name in ["ASPNETCORE_ENVIRONMENT", "DOTNET_ENVIRONMENT"] {
+                env.insert(name.to_string(), environment.clone());
+            }
+            self.log_info(format!("Environment: {}", environment));
+        }
+        if let Some(SdkPin::RollForward) = self.sdk_pins.get(&target.project_path) {
+            env.insert("DOTNET_ROLL_FORWARD".to_string(), "LatestMajor".to_string());
+        }
+
+        // After a separate build its problems stay, otherwise this run builds
+        if mode != RunMode::NoBuild {
+            self.problems.clear_build(&target.project);
+        }
+        // The framework goes with the MSBuild arguments, so that a restart keeps it
+        let mut build_args = Vec::new();
+        if let Some(framework) = &target.framework {
+            build_args.extend(["--framework".to_string(), framework.clone()]);
+        }
+        build_args.extend(self.build_args_for(Some(&target.project)));
+        let job = Self::spawn_run(
+            &target.project_dir,
+            launch_profile.as_deref(),
+            &configuration,
+            mode,
+            &build_args,
+            &target.args,
+            &env,
+        )?;
+        match target.args.as_slice() {
+            [] => self.log_info(format!("Running {}", target.project)),
+            args => self.log_info(format!(
+                "Running {} with arguments {:?}",
+                target.project, args
+            )),
+        }
+
+        self.process_started(&target.project, &target.project_dir);
+        let auto_restart =
+            target.auto_restart || self.config.auto_restart.contains(&target.project);
+        let process = RunningProcess {
+            job,
+            project: target.project,
+            solution: self.selected_sln.clone(),
+            project_dir: target.project_dir,
+            launch_profile,
+            configuration,
+            mode,
+            build_args,
+            args: target.args,
+            env,
+            urls: Vec::new(),
+            profile_urls,
+            build_errors: Vec::new(),
+            diagnostics: DiagnosticParser::default(),
+            output: self.new_output(),
+            status: None,
+            stopping: None,
+            restarting: false,
+            crashed: false,
+            auto_restart,
+            crashes: 0,
+            restart_at: None,
+            container: None,
+            spa: None,
+            recording: None,
+        };
+        self.add_process(previous, process);
+        Ok(())
+    }
+
+    /// Checks once a session, before the first launch over HTTPS, that a
+    /// development certificate is installed, which Kestrel cannot serve HTTPS
+    /// without. Returns whether the launch is held back for the prompt that
+    /// offers to trust one.
+    fn hold_for_dev_cert(
+        &mut self,
+        target: &RunTarget,
+        launch_profile: Option<&str>,
+        then: impl FnOnce() -> AfterHooks,
+    ) -> Result<bool> {
+        if !self.config.check_dev_certs || self.dev_cert_checked {
+            return Ok(false);
+        }
+        // Explicit --urls replace the profile's
+        let urls = match target.args.iter().position(|arg| arg == "--urls") {
+            Some(index) => target.args.get(index + 1).cloned(),
+            None => launch_profile
+                .and_then(|name| {
+                    read_launch_profiles(&target.project_dir)
+                        .into_iter()
+                        .find(|p| p.name == name)
+                })
+                .and_then(|p| p.application_url),
+        };
+        let Some(urls) = urls.filter(|urls| devcerts::uses_https(urls)) else {
+            return Ok(false);
+        };
+        // Without dotnet the launch fails on its own
+        let Ok(valid) = devcerts::check() else {
+            return Ok(false);
+        };
+        if valid {
+            self.dev_cert_checked = true;
+            return Ok(false);
+        }
+        self.log_warn(format!(
+            "No valid HTTPS development certificate to serve {} on {}",
+            target.project, urls
+        ));
+        self.dev_cert_prompt = Some(DevCertPrompt {
+            project: target.project.clone(),
+            urls,
+            then: then(),
+            state: ListState::default().with_selected(Some(0)),
+        });
+        Ok(true)
+    }
+
+    fn handle_dev_cert_prompt_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(prompt) = self.dev_cert_prompt.as_mut() else {
+            return Ok(());
+        };
+        let current = prompt.state.selected().unwrap_or(0);
+        let choice = match key.code {
+            KeyCode::Up => {
+                prompt.state.select(Some(current.saturating_sub(1)));
+                return Ok(());
+            }
+            KeyCode::Down => {
+                let last = DevCertChoice::ALL.len() - 1;
+                prompt.state.select(Some((current + 1).min(last)));
+                return Ok(());
+            }
+            KeyCode::Enter => DevCertChoice::ALL[current],
+            KeyCode::Char('t') => DevCertChoice::Trust,
+            KeyCode::Char('c') => DevCertChoice::Continue,
+            KeyCode::Esc => DevCertChoice::Abort,
+            _ => return Ok(()),
+        };
+        let Some(prompt) = self.dev_cert_prompt.take() else {
+            return Ok(());
+        };
+
+        match choice {
+            DevCertChoice::Trust => self.start_dev_cert_trust(prompt.then),
+            DevCertChoice::Continue => {
+                // Not asked again this session
+                self.dev_cert_checked = true;
+                self.log_warn(format!(
+                    "Running {} without an HTTPS development certificate",
+                    prompt.project
+                ));
+                self.resume_after_hooks(prompt.then)
+            }
+            DevCertChoice::Abort => {
+                self.log_info(format!(
+                    "Not running {}: no HTTPS development certificate",
+                    prompt.project
+                ));
+                Ok(())
+            }
+        }
+    }
+
+    fn start_dev_cert_trust(&mut self, then: AfterHooks) -> Result<()> {
+        let job = Job::spawn(&mut devcerts::trust_command()).map_err(dotnet_spawn_error)?;
+        self.log_info(format!("Running {}", devcerts::TRUST_COMMAND));
+        if let Some(hint) = devcerts::elevation_hint() {
+            self.status.flash(hint);
+            self.log_info(hint);
+        }
+        self.dev_cert_job = Some(DevCertJob {
+            job,
+            project: then.name(),
+            then,
+        });
+        Ok(())
+    }
+
+    fn cancel_dev_cert_job(&mut self) -> Result<()> {
+        if let Some(mut dev_cert) = self.dev_cert_job.take() {
+            dev_cert.job.kill()?;
+            self.log_warn(format!(
+                "Trusting the HTTPS development certificate cancelled, not running {}",
+                dev_cert.project
+            ));
+        }
+        Ok(())
+    }
+
+    /// Streams the output of `dotnet dev-certs https --trust` to the log, and
+    /// carries on with the launch once there is a certificate.
+    fn poll_dev_cert(&mut self) -> Result<()> {
+        let Some(dev_cert) = self.dev_cert_job.as_mut() else {
+            return Ok(());
+        };
+        let lines = dev_cert.job.drain();
+        let status = dev_cert.job.poll_exit()?;
+        for line in lines {
+            self.log_output(OutputLine {
+                text: format!("[dev-certs] {}", line.text),
+                is_stderr: line.is_stderr,
+            });
+        }
+        let Some(status) = status else {
+            return Ok(());
+        };
+        let Some(dev_cert) = self.dev_cert_job.take() else {
+            return Ok(());
+        };
+
+        if status.success() {
+            self.log(Level::Success, "HTTPS development certificate trusted");
+        } else if devcerts::check().unwrap_or(false) {
+            // Where the SDK cannot trust it, as on most Linux distributions, the
+            // certificate is still created
+            self.log_warn(format!(
+                "The HTTPS development certificate was created but not trusted ({}), browsers may warn about it",
+                status
+            ));
+        } else {
+            let message = format!(
+                "{} failed ({}), not running {}",
+                devcerts::TRUST_COMMAND,
+                status,
+                dev_cert.project
+            );
+            self.status.flash_error(message.clone());
+            self.log_error(message);
+            return Ok(());
+        }
+        self.dev_cert_checked = true;
+        self.resume_after_hooks(dev_cert.then)
+    }
+
+    /// Starts the dev server of the project's single-page app, when the
+    /// project runs with it and it is not running yet. Unless `wait_for_spa`
+    /// is off, the launch is held back until the dev server listens; returns
+    /// whether it is, and `poll_spa_wait` carries on.
+    fn hold_for_spa(
+        &mut self,
+        target: &RunTarget,
+        then: impl FnOnce() -> AfterHooks,
+    ) -> Result<bool> {
+        if !self.runs_spa(&target.project_path) {
+            return Ok(false);
+        }
+        let Some(spa) = Spa::detect(&target.project_path) else {
+            self.log_warn(format!(
+                "No SPA found for {} any more, running it without",
+                target.project
+            ));
+            return Ok(false);
+        };
+        let running = self
+            .processes
+            .iter()
+            .any(|p| p.project_dir == spa.root && p.status.is_none());
+        if running {
+            return Ok(false);
+        }
+
+        let previous = self.replace_run(&spa.root)?;
+        let job = Job::spawn(&mut spa.command())?;
+        self.log_info(format!(
+            "Starting the SPA dev server of {}: {}",
+            target.project, spa.command
+        ));
+        let root = spa.root.clone();
+        let probe = spa
+            .port()
+            .and_then(|port| Probe::start(&Readiness::Port(port)));
+        let process = RunningProcess {
+            job,
+            project: format!("{} SPA", target.project),
+            solution: self.selected_sln.clone(),
+            project_dir: spa.root.clone(),
+            launch_profile: None,
+            configuration: String::new(),
+            mode: RunMode::NoBuild,
+            build_args: Vec::new(),
+            args: Vec::new(),
+            env: BTreeMap::new(),
+            urls: Vec::new(),
+            profile_urls: spa.url.iter().cloned().collect(),
+            build_errors: Vec::new(),
+            diagnostics: DiagnosticParser::default(),
+            output: self.new_output(),
+            status: None,
+            stopping: None,
+            restarting: false,
+            crashed: false,
+            auto_restart: false,
+            crashes: 0,
+            restart_at: None,
+            container: None,
+            spa: Some(spa),
+            recording: None,
+        };
+        self.add_process(previous, process);
+        if !self.config.wait_for_spa {
+            return Ok(false);
+        }
+        self.spa_wait = Some(SpaWait {
+            project: target.project.clone(),
+            root,
+            probe,
+            started: Instant::now(),
+            then: then(),
+        });
+        Ok(true)
+    }
+
+    /// Carries on with the held back launch once the dev server listens, or
+    /// after a while without, and drops it when the dev server exits.
+    fn poll_spa_wait(&mut self) -> Result<()> {
+        let Some(wait) = &self.spa_wait else {
+            return Ok(());
+        };
+        let process = self.processes.iter().find(|p| p.project_dir == wait.root);
+        let exited = process.is_none_or(|p| p.status.is_some());
+        let listening = match &wait.probe {
+            Some(probe) => probe.is_ready(),
+            None => process.is_some_and(|p| !p.urls.is_empty()),
+        };
+        let timed_out = wait.started.elapsed() >= SPA_TIMEOUT;
+        if !exited && !listening && !timed_out {
+            return Ok(());
+        }
+        let Some(wait) = self.spa_wait.take() else {
+            return Ok(());
+        };
+        if exited {
+            let message = format!(
+                "The SPA dev server of {} exited, not running {}",
+                wait.project, wait.project
+            );
+            self.status.flash_error(message.clone());
+            self.log_error(message);
+            return Ok(());
+        }
+        if listening {
+            self.log_info(format!("The SPA dev server of {} listens", wait.project));
+        } else {
+            self.log_warn(format!(
+                "The SPA dev server of {} did not listen within {}s, running it anyway",
+                wait.project,
+                SPA_TIMEOUT.as_secs()
+            ));
+        }
+        self.resume_after_hooks(wait.then)
+    }
+
+    fn cancel_spa_wait(&mut self) {
+        if let Some(wait) = self.spa_wait.take() {
+            self.log_warn(format!(
+                "Stopped waiting for the SPA dev server of {}, not running it",
+                wait.project
+            ));
+        }
+    }
+
+    /// Stops the run of the project in `project_dir`, as a project runs at
+    /// most once and a new run replaces the old one. Returns where the old
+    /// one is listed.
+    fn replace_run(&mut self, project_dir: &Path) -> Result<Option<usize>> {
+        let previous = self
+            .processes
+            .iter()
+            .position(|p| p.project_dir == project_dir);
+        if let Some(previous) = previous
+            .map(|index| &mut self.processes[index])
+            .filter(|p| p.status.is_none())
+        {
+            // The new container needs the name and ports of the old one
+            if let Some(container) = &previous.container {
+                container.remove()?;
+            }
+            let status = previous.job.kill()?;
+            let project = previous.project.clone();
+            self.log_info(format!("Stopped {} ({})", project, status));
+            self.process_stopped(&project, project_dir);
+        }
+        Ok(previous)
+    }
+
+    /// An empty scrollback for the output of a process, within the limits
+    /// of the config.
+    fn new_output(&self) -> Scrollback<String> {
+        Scrollback::new(self.config.output_lines, self.config.scrollback_bytes())
+    }
+
+    /// Lists a process that just started, in place of the one it replaces,
+    /// and shows its output.
+    fn add_process(&mut self, previous: Option<usize>, process: RunningProcess) {
+        self.current_process = match previous {
+            Some(index) => {
+                self.processes[index] = process;
+                index
+            }
+            None => {
+                self.processes.push(process);
+                self.processes.len() - 1
+            }
+        };
+        self.last_launch = Some(Instant::now());
+        self.output_anchor = None;
+        self.output_match = None;
+        self.output_paused = None;
+        self.record(self.current_process);
+        self.set_view(View::Output);
+    }
+
+    /// Starts saving the output of the process's run to a file, with
+    /// record_output.
+    fn record(&mut self, index: usize) {
+        if !self.config.record_output {
+            return;
+        }
+        let Some(template) = self
+            .config
+            .record_path
+            .clone()
+            .or_else(recording::default_template)
+        else {
+            self.log_warn("Cannot record output: no data directory, set record_path");
+            return;
+        };
+        let Some(process) = self.processes.get_mut(index) else {
+            return;
+        };
+        let max_bytes = self.config.record_max_mb.saturating_mul(1024 * 1024);
+        match Recording::create(&template, &process.project, max_bytes) {
+            Ok(recording) => {
+                let message = format!(
+                    "Recording the output of {} to {}",
+                    process.project,
+                    recording.path.display()
+                );
+                process.recording = Some(recording);
+                self.log_info(message);
+            }
+            Err(e) => {
+                process.recording = None;
+                let message = format!("Cannot record the output of {}: {}", process.project, e);
+                self.log_warn(message);
+            }
+        }
+    }
+
+    fn spawn_run(
+        project_dir: &Path,
+        launch_profile: Option<&str>,
+        configuration: &str,
+        mode: RunMode,
+        build_args: &[String],
+        args: &[String],
+        env: &BTreeMap<String, String>,
+    ) -> Result<Job> {
+        let mut command = run_command(
+            project_dir,
+            launch_profile,
+            configuration,
+            mode,
+            build_args,
+            args,
+            env,
+        );
+        Job::spawn(&mut command).map_err(dotnet_spawn_error)
+    }
+
+    fn poll_process(&mut self, index: usize) -> Result<()> {
+        let Some(process) = self.processes.get_mut(index) else {
+            return Ok(());
+        };
+        if process.status.is_some() {
+            if process.restart_at.is_some_and(|at| at <= Instant::now()) {
+                // Cleared first, so a failed restart is not retried every tick
+                process.restart_at = None;
+                return self.respawn(index);
+            }
+            return Ok(());
+        }
+
+        let mut reload = None;
+        let mut opened = None;
+        let mut record_error = None;
+        let lines = process.job.drain();
+        if let Some(container) = process.container.as_mut() {
+            container.poll_id();
+        }
+        for line in &lines {
+            // Inside a container the app listens on the container's ports
+            let url = match &process.spa {
+                Some(_) => dev_server_url(&line.text),
+                None => listening_url(&line.text).filter(|_| process.container.is_none()),
+            };
+            if let Some(url) = url {
+                // The app is opened through the project rather than its SPA
+                if self.config.auto_open_browser && process.urls.is_empty() && process.spa.is_none()
+                {
+                    opened = Some(url.clone());
+                }
+                if !process.urls.contains(&url) {
+                    process.urls.push(url);
+                }
+            }
+            if process.mode != RunMode::NoBuild {
+                if let Some(parsed) = process.diagnostics.feed(&line.text) {
+                    self.problems.add(&process.project, parsed);
+                }
+            }
+            match process.mode {
+                RunMode::Run
+                    if line.is_error() && process.build_errors.len() < BUILD_ERROR_LINES =>
+                {
+                    process.build_errors.push(line.text.clone());
+                }
+                RunMode::Watch if line.text.contains("Hot reload") => {
+                    reload = Some(line.text.trim().to_string());
+                }
+                _ => {}
+            }
+            if let Some(recording) = process.recording.as_mut() {
+                if let Err(e) = recording.write(&line.text) {
+                    record_error = Some(e);
+                }
+            }
+            process.output.push(line.text.clone());
+        }
+        if let Some(recording) = process.recording.as_mut() {
+            if let Err(e) = recording.flush() {
+                record_error = Some(e);
+            }
+        }
+        // A full disk stops the recording rather than failing every line
+        if record_error.is_some() {
+            process.recording = None;
+        }
+        // The log panel interleaves all processes, so tag each line with its project
+        let project = process.project.clone();
+        if let Some(e) = record_error {
+            self.log_warn(format!(
+                "Stopped recording the output of {}: {}",
+                project, e
+            ));
+        }
+        let alert = lines.iter().find(|line| {
+            rules::find(&self.output_rules, &project, &line.text)
+                .is_some_and(|rule| rule.config.action == RuleAction::Alert)
+        });
+        if let Some(line) = alert {
+            self.status
+                .flash_error(format!("{}: {}", project, strip_ansi(&line.text).trim()));
+            if self
+                .last_alert
+                .is_none_or(|at| at.elapsed() >= ALERT_INTERVAL)
+            {
+                terminal::bell();
+                self.last_alert = Some(Instant::now());
+            }
+        }
+        for line in lines {
+            self.log_output(OutputLine {
+                text: format!("[{}] {}", project, line.text),
+                ..line
+            });
+        }
+        if let Some(message) = reload {
+            self.status.flash(message);
+        }
+        if let Some(url) = opened {
+            let result = open_url(&url);
+            self.report_open(&url, result);
+        }
+        let Some(process) = self.processes.get_mut(index) else {
+            return Ok(());
+        };
+
+        // Whatever ignores the interrupt is killed after a while
+        let overdue = process
+            .stopping
+            .is_some_and(|at| at.elapsed() >= STOP_TIMEOUT);
+        if overdue && !process.job.has_exited() {
+            process.job.kill()?;
+            let message = format!(
+                "{} did not stop within {}s, killed it",
+                process.project,
+                STOP_TIMEOUT.as_secs()
+            );
+            self.log_warn(message);
+            return Ok(());
+        }
+
+        let Some(status) = process.job.poll_exit()? else {
+            return Ok(());
+        };
+        process.status = Some(status);
+        let project = process.project.clone();
+        // A restart keeps what runs alongside
+        if process.stopping.is_none() || !process.restarting {
+            let project_dir = process.project_dir.clone();
+            self.process_stopped(&project, &project_dir);
+        }
+        let Some(process) = self.processes.get_mut(index) else {
+            return Ok(());
+        };
+        if process.stopping.take().is_some() {
+            if std::mem::take(&mut process.restarting) {
+                self.log_info(format!("{} exited ({}), restarting", project, status));
+                return self.respawn(index);
+            }
+            let message = format!("Stopped {} ({})", project, status);
+            self.status.flash(message.clone());
+            self.log_info(message);
+            return Ok(());
+        }
+        if status.success() {
+            let message = format!("{} exited ({})", project, status);
+            self.status.flash(message.clone());
+            self.log_info(message);
+            return Ok(());
+        }
+
+        // `dotnet run` reports compiler errors before the app ever starts
+        let build_errors = std::mem::take(&mut process.build_errors);
+        if !build_errors.is_empty() {
+            self.log_error(format!(
+                "Build of {} failed in dotnet run ({}), press E to list the problems",
+                project, status
+            ));
+            return Err(AppError::BuildFailed {
+                project,
+                stderr: build_errors,
+            });
+        }
+        process.crashed = true;
+        let tail = process.output.len().saturating_sub(CRASH_OUTPUT_LINES);
+        let output: Vec<String> = process.output.iter().skip(tail).cloned().collect();
+        let message = format!("{} crashed ({})", project, status);
+        self.status.flash_error(message.clone());
+        self.log_error(message);
+        if !output.is_empty() {
+            self.log_error(format!("Last {} lines of its output:", output.len()));
+            for line in output {
+                self.log_error(format!("  {}", line));
+            }
+        }
+        terminal::bell();
+        self.schedule_restart(index);
+        Ok(())
+    }
+
+    /// Restarts a crashed process after a delay that doubles with each crash
+    /// in a row, unless it is not set to restart or has run out of retries.
+    fn schedule_restart(&mut self, index: usize) {
+        let max_restarts = self.config.max_restarts;
+        let Some(process) = self.processes.get_mut(index).filter(|p| p.auto_restart) else {
+            return;
+        };
+        if process.job.uptime() >= STABLE_UPTIME {
+            process.crashes = 0;
+        }
+        if process.crashes >= max_restarts {
+            let message = format!(
+                "{} crashed {} times in a row, not restarting it",
+                process.project,
+                process.crashes + 1
+            );
+            self.log_error(message);
+            return;
+        }
+        let delay = RESTART_BACKOFF
+            .saturating_mul(2u32.saturating_pow(process.crashes))
+            .min(MAX_RESTART_DELAY);
+        process.crashes += 1;
+        process.restart_at = Some(Instant::now() + delay);
+        let message = format!(
+            "Restarting {} in {}s (attempt {} of {})",
+            process.project,
+            delay.as_secs(),
+            process.crashes,
+            max_restarts
+        );
+        self.log_warn(message);
+    }
+
+    fn process(&self) -> Option<&RunningProcess> {
+        self.processes.get(self.current_process)
+    }
+
+    fn select_process(&mut self, index: usize) {
+        self.current_process = index.min(self.processes.len().saturating_sub(1));
+    }
+
+    /// Drops the processes that have exited from the list.
+    fn clear_exited(&mut self) {
+        let current = self.process().map(|p| p.job.id());
+        let before = self.processes.len();
+        self.processes.retain(|p| p.status.is_none());
+        self.current_process = current
+            .and_then(|id| self.processes.iter().position(|p| p.job.id() == id))
+            .unwrap_or(0);
+        let message = format!("Cleared {} exited processes", before - self.processes.len());
+        self.status.flash(message.clone());
+        self.log_info(message);
+    }
+
+    fn switch_process(&mut self, delta: isize) {
+        let len = self.processes.len();
+        if len == 0 {
+            return;
+        }
+        self.current_process =
+            (self.current_process as isize + delta).rem_euclid(len as isize) as usize;
+        self.output_anchor = None;
+        self.output_match = None;
+        self.output_paused = None;
+    }
+
+    /// Starts the next marked project once the previous one is under way.
+    fn advance_launch_queue(&mut self) {
+        let busy = self.build.is_some()
+            || self.restore.is_some()
+            || self.pre_hook.is_some()
+            || self.hook_prompt.is_some()
+            || self.profile_picker.is_some()
+            || self.framework_picker.is_some()
+            || self.port_prompt.is_some()
+            || self.dev_cert_prompt.is_some()
+            || self.dev_cert_job.is_some()
+            || self
+                .last_launch
+                .is_some_and(|at| at.elapsed() < LAUNCH_STAGGER);
+        if busy {
+            return;
+        }
+        if let Some(target) = self.launch_queue.pop_front() {
+            self.last_launch = Some(Instant::now());
+            if let Err(e) = self.run_target(target) {
+                self.show_error(e);
+            }
+        }
+    }
+
+    /// Whether a launch is still being built or waits on a popup.
+    fn launch_in_progress(&self) -> bool {
+        self.build.is_some()
+            || self.restore.as_ref().is_some_and(|r| r.then.is_some())
+            || self.pre_hook.is_some()
+            || self.hook_prompt.is_some()
+            || self.profile_picker.is_some()
+            || self.framework_picker.is_some()
+            || self.port_prompt.is_some()
+            || self.dev_cert_prompt.is_some()
+            || self.dev_cert_job.is_some()
+    }
+
+    /// Moves the running group along: notices members becoming ready or
+    /// failing, and starts the next member whose dependencies are ready.
+    fn advance_group(&mut self) {
+        let in_progress = self.launch_in_progress();
+        let Some(run) = self.group_run.as_mut().filter(|run| !run.finished) else {
+            return;
+        };
+        let before: Vec<MemberState> = run.members.iter().map(|m| m.state.clone()).collect();
+
+        for member in &mut run.members {
+            let Some(launched) = member
+                .launched
+                .filter(|_| member.state == MemberState::Starting)
+            else {
+                continue;
+            };
+            let process = self.processes.iter().find(|p| {
+                Some(&p.project_dir) == member.project_dir.as_ref() && p.job.started >= launched
+            });
+            let Some(process) = process else {
+                if !in_progress {
+                    member.state = MemberState::Failed("did not start".to_string());
+                }
+                continue;
+            };
+            if let Some(status) = process.status {
+                member.state = MemberState::Failed(format!("exited ({})", status));
+                continue;
+            }
+
+            let running_since = *member.running_since.get_or_insert_with(Instant::now);
+            let ready = match &member.ready {
+                None => true,
+                Some(Readiness::Log(_)) => {
+                    let seen = member.seen;
+                    member.seen = process.output.pushed();
+                    member.log_pattern.as_ref().is_some_and(|pattern| {
+                        process
+                            .output
+                            .since(seen)
+                            .any(|line| pattern.is_match(&strip_ansi(line)))
+                    })
+                }
+                Some(ready) => {
+                    if member.probe.is_none() {
+                        member.probe = Probe::start(ready);
+                    }
+                    member.probe.as_ref().is_some_and(Probe::is_ready)
+                }
+            };
+            if ready {
+                member.state = MemberState::Ready;
+                member.probe = None;
+            } else if running_since.elapsed() >= member.timeout {
+                let waited_for = member.ready.as_ref().map(Readiness::describe);
+                member.state = MemberState::Failed(format!(
+                    "no {} within {}s",
+                    waited_for.unwrap_or_default(),
+                    member.timeout.as_secs()
+                ));
+                member.probe = None;
+            }
+        }
+        run.cascade_failures();
+
+        let changes: Vec<(String, MemberState)> = run
+            .members
+            .iter()
+            .zip(before)
+            .filter(|(member, before)| member.state != *before)
+            .map(|(member, _)| (member.name.clone(), member.state.clone()))
+            .collect();
+        for (name, state) in changes {
+            match state {
+                MemberState::Ready => self.log_info(format!("{} is ready", name)),
+                MemberState::Failed(reason) => {
+                    self.log_error(format!("{} failed: {}", name, reason))
+                }
+                MemberState::DependencyFailed(dependency) => self.log_warn(format!(
+                    "{} not started: dependency {} failed",
+                    name, dependency
+                )),
+                MemberState::Waiting | MemberState::Starting => {}
+            }
+        }
+
+        let Some(run) = self.group_run.as_mut() else {
+            return;
+        };
+        if run.is_done() {
+            run.finished = true;
+            let ready = run
+                .members
+                .iter()
+                .filter(|m| m.state == MemberState::Ready)
+                .count();
+            let message = format!(
+                "Group {}: {} of {} projects ready",
+                run.name,
+                ready,
+                run.members.len()
+            );
+            let name = run.name.clone();
+            let dirs: Vec<PathBuf> = run
+                .members
+                .iter()
+                .filter(|m| m.state == MemberState::Ready)
+                .filter_map(|m| m.project_dir.clone())
+                .collect();
+            if ready == run.members.len() {
+                self.status.flash(message.clone());
+                self.log_info(message);
+            } else {
+                self.status.flash_error(message.clone());
+                self.log_warn(message);
+            }
+            if !dirs.is_empty() {
+                self.group_started(&name, dirs);
+            }
+            return;
+        }
+
+        let stagger = self
+            .last_launch
+            .is_some_and(|at| at.elapsed() < LAUNCH_STAGGER);
+        // One launch at a time, as builds run one at a time
+        let launching = run
+            .members
+            .iter()
+            .any(|m| m.state == MemberState::Starting && m.running_since.is_none());
+        if in_progress || stagger || launching {
+            return;
+        }
+        let Some(index) = run.next_to_start() else {
+            return;
+        };
+        let member = &mut run.members[index];
+        let Some(project) = member.project.as_ref() else {
+            return;
+        };
+        let target = match Self::run_target_for(project) {
+            Ok(target) => RunTarget {
+                auto_restart: member.auto_restart,
+                ..target
+            },
+            Err(e) => {
+                member.state = MemberState::Failed(e.to_string());
+                return;
+            }
+        };
+        member.state = MemberState::Starting;
+        member.launched = Some(Instant::now());
+        member.project_dir = Some(target.project_dir.clone());
+        self.last_launch = Some(Instant::now());
+        if let Err(e) = self.run_target(target) {
+            if let Some(member) = self
+                .group_run
+                .as_mut()
+                .and_then(|run| run.members.get_mut(index))
+            {
+                member.state = MemberState::Failed(e.to_string());
+            }
+            self.show_error(e);
+        }
+    }
+
+    /// The group member's project in the open solution.
+    fn group_project(&self, member: &GroupMember) -> Option<&Project> {
+        let path = member.project.replace('\\', "/");
+        self.projects
+            .iter()
+            .find(|p| !p.missing && p.rel_path.replace('\\', "/") == path)
+    }
+
+    fn open_group_picker(&mut self) {
+        if self.config.groups.is_empty() && self.marked.is_empty() {
+            self.status
+                .flash("No groups yet: mark projects with Space, then save them as one");
+            return;
+        }
+        let groups = self
+            .config
+            .groups
+            .iter()
+            .map(|(name, members)| GroupRow {
+                name: name.clone(),
+                members: members.len(),
+                missing: members
+                    .iter()
+                    .filter(|member| self.group_project(member).is_none())
+                    .count(),
+            })
+            .collect();
+        self.group_picker = Some(GroupPicker {
+            groups,
+            save: !self.marked.is_empty(),
+            state: ListState::default().with_selected(Some(0)),
+        });
+    }
+
+    fn handle_group_picker_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(picker) = self.group_picker.as_mut() else {
+            return Ok(());
+        };
+        let current = picker.state.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Esc => self.group_picker = None,
+            KeyCode::Up => picker.state.select(Some(current.saturating_sub(1))),
+            KeyCode::Down => {
+                let last = (picker.groups.len() + picker.save as usize).saturating_sub(1);
+                picker.state.select(Some((current + 1).min(last)));
+            }
+            KeyCode::Enter => {
+                let group = picker.groups.get(current).map(|group| group.name.clone());
+                self.group_picker = None;
+                match group {
+                    Some(name) => self.run_group(&name)?,
+                    None => self.group_name = Some(TextInput::new(String::new())),
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Starts the group's projects in dependency order, each with its
+    /// remembered profile and environment like any other run.
+    fn run_group(&mut self, name: &str) -> Result<()> {
+        let members = self.config.groups.get(name).cloned().unwrap_or_default();
+        let missing: Vec<String> = members
+            .iter()
+            .filter(|member| self.group_project(member).is_none())
+            .map(|member| member.project.clone())
+            .collect();
+        let members = members
+            .into_iter()
+            .map(|member| {
+                let project = self.group_project(&member).cloned();
+                (member, project)
+            })
+            .collect();
+        let run = GroupRun::new(name, members).map_err(|message| AppError::Config {
+            path: Config::path().unwrap_or_default(),
+            message,
+        })?;
+        if self.hold_for_compose(name)? {
+            return Ok(());
+        }
+        let hooks = self.group_hooks(name);
+        let sln_dir = self
+            .sln_file
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let held = self.hold_for_hooks(
+            &format!("group {}", name),
+            &hooks,
+            &sln_dir,
+            &[HookStage::PreBuild, HookStage::PreRun],
+            || AfterHooks::Group(name.to_string()),
+        )?;
+        if held {
+            return Ok(());
+        }
+
+        // Past the hooks, so the next run brings the services up again
+        self.compose_passed.remove(name);
+        self.log_info(format!(
+            "Starting group {} ({} projects)",
+            name,
+            run.members.len()
+        ));
+        self.group_run = Some(run);
+        self.advance_group();
+        if !missing.is_empty() {
+            return Err(AppError::GroupMembersMissing {
+                group: name.to_string(),
+                members: missing,
+            });
+        }
+        Ok(())
+    }
+
+    fn handle_build_args_key(&mut self, key: KeyEvent) {
+        let Some(input) = self.build_args_input.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.build_args_input = None,
+            KeyCode::Enter => {
+                let line = input.value.trim().to_string();
+                let Some(args) = split_args(&line) else {
+                    self.log_warn("Unterminated quote in arguments");
+                    return;
+                };
+                self.build_args_input = None;
+                if args.is_empty() {
+                    self.clear_build_args();
+                    return;
+                }
+                self.log_info(format!("MSBuild arguments for this session: {}", line));
+                self.build_args_line = line;
+                self.build_args = args;
+            }
+            _ => {
+                input.handle_key(key);
+            }
+        }
+    }
+
+    fn clear_build_args(&mut self) {
+        if self.build_args.is_empty() {
+            return;
+        }
+        self.build_args.clear();
+        self.build_args_line.clear();
+        self.log_info("Cleared the MSBuild arguments for this session");
+    }
+
+    /// The MSBuild arguments of a build, run or publish: the solution's and
+    /// the project's from the config, then the ones set for the session.
+    fn build_args_for(&self, project: Option<&str>) -> Vec<String> {
+        let solution = solution_name(&self.selected_sln);
+        [Some(solution.as_str()), project]
+            .into_iter()
+            .flatten()
+            .filter_map(|key| self.config.build_args.get(key))
+            .flatten()
+            .chain(&self.build_args)
+            .cloned()
+            .collect()
+    }
+
+    fn handle_group_name_key(&mut self, key: KeyEvent) {
+        let Some(input) = self.group_name.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.group_name = None,
+            KeyCode::Enter => {
+                let name = input.value.trim().to_string();
+                if name.is_empty() {
+                    return;
+                }
+                self.group_name = None;
+                self.save_marked_group(name);
+            }
+            _ => {
+                input.handle_key(key);
+            }
+        }
+    }
+
+    /// Saves the marked projects to the config as group `name`, replacing any
+    /// group of that name.
+    fn save_marked_group(&mut self, name: String) {
+        let members: Vec<GroupMember> = self
+            .projects
+            .iter()
+            .filter(|p| self.marked.contains(&p.path))
+            .map(|p| GroupMember::new(p.rel_path.replace('\\', "/")))
+            .collect();
+        let mut groups = self.config.groups.clone();
+        groups.insert(name.clone(), members);
+
+        let saved = match Config::path() {
+            Some(path) => Config::save_groups(&path, &groups),
+            None => Err(AppError::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                "no config directory",
+            ))),
+        };
+        match saved {
+            Ok(()) => {
+                let message = format!("Saved group {} to the config", name);
+                self.status.flash(message.clone());
+                self.log_info(message);
+                self.config.groups = groups;
+                self.marked.clear();
+            }
+            Err(e) => {
+                self.status
+                    .flash_error(format!("Failed to save group: {}", e));
+                self.log_error(format!("Failed to save group {}: {}", name, e));
+            }
+        }
+    }
+
+    fn open_browser(&mut self) {
+        let Some(process) = self.process() else {
+            return;
+        };
+        match process.known_urls() {
+            [] => self.status.flash("No address known yet"),
+            [url] => {
+                let url = url.clone();
+                let result = open_url(&url);
+                self.report_open(&url, result);
+            }
+            urls => {
+                self.url_picker = Some(UrlPicker {
+                    urls: urls.to_vec(),
+                    state: ListState::default().with_selected(Some(0)),
+                });
+            }
+        }
+    }
+
+    fn handle_url_picker_key(&mut self, key: KeyEvent) {
+        let Some(picker) = self.url_picker.as_mut() else {
+            return;
+        };
+        let current = picker.state.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Esc => self.url_picker = None,
+            KeyCode::Up => picker.state.select(Some(current.saturating_sub(1))),
+            KeyCode::Down => {
+                let last = picker.urls.len().saturating_sub(1);
+                picker.state.select(Some((current + 1).min(last)));
+            }
+            KeyCode::Enter => {
+                let url = picker.urls[current].clone();
+                self.url_picker = None;
+                let result = open_url(&url);
+                self.report_open(&url, result);
+            }
+            _ => {}
+        }
+    }
+
+    /// Opens the highlighted solution, or the highlighted project of the open
+    /// one, falling back to the solution on a folder.
+    fn open_selected_in_ide(&mut self) {
+        if self.view == View::Solutions {
+            if let Some(sln) = self.highlighted_solution() {
+                self.open_in_ide(PathBuf::from(&sln), PathBuf::from(sln));
+            }
+            return;
+        }
+        let path = self
+            .selected_index()
+            .and_then(|selected| self.projects.get(selected))
+            .map(|project| project.path.clone())
+            .unwrap_or_else(|| PathBuf::from(&self.selected_sln));
+        self.open_in_ide(PathBuf::from(&self.selected_sln), path);
+    }
+
+    /// Opens the solution or project file in the one IDE there is, or asks
+    /// which when there are several.
+    fn open_in_ide(&mut self, sln: PathBuf, path: PathBuf) {
+        let mut ides = ide_commands(&self.config.ides);
+        if ides.len() == 1 {
+            let (name, template) = ides.remove(0);
+            self.launch_ide(&name, &template, &sln, &path);
+            return;
+        }
+        self.ide_picker = Some(IdePicker {
+            sln,
+            path,
+            ides,
+            state: ListState::default().with_selected(Some(0)),
+        });
+    }
+
+    fn handle_ide_picker_key(&mut self, key: KeyEvent) {
+        let Some(picker) = self.ide_picker.as_mut() else {
+            return;
+        };
+        let current = picker.state.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Esc => self.ide_picker = None,
+            KeyCode::Up => picker.state.select(Some(current.saturating_sub(1))),
+            KeyCode::Down => {
+                let last = picker.ides.len().saturating_sub(1);
+                picker.state.select(Some((current + 1).min(last)));
+            }
+            KeyCode::Enter => {
+                let Some(picker) = self.ide_picker.take() else {
+                    return;
+                };
+                let (name, template) = &picker.ides[current];
+                self.launch_ide(name, template, &picker.sln, &picker.path);
+            }
+            _ => {}
+        }
+    }
+
+    // Started on its own, so the IDE stays open after the TUI quits
+    fn launch_ide(&mut self, name: &str, template: &str, sln: &Path, path: &Path) {
+        let result = ide_command(template, sln, path).and_then(|ide| {
+            // `code` and others are batch files on Windows, which only cmd runs
+            let mut command = if cfg!(windows) {
+                let mut command = Command::new("cmd");
+                command.arg("/C").arg(&ide.program);
+                command
+            } else {
+                Command::new(&ide.program)
+            };
+            let mut child = command
+                .args(&ide.args)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .map_err(|e| match e.kind() {
+                    io::ErrorKind::NotFound => format!("{} not found", ide.program),
+                    _ => format!("{}: {}", ide.program, e),
+                })?;
+            // Reap it in the background so it doesn't linger as a zombie
+            thread::spawn(move || child.wait());
+            Ok(std::iter::once(ide.program)
+                .chain(ide.args)
+                .collect::<Vec<_>>()
+                .join(" "))
+        });
+        let file = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        match result {
+            Ok(command) => {
+                self.status.flash(format!("Opened {} in {}", file, name));
+                self.log_info(format!("Opened {} in {}: {}", file, name, command));
+            }
+            Err(e) => {
+                self.status
+                    .flash_error(format!("Failed to open {} in {}: {}", file, name, e));
+                self.log_error(format!("Failed to open {} in {}: {}", file, name, e));
+            }
+        }
+    }
+
+    /// Writes a launch configuration and a build task for the highlighted
+    /// project into the `.vscode` folder next to the solution, asking before
+    /// replacing ones of the same name.
+    fn export_to_vscode(&mut self) -> Result<()> {
+        let Some(project) = self
+            .selected_index()
+            .and_then(|selected| self.projects.get(selected))
+        else {
+            return Err(AppError::NoProjectSelected);
+        };
+        if !project.output.is_executable() {
+            self.log_warn(format!(
+                "{} can't run on its own, so there is nothing to launch",
+                project.name
+            ));
+            return Ok(());
+        }
+        let target = Self::run_target_for(project)?;
+        let key = target.project_path.to_string_lossy().into_owned();
+
+        let frameworks = target_frameworks(&target.project_path);
+        let framework = self
+            .state
+            .frameworks
+            .get(&key)
+            .filter(|framework| frameworks.contains(framework))
+            .or(frameworks.first())
+            .cloned();
+        let Some(framework) = framework else {
+            self.log_error(format!(
+                "Can't tell the target framework of {}",
+                target.project
+            ));
+            return Ok(());
+        };
+
+        // What a run from here would start the app with
+        let profiles = read_launch_profiles(&target.project_dir);
+        let profile = self
+            .next_profile(&key, &profiles)
+            .filter(|p| p.is_supported());
+        let profile_vars = profile
+            .map(|p| p.environment_variables.clone())
+            .unwrap_or_default();
+        let mut env = env::resolve(&self.env_vars(&target.project, &key, &profile_vars));
+        if let Some(urls) = profile.and_then(|p| p.application_url.clone()) {
+            env.entry("ASPNETCORE_URLS".to_string()).or_insert(urls);
+        }
+        if let Some(environment) = self.state.environments.get(&key) {
+            for name in ["ASPNETCORE_ENVIRONMENT", "DOTNET_ENVIRONMENT"] {
+                env.insert(name.to_string(), environment.clone());
+            }
+        }
+        let args = profile
+            .and_then(|p| p.command_line_args.as_deref())
+            .and_then(split_args)
+            .unwrap_or_default();
+
+        let workspace = self
+            .sln_file
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let export = Export::new(&vscode::Target {
+            workspace: std::path::absolute(&workspace).unwrap_or(workspace),
+            assembly: read_details(&target.project, &target.project_path).assembly_name,
+            project: target.project,
+            project_path: target.project_path,
+            configuration: self.configuration.clone(),
+            framework,
+            args,
+            env,
+            launch_browser: profile.is_some_and(|p| p.launch_browser),
+        });
+        let export = match export {
+            Ok(export) => export,
+            Err(e) => {
+                self.status
+                    .flash_error("Failed to write the VS Code configurations");
+                self.log_error(format!("Failed to write the VS Code configurations: {}", e));
+                return Ok(());
+            }
+        };
+        let replaced = export.replaced();
+        if replaced.is_empty() {
+            self.write_vscode_export(export);
+        } else {
+            self.vscode_prompt = Some(VsCodePrompt {
+                export,
+                replaced,
+                state: ListState::default().with_selected(Some(1)),
+            });
+        }
+        Ok(())
+    }
+
+    fn handle_vscode_prompt_key(&mut self, key: KeyEvent) {
+        let Some(prompt) = self.vscode_prompt.as_mut() else {
+            return;
+        };
+        let current = prompt.state.selected().unwrap_or(1);
+        let replace = match key.code {
+            KeyCode::Up => {
+                prompt.state.select(Some(0));
+                return;
+            }
+            KeyCode::Down => {
+                prompt.state.select(Some(1));
+                return;
+            }
+            KeyCode::Enter => current == 0,
+            KeyCode::Esc => false,
+            _ => return,
+        };
+        let Some(prompt) = self.vscode_prompt.take() else {
+            return;
+        };
+        if replace {
+            self.write_vscode_export(prompt.export);
+        }
+    }
+
+    fn write_vscode_export(&mut self, export: Export) {
+        let mut written = 0;
+        for merge in [&export.tasks, &export.launch] {
+            if merge.unchanged {
+                self.log_info(format!(
+                    "{} already has '{}'",
+                    merge.path.display(),
+                    merge.name
+                ));
+                continue;
+            }
+            if let Err(e) = merge.write() {
+                self.status
+                    .flash_error("Failed to write the VS Code configurations");
+                self.log_error(format!("Failed to write {}: {}", merge.path.display(), e));
+                return;
+            }
+            written += 1;
+            self.log_info(format!(
+                "Wrote '{}' to {}",
+                merge.name,
+                merge.path.display()
+            ));
+            if merge.drops_comments {
+                self.log_warn(format!(
+                    "The comments in {} were not kept",
+                    merge.path.display()
+                ));
+            }
+        }
+        if written > 0 {
+            self.status
+                .flash(format!("Wrote '{}' for VS Code", export.launch.name));
+        }
+    }
+
+    /// Copies the process's address, the first when it has several.
+    fn copy_url(&mut self) {
+        let Some(process) = self.process() else {
+            return;
+        };
+        match process.known_urls().first() {
+            Some(url) => {
+                let url = url.clone();
+                self.copy_to_clipboard(&url);
+            }
+            None => self.status.flash("No address known yet"),
+        }
+    }
+
+    fn copy_to_clipboard(&mut self, text: &str) {
+        let was_available = self.clipboard.unavailable.is_none();
+        match self.clipboard.copy(text) {
+            Ok(Copied::Native) => {
+                self.status.flash(format!("Copied {}", text));
+                self.log_info(format!("Copied {} to the clipboard", text));
+            }
+            Ok(Copied::Terminal) => {
+                if let Some(reason) = self.clipboard.unavailable.clone().filter(|_| was_available) {
+                    self.log_warn(format!(
+                        "The system clipboard is unavailable ({}), copying through the terminal instead",
+                        reason
+                    ));
+                }
+                self.status.flash(format!("Copied {}", text));
+                self.log_info(format!(
+                    "Sent {} to the terminal's clipboard (OSC 52), if the terminal allows it",
+                    text
+                ));
+            }
+            Err(e) => {
+                self.status
+                    .flash_error(format!("Failed to copy {}: {}", text, e));
+                self.log_error(format!("Failed to copy {}: {}", text, e));
+            }
+        }
+    }
+
+    fn report_open(&mut self, url: &str, result: io::Result<()>) {
+        match result {
+            Ok(()) => {
+                self.status.flash(format!("Opened {}", url));
+                self.log_info(format!("Opened {} in the browser", url));
+            }
+            Err(e) => {
+                self.status
+                    .flash_error(format!("Failed to open {}: {}", url, e));
+                self.log_error(format!("Failed to open {}: {}", url, e));
+            }
+        }
+    }
+
+    /// Asks the process to shut down; `poll_process` kills it if it takes
+    /// longer than STOP_TIMEOUT.
+    fn stop_process(&mut self, index: usize) -> Result<()> {
+        // A crashed process waiting to restart stays down
+        if let Some(process) = self
+            .processes
+            .get_mut(index)
+            .filter(|p| p.restart_at.is_some())
+        {
+            process.restart_at = None;
+            let message = format!("Cancelled the restart of {}", process.project);
+            self.status.flash(message.clone());
+            self.log_info(message);
+            return Ok(());
+        }
+        let Some(process) = self
+            .processes
+            .get_mut(index)
+            .filter(|p| p.status.is_none() && p.stopping.is_none())
+        else {
+            return Ok(());
+        };
+        process.stopping = Some(Instant::now());
+        let project = process.project.clone();
+        // The SPA dev server of the project goes with it, unless it restarts
+        let spa = Some(process.project_dir.clone())
+            .filter(|_| !process.restarting)
+            .and_then(|dir| {
+                self.processes
+                    .iter()
+                    .position(|p| p.spa.as_ref().is_some_and(|spa| spa.project_dir == dir))
+            });
+        let Some(process) = self.processes.get_mut(index) else {
+            return Ok(());
+        };
+        let stopped = match &process.container {
+            Some(container) => container.stop(STOP_TIMEOUT),
+            None => process.job.interrupt(),
+        };
+        if let Err(e) = stopped {
+            process.job.kill()?;
+            self.log_warn(format!(
+                "Could not interrupt {} ({}), killed it",
+                project, e
+            ));
+        } else {
+            self.log_info(format!("Stopping {}…", project));
+        }
+        match spa {
+            Some(spa) => self.stop_process(spa),
+            None => Ok(()),
+        }
+    }
+
+    fn stop_all(&mut self) -> Result<()> {
+        // Members not started yet stay that way
+        self.group_run = None;
+        self.launch_queue.clear();
+        for index in 0..self.processes.len() {
+            self.stop_process(index)?;
+        }
+        Ok(())
+    }
+
+    /// Restarts the current process once it has stopped.
+    fn restart_process(&mut self) -> Result<()> {
+        let index = self.current_process;
+        let Some(process) = self.processes.get_mut(index) else {
+            return Ok(());
+        };
+        // Restarting by hand starts over with the retries
+        process.crashes = 0;
+        if process.status.is_some() {
+            return self.respawn(index);
+        }
+        process.restarting = true;
+        self.stop_process(index)
+    }
+
+    /// Starts an exited process again with the same arguments, profile and
+    /// environment, continuing its output after a separator.
+    fn respawn(&mut self, index: usize) -> Result<()> {
+        let Some(process) = self.processes.get_mut(index) else {
+            return Ok(());
+        };
+        if let Some(spa) = &process.spa {
+            process.job = Job::spawn(&mut spa.command())?;
+        } else if let Some(container) = process.container.as_mut() {
+            container.id = None;
+            process.job =
+                Job::spawn(&mut container.run_command(&process.env)).map_err(docker_spawn_error)?;
+        } else {
+            // Restarting picks up code changes, so let `dotnet run` rebuild
+            if process.mode == RunMode::NoBuild {
+                process.mode = RunMode::Run;
+            }
+            process.job = Self::spawn_run(
+                &process.project_dir,
+                process.launch_profile.as_deref(),
+                &process.configuration,
+                process.mode,
+                &process.build_args,
+                &process.args,
+                &process.env,
+            )?;
+        }
+        process.output.push(format!(
+            "──── restarted at {} ────",
+            Local::now().format("%H:%M:%S")
+        ));
+        process.build_errors.clear();
+        process.diagnostics = DiagnosticParser::default();
+        self.problems.clear_build(&process.project);
+        process.urls.clear();
+        process.status = None;
+        process.crashed = false;
+        process.restart_at = None;
+        let project = process.project.clone();
+        let project_dir = process.project_dir.clone();
+        let message = format!("Restarted {}", project);
+        self.status.flash(message.clone());
+        self.log_info(message);
+        self.record(index);
+        self.process_started(&project, &project_dir);
+        if index == self.current_process {
+            self.output_anchor = None;
+            self.output_match = None;
+            self.output_paused = None;
+        }
+        Ok(())
+    }
+
+    fn project_hooks(&self, project: &str) -> Hooks {
+        self.config.hooks.get(project).cloned().unwrap_or_default()
+    }
+
+    fn group_hooks(&self, group: &str) -> Hooks {
+        self.config
+            .group_hooks
+            .get(group)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Holds back what `then` makes until the owner's hooks of `stages` have
+    /// run, one after the other, asking first when its hooks have not been
+    /// confirmed. Returns whether it is held back; `poll_hooks` carries on.
+    fn hold_for_hooks(
+        &mut self,
+        owner: &str,
+        hooks: &Hooks,
+        dir: &Path,
+        stages: &[HookStage],
+        then: impl FnOnce() -> AfterHooks,
+    ) -> Result<bool> {
+        let commands = hooks.commands();
+        if commands.is_empty() {
+            return Ok(false);
+        }
+        let key = hooks.confirmation_key(owner);
+        if !self.state.confirmed_hooks.contains(&key) {
+            self.hook_prompt = Some(HookPrompt {
+                owner: owner.to_string(),
+                commands: commands
+                    .into_iter()
+                    .map(|(stage, command)| (stage, command.to_string()))
+                    .collect(),
+                key,
+                then: then(),
+                state: ListState::default().with_selected(Some(0)),
+            });
+            return Ok(true);
+        }
+
+        let passed = |stage: &HookStage| self.hooks_passed.contains(&(owner.to_string(), *stage));
+        let due = stages
+            .iter()
+            .find(|stage| hooks.command(**stage).is_some() && !passed(stage));
+        let Some((&stage, command)) = due.and_then(|stage| Some((stage, hooks.command(*stage)?)))
+        else {
+            // All ran, so the next launch runs them again
+            for stage in stages {
+                self.hooks_passed.remove(&(owner.to_string(), *stage));
+            }
+            return Ok(false);
+        };
+        let hook = HookJob::spawn(owner, stage, command, dir, None)?;
+        self.log_info(format!("{} {}", hook.tag(), command));
+        self.pre_hook = Some((hook, then()));
+        Ok(true)
+    }
+
+    fn handle_hook_prompt_key(&mut self, key: KeyEvent) -> Result<()> {
+        let Some(prompt) = self.hook_prompt.as_mut() else {
+            return Ok(());
+        };
+        let current = prompt.state.selected().unwrap_or(1);
+        let confirmed = match key.code {
+            KeyCode::Up => {
+                prompt.state.select(Some(0));
+                return Ok(());
+            }
+            KeyCode::Down => {
+                prompt.state.select(Some(1));
+                return Ok(());
+            }
+            KeyCode::Enter => current == 0,
+            KeyCode::Esc => false,
+            _ => return Ok(()),
+        };
+        let Some(prompt) = self.hook_prompt.take() else {
+            return Ok(());
+        };
+        if !confirmed {
+            self.log_warn(format!(
+                "Hooks of {} not confirmed, not running {}",
+                prompt.owner,
+                prompt.then.name()
+            ));
+            return Ok(());
+        }
+        self.state.confirmed_hooks.insert(prompt.key);
+        self.save_state();
+        self.resume_after_hooks(prompt.then)
+    }
+
+    fn resume_after_hooks(&mut self, then: AfterHooks) -> Result<()> {
+        match then {
+            AfterHooks::Build {
+                target,
+                launch_profile,
+                run,
+            } => self.start_project_build(target, launch_profile, run),
+            AfterHooks::Launch {
+                target,
+                launch_profile,
+                configuration,
+                mode,
+            } => self.launch_project(target, launch_profile, configuration, mode),
+            AfterHooks::Group(name) => self.run_group(&name),
+        }
+    }
+
+    /// Logs the output of the hooks, carries on after a pre hook that
+    /// succeeded and starts `on_stop` of the groups that stopped.
+    fn poll_hooks(&mut self) -> Result<()> {
+        self.poll_pre_hook()?;
+
+        let mut index = 0;
+        while index < self.hook_jobs.len() {
+            let hook = &mut self.hook_jobs[index];
+            let lines = hook.job.drain();
+            let status = hook.job.poll_exit()?;
+            let tag = hook.tag();
+            for line in lines {
+                self.log_output(OutputLine {
+                    text: format!("{} {}", tag, line.text),
+                    ..line
+                });
+            }
+            match status {
+                Some(status) => {
+                    let hook = self.hook_jobs.remove(index);
+                    let message = format!("{} exited ({})", hook.tag(), status);
+                    if status.success() {
+                        self.log_info(message);
+                    } else {
+                        self.log_warn(message);
+                    }
+                }
+                None => index += 1,
+            }
+        }
+
+        let stopped: Vec<String> = self
+            .hooked_groups
+            .iter()
+            .filter(|(_, dirs)| {
+                !self
+                    .processes
+                    .iter()
+                    .any(|p| p.status.is_none() && dirs.contains(&p.project_dir))
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+        for name in stopped {
+            self.hooked_groups.retain(|(group, _)| *group != name);
+            let hooks = self.group_hooks(&name);
+            let sln_dir = self
+                .sln_file
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default();
+            self.stop_hooks(
+                &format!("group {}", name),
+                &hooks,
+                &sln_dir,
+                Companion::Group(name.clone()),
+            );
+        }
+        Ok(())
+    }
+
+    fn poll_pre_hook(&mut self) -> Result<()> {
+        let Some((hook, _)) = self.pre_hook.as_mut() else {
+            return Ok(());
+        };
+        let lines = hook.job.drain();
+        let status = hook.job.poll_exit()?;
+        let tag = hook.tag();
+        for line in lines {
+            self.log_output(OutputLine {
+                text: format!("{} {}", tag, line.text),
+                ..line
+            });
+        }
+        let Some(status) = status else {
+            return Ok(());
+        };
+        let Some((hook, then)) = self.pre_hook.take() else {
+            return Ok(());
+        };
+        if status.success() {
+            self.hooks_passed.insert((hook.owner, hook.stage));
+            return self.resume_after_hooks(then);
+        }
+        self.hooks_passed.retain(|(owner, _)| *owner != hook.owner);
+        let message = format!(
+            "{} hook of {} failed ({}), not running {}",
+            hook.stage.label(),
+            hook.owner,
+            status,
+            then.name()
+        );
+        self.status.flash_error(message.clone());
+        self.log_error(message);
+        Ok(())
+    }
+
+    fn cancel_pre_hook(&mut self) -> Result<()> {
+        if let Some((mut hook, then)) = self.pre_hook.take() {
+            hook.job.kill()?;
+            for line in hook.job.drain() {
+                self.log_output(line);
+            }
+            self.hooks_passed.retain(|(owner, _)| *owner != hook.owner);
+            self.log_warn(format!(
+                "{} hook of {} cancelled, not running {}",
+                hook.stage.label(),
+                hook.owner,
+                then.name()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Starts the `post_start` hook of a project that started, unless it
+    /// runs already.
+    fn process_started(&mut self, project: &str, project_dir: &Path) {
+        let hooks = self.project_hooks(project);
+        let companion = Companion::Process(project_dir.to_path_buf());
+        self.start_hook(project, &hooks, project_dir, companion);
+    }
+
+    /// Stops the `post_start` hook of a project that stopped, and starts its
+    /// `on_stop` hook.
+    fn process_stopped(&mut self, project: &str, project_dir: &Path) {
+        let hooks = self.project_hooks(project);
+        let companion = Companion::Process(project_dir.to_path_buf());
+        self.stop_hooks(project, &hooks, project_dir, companion);
+    }
+
+    /// Starts the `post_start` hook of a group whose members started, and
+    /// watches them to run `on_stop` once they all stopped.
+    fn group_started(&mut self, name: &str, dirs: Vec<PathBuf>) {
+        let hooks = self.group_hooks(name);
+        if hooks.post_start.is_none() && hooks.on_stop.is_none() {
+            return;
+        }
+        let sln_dir = self
+            .sln_file
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let owner = format!("group {}", name);
+        self.start_hook(&owner, &hooks, &sln_dir, Companion::Group(name.to_string()));
+        self.hooked_groups.retain(|(group, _)| group != name);
+        self.hooked_groups.push((name.to_string(), dirs));
+    }
+
+    fn start_hook(&mut self, owner: &str, hooks: &Hooks, dir: &Path, companion: Companion) {
+        let Some(command) = hooks.command(HookStage::PostStart) else {
+            return;
+        };
+        // Only hooks confirmed before the pre hooks ran
+        if !self
+            .state
+            .confirmed_hooks
+            .contains(&hooks.confirmation_key(owner))
+        {
+            return;
+        }
+        let running = self
+            .hook_jobs
+            .iter()
+            .any(|hook| hook.companion.as_ref() == Some(&companion) && !hook.job.has_exited());
+        if running {
+            return;
+        }
+        match HookJob::spawn(owner, HookStage::PostStart, command, dir, Some(companion)) {
+            Ok(hook) => {
+                self.log_info(format!("{} {}", hook.tag(), command));
+                self.hook_jobs.push(hook);
+            }
+            Err(e) => self.log_error(format!(
+                "Failed to start the post_start hook of {}: {}",
+                owner, e
+            )),
+        }
+    }
+
+    fn stop_hooks(&mut self, owner: &str, hooks: &Hooks, dir: &Path, companion: Companion) {
+        for hook in &mut self.hook_jobs {
+            if hook.companion.as_ref() == Some(&companion) && !hook.job.has_exited() {
+                // Logged as it exits
+                let _ = hook.job.kill();
+            }
+        }
+        let Some(command) = hooks.command(HookStage::OnStop) else {
+            return;
+        };
+        if !self
+            .state
+            .confirmed_hooks
+            .contains(&hooks.confirmation_key(owner))
+        {
+            return;
+        }
+        match HookJob::spawn(owner, HookStage::OnStop, command, dir, None) {
+            Ok(hook) => {
+                self.log_info(format!("{} {}", hook.tag(), command));
+                self.hook_jobs.push(hook);
+            }
+            Err(e) => self.log_error(format!(
+                "Failed to start the on_stop hook of {}: {}",
+                owner, e
+            )),
+        }
+    }
+
+    fn select_solution(&mut self) -> Result<()> {
+        if let Some(sln) = self
+            .selected_index()
+            .and_then(|selected| self.sln_files.get(selected))
+        {
+            let changed = self.selected_sln != sln.path;
+            if changed {
+                self.selected_sln = sln.path.clone();
+                self.state.last_solution = Some(self.selected_sln.clone());
+                self.save_state();
+            }
+            let sln_error = |source| AppError::SlnParse {
+                path: PathBuf::from(&self.selected_sln),
+                source,
+            };
+            self.sln_file = PathBuf::from(underlying_sln(&self.selected_sln).map_err(sln_error)?);
+            let (projects, skipped) =
+                parse_sln_for_projects(&self.selected_sln).map_err(sln_error)?;
+            self.projects = projects;
+            self.folders = solution_folders(&self.projects);
+            if changed {
+                self.collapsed_folders.clear();
+            }
+            self.configurations =
+                parse_sln_configurations(&self.selected_sln).map_err(sln_error)?;
+            self.load_graph();
+            for line in skipped {
+                self.log_warn(format!(
+                    "Skipped a project entry of {} that could not be read, {}",
+                    self.sln_file.display(),
+                    line
+                ));
+            }
+
+            self.configuration = self
+                .state
+                .configurations
+                .get(&self.selected_sln)
+                .filter(|c| self.configurations.contains(c))
+                .cloned()
+                .unwrap_or_else(|| "Debug".to_string());
+            self.check_global_json();
+            self.set_view(View::Projects);
+
+            if changed {
+                let last_project =
+                    self.state
+                        .last_projects
+                        .get(&self.selected_sln)
+                        .and_then(|path| {
+                            self.projects
+                                .iter()
+                                .position(|p| p.path.to_string_lossy() == path.as_str())
+                        });
+                self.select_original(last_project.unwrap_or(0));
+            }
+            // Reopening the same solution keeps the highlight, but the file may have lost projects
+            self.clamp_selection();
+        }
+        Ok(())
+    }
+}
+
+/// A pinned solution as listed before the scan finds it, or when it doesn't.
+fn pinned_solution(path: &str) -> SolutionFile {
+    SolutionFile {
+        path: path.to_string(),
+        root: Path::new(path)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default(),
+        modified: fs::metadata(path).and_then(|meta| meta.modified()).ok(),
+    }
+}
+
+/// Every solution folder holding a project, directly or in a folder of its own.
+fn solution_folders(projects: &[Project]) -> Vec<Vec<String>> {
+    let folders: BTreeSet<&[String]> = projects
+        .iter()
+        .flat_map(|project| (1..=project.folder.len()).map(|depth| &project.folder[..depth]))
+        .collect();
+    folders.into_iter().map(<[String]>::to_vec).collect()
+}
+
+fn centered_rect(area: Rect, percent_x: u16, height: u16) -> Rect {
+    let width = area.width * percent_x / 100;
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// A duration as `45s`, `12m 05s` or `2h 03m`.
+fn format_uptime(uptime: Duration) -> String {
+    let secs = uptime.as_secs();
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+// "14s ago", "5m ago", "3h ago", "2d ago"
+fn format_ago(secs: i64) -> String {
+    match secs.max(0) {
+        secs @ 0..60 => format!("{}s ago", secs),
+        secs @ 60..3600 => format!("{}m ago", secs / 60),
+        secs @ 3600..86400 => format!("{}h ago", secs / 3600),
+        secs => format!("{}d ago", secs / 86400),
+    }
+}
+
+// "12ms", "1.4s"
+fn format_test_duration(duration: Duration) -> String {
+    match duration.as_millis() {
+        0..1000 => format!("{}ms", duration.as_millis()),
+        _ => format!("{:.1}s", duration.as_secs_f32()),
+    }
+}
+
+/// Shortens `text` to `max` characters by cutting from the left, keeping the end visible.
+fn truncate_left(text: &str, max: usize) -> String {
+    let len = text.chars().count();
+    if len <= max {
+        return text.to_string();
+    }
+    if max == 0 {
+        return String::new();
+    }
+    let tail: String = text.chars().skip(len - (max - 1)).collect();
+    format!("…{}", tail)
+}
+
+/// What running with `profile` applies, one setting per line.
+fn profile_details(profile: &LaunchProfile) -> Vec<Line<'static>> {
+    let dim = Style::default().fg(Color::DarkGray);
+    let setting = |name: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{:<18}", name), dim),
+            Span::raw(value),
+        ])
+    };
+
+    let mut lines = vec![setting(
+        "commandName",
+        profile.command_name.clone().unwrap_or_default(),
+    )];
+    if !profile.is_supported() {
+        lines.push(Line::styled(
+            "Not supported by dotnet run, pick another profile or none",
+            Style::default().fg(Color::Red),
+        ));
+        return lines;
+    }
+    if let Some(url) = &profile.application_url {
+        lines.push(setting("applicationUrl", url.clone()));
+    }
+    if let Some(args) = &profile.command_line_args {
+        lines.push(setting("commandLineArgs", args.clone()));
+    }
+    lines.push(setting("launchBrowser", profile.launch_browser.to_string()));
+    lines.push(setting(
+        "dotnetRunMessages",
+        profile.dotnet_run_messages.to_string(),
+    ));
+    for (name, value) in &profile.environment_variables {
+        let value = if env::is_secret(name, value) {
+            "••••••••".to_string()
+        } else {
+            value.clone()
+        };
+        lines.push(setting("env", format!("{}={}", name, value)));
+    }
+    lines
+}
+
+/// Production stands out so it is not run against by accident.
+fn details_lines(details: &ProjectDetails) -> Vec<Line<'static>> {
+    let dim = Style::default().fg(Color::DarkGray);
+    let heading = Style::default().fg(Color::Yellow);
+    let field = |label: &str, value: Span<'static>| {
+        Line::from(vec![Span::styled(format!("{:<11}", label), dim), value])
+    };
+    let or_none = |value: String| match value.is_empty() {
+        true => Span::styled("none", dim),
+        false => Span::raw(value),
+    };
+
+    let mut lines = vec![
+        field(
+            "Kind",
+            Span::styled(details.output.label(), output_style(details.output)),
+        ),
+        field("Output", Span::raw(details.output_type.clone())),
+        field("SDK", or_none(details.sdk.clone().unwrap_or_default())),
+        field("Assembly", Span::raw(details.assembly_name.clone())),
+        field("Frameworks", or_none(details.frameworks.join(", "))),
+    ];
+
+    lines.push(Line::default());
+    lines.push(Line::styled(
+        format!("Packages ({})", details.packages.len()),
+        heading,
+    ));
+    for (package, version) in &details.packages {
+        let version = version.as_deref().unwrap_or("no version");
+        lines.push(Line::from(vec![
+            Span::raw(format!("  {} ", package)),
+            Span::styled(version.to_string(), dim),
+        ]));
+    }
+
+    lines.push(Line::default());
+    lines.push(Line::styled(
+        format!("Project references ({})", details.references.len()),
+        heading,
+    ));
+    for reference in &details.references {
+        lines.push(Line::raw(format!("  {}", reference)));
+    }
+
+    lines.push(Line::default());
+    lines.push(Line::styled(
+        format!("Launch profiles ({})", details.profiles.len()),
+        heading,
+    ));
+    for (profile, urls) in &details.profiles {
+        let mut spans = vec![Span::raw(format!("  {}", profile))];
+        if !urls.is_empty() {
+            spans.push(Span::styled(
+                format!("  {}", urls.join(" ")),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+    lines
+}
+
+fn output_style(output: OutputKind) -> Style {
+    match output {
+        OutputKind::Web => Style::default().fg(Color::Blue),
+        OutputKind::Worker => Style::default().fg(Color::LightBlue),
+        OutputKind::Console => Style::default().fg(Color::Green),
+        OutputKind::Library => Style::default().fg(Color::DarkGray),
+        OutputKind::Test => Style::default().fg(Color::Magenta),
+    }
+}
+
+fn environment_style(environment: &str) -> Style {
+    match environment.to_ascii_lowercase().as_str() {
+        "production" | "prod" => Style::default()
+            .fg(Color::White)
+            .bg(Color::Red)
+            .add_modifier(Modifier::BOLD),
+        "development" | "dev" => Style::default().fg(Color::Green),
+        _ => Style::default().fg(Color::Yellow),
+    }
+}
+
+/// The project of the solution whose file the diagnostic ends with.
+fn reporting_project(projects: &[Project], diagnostic: &Diagnostic) -> Option<String> {
+    let reported = diagnostic.project.as_deref()?;
+    let file = reported.rsplit(['/', '\\']).next().unwrap_or(reported);
+    projects
+        .iter()
+        .find(|p| {
+            p.path
+                .file_name()
+                .is_some_and(|name| name.eq_ignore_ascii_case(file))
+        })
+        .map(|p| p.name.clone())
+        .or_else(|| file.rsplit_once('.').map(|(stem, _)| stem.to_string()))
+}
+
+fn solution_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Splits `text` into spans, emphasising the characters at `positions`.
+fn highlight_matches(text: &str, positions: &[usize], style: Style) -> Vec<Span<'static>> {
+    if positions.is_empty() {
+        return vec![Span::styled(text.to_string(), style)];
+    }
+
+    let matched = style.fg(Color::LightCyan).add_modifier(Modifier::BOLD);
+    let mut spans: Vec<Span> = Vec::new();
+    let mut current = String::new();
+    let mut current_is_match = false;
+    for (i, c) in text.chars().enumerate() {
+        let is_match = positions.contains(&i);
+        if is_match != current_is_match && !current.is_empty() {
+            let style = if current_is_match { matched } else { style };
+            spans.push(Span::styled(std::mem::take(&mut current), style));
+        }
+        current_is_match = is_match;
+        current.push(c);
+    }
+    if !current.is_empty() {
+        spans.push(Span::styled(
+            current,
+            if current_is_match { matched } else { style },
+        ));
+    }
+    spans
+}
+
+/// Splits `text` into spans, emphasising every case-insensitive occurrence of
+/// `query`, which must already be lowercase.
+fn highlight_substrings(
+    text: &str,
+    query: &str,
+    style: Style,
+    highlight: Style,
+) -> Vec<Span<'static>> {
+    let lower = text.to_lowercase();
+    // Lowercasing can change byte lengths, in which case offsets would not line up
+    if query.is_empty() || lower.len() != text.len() {
+        return vec![Span::styled(text.to_string(), style)];
+    }
+
+    let mut spans = Vec::new();
+    let mut rest = 0;
+    for (start, found) in lower.match_indices(query) {
+        if start > rest {
+            spans.push(Span::styled(text[rest..start].to_string(), style));
+        }
+        let end = start + found.len();
+        spans.push(Span::styled(text[start..end].to_string(), highlight));
+        rest = end;
+    }
+    spans.push(Span::styled(text[rest..].to_string(), style));
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use ratatui::backend::TestBackend;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn app(root: &Path) -> App {
+        App::with(
+            Config::default(),
+            State::default(),
+            None,
+            Some(root.to_path_buf()),
+            Vec::new(),
+        )
+        .unwrap()
+    }
+
+    fn solutions(dir: &TempDir, names: &[&str]) -> Vec<SolutionFile> {
+        names
+            .iter()
+            .map(|name| SolutionFile {
+                path: dir.path().join(name).to_string_lossy().into_owned(),
+                root: dir.path().to_path_buf(),
+                modified: None,
+            })
+            .collect()
+    }
+
+    // A solution with a project for each name
+    fn write_solution(path: &Path, projects: &[&str]) {
+        let mut contents =
+            String::from("Microsoft Visual Studio Solution File, Format Version 12.00\n");
+        for (i, name) in projects.iter().enumerate() {
+            contents.push_str(&format!(
+                "Project(\"{{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}}\") = \"{0}\", \"{0}\\{0}.csproj\", \"{{00000000-0000-0000-0000-{1:012}}}\"\nEndProject\n",
+                name, i
+            ));
+        }
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn the_solution_highlight_stays_in_a_list_that_shrank() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = app(dir.path());
+        app.apply_rescan(solutions(
+            &dir,
+            &["A.sln", "B.sln", "C.sln", "D.sln", "E.sln"],
+        ));
+        app.sln_state.select(Some(4));
+
+        app.apply_rescan(solutions(&dir, &["A.sln", "B.sln"]));
+
+        assert_eq!(app.sln_state.selected(), Some(1));
+        assert!(app
+            .selected_index()
+            .is_some_and(|index| index < app.sln_files.len()));
+    }
+
+    #[test]
+    fn the_project_highlight_stays_in_a_solution_that_lost_projects() {
+        let dir = tempfile::tempdir().unwrap();
+        let sln = dir.path().join("App.sln");
+        write_solution(&sln, &["A", "B", "C", "D", "E"]);
+        let mut app = app(dir.path());
+        app.apply_rescan(solutions(&dir, &["App.sln"]));
+        app.sln_state.select(Some(0));
+        app.select_solution().unwrap();
+        assert_eq!(app.projects.len(), 5);
+        app.project_state.select(Some(4));
+
+        write_solution(&sln, &["A", "B"]);
+        app.set_view(View::Solutions);
+        app.select_solution().unwrap();
+
+        assert_eq!(app.projects.len(), 2);
+        assert_eq!(app.project_state.selected(), Some(1));
+        assert!(app
+            .selected_index()
+            .is_some_and(|index| index < app.projects.len()));
+    }
+
+    // Every row of the buffer in the area, as text
+    fn rows(terminal: &Terminal<TestBackend>, area: Rect) -> Vec<String> {
+        let buffer = terminal.backend().buffer();
+        (area.top()..area.bottom())
+            .map(|y| {
+                (area.left()..area.right())
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn the_lists_and_the_log_panel_are_drawn_apart() {
+        let dir = tempfile::tempdir().unwrap();
+        let names: Vec<String> = (0..40).map(|i| format!("Listed{i:02}")).collect();
+        let sln = dir.path().join("Listed.sln");
+        write_solution(&sln, &names.iter().map(String::as_str).collect::<Vec<_>>());
+        let mut app = app(dir.path());
+        app.apply_rescan(solutions(&dir, &["Listed.sln"]));
+        app.sln_state.select(Some(0));
+        app.log(Level::Info, "a line of the log panel");
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+
+        for view in [View::Solutions, View::Projects] {
+            if view == View::Projects {
+                app.select_solution().unwrap();
+            }
+            app.draw(&mut terminal).unwrap();
+
+            assert_eq!(app.view, view);
+            assert!(app.list_area.height > 0 && app.logs_area.height > 0);
+            assert!(!app.list_area.intersects(app.logs_area));
+            let logs = rows(&terminal, app.logs_area);
+            assert!(logs
+                .iter()
+                .any(|row| row.contains("a line of the log panel")));
+            assert!(!logs.iter().any(|row| row.contains("Listed")));
+            let list = rows(&terminal, app.list_area);
+            assert!(list.iter().any(|row| row.contains("Listed")));
         }
-        Ok(())
     }
 }
@@ -0,0 +1,217 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, TryRecvError},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Instant,
+};
+
+/// A `bin` or `obj` directory of a project and how much it holds.
+#[derive(Debug, Clone)]
+pub struct BuildDir {
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// The `bin` and `obj` directories of the projects, leaving out any that are
+/// links or end up outside `root`.
+pub fn build_dirs(project_dirs: &[PathBuf], root: &Path) -> Vec<BuildDir> {
+    let Ok(root) = root.canonicalize() else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<BuildDir> = Vec::new();
+    for project_dir in project_dirs {
+        for name in ["bin", "obj"] {
+            let path = project_dir.join(name);
+            let is_dir = fs::symlink_metadata(&path).is_ok_and(|meta| meta.is_dir());
+            let Ok(path) = path.canonicalize() else {
+                continue;
+            };
+            if !is_dir || path == root || !path.starts_with(&root) {
+                continue;
+            }
+            if dirs.iter().any(|dir| dir.path == path) {
+                continue;
+            }
+            dirs.push(BuildDir {
+                size: dir_size(&path),
+                path,
+            });
+        }
+    }
+    dirs
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok()?;
+            Some(if meta.is_dir() {
+                dir_size(&entry.path())
+            } else {
+                meta.len()
+            })
+        })
+        .sum()
+}
+
+/// "12.3 MB"
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+pub enum CleanEvent {
+    /// A directory is gone, but for the files that could not be deleted.
+    Removed {
+        path: PathBuf,
+        freed: u64,
+        skipped: Vec<(PathBuf, io::Error)>,
+    },
+    Done,
+}
+
+/// Deletes build directories on a worker thread, one at a time.
+pub struct DeepClean {
+    rx: Receiver<CleanEvent>,
+    cancel: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+    pub started: Instant,
+    pub name: String,
+    pub freed: u64,
+    pub skipped: usize,
+}
+
+impl DeepClean {
+    pub fn start(name: String, dirs: Vec<BuildDir>) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let worker_cancel = Arc::clone(&cancel);
+
+        let handle = thread::spawn(move || {
+            for dir in dirs {
+                if worker_cancel.load(Ordering::Relaxed) {
+                    break;
+                }
+                let mut freed = 0;
+                let mut skipped = Vec::new();
+                remove_tree(&dir.path, &worker_cancel, &mut freed, &mut skipped);
+                let _ = tx.send(CleanEvent::Removed {
+                    path: dir.path,
+                    freed,
+                    skipped,
+                });
+            }
+            let _ = tx.send(CleanEvent::Done);
+        });
+
+        Self {
+            rx,
+            cancel,
+            handle: Some(handle),
+            started: Instant::now(),
+            name,
+            freed: 0,
+            skipped: 0,
+        }
+    }
+
+    /// Returns everything the worker has sent since the last call, without blocking.
+    pub fn drain(&mut self) -> Vec<CleanEvent> {
+        let mut events = Vec::new();
+        loop {
+            match self.rx.try_recv() {
+                Ok(CleanEvent::Removed {
+                    path,
+                    freed,
+                    skipped,
+                }) => {
+                    self.freed += freed;
+                    self.skipped += skipped.len();
+                    events.push(CleanEvent::Removed {
+                        path,
+                        freed,
+                        skipped,
+                    });
+                }
+                Ok(CleanEvent::Done) | Err(TryRecvError::Disconnected) => {
+                    events.push(CleanEvent::Done);
+                    break;
+                }
+                Err(TryRecvError::Empty) => break,
+            }
+        }
+        events
+    }
+
+    pub fn cancel(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for DeepClean {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+// Deletes what it can, file by file, so that one file held open by a running
+// process doesn't keep the rest
+fn remove_tree(
+    path: &Path,
+    cancel: &AtomicBool,
+    freed: &mut u64,
+    skipped: &mut Vec<(PathBuf, io::Error)>,
+) {
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            skipped.push((path.to_path_buf(), e));
+            return;
+        }
+    };
+    for entry in entries.flatten() {
+        if cancel.load(Ordering::Relaxed) {
+            return;
+        }
+        let path = entry.path();
+        let Ok(meta) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if meta.is_dir() {
+            remove_tree(&path, cancel, freed, skipped);
+            continue;
+        }
+        let removed = fs::remove_file(&path).or_else(|e| match meta.is_symlink() {
+            // A link to a directory is one on Windows
+            true => fs::remove_dir(&path),
+            false => Err(e),
+        });
+        match removed {
+            Ok(()) => *freed += meta.len(),
+            Err(e) => skipped.push((path, e)),
+        }
+    }
+    // Not empty when something was skipped, which is reported already
+    let _ = fs::remove_dir(path);
+}
@@ -0,0 +1,104 @@
+use std::{
+    env,
+    io::{self, Write},
+};
+
+use crate::app::terminal::screen;
+
+/// Where a copy went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Copied {
+    /// The clipboard of the machine sln-runner runs on.
+    Native,
+    /// The terminal, asked with OSC 52 to put it on its clipboard, which it
+    /// may not do.
+    Terminal,
+}
+
+/// Copies text to the system clipboard, or through the terminal where there
+/// is none to reach, such as over SSH.
+pub struct Clipboard {
+    // Kept open, since on X11 the copy is only there while its owner is
+    native: Option<arboard::Clipboard>,
+    // Why the system clipboard could not be used, once it failed
+    pub unavailable: Option<String>,
+}
+
+impl Clipboard {
+    pub fn new() -> Self {
+        Self {
+            native: None,
+            unavailable: None,
+        }
+    }
+
+    pub fn copy(&mut self, text: &str) -> io::Result<Copied> {
+        // The local clipboard of an SSH session is on the wrong machine
+        if !over_ssh() && self.unavailable.is_none() {
+            match self.copy_native(text) {
+                Ok(()) => return Ok(Copied::Native),
+                Err(e) => {
+                    self.native = None;
+                    self.unavailable = Some(e);
+                }
+            }
+        }
+        osc52(text)?;
+        Ok(Copied::Terminal)
+    }
+
+    fn copy_native(&mut self, text: &str) -> Result<(), String> {
+        // Rather than waiting for an X server connection to time out
+        if cfg!(all(unix, not(target_os = "macos")))
+            && env::var_os("DISPLAY").is_none()
+            && env::var_os("WAYLAND_DISPLAY").is_none()
+        {
+            return Err("no display to reach".to_string());
+        }
+        let clipboard = match self.native.as_mut() {
+            Some(clipboard) => clipboard,
+            None => self
+                .native
+                .insert(arboard::Clipboard::new().map_err(|e| e.to_string())?),
+        };
+        clipboard.set_text(text).map_err(|e| e.to_string())
+    }
+}
+
+fn over_ssh() -> bool {
+    ["SSH_CONNECTION", "SSH_TTY", "SSH_CLIENT"]
+        .iter()
+        .any(|name| env::var_os(name).is_some())
+}
+
+// `ESC ] 52 ; c ; <base64> BEL`, wrapped for tmux to pass it on to the
+// terminal it runs in
+fn osc52(text: &str) -> io::Result<()> {
+    let sequence = format!("\x1b]52;c;{}\x07", base64(text.as_bytes()));
+    let sequence = if env::var_os("TMUX").is_some() {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    };
+    let mut out = screen();
+    out.write_all(sequence.as_bytes())?;
+    out.flush()
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| {
+            group | (byte as u32) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(group >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
@@ -0,0 +1,117 @@
+use std::{fs, path::Path};
+
+use clap::Command;
+use clap_complete::Shell;
+
+use crate::app::{error::Result, state::State};
+
+/// The hidden subcommand the scripts call to complete `--sln`.
+pub const COMPLETE_SLNS: &str = "__complete-slns";
+
+/// The completion script for `shell`, generated from `command`. `--sln` is
+/// completed with the solutions sln-runner knows where the shell can ask for
+/// them.
+pub fn completion_script(shell: Shell, command: Command) -> String {
+    let name = env!("CARGO_PKG_NAME");
+    let mut command = without_hidden(command);
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut command, name, &mut script);
+    let script = String::from_utf8_lossy(&script).into_owned();
+
+    match shell {
+        Shell::Bash => format!(
+            r#"{script}
+_{function}__slns() {{
+    if [[ "${{COMP_WORDS[COMP_CWORD-1]}}" == "--sln" ]]; then
+        local IFS=$'\n'
+        COMPREPLY=($(compgen -W "$({name} {COMPLETE_SLNS} 2>/dev/null)" -- "${{COMP_WORDS[COMP_CWORD]}}"))
+        [[ ${{#COMPREPLY[@]}} -gt 0 ]] && return
+    fi
+    _{function} "$@"
+}}
+complete -F _{function}__slns -o bashdefault -o default {name}
+"#,
+            function = name.replace('-', "__"),
+        ),
+        Shell::Zsh => {
+            let function = format!(
+                r#"_{name}_slns() {{
+    local -a slns
+    slns=(${{(f)"$({name} {COMPLETE_SLNS} 2>/dev/null)"}})
+    _alternative 'slns:solution:compadd -a slns' 'files:file:_files'
+}}
+
+"#
+            );
+            // Defined before the script calls its main function at the end
+            let script = script.replace(":SLN:_files'", &format!(":SLN:_{name}_slns'"));
+            match script.rfind("if [ \"$funcstack[1]\"") {
+                Some(end) => format!("{}{}{}", &script[..end], function, &script[end..]),
+                None => script,
+            }
+        }
+        Shell::Fish => format!(
+            "{script}complete -c {name} -n \"__fish_seen_subcommand_from list run\" -l sln -f -a \"({name} {COMPLETE_SLNS})\"\n"
+        ),
+        _ => script,
+    }
+}
+
+// clap_complete offers hidden subcommands as well
+fn without_hidden(command: Command) -> Command {
+    let subcommands: Vec<_> = command
+        .get_subcommands()
+        .filter(|subcommand| !subcommand.is_hide_set())
+        .cloned()
+        .collect();
+    Command::new(env!("CARGO_PKG_NAME"))
+        .version(env!("CARGO_PKG_VERSION"))
+        .args(command.get_arguments().cloned())
+        .subcommands(subcommands)
+}
+
+/// Prints the solutions to complete `--sln` with: those in the current
+/// directory, then those pinned and recently run.
+pub fn complete_solutions() -> Result<()> {
+    let mut solutions: Vec<String> = Vec::new();
+    if let Ok(entries) = fs::read_dir(".") {
+        let mut here: Vec<String> = entries
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .filter(|name| is_solution(Path::new(name)))
+            .collect();
+        here.sort();
+        solutions.extend(here);
+    }
+
+    let state = match State::path() {
+        Some(path) => State::load(&path)?,
+        None => State::default(),
+    };
+    let mut recent: Vec<(&String, &i64)> = state.last_runs.iter().collect();
+    recent.sort_by_key(|(_, time)| -**time);
+    let known = state
+        .pinned
+        .iter()
+        .chain(state.last_solution.as_ref())
+        .chain(recent.into_iter().map(|(path, _)| path));
+    for path in known {
+        let here = std::path::absolute(path)
+            .ok()
+            .and_then(|path| path.parent().map(Path::to_path_buf))
+            == std::env::current_dir().ok();
+        if !here && !solutions.contains(path) && Path::new(path).is_file() {
+            solutions.push(path.clone());
+        }
+    }
+
+    for solution in solutions {
+        println!("{}", solution);
+    }
+    Ok(())
+}
+
+fn is_solution(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == "sln" || ext == "slnx" || ext == "slnf")
+}
@@ -0,0 +1,209 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::Duration,
+};
+
+use ratatui::style::Color;
+use serde_json::Value;
+
+// In the order `docker compose` itself looks for them
+const FILE_NAMES: [&str; 4] = [
+    "compose.yaml",
+    "compose.yml",
+    "docker-compose.yaml",
+    "docker-compose.yml",
+];
+
+// How often `docker compose ps` is asked while the panel is open
+const POLL_EVERY: Duration = Duration::from_secs(2);
+
+/// The compose file next to the solution, if there is one.
+pub fn find_compose_file(sln_dir: &Path) -> Option<PathBuf> {
+    FILE_NAMES
+        .iter()
+        .map(|name| sln_dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// The names of the services the compose file defines, in its order. Only
+/// the keys of the top-level `services` mapping are read, which spares a
+/// YAML parser.
+pub fn services(file: &Path) -> Vec<String> {
+    let contents = fs::read_to_string(file).unwrap_or_default();
+    let mut services = Vec::new();
+    let mut in_services = false;
+    // The indentation of the service names, from the first of them
+    let mut indent = None;
+    for line in contents.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let depth = line.len() - trimmed.len();
+        if depth == 0 {
+            in_services = trimmed.split('#').next().map(str::trim_end) == Some("services:");
+            continue;
+        }
+        if !in_services || *indent.get_or_insert(depth) != depth {
+            continue;
+        }
+        if let Some((name, rest)) = trimmed.split_once(':') {
+            let rest = rest.trim_start();
+            if rest.is_empty() || rest.starts_with('#') {
+                services.push(name.trim().trim_matches(['"', '\'']).to_string());
+            }
+        }
+    }
+    services
+}
+
+// `docker compose` for the file, run where it is so that relative paths in it
+// resolve
+fn compose(file: &Path) -> Command {
+    let mut command = Command::new("docker");
+    command.arg("compose").arg("--file").arg(file);
+    if let Some(dir) = file.parent() {
+        command.current_dir(dir);
+    }
+    command
+}
+
+/// `docker compose up` of the services, or of all with none, attached so
+/// that their output streams in.
+pub fn up_command(file: &Path, services: &[String]) -> Command {
+    let mut command = compose(file);
+    command.arg("up").args(services);
+    command
+}
+
+/// `docker compose up` of the services in the background, exiting once they
+/// run and those with a health check are healthy.
+pub fn wait_command(file: &Path, services: &[String]) -> Command {
+    let mut command = compose(file);
+    command
+        .arg("up")
+        .arg("--detach")
+        .arg("--wait")
+        .args(services);
+    command
+}
+
+pub fn down_command(file: &Path) -> Command {
+    let mut command = compose(file);
+    command.arg("down");
+    command
+}
+
+/// What `docker compose ps` tells of a service's container.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceStatus {
+    // Like running or exited
+    pub state: String,
+    // Like healthy or starting, for services with a health check
+    pub health: Option<String>,
+}
+
+impl ServiceStatus {
+    pub fn label(&self) -> String {
+        match &self.health {
+            Some(health) => format!("{} ({})", self.state, health),
+            None => self.state.clone(),
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match (self.state.as_str(), self.health.as_deref()) {
+            ("running", Some("unhealthy")) => Color::Red,
+            ("running", Some("starting")) => Color::Yellow,
+            ("running", _) => Color::Green,
+            ("exited" | "dead", _) => Color::Red,
+            _ => Color::Yellow,
+        }
+    }
+}
+
+// The services in the output of `docker compose ps --format json`, one object
+// per line, or a single array from versions before 2.21
+fn parse_ps(output: &str) -> HashMap<String, ServiceStatus> {
+    let containers: Vec<Value> = match serde_json::from_str(output.trim()) {
+        Ok(Value::Array(containers)) => containers,
+        _ => output
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect(),
+    };
+    containers
+        .iter()
+        .filter_map(|container| {
+            let service = container["Service"].as_str()?;
+            let health = container["Health"]
+                .as_str()
+                .filter(|health| !health.is_empty());
+            Some((
+                service.to_string(),
+                ServiceStatus {
+                    state: container["State"].as_str().unwrap_or("unknown").to_string(),
+                    health: health.map(str::to_string),
+                },
+            ))
+        })
+        .collect()
+}
+
+fn read_statuses(file: &Path) -> Option<HashMap<String, ServiceStatus>> {
+    let output = compose(file)
+        .args(["ps", "--all", "--format", "json"])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| parse_ps(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Asks `docker compose ps` for the status of the services on a worker
+/// thread, every few seconds until dropped.
+pub struct StatusPoller {
+    rx: Receiver<Option<HashMap<String, ServiceStatus>>>,
+    // None until the first answer, kept when `docker compose ps` fails
+    pub statuses: Option<HashMap<String, ServiceStatus>>,
+    pub failed: bool,
+}
+
+impl StatusPoller {
+    pub fn new(file: &Path) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let file = file.to_path_buf();
+        thread::spawn(move || {
+            while tx.send(read_statuses(&file)).is_ok() {
+                thread::sleep(POLL_EVERY);
+            }
+        });
+        Self {
+            rx,
+            statuses: None,
+            failed: false,
+        }
+    }
+
+    /// Takes the latest answer, if one came in.
+    pub fn poll(&mut self) {
+        while let Ok(statuses) = self.rx.try_recv() {
+            self.failed = statuses.is_none();
+            if statuses.is_some() {
+                self.statuses = statuses;
+            }
+        }
+    }
+
+    pub fn status(&self, service: &str) -> Option<&ServiceStatus> {
+        self.statuses.as_ref()?.get(service)
+    }
+}
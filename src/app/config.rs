@@ -0,0 +1,665 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::{
+    error::{AppError, Result},
+    hooks::Hooks,
+    keymap::KeySpecs,
+    ready::Readiness,
+    rules::OutputRule,
+};
+
+const DEFAULT_CONFIG: &str = r#"# sln-runner configuration
+
+# Directories scanned for .sln, .slnx and .slnf files. `~` expands to your
+# home directory. When the list is empty, the current working directory is
+# scanned.
+# roots = ["~/work", "~/oss"]
+roots = []
+
+# Directory names skipped while scanning, in addition to bin, obj,
+# node_modules and .git.
+# exclude_dirs = ["packages", "artifacts"]
+exclude_dirs = []
+
+# Solutions hidden from the list, as paths or glob patterns. * and ? stay
+# within a directory, ** crosses them, and a pattern without a / matches a
+# name anywhere. A pattern matching a directory skips scanning it. Press h in
+# the solution list to add one, and H to show the hidden solutions again.
+# ignore = ["**/samples/**", "Templates", "~/work/legacy/Old.sln"]
+ignore = []
+
+# Capture the mouse for clicking and scrolling the lists. Turn this off to
+# keep the terminal's own text selection.
+mouse = true
+
+# Number of lines kept in the log panel's scrollback.
+log_lines = 1000
+
+# Number of lines of output kept per process for the output view. Both that
+# and the log panel also keep no more than scrollback_mb megabytes of text
+# each. Past either limit the oldest lines are dropped, and a line at the top
+# says how many.
+output_lines = 10000
+scrollback_mb = 10
+
+# Open the browser as soon as a web project reports the address it listens
+# on. Otherwise press o to open it.
+auto_open_browser = false
+
+# Where Ctrl+S saves the log. Defaults to the current directory.
+# log_dir = "~/logs"
+
+# Save the output of every run, without its colors, to a file as it comes,
+# named by record_path with {project} and {timestamp} filled in. A file
+# growing past record_max_mb starts over, keeping the previous part as
+# <file>.1, so a chatty service takes twice that at most. Press v on a
+# process in the process list (p) to open its recording in the editor.
+record_output = false
+# record_path = "~/.local/share/sln-runner/runs/{project}/{timestamp}.log"
+record_max_mb = 10
+
+# Command that opens a file at a line, for Enter in the problems list (P),
+# with {file}, {line} and {col} filled in. Defaults to $VISUAL or $EDITOR in
+# the form it takes a line number.
+# editor = "code -g {file}:{line}:{col}"
+# editor = "vim +{line} {file}"
+
+# How a project is built before it runs: "separate" runs `dotnet build` and
+# then `dotnet run --no-build`, "run" leaves the build to `dotnet run`.
+# Press b in the project list to switch.
+build = "separate"
+
+# Order of the solution list: "name", "path", "modified" (most recently
+# changed first) or "recent" (the one a project last ran from first). Press
+# o in the solution list to switch; the choice is remembered.
+sort = "name"
+
+# Where builds write the MSBuild binary log while it is switched on (D in
+# the project list), as <project>.binlog. Defaults to msbuild.binlog next to
+# the project or solution.
+# binlog_dir = "~/binlogs"
+
+# Run `dotnet restore` for the solution before its first build of a session,
+# so that a feed turning the credentials away shows up on its own rather than
+# deep in the build output. Ctrl+R in the project list restores at any time.
+auto_restore = false
+
+# Values offered by E in the project list for ASPNETCORE_ENVIRONMENT and
+# DOTNET_ENVIRONMENT, overriding the launch profile.
+environments = ["Development", "Staging", "Production"]
+
+# Projects, by name, started again when they crash: exit with an error
+# without being stopped. Restarts wait 1s, 2s, 4s and so on, and give up
+# after max_restarts crashes in a row. Group members can set
+# `auto_restart = true` instead.
+# auto_restart = ["Api", "Worker"]
+auto_restart = []
+max_restarts = 5
+
+# Whether a project run with the dev server of its single-page app (n in the
+# project list) waits for the dev server to listen before it starts, or
+# starts alongside it.
+wait_for_spa = true
+
+# Whether launching a project over HTTPS first checks, once a session, that
+# the ASP.NET Core development certificate is installed, offering to trust
+# one when it is not. Off where TLS is terminated elsewhere.
+check_dev_certs = true
+
+# Rules for the lines of process output, a regex each: "highlight" shows the
+# lines it matches in `color` (yellow), "mute" hides them from the output
+# view, counting how many, and "alert" flashes the status bar and rings the
+# bell as one arrives. The first matching rule wins. A rule with a `project`
+# applies to that project only. m in the output view switches them on and
+# off for the session; the log and saved logs keep every line as it was.
+# [[output_rules]]
+# pattern = "GET /health"
+# action = "mute"
+# [[output_rules]]
+# pattern = "Exception|fail:"
+# action = "alert"
+# [[output_rules]]
+# pattern = "Order \\d+ placed"
+# action = "highlight"
+# color = "lightgreen"
+# project = "Api"
+
+# IDEs that O opens the highlighted solution or project in, by the name the
+# picker shows when there are several. {sln} is the solution file, {path}
+# the solution or, in the project list, the project file, and {dir} the
+# directory {path} is in. Defaults to Rider, VS Code and, on Windows, Visual
+# Studio, whichever are on PATH.
+# [ides]
+# "VS Code" = "code {dir}"
+# Rider = "rider {sln}"
+
+# Environment variables per project name, applied on top of the launch
+# profile's. Press e on a project to edit them, and s there to save them here.
+# [env.Api]
+# ASPNETCORE_ENVIRONMENT = "Staging"
+
+# MSBuild arguments added to every build, run and publish, per solution file
+# name and per project name. Press m in the project list to add some for the
+# session only, and M to clear those.
+# [build_args]
+# "Shop.sln" = ["-p:DefineConstants=LOCAL"]
+# Api = ["-p:Version=1.2.3"]
+
+# Commands run around a project's runs, per project name, in its directory
+# with the shell: pre_build before it builds, pre_run before it starts,
+# post_start alongside it once started, stopped with it, and on_stop after it
+# stops. The output goes to the log. A failing pre_build or pre_run stops the
+# run. Each project's hooks are shown once for confirmation before they first
+# run, and again when they change. [group_hooks.<group>] does the same for a
+# group from the group picker, in the solution's directory: its pre hooks
+# run before the first member starts, post_start once all have started and
+# on_stop once all have stopped.
+# [hooks.Api]
+# pre_run = "docker compose up -d db"
+# post_start = "npm run watch:css"
+
+# Projects started together from the group picker (G), by their path in the
+# solution. Mark projects with space and pick "Save marked projects" there to
+# add one. A member can instead be a table naming the members it waits for
+# and when it counts as ready: a log line matching a regex, a port accepting
+# connections or a URL answering 200, within `timeout` seconds (60).
+# [groups]
+# local-stack = ["Api/Api.csproj", "Worker/Worker.csproj"]
+# with-auth = [
+#   { project = "Auth/Auth.csproj", ready = { log = "Now listening" } },
+#   { project = "Api/Api.csproj", depends_on = ["Auth/Auth.csproj"], ready = { http = "http://localhost:5000/health" }, timeout = 30 },
+#   { project = "Web/Web.csproj", depends_on = ["Api/Api.csproj"], auto_restart = true },
+# ]
+
+# The project the `dotnet ef` commands of the migrations panel (Ctrl+E)
+# start the app from, per name of the project with the migrations, by its
+# path in the solution. Without one, the project itself is started.
+# [ef_startup_projects]
+# Data = "Api/Api.csproj"
+
+# Services of the compose file next to the solution that a group needs, per
+# group name. They are brought up with `docker compose up --detach --wait`
+# before the group's projects start, which waits for their health checks.
+# [group_services]
+# local-stack = ["postgres", "redis"]
+
+# Key remapping. Each action takes one key or a list of keys, replacing its
+# defaults everywhere it is bound. Keys are written like "q", "G", "enter",
+# "esc", "space", "pagedown", "f5", "shift+up" or "ctrl+c". Press ? in the
+# app to see the current bindings. Actions: help, palette, quit, back, up,
+# down, select, force_run, expand, collapse, tree, details, graph, reverse,
+# build_impacted, filter, clear_filter, rescan, sort, pin, hide,
+# show_hidden, edit_root, configuration, profile, framework, output, cancel,
+# build_strategy, watch, spa, test, explore_tests, fold, publish,
+# open_publish, open_ide, export_vscode, docker, compose, migrations,
+# secrets, packages, sdk, copy, build_solution, restore, clean,
+# run_with_args, build_args, clear_build_args, verbosity, binlog,
+# environment, environment_name, open_browser, mark, next_process,
+# previous_process, stop_all, groups, processes, clear_exited,
+# open_recording, problems, failed_tests, rerun_failed, page_up, page_down,
+# top, bottom, follow, pause_output, raw_output, output_rules, stop,
+# restart, log_up, log_down, log_page_up, log_page_down, log_follow,
+# timestamps, focus_logs, next_match, previous_match, log_level,
+# export_logs, grow_logs, shrink_logs, collapse_logs, zoom_logs.
+[keys]
+# quit = ["q", "ctrl+q"]
+# up = ["up", "ctrl+p"]
+"#;
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub roots: Vec<PathBuf>,
+    pub exclude_dirs: Vec<String>,
+    pub ignore: Vec<String>,
+    pub mouse: bool,
+    pub log_lines: usize,
+    pub output_lines: usize,
+    pub scrollback_mb: usize,
+    pub log_dir: Option<PathBuf>,
+    pub record_output: bool,
+    pub record_path: Option<PathBuf>,
+    pub record_max_mb: u64,
+    pub auto_open_browser: bool,
+    pub editor: Option<String>,
+    pub ides: BTreeMap<String, String>,
+    pub build: BuildStrategy,
+    pub sort: SolutionSort,
+    pub build_args: BTreeMap<String, Vec<String>>,
+    pub auto_restore: bool,
+    pub binlog_dir: Option<PathBuf>,
+    pub environments: Vec<String>,
+    pub auto_restart: Vec<String>,
+    pub max_restarts: u32,
+    pub wait_for_spa: bool,
+    pub check_dev_certs: bool,
+    pub output_rules: Vec<OutputRule>,
+    pub env: BTreeMap<String, BTreeMap<String, String>>,
+    pub groups: BTreeMap<String, Vec<GroupMember>>,
+    pub hooks: BTreeMap<String, Hooks>,
+    pub group_hooks: BTreeMap<String, Hooks>,
+    pub group_services: BTreeMap<String, Vec<String>>,
+    pub ef_startup_projects: BTreeMap<String, String>,
+    pub keys: BTreeMap<String, KeySpecs>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            roots: Vec::new(),
+            exclude_dirs: Vec::new(),
+            ignore: Vec::new(),
+            mouse: true,
+            log_lines: 1000,
+            output_lines: 10_000,
+            scrollback_mb: 10,
+            log_dir: None,
+            record_output: false,
+            record_path: None,
+            record_max_mb: 10,
+            auto_open_browser: false,
+            editor: None,
+            ides: BTreeMap::new(),
+            build: BuildStrategy::default(),
+            sort: SolutionSort::default(),
+            build_args: BTreeMap::new(),
+            auto_restore: false,
+            binlog_dir: None,
+            environments: ["Development", "Staging", "Production"]
+                .map(String::from)
+                .to_vec(),
+            auto_restart: Vec::new(),
+            max_restarts: 5,
+            wait_for_spa: true,
+            check_dev_certs: true,
+            output_rules: Vec::new(),
+            env: BTreeMap::new(),
+            groups: BTreeMap::new(),
+            hooks: BTreeMap::new(),
+            group_hooks: BTreeMap::new(),
+            group_services: BTreeMap::new(),
+            ef_startup_projects: BTreeMap::new(),
+            keys: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BuildStrategy {
+    /// `dotnet build`, then `dotnet run --no-build`.
+    #[default]
+    Separate,
+    /// `dotnet run` alone, which builds the project itself.
+    Run,
+}
+
+impl BuildStrategy {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Separate => "build, then run",
+            Self::Run => "run builds",
+        }
+    }
+
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::Separate => Self::Run,
+            Self::Run => Self::Separate,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SolutionSort {
+    #[default]
+    Name,
+    Path,
+    Modified,
+    Recent,
+}
+
+impl SolutionSort {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Path => "path",
+            Self::Modified => "last modified",
+            Self::Recent => "last run",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Name => Self::Path,
+            Self::Path => Self::Modified,
+            Self::Modified => Self::Recent,
+            Self::Recent => Self::Name,
+        }
+    }
+}
+
+/// A project of a run group: its path in the solution, plus the members it
+/// waits for and when it counts as ready.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(from = "MemberEntry")]
+pub struct GroupMember {
+    pub project: String,
+    pub depends_on: Vec<String>,
+    pub ready: Option<Readiness>,
+    // Seconds to wait for `ready`
+    pub timeout: Option<u64>,
+    pub auto_restart: bool,
+}
+
+impl GroupMember {
+    pub fn new(project: String) -> Self {
+        Self {
+            project,
+            depends_on: Vec::new(),
+            ready: None,
+            timeout: None,
+            auto_restart: false,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MemberEntry {
+    Path(String),
+    Table(MemberTable),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MemberTable {
+    project: String,
+    #[serde(default)]
+    depends_on: Vec<String>,
+    ready: Option<Readiness>,
+    timeout: Option<u64>,
+    #[serde(default)]
+    auto_restart: bool,
+}
+
+impl From<MemberEntry> for GroupMember {
+    fn from(entry: MemberEntry) -> Self {
+        match entry {
+            MemberEntry::Path(project) => Self::new(project),
+            MemberEntry::Table(table) => Self {
+                project: table.project,
+                depends_on: table.depends_on,
+                ready: table.ready,
+                timeout: table.timeout,
+                auto_restart: table.auto_restart,
+            },
+        }
+    }
+}
+
+impl Config {
+    /// The most bytes of text a process's output or the log panel keeps.
+    pub fn scrollback_bytes(&self) -> usize {
+        self.scrollback_mb.saturating_mul(1024 * 1024)
+    }
+
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("sln-runner").join("config.toml"))
+    }
+
+    /// Loads the config file, writing the default one if it does not exist yet.
+    pub fn load_or_create(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, DEFAULT_CONFIG)?;
+        }
+
+        let contents = fs::read_to_string(path)?;
+        let mut config: Config = toml::from_str(&contents).map_err(|e| AppError::Config {
+            path: path.to_path_buf(),
+            message: e.message().to_string(),
+        })?;
+        config.roots = config.roots.iter().map(|root| expand_tilde(root)).collect();
+        config.log_dir = config.log_dir.as_deref().map(expand_tilde);
+        config.record_path = config.record_path.as_deref().map(expand_tilde);
+        config.binlog_dir = config.binlog_dir.as_deref().map(expand_tilde);
+        Ok(config)
+    }
+
+    /// Replaces the `[env]` table of `project` in the config file, leaving the
+    /// rest of the file, comments included, as it was.
+    pub fn save_env(path: &Path, project: &str, vars: &BTreeMap<String, String>) -> Result<()> {
+        let entries = vars
+            .iter()
+            .map(|(name, value)| format!("{} = {}", toml_key(name), toml_string(value)))
+            .collect();
+        replace_table(path, &["env", project], entries, |parsed| {
+            parsed.env.get(project).cloned().unwrap_or_default() == *vars
+        })
+    }
+
+    /// Sets the `ignore` list of the config file, leaving the rest of the
+    /// file as it was.
+    pub fn save_ignore(path: &Path, patterns: &[String]) -> Result<()> {
+        let patterns_toml: Vec<String> = patterns.iter().map(|p| toml_string(p)).collect();
+        let entry = format!("ignore = [{}]", patterns_toml.join(", "));
+        replace_key(path, "ignore", entry, |parsed| parsed.ignore == patterns)
+    }
+
+    /// Rewrites the `[groups]` table of the config file with `groups`.
+    pub fn save_groups(path: &Path, groups: &BTreeMap<String, Vec<GroupMember>>) -> Result<()> {
+        let entries = groups
+            .iter()
+            .map(|(name, members)| {
+                let members: Vec<String> = members.iter().map(toml_member).collect();
+                format!("{} = [{}]", toml_key(name), members.join(", "))
+            })
+            .collect();
+        replace_table(path, &["groups"], entries, |parsed| {
+            parsed.groups == *groups
+        })
+    }
+}
+
+/// Replaces the table at `table_path` in the config file with `entries`,
+/// dropping it when there are none, and checks the result with `applied`.
+fn replace_table(
+    path: &Path,
+    table_path: &[&str],
+    entries: Vec<String>,
+    applied: impl Fn(&Config) -> bool,
+) -> Result<()> {
+    let contents = fs::read_to_string(path)?;
+    let mut lines = Vec::new();
+    let mut in_table = false;
+    for line in contents.lines() {
+        if line.trim_start().starts_with('[') {
+            in_table = is_table_header(line, table_path);
+        }
+        if !in_table {
+            lines.push(line);
+        }
+    }
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+
+    let header = table_path
+        .iter()
+        .map(|key| toml_key(key))
+        .collect::<Vec<_>>()
+        .join(".");
+    let mut updated = lines.join("\n");
+    if !entries.is_empty() {
+        updated.push_str(&format!("\n\n[{}]", header));
+        for entry in entries {
+            updated.push('\n');
+            updated.push_str(&entry);
+        }
+    }
+    updated.push('\n');
+
+    // The table may also have been written inline or in dotted form, which
+    // the line-based edit above does not see
+    let invalid = |message: String| AppError::Config {
+        path: path.to_path_buf(),
+        message,
+    };
+    let parsed: Config = toml::from_str(&updated)
+        .map_err(|e| invalid(format!("cannot update [{}]: {}", header, e.message())))?;
+    if !applied(&parsed) {
+        return Err(invalid(format!(
+            "cannot update [{}], edit the file by hand",
+            header
+        )));
+    }
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Replaces the top-level `key` of the config file with `entry`, or adds it
+/// before the first table, and checks the result with `applied`.
+fn replace_key(
+    path: &Path,
+    key: &str,
+    entry: String,
+    applied: impl Fn(&Config) -> bool,
+) -> Result<()> {
+    let contents = fs::read_to_string(path)?;
+    let mut lines: Vec<&str> = Vec::new();
+    let mut in_table = false;
+    let mut replaced = false;
+    // The lines of a value spread over several, until they make one
+    let mut value: Option<String> = None;
+    for line in contents.lines() {
+        if let Some(value_lines) = value.as_mut() {
+            value_lines.push('\n');
+            value_lines.push_str(line);
+            if toml::from_str::<toml::Table>(value_lines).is_ok() {
+                value = None;
+            }
+            continue;
+        }
+        if line.trim_start().starts_with('[') && !in_table {
+            in_table = true;
+            if !replaced {
+                lines.extend([entry.as_str(), ""]);
+                replaced = true;
+            }
+        }
+        let is_key = !in_table
+            && line
+                .split_once('=')
+                .is_some_and(|(name, _)| name.trim().trim_matches('"') == key);
+        if !is_key {
+            lines.push(line);
+            continue;
+        }
+        if !replaced {
+            lines.push(&entry);
+            replaced = true;
+        }
+        if toml::from_str::<toml::Table>(line).is_err() {
+            value = Some(line.to_string());
+        }
+    }
+    if !replaced {
+        lines.push(&entry);
+    }
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+
+    let invalid = |message: String| AppError::Config {
+        path: path.to_path_buf(),
+        message,
+    };
+    let parsed: Config = toml::from_str(&updated)
+        .map_err(|e| invalid(format!("cannot update {}: {}", key, e.message())))?;
+    if !applied(&parsed) {
+        return Err(invalid(format!(
+            "cannot update {}, edit the file by hand",
+            key
+        )));
+    }
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+/// Whether `line` is the header of the table at `table_path`, in any quoting.
+fn is_table_header(line: &str, table_path: &[&str]) -> bool {
+    let Ok(mut table) = toml::from_str::<toml::Table>(line) else {
+        return false;
+    };
+    for (i, key) in table_path.iter().enumerate() {
+        if table.len() != 1 {
+            return false;
+        }
+        match table.remove(*key) {
+            Some(toml::Value::Table(inner)) if i + 1 < table_path.len() => table = inner,
+            Some(toml::Value::Table(_)) => return true,
+            _ => return false,
+        }
+    }
+    false
+}
+
+fn toml_key(key: &str) -> String {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if bare {
+        key.to_string()
+    } else {
+        toml_string(key)
+    }
+}
+
+fn toml_string(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
+
+/// A group member as written in the config, a plain path unless it has
+/// dependencies, a readiness condition or restarts.
+fn toml_member(member: &GroupMember) -> String {
+    if *member == GroupMember::new(member.project.clone()) {
+        return toml_string(&member.project);
+    }
+    let mut fields = vec![format!("project = {}", toml_string(&member.project))];
+    if !member.depends_on.is_empty() {
+        let depends_on: Vec<String> = member.depends_on.iter().map(|d| toml_string(d)).collect();
+        fields.push(format!("depends_on = [{}]", depends_on.join(", ")));
+    }
+    if let Some(ready) = &member.ready {
+        let condition = match ready {
+            Readiness::Log(pattern) => format!("log = {}", toml_string(pattern)),
+            Readiness::Port(port) => format!("port = {}", port),
+            Readiness::Http(url) => format!("http = {}", toml_string(url)),
+        };
+        fields.push(format!("ready = {{ {} }}", condition));
+    }
+    if let Some(timeout) = member.timeout {
+        fields.push(format!("timeout = {}", timeout));
+    }
+    if member.auto_restart {
+        fields.push("auto_restart = true".to_string());
+    }
+    format!("{{ {} }}", fields.join(", "))
+}
+
+pub fn expand_tilde(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
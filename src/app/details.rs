@@ -0,0 +1,191 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::SystemTime,
+};
+
+use crate::app::{
+    launch::{launch_settings_path, read_launch_profiles},
+    util::{
+        attribute, elements, from_project_or_props, output_kind, property_values,
+        target_frameworks, OutputKind,
+    },
+};
+
+/// What the details panel shows about a project.
+#[derive(Debug, Clone)]
+pub struct ProjectDetails {
+    pub frameworks: Vec<String>,
+    pub output: OutputKind,
+    pub output_type: String,
+    pub sdk: Option<String>,
+    pub assembly_name: String,
+    // Name and version, which central package management may leave out
+    pub packages: Vec<(String, Option<String>)>,
+    // File names of the referenced projects
+    pub references: Vec<String>,
+    // Profile names and their URLs
+    pub profiles: Vec<(String, Vec<String>)>,
+    // When the files they were read from last changed
+    stamp: Stamp,
+}
+
+type Stamp = [Option<SystemTime>; 2];
+
+fn stamp(project_path: &Path) -> Stamp {
+    let modified = |path: &Path| fs::metadata(path).and_then(|meta| meta.modified()).ok();
+    let project_dir = project_path.parent().unwrap_or(Path::new(""));
+    [
+        modified(project_path),
+        modified(&launch_settings_path(project_dir)),
+    ]
+}
+
+/// Reads the details of a project from its file and its launch settings.
+pub fn read_details(name: &str, project_path: &Path) -> ProjectDetails {
+    let stamp = stamp(project_path);
+    let contents = fs::read_to_string(project_path).unwrap_or_default();
+    let project_dir = project_path.parent().unwrap_or(Path::new(""));
+    let output = output_kind(name, project_path);
+
+    let output_type = from_project_or_props(project_path, |contents| {
+        property_values(contents, "OutputType").find(|value| !value.contains("$("))
+    })
+    // The web, worker and test SDKs make programs without saying so
+    .unwrap_or_else(|| match output {
+        OutputKind::Library => "Library".to_string(),
+        _ => "Exe".to_string(),
+    });
+    let sdk = elements(&contents, "Project")
+        .next()
+        .and_then(|(tag, _)| attribute(tag, "Sdk"))
+        .or_else(|| elements(&contents, "Sdk").find_map(|(tag, _)| attribute(tag, "Name")))
+        .map(|sdk| sdk.split('/').next().unwrap_or(sdk).to_string());
+    let assembly_name = property_values(&contents, "AssemblyName")
+        .find(|value| !value.contains("$("))
+        .unwrap_or_else(|| {
+            project_path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| name.to_string())
+        });
+
+    let central = central_versions(project_dir);
+    let packages = elements(&contents, "PackageReference")
+        .filter_map(|(tag, body)| {
+            let package = attribute(tag, "Include")?;
+            let version = attribute(tag, "Version")
+                .or_else(|| attribute(tag, "VersionOverride"))
+                .map(str::to_string)
+                .or_else(|| property_values(body, "Version").next())
+                .or_else(|| central.get(&package.to_ascii_lowercase()).cloned());
+            Some((package.to_string(), version))
+        })
+        .collect();
+    let references = elements(&contents, "ProjectReference")
+        .filter_map(|(tag, _)| attribute(tag, "Include"))
+        .map(|path| path.rsplit(['\\', '/']).next().unwrap_or(path).to_string())
+        .collect();
+    let profiles = read_launch_profiles(project_dir)
+        .into_iter()
+        .map(|profile| {
+            let urls = profile
+                .application_url
+                .as_deref()
+                .unwrap_or_default()
+                .split(';')
+                .map(str::trim)
+                .filter(|url| !url.is_empty())
+                .map(str::to_string)
+                .collect();
+            (profile.name, urls)
+        })
+        .collect();
+
+    ProjectDetails {
+        frameworks: target_frameworks(project_path),
+        output,
+        output_type,
+        sdk,
+        assembly_name,
+        packages,
+        references,
+        profiles,
+        stamp,
+    }
+}
+
+// Package versions from the nearest `Directory.Packages.props`, by lowercase
+// name, for projects using central package management
+fn central_versions(project_dir: &Path) -> HashMap<String, String> {
+    let Some(contents) = project_dir
+        .ancestors()
+        .map(|dir| dir.join("Directory.Packages.props"))
+        .find(|props| props.is_file())
+        .and_then(|props| fs::read_to_string(props).ok())
+    else {
+        return HashMap::new();
+    };
+    elements(&contents, "PackageVersion")
+        .filter_map(|(tag, _)| {
+            let package = attribute(tag, "Include")?;
+            Some((
+                package.to_ascii_lowercase(),
+                attribute(tag, "Version")?.to_string(),
+            ))
+        })
+        .collect()
+}
+
+/// Reads project details on a worker thread and keeps them until the files
+/// they came from change, so moving through the list never waits on the disk.
+pub struct DetailsCache {
+    requests: Sender<(String, PathBuf)>,
+    rx: Receiver<(PathBuf, ProjectDetails)>,
+    details: HashMap<PathBuf, ProjectDetails>,
+    pending: HashSet<PathBuf>,
+}
+
+impl DetailsCache {
+    pub fn new() -> Self {
+        let (requests, worker_requests) = mpsc::channel::<(String, PathBuf)>();
+        let (tx, rx) = mpsc::channel();
+        // Ends once the cache is dropped and the requests run out
+        thread::spawn(move || {
+            for (name, path) in worker_requests {
+                let details = read_details(&name, &path);
+                if tx.send((path, details)).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            requests,
+            rx,
+            details: HashMap::new(),
+            pending: HashSet::new(),
+        }
+    }
+
+    /// The details of the project as last read, asking for them to be read
+    /// again when they are missing or out of date.
+    pub fn get(&mut self, name: &str, project_path: &Path) -> Option<&ProjectDetails> {
+        for (path, details) in self.rx.try_iter() {
+            self.pending.remove(&path);
+            self.details.insert(path, details);
+        }
+        let stale = self
+            .details
+            .get(project_path)
+            .is_none_or(|details| details.stamp != stamp(project_path));
+        if stale && self.pending.insert(project_path.to_path_buf()) {
+            let _ = self
+                .requests
+                .send((name.to_string(), project_path.to_path_buf()));
+        }
+        self.details.get(project_path)
+    }
+}
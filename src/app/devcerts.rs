@@ -0,0 +1,44 @@
+use std::process::{Command, Stdio};
+
+use crate::app::error::{dotnet_spawn_error, Result};
+
+/// The command line that creates and trusts the development certificate.
+pub const TRUST_COMMAND: &str = "dotnet dev-certs https --trust";
+
+/// Whether a valid ASP.NET Core HTTPS development certificate is installed,
+/// from `dotnet dev-certs https --check --quiet`.
+pub fn check() -> Result<bool> {
+    let status = Command::new("dotnet")
+        .args(["dev-certs", "https", "--check", "--quiet"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(dotnet_spawn_error)?;
+    Ok(status.success())
+}
+
+pub fn trust_command() -> Command {
+    let mut command = Command::new("dotnet");
+    command.args(["dev-certs", "https", "--trust"]);
+    command
+}
+
+/// What the system asks of the user while the certificate is trusted, which
+/// happens outside of the terminal.
+pub fn elevation_hint() -> Option<&'static str> {
+    if cfg!(windows) {
+        Some("Windows asks to confirm installing the certificate")
+    } else if cfg!(target_os = "macos") {
+        Some("macOS asks for your password to add it to the keychain")
+    } else {
+        None
+    }
+}
+
+/// Whether any of the `;` separated addresses, like an applicationUrl, is
+/// served over HTTPS.
+pub fn uses_https(urls: &str) -> bool {
+    urls.split(';')
+        .any(|url| url.trim().to_ascii_lowercase().starts_with("https://"))
+}
@@ -0,0 +1,294 @@
+use std::sync::LazyLock;
+
+use ratatui::style::Color;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::app::logs::strip_ansi;
+
+// `origin(position): severity CODE: message [project]`, where the origin is a
+// file or a tool like `CSC`, and the position `line`, `line,col` or
+// `line,col,endLine,endCol`
+static DIAGNOSTIC: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^\s*(?P<origin>\S.*?)(?:\((?P<position>\d+(?:,\d+){0,3})\))?\s*:\s*(?P<severity>error|warning)\s*(?P<code>[A-Za-z]*\d+)?\s*:\s*(?P<message>.*)$",
+    )
+    .expect("valid diagnostic regex")
+});
+
+// MSBuild ends a diagnostic with the project that reported it
+static PROJECT_SUFFIX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\s*\[(?P<project>[^\[\]]+\.\w+proj)(?:::[^\]]*)?\]\s*$")
+        .expect("valid suffix regex")
+});
+
+// `  Api -> /src/Api/bin/Debug/net8.0/Api.dll`, once a project is built
+static BUILT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(?P<project>[^\s:/\\]+) -> \S").expect("valid built regex"));
+
+/// The project a line of build output reports as built.
+pub fn built_project(line: &str) -> Option<String> {
+    BUILT
+        .captures(&strip_ansi(line))
+        .map(|captures| captures["project"].to_string())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            Self::Error => Color::Red,
+            Self::Warning => Color::Yellow,
+        }
+    }
+}
+
+/// An error or warning MSBuild reported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    // A source file, project file or tool name
+    pub origin: String,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub code: Option<String>,
+    pub message: String,
+    // The project file that reported it
+    pub project: Option<String>,
+}
+
+impl Diagnostic {
+    /// Where it was reported, like `/src/Api/Program.cs(12,5)`.
+    pub fn location(&self) -> String {
+        self.location_in(&self.origin)
+    }
+
+    /// The location with the file name only, like `Program.cs(12,5)`.
+    pub fn short_location(&self) -> String {
+        let file = self
+            .origin
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or(&self.origin);
+        self.location_in(file)
+    }
+
+    fn location_in(&self, origin: &str) -> String {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => format!("{}({},{})", origin, line, column),
+            (Some(line), None) => format!("{}({})", origin, line),
+            _ => origin.to_string(),
+        }
+    }
+}
+
+/// Picks diagnostics out of one stream of build output. A diagnostic whose
+/// line doesn't end with the project continues on the following lines.
+#[derive(Debug, Default)]
+pub struct DiagnosticParser {
+    // Lines still belong to the last diagnostic
+    open: bool,
+}
+
+pub enum Parsed {
+    Diagnostic(Diagnostic),
+    /// More of the last diagnostic's message, and its project once it ends.
+    Continuation {
+        text: String,
+        project: Option<String>,
+    },
+}
+
+impl DiagnosticParser {
+    pub fn feed(&mut self, line: &str) -> Option<Parsed> {
+        let line = strip_ansi(line);
+        if let Some(captures) = DIAGNOSTIC.captures(&line) {
+            let (message, project) = split_project(&captures["message"]);
+            self.open = project.is_none();
+            let mut position = captures
+                .name("position")
+                .map(|p| p.as_str())
+                .unwrap_or_default()
+                .split(',')
+                .map(|n| n.parse().ok());
+            return Some(Parsed::Diagnostic(Diagnostic {
+                severity: match &captures["severity"] {
+                    "error" => Severity::Error,
+                    _ => Severity::Warning,
+                },
+                origin: captures["origin"].trim().to_string(),
+                line: position.next().flatten(),
+                column: position.next().flatten(),
+                code: captures.name("code").map(|c| c.as_str().to_string()),
+                message,
+                project,
+            }));
+        }
+
+        if !self.open {
+            return None;
+        }
+        // Anything MSBuild prints next without indentation is not part of it
+        if line.trim().is_empty() || !line.starts_with(char::is_whitespace) {
+            self.open = false;
+            return None;
+        }
+        let (text, project) = split_project(line.trim());
+        self.open = project.is_none();
+        Some(Parsed::Continuation { text, project })
+    }
+}
+
+/// A diagnostic and the project whose build reported it.
+pub struct Problem {
+    pub build: String,
+    pub diagnostic: Diagnostic,
+    // Continuation lines still go to it
+    open: bool,
+}
+
+/// The diagnostics of each project's latest build.
+#[derive(Default)]
+pub struct Problems {
+    entries: Vec<Problem>,
+}
+
+impl Problems {
+    /// Forgets what the previous build of the project reported.
+    pub fn clear_build(&mut self, build: &str) {
+        self.entries.retain(|problem| problem.build != build);
+    }
+
+    pub fn add(&mut self, build: &str, parsed: Parsed) {
+        match parsed {
+            Parsed::Diagnostic(diagnostic) => {
+                // MSBuild repeats every diagnostic in its summary at the end
+                let repeated = self.entries.iter().any(|problem| {
+                    problem.build == build && same_diagnostic(&problem.diagnostic, &diagnostic)
+                });
+                for problem in self.entries.iter_mut().filter(|p| p.build == build) {
+                    problem.open = false;
+                }
+                if !repeated {
+                    let open = diagnostic.project.is_none();
+                    // Keep each build's problems together, as a solution
+                    // build reports its projects interleaved
+                    let at = self
+                        .entries
+                        .iter()
+                        .rposition(|p| p.build == build)
+                        .map_or(self.entries.len(), |last| last + 1);
+                    self.entries.insert(
+                        at,
+                        Problem {
+                            build: build.to_string(),
+                            diagnostic,
+                            open,
+                        },
+                    );
+                }
+            }
+            Parsed::Continuation { text, project } => {
+                let Some(problem) = self
+                    .entries
+                    .iter_mut()
+                    .rev()
+                    .find(|p| p.build == build)
+                    .filter(|p| p.open)
+                else {
+                    return;
+                };
+                problem.diagnostic.message.push('\n');
+                problem.diagnostic.message.push_str(&text);
+                if project.is_some() {
+                    problem.diagnostic.project = project;
+                    problem.open = false;
+                }
+            }
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Problem> {
+        self.entries.get(index)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Problem> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The errors and warnings of one build or of all of them.
+    pub fn counts(&self, build: Option<&str>) -> (usize, usize) {
+        let count = |severity| {
+            self.entries
+                .iter()
+                .filter(|p| build.is_none_or(|build| p.build == build))
+                .filter(|p| p.diagnostic.severity == severity)
+                .count()
+        };
+        (count(Severity::Error), count(Severity::Warning))
+    }
+
+    /// Counts like "2 errors, 1 warning", of one build or of all of them.
+    pub fn summary(&self, build: Option<&str>) -> String {
+        let (errors, warnings) = self.counts(build);
+        count_summary(errors, warnings)
+    }
+}
+
+/// "2 errors, 1 warning"
+pub fn count_summary(errors: usize, warnings: usize) -> String {
+    let plural = |n: usize, word: &str| match n {
+        1 => format!("1 {}", word),
+        n => format!("{} {}s", n, word),
+    };
+    format!(
+        "{}, {}",
+        plural(errors, "error"),
+        plural(warnings, "warning")
+    )
+}
+
+// Compares the first line only, since the summary repeats multi-line
+// messages line by line as well
+pub fn same_diagnostic(stored: &Diagnostic, new: &Diagnostic) -> bool {
+    stored.severity == new.severity
+        && stored.origin == new.origin
+        && stored.line == new.line
+        && stored.column == new.column
+        && stored.code == new.code
+        && stored.message.lines().next() == new.message.lines().next()
+}
+
+fn split_project(text: &str) -> (String, Option<String>) {
+    match PROJECT_SUFFIX.captures(text) {
+        Some(captures) => {
+            let start = captures.get(0).map_or(text.len(), |m| m.start());
+            (
+                text[..start].trim_end().to_string(),
+                Some(captures["project"].to_string()),
+            )
+        }
+        None => (text.trim_end().to_string(), None),
+    }
+}
@@ -0,0 +1,215 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+    time::Duration,
+};
+
+use crate::app::{
+    launch::{read_launch_profiles, LaunchProfile},
+    util::property_values,
+};
+
+// Where ASP.NET Core images listen since .NET 8, for Dockerfiles without EXPOSE
+const DEFAULT_CONTAINER_PORT: u16 = 8080;
+
+/// Whether the project runs in a container: it has a `Dockerfile` or a
+/// Docker launch profile.
+pub fn has_docker(project_dir: &Path) -> bool {
+    project_dir.join("Dockerfile").is_file()
+        || read_launch_profiles(project_dir)
+            .iter()
+            .any(LaunchProfile::is_docker)
+}
+
+/// The image name for a project, which Docker wants in lowercase.
+pub fn image_tag(project: &str) -> String {
+    let tag: String = project
+        .chars()
+        .map(|c| match c.to_ascii_lowercase() {
+            c @ ('a'..='z' | '0'..='9' | '.' | '_' | '-') => c,
+            _ => '-',
+        })
+        .collect();
+    tag.trim_start_matches(['.', '_', '-']).to_string()
+}
+
+/// `docker build` of the project's Dockerfile. The context is the project's
+/// `DockerfileContext`, else the solution directory when the Dockerfile copies
+/// paths from there, as the ones Visual Studio writes do, else the project
+/// directory.
+pub fn build_command(project_path: &Path, sln_dir: &Path, image: &str) -> Result<Command, String> {
+    let project_dir = project_path.parent().unwrap_or(Path::new("."));
+    let dockerfile = project_dir.join("Dockerfile");
+    let contents = fs::read_to_string(&dockerfile)
+        .map_err(|_| format!("no Dockerfile in {}", project_dir.display()))?;
+
+    let configured = fs::read_to_string(project_path)
+        .ok()
+        .and_then(|project| property_values(&project, "DockerfileContext").next());
+    let from_solution = project_path
+        .strip_prefix(sln_dir)
+        .is_ok_and(|relative| contents.contains(&relative.to_string_lossy().replace('\\', "/")));
+    let context = match configured {
+        Some(context) => project_dir.join(context.replace('\\', "/")),
+        None if from_solution => sln_dir.to_path_buf(),
+        None => project_dir.to_path_buf(),
+    };
+
+    let mut command = Command::new("docker");
+    command
+        .arg("build")
+        .arg("--tag")
+        .arg(image)
+        .arg("--file")
+        .arg(&dockerfile)
+        .arg(&context)
+        .current_dir(&context);
+    Ok(command)
+}
+
+/// The host and container ports of a project's URLs: the ports of the Docker
+/// launch profile or of the launch profile's applicationUrl, mapped in order
+/// onto the ports the Dockerfile exposes.
+pub fn port_mappings(project_dir: &Path, profile: Option<&LaunchProfile>) -> Vec<(u16, u16)> {
+    let docker = read_launch_profiles(project_dir)
+        .into_iter()
+        .find(LaunchProfile::is_docker);
+    let host_ports: Vec<u16> = match docker.as_ref().filter(|p| p.http_port.is_some()) {
+        Some(docker) => [docker.http_port, docker.ssl_port]
+            .into_iter()
+            .flatten()
+            .collect(),
+        None => profile
+            .and_then(|p| p.application_url.as_deref())
+            .map(|urls| urls.split(';').filter_map(url_port).collect())
+            .unwrap_or_default(),
+    };
+    let mut exposed = exposed_ports(&project_dir.join("Dockerfile"));
+    if exposed.is_empty() {
+        exposed.push(DEFAULT_CONTAINER_PORT);
+    }
+    host_ports.into_iter().zip(exposed).collect()
+}
+
+fn url_port(url: &str) -> Option<u16> {
+    let (_, rest) = url.trim().split_once("://")?;
+    let host = rest.split('/').next()?;
+    host.rsplit_once(':')?.1.parse().ok()
+}
+
+fn exposed_ports(dockerfile: &Path) -> Vec<u16> {
+    let contents = fs::read_to_string(dockerfile).unwrap_or_default();
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (instruction, ports) = line.trim().split_once(char::is_whitespace)?;
+            instruction.eq_ignore_ascii_case("EXPOSE").then_some(ports)
+        })
+        .flat_map(str::split_whitespace)
+        // Like 8080/tcp
+        .filter_map(|port| port.split('/').next()?.parse().ok())
+        .collect()
+}
+
+/// A container run from a project's image, attached so that its output
+/// streams in like that of `dotnet run`.
+#[derive(Debug, Clone)]
+pub struct Container {
+    pub image: String,
+    pub name: String,
+    // Host port, container port
+    pub ports: Vec<(u16, u16)>,
+    cidfile: PathBuf,
+    // Read from `cidfile` once Docker wrote it
+    pub id: Option<String>,
+}
+
+impl Container {
+    pub fn new(image: &str, ports: Vec<(u16, u16)>) -> Self {
+        let name = format!("sln-runner-{}", image);
+        Self {
+            image: image.to_string(),
+            cidfile: std::env::temp_dir().join(format!("{}.cid", name)),
+            name,
+            ports,
+            id: None,
+        }
+    }
+
+    /// `docker run`, removing the container once it stops.
+    pub fn run_command(&self, env: &BTreeMap<String, String>) -> Command {
+        // Docker refuses to overwrite the file of a previous run
+        let _ = fs::remove_file(&self.cidfile);
+        let mut command = Command::new("docker");
+        command
+            .arg("run")
+            .arg("--rm")
+            .arg("--name")
+            .arg(&self.name)
+            .arg("--cidfile")
+            .arg(&self.cidfile);
+        for (host, container) in &self.ports {
+            command
+                .arg("--publish")
+                .arg(format!("{}:{}", host, container));
+        }
+        for (name, value) in env {
+            command.arg("--env").arg(format!("{}={}", name, value));
+        }
+        command.arg(&self.image);
+        command
+    }
+
+    /// Picks up the short container id once Docker wrote it.
+    pub fn poll_id(&mut self) {
+        if self.id.is_none() {
+            self.id = fs::read_to_string(&self.cidfile)
+                .ok()
+                .map(|id| id.trim().chars().take(12).collect())
+                .filter(|id: &String| !id.is_empty());
+        }
+    }
+
+    /// Where the app is reached from the host: the first mapped port, as
+    /// the container has no certificate for HTTPS.
+    pub fn urls(&self) -> Vec<String> {
+        self.ports
+            .first()
+            .map(|(host, _)| format!("http://localhost:{}", host))
+            .into_iter()
+            .collect()
+    }
+
+    /// Starts `docker stop`, which sends the app SIGTERM and kills it after
+    /// `timeout`. The attached `docker run` exits with it.
+    pub fn stop(&self, timeout: Duration) -> io::Result<()> {
+        let mut child = Command::new("docker")
+            .arg("stop")
+            .arg("--time")
+            .arg(timeout.as_secs().to_string())
+            .arg(&self.name)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        thread::spawn(move || child.wait());
+        Ok(())
+    }
+
+    /// Removes the container at once, killing the app, for a run that
+    /// replaces it and needs its name and ports.
+    pub fn remove(&self) -> io::Result<()> {
+        Command::new("docker")
+            .arg("rm")
+            .arg("--force")
+            .arg(&self.name)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|_| ())
+    }
+}
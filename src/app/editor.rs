@@ -0,0 +1,185 @@
+use std::{collections::BTreeMap, env, path::Path};
+
+use crate::app::util::split_args;
+
+// Editors that open a window of their own and return right away, or at least
+// don't need the terminal
+const GUI_EDITORS: &[&str] = &[
+    "code",
+    "code-insiders",
+    "codium",
+    "cursor",
+    "windsurf",
+    "subl",
+    "zed",
+    "rider",
+    "rider64",
+    "idea",
+    "fleet",
+    "gedit",
+    "kate",
+    "notepad",
+    "notepad++",
+];
+
+/// How to open a file at a line: the program with its arguments, and whether
+/// it runs in the terminal, which the TUI then has to hand over.
+pub struct EditorCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    pub in_terminal: bool,
+}
+
+/// Fills in the `{file}`, `{line}` and `{col}` placeholders of the configured
+/// command, or of a default for `$VISUAL` or `$EDITOR`.
+pub fn editor_command(
+    template: Option<&str>,
+    file: &Path,
+    line: u32,
+    column: u32,
+) -> Result<EditorCommand, String> {
+    let template = match template {
+        Some(template) => template.to_string(),
+        None => default_template(),
+    };
+    let Some(words) = split_args(&template).filter(|words| !words.is_empty()) else {
+        return Err(format!("editor command '{}' is not valid", template));
+    };
+    if !template.contains("{file}") {
+        return Err(format!("editor command '{}' has no {{file}}", template));
+    }
+
+    let file = file.to_string_lossy();
+    let mut words = words.into_iter().map(|word| {
+        word.replace("{file}", &file)
+            .replace("{line}", &line.to_string())
+            .replace("{col}", &column.to_string())
+    });
+    let program = words.next().unwrap_or_default();
+    let args: Vec<String> = words.collect();
+    Ok(EditorCommand {
+        in_terminal: !GUI_EDITORS.contains(&program_name(&program).as_str()),
+        program,
+        args,
+    })
+}
+
+/// The IDEs a solution or project can be opened in, by name: the configured
+/// ones, or the defaults for the platform that are installed.
+pub fn ide_commands(configured: &BTreeMap<String, String>) -> Vec<(String, String)> {
+    if !configured.is_empty() {
+        return configured
+            .iter()
+            .map(|(name, template)| (name.clone(), template.clone()))
+            .collect();
+    }
+    let defaults: &[(&str, &str)] = if cfg!(windows) {
+        &[
+            ("Visual Studio", "devenv {path}"),
+            ("Rider", "rider {path}"),
+            ("VS Code", "code {dir}"),
+        ]
+    } else if cfg!(target_os = "macos") {
+        &[("Rider", "open -a Rider {path}"), ("VS Code", "code {dir}")]
+    } else {
+        &[("Rider", "rider {path}"), ("VS Code", "code {dir}")]
+    };
+    let installed: Vec<(String, String)> = defaults
+        .iter()
+        .filter(|(_, template)| {
+            split_args(template)
+                .and_then(|words| words.into_iter().next())
+                .is_some_and(|program| on_path(&program))
+        })
+        .map(|(name, template)| (name.to_string(), template.to_string()))
+        .collect();
+    // Offer them all when none is found, so trying one says what is missing
+    if installed.is_empty() {
+        return defaults
+            .iter()
+            .map(|(name, template)| (name.to_string(), template.to_string()))
+            .collect();
+    }
+    installed
+}
+
+/// Fills in the `{sln}`, `{path}` and `{dir}` placeholders of an IDE command:
+/// the solution, the solution or project file opened, and its directory.
+pub fn ide_command(template: &str, sln: &Path, path: &Path) -> Result<EditorCommand, String> {
+    let Some(words) = split_args(template).filter(|words| !words.is_empty()) else {
+        return Err(format!("IDE command '{}' is not valid", template));
+    };
+    let sln = sln.to_string_lossy();
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    }
+    .to_string_lossy();
+    let path = path.to_string_lossy();
+    let mut words = words.into_iter().map(|word| {
+        word.replace("{sln}", &sln)
+            .replace("{path}", &path)
+            .replace("{dir}", &dir)
+    });
+    let program = words.next().unwrap_or_default();
+    Ok(EditorCommand {
+        program,
+        args: words.collect(),
+        in_terminal: false,
+    })
+}
+
+// Whether the program is a file, or one of the directories in PATH has it
+fn on_path(program: &str) -> bool {
+    let program = Path::new(program);
+    if program.components().count() > 1 {
+        return program.is_file();
+    }
+    env::var_os("PATH").is_some_and(|paths| {
+        env::split_paths(&paths).any(|dir| {
+            let candidate = dir.join(program);
+            // `code` is `code.cmd` there
+            candidate.is_file()
+                || cfg!(windows)
+                    && ["exe", "cmd", "bat"]
+                        .iter()
+                        .any(|extension| candidate.with_extension(extension).is_file())
+        })
+    })
+}
+
+/// A command for the editor from the environment, in the form it takes a line
+/// number in.
+fn default_template() -> String {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+    let name = split_args(&editor)
+        .and_then(|words| words.into_iter().next())
+        .map(|program| program_name(&program))
+        .unwrap_or_default();
+    match name.as_str() {
+        "code" | "code-insiders" | "codium" | "cursor" | "windsurf" => {
+            format!("{} -g {{file}}:{{line}}:{{col}}", editor)
+        }
+        "subl" | "zed" | "hx" | "helix" => format!("{} {{file}}:{{line}}:{{col}}", editor),
+        "rider" | "rider64" | "idea" => format!("{} --line {{line}} {{file}}", editor),
+        "emacs" | "emacsclient" => format!("{} +{{line}}:{{col}} {{file}}", editor),
+        "vi" | "vim" | "nvim" | "nano" | "micro" | "kak" | "joe" | "mcedit" => {
+            format!("{} +{{line}} {{file}}", editor)
+        }
+        _ => format!("{} {{file}}", editor),
+    }
+}
+
+// `code` from `/usr/bin/code` or `C:\...\Code.exe`
+fn program_name(program: &str) -> String {
+    Path::new(program)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
@@ -0,0 +1,126 @@
+use std::collections::BTreeMap;
+
+use ratatui::widgets::ListState;
+
+use crate::app::input::TextInput;
+
+// Name fragments of variables whose values are masked in the editor
+const SECRET_NAMES: &[&str] = &[
+    "PASSWORD", "PASSWD", "PWD", "SECRET", "TOKEN", "APIKEY", "API_KEY",
+];
+
+/// Where an environment variable for a run comes from. Later sources override
+/// earlier ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvSource {
+    Profile,
+    Config,
+    Session,
+}
+
+impl EnvSource {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Profile => "profile",
+            Self::Config => "config",
+            Self::Session => "session",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EnvVar {
+    pub name: String,
+    // None when a session override removes a config variable
+    pub value: Option<String>,
+    pub source: EnvSource,
+}
+
+impl EnvVar {
+    pub fn is_secret(&self) -> bool {
+        is_secret(&self.name, self.value.as_deref().unwrap_or_default())
+    }
+}
+
+/// Whether a variable looks like it holds a password, token or key.
+pub fn is_secret(name: &str, value: &str) -> bool {
+    let name = name.to_ascii_uppercase();
+    let value = value.to_ascii_lowercase();
+    SECRET_NAMES.iter().any(|secret| name.contains(secret))
+        || value.contains("password=")
+        || value.contains("pwd=")
+}
+
+/// Merges the launch profile's variables, the config file's entries for the
+/// project and the session overrides, sorted by name.
+pub fn merge(
+    profile: &BTreeMap<String, String>,
+    config: &BTreeMap<String, String>,
+    session: &BTreeMap<String, Option<String>>,
+) -> Vec<EnvVar> {
+    let mut merged = BTreeMap::new();
+    let mut add = |source, name: &String, value: Option<&String>| {
+        let var = EnvVar {
+            name: name.clone(),
+            value: value.cloned(),
+            source,
+        };
+        merged.insert(name.clone(), var);
+    };
+    profile
+        .iter()
+        .for_each(|(name, value)| add(EnvSource::Profile, name, Some(value)));
+    config
+        .iter()
+        .for_each(|(name, value)| add(EnvSource::Config, name, Some(value)));
+    session
+        .iter()
+        .for_each(|(name, value)| add(EnvSource::Session, name, value.as_ref()));
+    merged.into_values().collect()
+}
+
+/// The variables to set on the `dotnet run` process.
+pub fn resolve(vars: &[EnvVar]) -> BTreeMap<String, String> {
+    vars.iter()
+        .filter_map(|var| Some((var.name.clone(), var.value.clone()?)))
+        .collect()
+}
+
+/// Splits `NAME=value` as typed into the editor.
+pub fn parse_assignment(line: &str) -> Option<(String, String)> {
+    let (name, value) = line.split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((name.to_string(), value.to_string()))
+}
+
+/// The environment popup for one project.
+pub struct EnvEditor {
+    pub project: String,
+    pub project_path: String,
+    pub profile: Option<String>,
+    pub profile_vars: BTreeMap<String, String>,
+    pub state: ListState,
+    // `NAME=value` being added or edited
+    pub input: Option<TextInput>,
+}
+
+impl EnvEditor {
+    pub fn new(
+        project: String,
+        project_path: String,
+        profile: Option<String>,
+        profile_vars: BTreeMap<String, String>,
+    ) -> Self {
+        Self {
+            project,
+            project_path,
+            profile,
+            profile_vars,
+            state: ListState::default().with_selected(Some(0)),
+            input: None,
+        }
+    }
+}
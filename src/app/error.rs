@@ -0,0 +1,113 @@
+use std::{io, path::PathBuf};
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum AppError {
+    #[error("no solutions found under {root}")]
+    NoSolutionsFound { root: String },
+
+    #[error("failed to read solution {}: {source}", path.display())]
+    SlnParse {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("build of {project} failed")]
+    BuildFailed {
+        project: String,
+        stderr: Vec<String>,
+    },
+
+    #[error("dotnet CLI not found on PATH — install the .NET SDK")]
+    DotnetMissing,
+
+    #[error("docker CLI not found on PATH — install Docker")]
+    DockerMissing,
+
+    #[error("invalid config file {}: {message}", path.display())]
+    Config { path: PathBuf, message: String },
+
+    #[error("invalid state file {}: {source}", path.display())]
+    State {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("failed to write log file {}: {source}", path.display())]
+    LogExport {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("no project selected")]
+    NoProjectSelected,
+
+    #[error("group {group} has projects that are not in the solution")]
+    GroupMembersMissing { group: String, members: Vec<String> },
+
+    #[error(
+        "group {group} needs compose services, but there is no compose file next to the solution"
+    )]
+    NoComposeFile { group: String },
+
+    #[error("group {group} needs compose services that {file} does not define")]
+    ComposeServicesMissing {
+        group: String,
+        file: String,
+        services: Vec<String>,
+    },
+
+    #[error("no project {project} in {solution}")]
+    ProjectNotFound { project: String, solution: String },
+
+    #[error("{project} targets {}, pick one with --framework", frameworks.join(", "))]
+    FrameworkNeeded {
+        project: String,
+        frameworks: Vec<String>,
+    },
+
+    #[error("launch profile {profile} of {project} is missing or not runnable with dotnet run")]
+    ProfileNotRunnable { project: String, profile: String },
+
+    #[error("cannot open the editor: {message}")]
+    Editor { message: String },
+
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+impl AppError {
+    /// Extra lines shown under the message in the error popup.
+    pub fn details(&self) -> &[String] {
+        match self {
+            Self::BuildFailed { stderr, .. } => stderr,
+            Self::GroupMembersMissing { members, .. } => members,
+            Self::ComposeServicesMissing { services, .. } => services,
+            _ => &[],
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, AppError>;
+
+/// Maps a failure to spawn `dotnet` to [`AppError::DotnetMissing`] when the binary is absent.
+pub fn dotnet_spawn_error(e: io::Error) -> AppError {
+    if e.kind() == io::ErrorKind::NotFound {
+        AppError::DotnetMissing
+    } else {
+        AppError::Io(e)
+    }
+}
+
+/// Maps a failure to spawn `docker` to [`AppError::DockerMissing`] when the binary is absent.
+pub fn docker_spawn_error(e: io::Error) -> AppError {
+    if e.kind() == io::ErrorKind::NotFound {
+        AppError::DockerMissing
+    } else {
+        AppError::Io(e)
+    }
+}
@@ -0,0 +1,354 @@
+use std::{
+    io::{self, Write},
+    process::{Command, ExitStatus},
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::{
+    diagnostics::{same_diagnostic, Diagnostic, DiagnosticParser, Parsed, Severity},
+    error::{dotnet_spawn_error, Result},
+    headless::exit_code,
+    logs::strip_ansi,
+    process::{Job, OutputLine},
+};
+
+const POLL: Duration = Duration::from_millis(20);
+
+/// One line of `sln-runner run --json-events`, named by its `event` field.
+/// The events of the build come first, then those of the app.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    BuildStarted {
+        project: String,
+        configuration: String,
+    },
+    /// An error or warning, once the lines that continue its message are in.
+    /// The ones MSBuild repeats in its summary are left out.
+    BuildDiagnostic {
+        severity: Severity,
+        // A source file, project file or tool name
+        file: String,
+        line: Option<u32>,
+        column: Option<u32>,
+        code: Option<String>,
+        message: String,
+        // The project file that reported it
+        project: Option<String>,
+    },
+    BuildFinished {
+        success: bool,
+        duration_ms: u64,
+        errors: usize,
+        warnings: usize,
+    },
+    /// The URLs are those of the launch profile, if it has any.
+    ProcessStarted { pid: u32, urls: Vec<String> },
+    /// A line the build or the app wrote, without terminal colors.
+    OutputLine { stream: Stream, text: String },
+    /// A signal counts as 128 and its number, as shells report it.
+    ProcessExited { code: i32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Writes an event to stdout as a line of JSON.
+pub fn emit(event: &Event) -> io::Result<()> {
+    let json = serde_json::to_string(event)?;
+    let mut out = io::stdout().lock();
+    writeln!(out, "{}", json)?;
+    out.flush()
+}
+
+/// Builds with `command`, reporting its output and the diagnostics in it.
+pub fn build(
+    command: &mut Command,
+    project: &str,
+    configuration: &str,
+    interrupted: &AtomicBool,
+) -> Result<ExitStatus> {
+    emit(&Event::BuildStarted {
+        project: project.to_string(),
+        configuration: configuration.to_string(),
+    })?;
+    let started = Instant::now();
+    let mut job = Job::spawn(command).map_err(dotnet_spawn_error)?;
+    let mut diagnostics = BuildDiagnostics::default();
+    let status = follow(&mut job, interrupted, |line| {
+        emit_line(line)?;
+        diagnostics.feed(line)
+    })?;
+    let (errors, warnings) = diagnostics.finish()?;
+    emit(&Event::BuildFinished {
+        success: status.success(),
+        duration_ms: started.elapsed().as_millis() as u64,
+        errors,
+        warnings,
+    })?;
+    Ok(status)
+}
+
+/// Runs the app with `command`, reporting its output and how it exited.
+pub fn run(command: &mut Command, urls: Vec<String>, interrupted: &AtomicBool) -> Result<i32> {
+    let mut job = Job::spawn(command).map_err(dotnet_spawn_error)?;
+    emit(&Event::ProcessStarted {
+        pid: job.id(),
+        urls,
+    })?;
+    let status = follow(&mut job, interrupted, emit_line)?;
+    let code = exit_code(status);
+    emit(&Event::ProcessExited { code })?;
+    Ok(code)
+}
+
+fn emit_line(line: &OutputLine) -> io::Result<()> {
+    emit(&Event::OutputLine {
+        stream: if line.is_stderr {
+            Stream::Stderr
+        } else {
+            Stream::Stdout
+        },
+        text: strip_ansi(&line.text),
+    })
+}
+
+// The job has a process group of its own, so Ctrl+C only reaches it through
+// sln-runner
+fn follow(
+    job: &mut Job,
+    interrupted: &AtomicBool,
+    mut on_line: impl FnMut(&OutputLine) -> io::Result<()>,
+) -> io::Result<ExitStatus> {
+    loop {
+        for line in job.drain() {
+            on_line(&line)?;
+        }
+        if interrupted.swap(false, Ordering::Relaxed) {
+            job.interrupt()?;
+        }
+        if let Some(status) = job.poll_exit()? {
+            return Ok(status);
+        }
+        thread::sleep(POLL);
+    }
+}
+
+/// Picks the diagnostics out of build output and reports each one once.
+#[derive(Default)]
+struct BuildDiagnostics {
+    stdout: DiagnosticParser,
+    stderr: DiagnosticParser,
+    // Held back while its message may continue
+    pending: Option<Diagnostic>,
+    reported: Vec<Diagnostic>,
+}
+
+impl BuildDiagnostics {
+    fn feed(&mut self, line: &OutputLine) -> io::Result<()> {
+        let parser = if line.is_stderr {
+            &mut self.stderr
+        } else {
+            &mut self.stdout
+        };
+        match parser.feed(&line.text) {
+            Some(Parsed::Diagnostic(diagnostic)) => {
+                self.flush()?;
+                let complete = diagnostic.project.is_some();
+                self.pending = Some(diagnostic);
+                if complete {
+                    self.flush()?;
+                }
+            }
+            Some(Parsed::Continuation { text, project }) => {
+                if let Some(pending) = &mut self.pending {
+                    pending.message.push('\n');
+                    pending.message.push_str(&text);
+                    if project.is_some() {
+                        pending.project = project;
+                        self.flush()?;
+                    }
+                }
+            }
+            None => self.flush()?,
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let Some(diagnostic) = self.pending.take() else {
+            return Ok(());
+        };
+        // MSBuild repeats every diagnostic in its summary at the end
+        if self
+            .reported
+            .iter()
+            .any(|reported| same_diagnostic(reported, &diagnostic))
+        {
+            return Ok(());
+        }
+        emit(&Event::BuildDiagnostic {
+            severity: diagnostic.severity,
+            file: diagnostic.origin.clone(),
+            line: diagnostic.line,
+            column: diagnostic.column,
+            code: diagnostic.code.clone(),
+            message: diagnostic.message.clone(),
+            project: diagnostic.project.clone(),
+        })?;
+        self.reported.push(diagnostic);
+        Ok(())
+    }
+
+    /// Reports the last diagnostic, and returns the errors and warnings.
+    fn finish(&mut self) -> io::Result<(usize, usize)> {
+        self.flush()?;
+        let count = |severity| {
+            self.reported
+                .iter()
+                .filter(|d| d.severity == severity)
+                .count()
+        };
+        Ok((count(Severity::Error), count(Severity::Warning)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    // The event serializes to exactly the JSON of the documented schema, and
+    // reads back the same
+    fn round_trip(event: Event, expected: Value) {
+        let line = serde_json::to_string(&event).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&line).unwrap(), expected);
+        assert_eq!(serde_json::from_str::<Event>(&line).unwrap(), event);
+    }
+
+    #[test]
+    fn build_started() {
+        round_trip(
+            Event::BuildStarted {
+                project: "Web".into(),
+                configuration: "Debug".into(),
+            },
+            json!({"event": "build_started", "project": "Web", "configuration": "Debug"}),
+        );
+    }
+
+    #[test]
+    fn build_diagnostic() {
+        round_trip(
+            Event::BuildDiagnostic {
+                severity: Severity::Warning,
+                file: "/src/Api/Program.cs".into(),
+                line: Some(12),
+                column: Some(5),
+                code: Some("CS0168".into()),
+                message: "The variable 'x' is declared but never used".into(),
+                project: Some("/src/Api/Api.csproj".into()),
+            },
+            json!({
+                "event": "build_diagnostic",
+                "severity": "warning",
+                "file": "/src/Api/Program.cs",
+                "line": 12,
+                "column": 5,
+                "code": "CS0168",
+                "message": "The variable 'x' is declared but never used",
+                "project": "/src/Api/Api.csproj",
+            }),
+        );
+        round_trip(
+            Event::BuildDiagnostic {
+                severity: Severity::Error,
+                file: "CSC".into(),
+                line: None,
+                column: None,
+                code: None,
+                message: "error without a location".into(),
+                project: None,
+            },
+            json!({
+                "event": "build_diagnostic",
+                "severity": "error",
+                "file": "CSC",
+                "line": null,
+                "column": null,
+                "code": null,
+                "message": "error without a location",
+                "project": null,
+            }),
+        );
+    }
+
+    #[test]
+    fn build_finished() {
+        round_trip(
+            Event::BuildFinished {
+                success: false,
+                duration_ms: 1234,
+                errors: 1,
+                warnings: 2,
+            },
+            json!({
+                "event": "build_finished",
+                "success": false,
+                "duration_ms": 1234,
+                "errors": 1,
+                "warnings": 2,
+            }),
+        );
+    }
+
+    #[test]
+    fn process_started() {
+        round_trip(
+            Event::ProcessStarted {
+                pid: 4242,
+                urls: vec!["http://localhost:5080".into()],
+            },
+            json!({"event": "process_started", "pid": 4242, "urls": ["http://localhost:5080"]}),
+        );
+    }
+
+    #[test]
+    fn output_line() {
+        round_trip(
+            Event::OutputLine {
+                stream: Stream::Stdout,
+                text: "info: Now listening on: http://[::]:5000".into(),
+            },
+            json!({
+                "event": "output_line",
+                "stream": "stdout",
+                "text": "info: Now listening on: http://[::]:5000",
+            }),
+        );
+        round_trip(
+            Event::OutputLine {
+                stream: Stream::Stderr,
+                text: "oops".into(),
+            },
+            json!({"event": "output_line", "stream": "stderr", "text": "oops"}),
+        );
+    }
+
+    #[test]
+    fn process_exited() {
+        round_trip(
+            Event::ProcessExited { code: 130 },
+            json!({"event": "process_exited", "code": 130}),
+        );
+    }
+}
@@ -0,0 +1,243 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use crate::app::testing::{escape_filter, Outcome, TestResult};
+
+/// A test from `dotnet test --list-tests`. The cases of a theory share one.
+#[derive(Debug, Clone)]
+pub struct ListedTest {
+    pub namespace: String,
+    pub class: String,
+    pub method: String,
+    // `Namespace.Class.Method`, for `--filter`
+    pub full_name: String,
+    pub cases: usize,
+}
+
+/// The test names `dotnet test --list-tests` printed after its heading.
+pub fn parse_test_list(lines: &[String]) -> Vec<ListedTest> {
+    let mut tests: Vec<ListedTest> = Vec::new();
+    let names = lines
+        .iter()
+        .skip_while(|line| !line.contains("The following Tests are available"))
+        .skip(1)
+        .filter(|line| line.starts_with(char::is_whitespace) && !line.trim().is_empty());
+    for name in names {
+        // `Ns.Class.Method(a: 1)` is one case of `Ns.Class.Method`
+        let full_name = name.trim().split('(').next().unwrap_or_default().trim();
+        if let Some(test) = tests.iter_mut().find(|t| t.full_name == full_name) {
+            test.cases += 1;
+            continue;
+        }
+        let mut parts = full_name.rsplitn(3, '.');
+        let method = parts.next().unwrap_or_default();
+        let class = parts.next().unwrap_or_default();
+        let namespace = parts.next().unwrap_or_default();
+        tests.push(ListedTest {
+            namespace: namespace.to_string(),
+            class: class.to_string(),
+            method: method.to_string(),
+            full_name: full_name.to_string(),
+            cases: 1,
+        });
+    }
+    tests.sort_by(|a, b| a.full_name.cmp(&b.full_name));
+    tests
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    Namespace(String),
+    // `Namespace.Class`
+    Class(String),
+    // Index into the tests
+    Test(usize),
+}
+
+pub struct Row {
+    pub node: Node,
+    pub depth: usize,
+    pub label: String,
+    pub collapsed: bool,
+}
+
+/// How the cases of a test went in the latest run that included it.
+#[derive(Debug, Clone, Copy)]
+pub struct TestOutcome {
+    pub outcome: Outcome,
+    pub duration: Duration,
+}
+
+/// The tests of a project as a tree of namespaces, classes and tests.
+pub struct TestTree {
+    pub tests: Vec<ListedTest>,
+    // Namespaces and classes folded away
+    collapsed: HashSet<String>,
+    // The latest outcome per test, by full name
+    outcomes: HashMap<String, TestOutcome>,
+}
+
+impl TestTree {
+    pub fn new(tests: Vec<ListedTest>) -> Self {
+        Self {
+            tests,
+            collapsed: HashSet::new(),
+            outcomes: HashMap::new(),
+        }
+    }
+
+    /// The rows to show, with every group opened while `query` narrows the
+    /// tests down.
+    pub fn rows(&self, query: &str) -> Vec<Row> {
+        let query = query.to_lowercase();
+        let mut rows = Vec::new();
+        let mut namespace = None;
+        let mut class = None;
+        for (index, test) in self.tests.iter().enumerate() {
+            if !query.is_empty() && !test.full_name.to_lowercase().contains(&query) {
+                continue;
+            }
+            let filtering = !query.is_empty();
+            if namespace != Some(&test.namespace) {
+                namespace = Some(&test.namespace);
+                class = None;
+                if !test.namespace.is_empty() {
+                    rows.push(Row {
+                        node: Node::Namespace(test.namespace.clone()),
+                        depth: 0,
+                        label: test.namespace.clone(),
+                        collapsed: !filtering && self.collapsed.contains(&test.namespace),
+                    });
+                }
+            }
+            if !filtering && self.collapsed.contains(&test.namespace) {
+                continue;
+            }
+            let namespace_depth = usize::from(!test.namespace.is_empty());
+            let class_key = class_key(test);
+            // Names without a class, like NUnit lists, sit right under the namespace
+            if test.class.is_empty() {
+                rows.push(Row {
+                    node: Node::Test(index),
+                    depth: namespace_depth,
+                    label: test.method.clone(),
+                    collapsed: false,
+                });
+                continue;
+            }
+            if class.as_ref() != Some(&class_key) {
+                rows.push(Row {
+                    node: Node::Class(class_key.clone()),
+                    depth: namespace_depth,
+                    label: test.class.clone(),
+                    collapsed: !filtering && self.collapsed.contains(&class_key),
+                });
+                class = Some(class_key.clone());
+            }
+            if !filtering && self.collapsed.contains(&class_key) {
+                continue;
+            }
+            let label = match test.cases {
+                1 => test.method.clone(),
+                cases => format!("{} ({} cases)", test.method, cases),
+            };
+            rows.push(Row {
+                node: Node::Test(index),
+                depth: namespace_depth + 1,
+                label,
+                collapsed: false,
+            });
+        }
+        rows
+    }
+
+    /// Swaps in a fresh list of tests, keeping what was folded and the outcomes.
+    pub fn replace_tests(&mut self, tests: Vec<ListedTest>) {
+        self.tests = tests;
+    }
+
+    /// Folds a namespace or class away, or opens it again.
+    pub fn toggle(&mut self, node: &Node) {
+        let key = match node {
+            Node::Namespace(key) | Node::Class(key) => key,
+            Node::Test(_) => return,
+        };
+        if !self.collapsed.remove(key) {
+            self.collapsed.insert(key.clone());
+        }
+    }
+
+    /// The `--filter` that runs what the node holds.
+    pub fn filter(&self, node: &Node) -> String {
+        match node {
+            Node::Namespace(key) | Node::Class(key) => {
+                format!("FullyQualifiedName~{}.", escape_filter(key))
+            }
+            Node::Test(index) => format!(
+                "FullyQualifiedName={}",
+                escape_filter(&self.tests[*index].full_name)
+            ),
+        }
+    }
+
+    /// What the node is called in messages.
+    pub fn name(&self, node: &Node) -> String {
+        match node {
+            Node::Namespace(key) | Node::Class(key) => key.clone(),
+            Node::Test(index) => self.tests[*index].full_name.clone(),
+        }
+    }
+
+    /// Takes in the results of a run, adding up the cases of each test.
+    pub fn record(&mut self, results: &[TestResult]) {
+        let mut outcomes: HashMap<String, TestOutcome> = HashMap::new();
+        for result in results {
+            let outcome = outcomes
+                .entry(result.full_name.clone())
+                .or_insert(TestOutcome {
+                    outcome: result.outcome,
+                    duration: Duration::ZERO,
+                });
+            outcome.outcome = outcome.outcome.max(result.outcome);
+            outcome.duration += result.duration.unwrap_or_default();
+        }
+        self.outcomes.extend(outcomes);
+    }
+
+    /// The latest outcome of a test, or of a group: failed when any of its
+    /// tests did, passed once all of them ran and none failed.
+    pub fn outcome(&self, node: &Node) -> Option<TestOutcome> {
+        let tests: Vec<&ListedTest> = match node {
+            Node::Test(index) => return self.outcomes.get(&self.tests[*index].full_name).copied(),
+            Node::Namespace(key) => self.tests.iter().filter(|t| &t.namespace == key).collect(),
+            Node::Class(key) => self.tests.iter().filter(|t| &class_key(t) == key).collect(),
+        };
+        let outcomes: Vec<&TestOutcome> = tests
+            .iter()
+            .map(|test| self.outcomes.get(&test.full_name))
+            .collect::<Option<_>>()
+            .or_else(|| {
+                // Not all of them ran, which only says something when one failed
+                let ran: Vec<_> = tests
+                    .iter()
+                    .filter_map(|test| self.outcomes.get(&test.full_name))
+                    .collect();
+                ran.iter()
+                    .any(|o| o.outcome == Outcome::Failed)
+                    .then_some(ran)
+            })?;
+        Some(TestOutcome {
+            outcome: outcomes.iter().map(|o| o.outcome).max()?,
+            duration: outcomes.iter().map(|o| o.duration).sum(),
+        })
+    }
+}
+
+fn class_key(test: &ListedTest) -> String {
+    match test.namespace.as_str() {
+        "" => test.class.clone(),
+        namespace => format!("{}.{}", namespace, test.class),
+    }
+}
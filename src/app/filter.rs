@@ -0,0 +1,53 @@
+pub struct FuzzyMatch {
+    pub score: i32,
+    /// Character indices in the candidate that matched the query.
+    pub positions: Vec<usize>,
+}
+
+/// Case-insensitive subsequence match. Consecutive matches and matches at word
+/// boundaries score higher; gaps between matched characters cost a little.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<FuzzyMatch> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if query.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut positions = Vec::with_capacity(query.len());
+    let mut score = 0;
+    let mut next = 0;
+
+    for (i, c) in chars.iter().enumerate() {
+        if next == query.len() {
+            break;
+        }
+        if !c.to_lowercase().eq(std::iter::once(query[next])) {
+            continue;
+        }
+
+        score += 1;
+        match positions.last() {
+            Some(&last) if last + 1 == i => score += 5,
+            Some(&last) => score -= ((i - last - 1) as i32).min(3),
+            None => score -= (i as i32).min(3),
+        }
+        let at_boundary = i == 0
+            || matches!(chars[i - 1], '.' | '/' | '\\' | '_' | '-' | ' ')
+            || (chars[i - 1].is_lowercase() && c.is_uppercase());
+        if at_boundary {
+            score += 8;
+        }
+
+        positions.push(i);
+        next += 1;
+    }
+
+    (next == query.len()).then_some(FuzzyMatch { score, positions })
+}
@@ -0,0 +1,129 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+// How long a status is shown before asking git again
+const FRESH_FOR: Duration = Duration::from_secs(10);
+
+/// The checkout a solution is in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitStatus {
+    // The branch, or `detached` and the commit
+    pub branch: String,
+    // Unknown when git itself could not be run
+    pub dirty: Option<bool>,
+}
+
+// The top of the working tree the directory is in, and its git directory,
+// which a worktree or submodule keeps elsewhere and names in a `.git` file
+fn find_repo(dir: &Path) -> Option<(PathBuf, PathBuf)> {
+    for dir in dir.ancestors() {
+        let dot_git = dir.join(".git");
+        if dot_git.is_dir() {
+            return Some((dir.to_path_buf(), dot_git));
+        }
+        if dot_git.is_file() {
+            let contents = fs::read_to_string(&dot_git).ok()?;
+            let git_dir = contents.trim().strip_prefix("gitdir:")?.trim();
+            return Some((dir.to_path_buf(), dir.join(git_dir)));
+        }
+    }
+    None
+}
+
+// `ref: refs/heads/main`, or the commit itself when detached
+fn read_branch(git_dir: &Path) -> Option<String> {
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let head = head.trim();
+    Some(match head.strip_prefix("ref:") {
+        Some(reference) => {
+            let reference = reference.trim();
+            reference
+                .strip_prefix("refs/heads/")
+                .unwrap_or(reference)
+                .to_string()
+        }
+        None => format!("detached {}", &head[..head.len().min(7)]),
+    })
+}
+
+fn is_dirty(work_tree: &Path) -> Option<bool> {
+    let output = Command::new("git")
+        .args(["status", "--porcelain"])
+        .current_dir(work_tree)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output.status.success().then_some(!output.stdout.is_empty())
+}
+
+/// Reads the git status of solution directories on a worker thread, once per
+/// repository however many solutions it holds, and keeps it for a few
+/// seconds, so drawing the list never waits on git.
+pub struct GitCache {
+    requests: Sender<PathBuf>,
+    rx: Receiver<(PathBuf, Option<GitStatus>)>,
+    statuses: HashMap<PathBuf, (Instant, Option<GitStatus>)>,
+    pending: HashSet<PathBuf>,
+}
+
+impl GitCache {
+    pub fn new() -> Self {
+        let (requests, worker_requests) = mpsc::channel::<PathBuf>();
+        let (tx, rx) = mpsc::channel();
+        // Ends once the cache is dropped and the requests run out
+        thread::spawn(move || {
+            let mut repos: HashMap<PathBuf, (Instant, GitStatus)> = HashMap::new();
+            for dir in worker_requests {
+                let status = find_repo(&dir).and_then(|(work_tree, git_dir)| {
+                    if let Some((read, status)) = repos.get(&work_tree) {
+                        if read.elapsed() < FRESH_FOR {
+                            return Some(status.clone());
+                        }
+                    }
+                    let status = GitStatus {
+                        branch: read_branch(&git_dir)?,
+                        dirty: is_dirty(&work_tree),
+                    };
+                    repos.insert(work_tree, (Instant::now(), status.clone()));
+                    Some(status)
+                });
+                if tx.send((dir, status)).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            requests,
+            rx,
+            statuses: HashMap::new(),
+            pending: HashSet::new(),
+        }
+    }
+
+    /// The status of the checkout the directory is in as last read, asking
+    /// for it again when it is missing or getting old.
+    pub fn get(&mut self, dir: &Path) -> Option<&GitStatus> {
+        for (dir, status) in self.rx.try_iter() {
+            self.pending.remove(&dir);
+            self.statuses.insert(dir, (Instant::now(), status));
+        }
+        let stale = self
+            .statuses
+            .get(dir)
+            .is_none_or(|(read, _)| read.elapsed() >= FRESH_FOR);
+        if stale && self.pending.insert(dir.to_path_buf()) {
+            let _ = self.requests.send(dir.to_path_buf());
+        }
+        self.statuses
+            .get(dir)
+            .and_then(|(_, status)| status.as_ref())
+    }
+}
@@ -0,0 +1,166 @@
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
+    fs,
+    path::{Component, Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::SystemTime,
+};
+
+use crate::app::util::{attribute, elements, resolve_sln_relative};
+
+/// The `<ProjectReference>`s between the projects of a solution, by their
+/// index in the solution's project list.
+pub struct ProjectGraph {
+    pub solution: String,
+    pub references: Vec<Vec<usize>>,
+    pub referenced_by: Vec<Vec<usize>>,
+    // References to project files the solution doesn't list, by file name
+    pub outside: Vec<Vec<String>>,
+    // Projects that end up referencing themselves
+    pub cyclic: HashSet<usize>,
+    // When each project file last changed, to tell when to read them again
+    stamp: Vec<Option<SystemTime>>,
+}
+
+fn stamp(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| fs::metadata(path).and_then(|meta| meta.modified()).ok())
+        .collect()
+}
+
+// `a/b/../c` is `a/c`, without asking the file system, which may not have it
+fn normalize(path: &Path) -> PathBuf {
+    let mut normal = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normal.pop();
+            }
+            component => normal.push(component),
+        }
+    }
+    normal
+}
+
+impl ProjectGraph {
+    /// Reads the references of every project file in `paths`.
+    pub fn read(solution: String, paths: &[PathBuf]) -> Self {
+        let stamp = stamp(paths);
+        let index: HashMap<PathBuf, usize> = paths
+            .iter()
+            .enumerate()
+            .map(|(i, path)| (normalize(path), i))
+            .collect();
+
+        let mut references = vec![Vec::new(); paths.len()];
+        let mut referenced_by = vec![Vec::new(); paths.len()];
+        let mut outside = vec![Vec::new(); paths.len()];
+        for (i, path) in paths.iter().enumerate() {
+            let contents = fs::read_to_string(path).unwrap_or_default();
+            let project_dir = path.parent().unwrap_or(Path::new(""));
+            for (tag, _) in elements(&contents, "ProjectReference") {
+                let Some(include) = attribute(tag, "Include") else {
+                    continue;
+                };
+                let target = normalize(&resolve_sln_relative(project_dir, include));
+                match index.get(&target) {
+                    Some(&j) if !references[i].contains(&j) => {
+                        references[i].push(j);
+                        referenced_by[j].push(i);
+                    }
+                    Some(_) => {}
+                    None => outside[i].push(
+                        include
+                            .rsplit(['\\', '/'])
+                            .next()
+                            .unwrap_or(include)
+                            .to_string(),
+                    ),
+                }
+            }
+        }
+
+        let mut graph = Self {
+            solution,
+            references,
+            referenced_by,
+            outside,
+            cyclic: HashSet::new(),
+            stamp,
+        };
+        graph.cyclic = (0..paths.len())
+            .filter(|&i| graph.cycle(i).is_some())
+            .collect();
+        graph
+    }
+
+    /// Whether the graph was read from the project files as they are now.
+    pub fn is_current(&self, solution: &str, paths: &[PathBuf]) -> bool {
+        self.solution == solution && self.stamp == stamp(paths)
+    }
+
+    /// The shortest way from the project back to itself through its
+    /// references, starting and ending with it, if there is one.
+    pub fn cycle(&self, start: usize) -> Option<Vec<usize>> {
+        let mut came_from: HashMap<usize, usize> = HashMap::new();
+        let mut queue: VecDeque<usize> = VecDeque::from([start]);
+        while let Some(current) = queue.pop_front() {
+            for &next in &self.references[current] {
+                if next == start {
+                    let mut path = vec![start, current];
+                    let mut at = current;
+                    while let Some(&previous) = came_from.get(&at) {
+                        path.push(previous);
+                        at = previous;
+                    }
+                    path.reverse();
+                    return Some(path);
+                }
+                if let Entry::Vacant(entry) = came_from.entry(next) {
+                    entry.insert(current);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Every project that references the project, directly or through others.
+    pub fn dependents(&self, project: usize) -> Vec<usize> {
+        let mut seen: HashSet<usize> = HashSet::from([project]);
+        let mut queue: VecDeque<usize> = VecDeque::from([project]);
+        let mut dependents = Vec::new();
+        while let Some(current) = queue.pop_front() {
+            for &next in &self.referenced_by[current] {
+                if seen.insert(next) {
+                    dependents.push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+        dependents
+    }
+}
+
+/// Reads a solution's project graph on a worker thread.
+pub struct GraphLoad {
+    rx: Receiver<ProjectGraph>,
+}
+
+impl GraphLoad {
+    pub fn start(solution: String, paths: Vec<PathBuf>) -> Self {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = tx.send(ProjectGraph::read(solution, &paths));
+        });
+        Self { rx }
+    }
+
+    /// The graph once it is read, without blocking.
+    pub fn poll(&self) -> Option<ProjectGraph> {
+        self.rx.try_recv().ok()
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use ratatui::style::Color;
+use regex::Regex;
+
+use crate::app::{
+    config::GroupMember,
+    ready::{Probe, Readiness},
+    util::Project,
+};
+
+// How long a member may take to become ready when the config sets no timeout
+const DEFAULT_READY_TIMEOUT: u64 = 60;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MemberState {
+    /// Waiting for its dependencies, or for its turn to start.
+    Waiting,
+    /// Launched, but not ready yet.
+    Starting,
+    Ready,
+    Failed(String),
+    /// Not started because the named dependency failed.
+    DependencyFailed(String),
+}
+
+impl MemberState {
+    pub fn label(&self) -> String {
+        match self {
+            Self::Waiting => "waiting".to_string(),
+            Self::Starting => "starting".to_string(),
+            Self::Ready => "ready".to_string(),
+            Self::Failed(reason) => format!("failed: {}", reason),
+            Self::DependencyFailed(dependency) => format!("dependency {} failed", dependency),
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            Self::Waiting => Color::DarkGray,
+            Self::Starting => Color::Yellow,
+            Self::Ready => Color::Green,
+            Self::Failed(_) | Self::DependencyFailed(_) => Color::Red,
+        }
+    }
+
+    fn failed(&self) -> bool {
+        matches!(self, Self::Failed(_) | Self::DependencyFailed(_))
+    }
+}
+
+/// One project of a group being started.
+pub struct MemberRun {
+    pub name: String,
+    // None when it is not in the solution
+    pub project: Option<Project>,
+    // Set once launched, to find its process
+    pub project_dir: Option<PathBuf>,
+    // Indices into the group's members
+    pub depends_on: Vec<usize>,
+    pub ready: Option<Readiness>,
+    pub log_pattern: Option<Regex>,
+    pub timeout: Duration,
+    pub auto_restart: bool,
+    pub state: MemberState,
+    // When the member was launched, and when its process appeared
+    pub launched: Option<Instant>,
+    pub running_since: Option<Instant>,
+    // How many of the lines the process ever output were searched for the
+    // log pattern
+    pub seen: usize,
+    pub probe: Option<Probe>,
+}
+
+/// A run group started member by member in dependency order.
+pub struct GroupRun {
+    pub name: String,
+    // Sorted so that dependencies come before their dependents
+    pub members: Vec<MemberRun>,
+    pub finished: bool,
+}
+
+impl GroupRun {
+    /// Orders the members so each comes after the members it depends on,
+    /// rejecting unknown dependencies, cycles and invalid conditions.
+    pub fn new(name: &str, members: Vec<(GroupMember, Option<Project>)>) -> Result<Self, String> {
+        let key = |path: &str| path.replace('\\', "/");
+        let keys: Vec<String> = members.iter().map(|(m, _)| key(&m.project)).collect();
+
+        let mut depends_on = Vec::new();
+        for (member, _) in &members {
+            let mut indices = Vec::new();
+            for dependency in &member.depends_on {
+                let Some(index) = keys.iter().position(|k| *k == key(dependency)) else {
+                    return Err(format!(
+                        "{} depends on {}, which is not in group {}",
+                        member.project, dependency, name
+                    ));
+                };
+                indices.push(index);
+            }
+            depends_on.push(indices);
+            if let Some(ready) = &member.ready {
+                ready
+                    .validate()
+                    .map_err(|e| format!("ready condition of {}: {}", member.project, e))?;
+            }
+        }
+
+        // Kahn's algorithm, keeping the config's order among members that are
+        // free to start
+        let mut order = Vec::new();
+        let mut placed = vec![false; members.len()];
+        while order.len() < members.len() {
+            let next = (0..members.len()).find(|&i| {
+                !placed[i] && depends_on[i].iter().all(|&dependency| placed[dependency])
+            });
+            let Some(next) = next else {
+                let cycle: Vec<&str> = (0..members.len())
+                    .filter(|&i| !placed[i])
+                    .map(|i| members[i].0.project.as_str())
+                    .collect();
+                return Err(format!(
+                    "group {} has a dependency cycle between {}",
+                    name,
+                    cycle.join(", ")
+                ));
+            };
+            placed[next] = true;
+            order.push(next);
+        }
+
+        let position = |index: usize| order.iter().position(|&i| i == index).unwrap_or(index);
+        let mut slots: Vec<Option<(GroupMember, Option<Project>)>> =
+            members.into_iter().map(Some).collect();
+        let members = order
+            .iter()
+            .filter_map(|&index| {
+                let (member, project) = slots[index].take()?;
+                let state = match project {
+                    Some(_) => MemberState::Waiting,
+                    None => MemberState::Failed("not in the solution".to_string()),
+                };
+                Some(MemberRun {
+                    name: project
+                        .as_ref()
+                        .map_or_else(|| project_name(&member.project), |p| p.name.clone()),
+                    project,
+                    project_dir: None,
+                    depends_on: depends_on[index].iter().map(|&d| position(d)).collect(),
+                    log_pattern: member.ready.as_ref().and_then(Readiness::log_regex),
+                    ready: member.ready,
+                    timeout: Duration::from_secs(member.timeout.unwrap_or(DEFAULT_READY_TIMEOUT)),
+                    auto_restart: member.auto_restart,
+                    state,
+                    launched: None,
+                    running_since: None,
+                    seen: 0,
+                    probe: None,
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            name: name.to_string(),
+            members,
+            finished: false,
+        })
+    }
+
+    /// Marks the waiting members whose dependencies failed, returning their
+    /// indices. One pass suffices, since dependencies come first.
+    pub fn cascade_failures(&mut self) -> Vec<usize> {
+        let mut cascaded = Vec::new();
+        for index in 0..self.members.len() {
+            if self.members[index].state != MemberState::Waiting {
+                continue;
+            }
+            let failed = self.members[index]
+                .depends_on
+                .iter()
+                .find(|&&dependency| self.members[dependency].state.failed());
+            if let Some(&dependency) = failed {
+                let name = self.members[dependency].name.clone();
+                self.members[index].state = MemberState::DependencyFailed(name);
+                cascaded.push(index);
+            }
+        }
+        cascaded
+    }
+
+    /// The first waiting member whose dependencies are all ready.
+    pub fn next_to_start(&self) -> Option<usize> {
+        self.members.iter().position(|member| {
+            member.state == MemberState::Waiting
+                && member
+                    .depends_on
+                    .iter()
+                    .all(|&dependency| self.members[dependency].state == MemberState::Ready)
+        })
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.members
+            .iter()
+            .all(|member| !matches!(member.state, MemberState::Waiting | MemberState::Starting))
+    }
+}
+
+/// The project name from a path like `Api/Api.csproj`.
+fn project_name(path: &str) -> String {
+    let file = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    file.rsplit_once('.')
+        .map_or(file, |(name, _)| name)
+        .to_string()
+}
@@ -0,0 +1,268 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    process::ExitStatus,
+    sync::{atomic::AtomicBool, Arc},
+};
+
+use serde_json::json;
+
+use crate::app::{
+    config::{BuildStrategy, Config},
+    env,
+    error::{dotnet_spawn_error, AppError, Result},
+    events,
+    launch::read_launch_profiles,
+    process::{build_command, run_command, RunMode},
+    util::{find_sln_files, parse_sln_for_projects, target_frameworks, IgnoreList, Project},
+};
+
+/// What `sln-runner run` runs, and how.
+pub struct RunOptions {
+    pub sln: PathBuf,
+    // A project name, or the path of its project file
+    pub project: String,
+    pub configuration: Option<String>,
+    pub profile: Option<String>,
+    pub framework: Option<String>,
+    pub args: Vec<String>,
+    // Report as JSON events on stdout instead of passing the output through
+    pub json_events: bool,
+}
+
+fn load_config() -> Result<Config> {
+    match Config::path() {
+        Some(path) => Config::load_or_create(&path),
+        None => Ok(Config::default()),
+    }
+}
+
+/// Prints the solutions under the root, or the configured roots, or the
+/// current directory, one path per line or as JSON.
+pub fn list_solutions(root: Option<PathBuf>, json: bool) -> Result<()> {
+    let config = load_config()?;
+    let roots = match root {
+        Some(root) => vec![root],
+        None if !config.roots.is_empty() => config.roots.clone(),
+        None => vec![std::env::current_dir()?],
+    };
+    let mut solutions = Vec::new();
+    find_sln_files(
+        &roots,
+        &config.exclude_dirs,
+        &IgnoreList::new(&config.ignore),
+        &AtomicBool::new(false),
+        |sln| solutions.push(sln.path),
+    );
+    solutions.sort();
+
+    if json {
+        let solutions: Vec<_> = solutions
+            .iter()
+            .map(|path| json!({ "name": file_name(Path::new(path)), "path": path }))
+            .collect();
+        println!("{}", serde_json::Value::Array(solutions));
+    } else {
+        for path in solutions {
+            println!("{}", path);
+        }
+    }
+    Ok(())
+}
+
+/// Prints the projects of a solution, aligned in columns or as JSON.
+pub fn list_projects(sln: &Path, json: bool) -> Result<()> {
+    let projects = read_projects(sln)?;
+    if json {
+        let projects: Vec<_> = projects
+            .iter()
+            .map(|project| {
+                json!({
+                    "name": project.name,
+                    "path": project.path,
+                    "relative_path": project.rel_path,
+                    "language": project.kind.label(),
+                    "output": project.output.label(),
+                    "frameworks": project.frameworks,
+                    "missing": project.missing,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::Value::Array(projects));
+        return Ok(());
+    }
+
+    let width = projects.iter().map(|p| p.name.chars().count()).max();
+    for project in &projects {
+        println!(
+            "{:<width$}  {:<7}  {}",
+            project.name,
+            project.output.label(),
+            project.rel_path,
+            width = width.unwrap_or(0)
+        );
+    }
+    Ok(())
+}
+
+fn read_projects(sln: &Path) -> Result<Vec<Project>> {
+    let sln = &std::path::absolute(sln)?;
+    let (projects, skipped) =
+        parse_sln_for_projects(&sln.to_string_lossy()).map_err(|source| AppError::SlnParse {
+            path: sln.to_path_buf(),
+            source,
+        })?;
+    for line in skipped {
+        eprintln!(
+            "warning: skipped a project line of {}: {}",
+            sln.display(),
+            line
+        );
+    }
+    Ok(projects)
+}
+
+/// Builds and runs a project the way Enter does in the TUI, with the output
+/// going straight to the terminal. Returns the exit code to exit with: the
+/// build's when it fails, otherwise the app's.
+pub fn run_project(options: RunOptions) -> Result<i32> {
+    let config = load_config()?;
+    let projects = read_projects(&options.sln)?;
+    let project =
+        find_project(&projects, &options.project).ok_or_else(|| AppError::ProjectNotFound {
+            project: options.project.clone(),
+            solution: options.sln.display().to_string(),
+        })?;
+    let project_dir = project.path.parent().unwrap_or(Path::new("."));
+
+    let frameworks = target_frameworks(&project.path);
+    let framework = match options.framework {
+        Some(framework) => Some(framework),
+        None if frameworks.len() > 1 => {
+            return Err(AppError::FrameworkNeeded {
+                project: project.name.clone(),
+                frameworks,
+            })
+        }
+        None => None,
+    };
+
+    // Without one, dotnet run picks the first profile itself
+    let profile = match &options.profile {
+        Some(name) => Some(
+            read_launch_profiles(project_dir)
+                .into_iter()
+                .find(|profile| &profile.name == name && profile.is_supported())
+                .ok_or_else(|| AppError::ProfileNotRunnable {
+                    project: project.name.clone(),
+                    profile: name.clone(),
+                })?,
+        ),
+        None => None,
+    };
+    let profile_vars = profile
+        .as_ref()
+        .map(|profile| profile.environment_variables.clone())
+        .unwrap_or_default();
+    let vars = env::resolve(&env::merge(
+        &profile_vars,
+        &config.env.get(&project.name).cloned().unwrap_or_default(),
+        &BTreeMap::new(),
+    ));
+
+    let solution = file_name(&options.sln);
+    let mut build_args: Vec<String> = [solution.as_str(), project.name.as_str()]
+        .into_iter()
+        .filter_map(|key| config.build_args.get(key))
+        .flatten()
+        .cloned()
+        .collect();
+    if let Some(framework) = &framework {
+        build_args.extend(["--framework".to_string(), framework.clone()]);
+    }
+    let configuration = options.configuration.as_deref().unwrap_or("Debug");
+
+    // Ctrl+C reaches dotnet too, which gets to shut the app down and say how
+    // it went; only then does sln-runner exit, with its code
+    let interrupted = Arc::new(AtomicBool::new(false));
+    #[cfg(unix)]
+    signal_hook::flag::register(signal_hook::consts::SIGINT, interrupted.clone())?;
+
+    // Events build separately, so that those of the build come first
+    let mode = match config.build {
+        BuildStrategy::Separate => RunMode::NoBuild,
+        BuildStrategy::Run if options.json_events => RunMode::NoBuild,
+        BuildStrategy::Run => RunMode::Run,
+    };
+    if mode == RunMode::NoBuild {
+        eprintln!("Building {} ({})", project.name, configuration);
+        let mut command = build_command(&project.path, configuration, &build_args);
+        let status = if options.json_events {
+            events::build(&mut command, &project.name, configuration, &interrupted)?
+        } else {
+            command.status().map_err(dotnet_spawn_error)?
+        };
+        if !status.success() {
+            eprintln!("error: build of {} failed", project.name);
+            return Ok(exit_code(status));
+        }
+    }
+
+    eprintln!("Running {}", project.name);
+    let mut command = run_command(
+        project_dir,
+        profile.as_ref().map(|profile| profile.name.as_str()),
+        configuration,
+        mode,
+        &build_args,
+        &options.args,
+        &vars,
+    );
+    if options.json_events {
+        // The profile dotnet run picks when none is given
+        let profile = profile.or_else(|| {
+            read_launch_profiles(project_dir)
+                .into_iter()
+                .find(|profile| profile.is_supported())
+        });
+        let urls = profile
+            .and_then(|profile| profile.application_url)
+            .map(|urls| urls.split(';').map(|url| url.trim().to_string()).collect())
+            .unwrap_or_default();
+        return events::run(&mut command, urls, &interrupted);
+    }
+    let mut child = command.spawn().map_err(dotnet_spawn_error)?;
+    Ok(exit_code(child.wait()?))
+}
+
+// By name, ignoring case, by its path in the solution, or by the path of the
+// project file
+fn find_project<'a>(projects: &'a [Project], wanted: &str) -> Option<&'a Project> {
+    let slashes = |path: &str| path.replace('\\', "/");
+    let wanted_file = Path::new(wanted).canonicalize().ok();
+    projects.iter().find(|project| {
+        project.name.eq_ignore_ascii_case(wanted)
+            || slashes(&project.rel_path) == slashes(wanted)
+            || wanted_file
+                .as_ref()
+                .is_some_and(|file| project.path.canonicalize().ok().as_ref() == Some(file))
+    })
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+// A signal counts as 128 and its number, as shells report it
+pub fn exit_code(status: ExitStatus) -> i32 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    status.code().unwrap_or(1)
+}
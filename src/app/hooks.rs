@@ -0,0 +1,118 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::app::{process::Job, util::shell_command};
+
+/// When a hook runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HookStage {
+    /// Before the project builds, by itself or as part of `dotnet run`.
+    PreBuild,
+    /// Before the project starts.
+    PreRun,
+    /// Alongside the project once it started, stopped with it.
+    PostStart,
+    /// After the project stopped.
+    OnStop,
+}
+
+impl HookStage {
+    pub const ALL: [Self; 4] = [Self::PreBuild, Self::PreRun, Self::PostStart, Self::OnStop];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::PreBuild => "pre_build",
+            Self::PreRun => "pre_run",
+            Self::PostStart => "post_start",
+            Self::OnStop => "on_stop",
+        }
+    }
+}
+
+/// The commands a project or group runs around its runs, from `[hooks]` or
+/// `[group_hooks]` in the config.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Hooks {
+    pub pre_build: Option<String>,
+    pub pre_run: Option<String>,
+    pub post_start: Option<String>,
+    pub on_stop: Option<String>,
+}
+
+impl Hooks {
+    pub fn command(&self, stage: HookStage) -> Option<&str> {
+        match stage {
+            HookStage::PreBuild => self.pre_build.as_deref(),
+            HookStage::PreRun => self.pre_run.as_deref(),
+            HookStage::PostStart => self.post_start.as_deref(),
+            HookStage::OnStop => self.on_stop.as_deref(),
+        }
+        .map(str::trim)
+        .filter(|command| !command.is_empty())
+    }
+
+    /// The stages that have a command, with it.
+    pub fn commands(&self) -> Vec<(HookStage, &str)> {
+        HookStage::ALL
+            .into_iter()
+            .filter_map(|stage| Some((stage, self.command(stage)?)))
+            .collect()
+    }
+
+    /// What confirming the hooks of `owner` is remembered by, so that a
+    /// changed command is asked about again.
+    pub fn confirmation_key(&self, owner: &str) -> String {
+        let commands: Vec<String> = self
+            .commands()
+            .into_iter()
+            .map(|(stage, command)| format!("{}={}", stage.label(), command))
+            .collect();
+        format!("{}: {}", owner, commands.join("; "))
+    }
+}
+
+/// A hook command that is running.
+pub struct HookJob {
+    pub job: Job,
+    // The project, or "group <name>", whose hook it is
+    pub owner: String,
+    pub stage: HookStage,
+    pub companion: Option<Companion>,
+}
+
+/// What a `post_start` hook runs alongside, to be stopped with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Companion {
+    // The project directory of the process
+    Process(PathBuf),
+    Group(String),
+}
+
+impl HookJob {
+    /// Starts the command line with the shell, in `dir`.
+    pub fn spawn(
+        owner: &str,
+        stage: HookStage,
+        command: &str,
+        dir: &Path,
+        companion: Option<Companion>,
+    ) -> io::Result<Self> {
+        let job = Job::spawn(shell_command(command).current_dir(dir))?;
+        Ok(Self {
+            job,
+            owner: owner.to_string(),
+            stage,
+            companion,
+        })
+    }
+
+    /// Like "[Api pre_run]", for its lines in the log.
+    pub fn tag(&self) -> String {
+        format!("[{} {}]", self.owner, self.stage.label())
+    }
+}
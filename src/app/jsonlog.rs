@@ -0,0 +1,181 @@
+use chrono::{DateTime, Local};
+use ratatui::style::Color;
+use serde_json::{Map, Value};
+
+/// A line of JSON console logging, from Serilog's compact or plain JSON
+/// formatter or from Microsoft's JSON console formatter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEvent {
+    pub timestamp: Option<String>,
+    // Like Information, as the formatter names it
+    pub level: String,
+    pub category: Option<String>,
+    // With the message template's holes filled in
+    pub message: String,
+    pub exception: Option<String>,
+}
+
+impl LogEvent {
+    /// The level the way the console formatter shows it, like info.
+    pub fn level_label(&self) -> &'static str {
+        match self.level.to_ascii_lowercase().as_str() {
+            "verbose" | "trace" => "trce",
+            "debug" => "dbug",
+            "warning" => "warn",
+            "error" => "fail",
+            "fatal" | "critical" => "crit",
+            _ => "info",
+        }
+    }
+
+    pub fn level_color(&self) -> Color {
+        match self.level_label() {
+            "trce" | "dbug" => Color::DarkGray,
+            "warn" => Color::Yellow,
+            "fail" => Color::Red,
+            "crit" => Color::Magenta,
+            _ => Color::Green,
+        }
+    }
+}
+
+/// The object the line holds, if it is JSON at all.
+pub fn object(line: &str) -> Option<Map<String, Value>> {
+    let line = line.trim();
+    if !line.starts_with('{') {
+        return None;
+    }
+    match serde_json::from_str(line).ok()? {
+        Value::Object(object) => Some(object),
+        _ => None,
+    }
+}
+
+/// The event the line logs, or None when it is not JSON of a known shape.
+pub fn parse(line: &str) -> Option<LogEvent> {
+    let object = object(line)?;
+    let text = |key: &str| object.get(key).and_then(Value::as_str).map(str::to_string);
+
+    // Serilog's CompactJsonFormatter, where Information has no @l
+    if object.contains_key("@t") || object.contains_key("@mt") || object.contains_key("@m") {
+        let message = match (text("@m"), text("@mt")) {
+            (Some(message), _) => message,
+            (None, Some(template)) => render(&template, &object),
+            (None, None) => String::new(),
+        };
+        return Some(LogEvent {
+            timestamp: text("@t"),
+            level: text("@l").unwrap_or_else(|| "Information".to_string()),
+            category: text("SourceContext"),
+            message,
+            exception: text("@x"),
+        });
+    }
+
+    // Microsoft's JsonConsoleFormatter, with the message rendered already
+    if let (Some(level), Some(message)) = (text("LogLevel"), text("Message")) {
+        return Some(LogEvent {
+            timestamp: text("Timestamp"),
+            level,
+            category: text("Category"),
+            message,
+            exception: text("Exception"),
+        });
+    }
+
+    // Serilog's JsonFormatter, with the properties apart
+    if let (Some(level), Some(template)) = (text("Level"), text("MessageTemplate")) {
+        let properties = match object.get("Properties") {
+            Some(Value::Object(properties)) => properties.clone(),
+            _ => Map::new(),
+        };
+        let category = properties
+            .get("SourceContext")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        return Some(LogEvent {
+            timestamp: text("Timestamp"),
+            level,
+            category,
+            message: text("RenderedMessage").unwrap_or_else(|| render(&template, &properties)),
+            exception: text("Exception"),
+        });
+    }
+    None
+}
+
+/// The time of day of the timestamp, like 14:03:07.512, or the timestamp as
+/// it is when it is not RFC 3339.
+pub fn time_of_day(timestamp: &str) -> String {
+    match DateTime::parse_from_rfc3339(timestamp) {
+        Ok(time) => time
+            .with_timezone(&Local)
+            .format("%H:%M:%S%.3f")
+            .to_string(),
+        Err(_) => timestamp.to_string(),
+    }
+}
+
+/// The message template with its holes, like `{OrderId}`, `{@Order}` or
+/// `{Elapsed:0.00}`, filled from the properties. Holes without a property
+/// stay as they are, and `{{` and `}}` are literal braces.
+pub fn render(template: &str, properties: &Map<String, Value>) -> String {
+    let mut rendered = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                rendered.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                rendered.push('}');
+            }
+            '{' => {
+                let mut hole = String::new();
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        closed = true;
+                        break;
+                    }
+                    hole.push(c);
+                }
+                let name = hole
+                    .trim_start_matches(['@', '$'])
+                    .split([':', ','])
+                    .next()
+                    .unwrap_or_default();
+                match properties.get(name).filter(|_| closed) {
+                    Some(value) => rendered.push_str(&display(value)),
+                    None => {
+                        rendered.push('{');
+                        rendered.push_str(&hole);
+                        if closed {
+                            rendered.push('}');
+                        }
+                    }
+                }
+            }
+            c => rendered.push(c),
+        }
+    }
+    rendered
+}
+
+// Strings without their quotes, everything else as JSON
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// The line's JSON indented, one line of text per row, for the payload
+/// popup.
+pub fn pretty(line: &str) -> Option<Vec<String>> {
+    let object = object(line)?;
+    let pretty = serde_json::to_string_pretty(&Value::Object(object)).ok()?;
+    Some(pretty.lines().map(str::to_string).collect())
+}
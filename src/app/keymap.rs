@@ -0,0 +1,807 @@
+use std::{borrow::Cow, collections::BTreeMap};
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+/// Where a binding applies. `Global` bindings work in every context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Context {
+    Global,
+    Solutions,
+    Projects,
+    Filter,
+    Building,
+    Output,
+    Processes,
+    Problems,
+    Tests,
+    Explorer,
+    Graph,
+    Logs,
+}
+
+impl Context {
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Global => "Everywhere",
+            Self::Solutions => "Solution list",
+            Self::Projects => "Project list",
+            Self::Filter => "While filtering",
+            Self::Building => "While building",
+            Self::Output => "Process output",
+            Self::Processes => "Process list",
+            Self::Problems => "Problems",
+            Self::Tests => "Failed tests",
+            Self::Explorer => "Test explorer",
+            Self::Graph => "Project references",
+            Self::Logs => "Log panel",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Help,
+    Palette,
+    Quit,
+    Back,
+    Up,
+    Down,
+    Select,
+    ForceRun,
+    Expand,
+    Collapse,
+    ToggleTree,
+    ToggleDetails,
+    ShowGraph,
+    ToggleReverse,
+    BuildImpacted,
+    Filter,
+    ClearFilter,
+    Rescan,
+    CycleSort,
+    TogglePin,
+    Hide,
+    ToggleHidden,
+    EditRoot,
+    CycleConfiguration,
+    ToggleBuildStrategy,
+    CycleVerbosity,
+    ToggleBinlog,
+    ToggleWatch,
+    ToggleSpa,
+    RunTests,
+    ExploreTests,
+    ToggleGroup,
+    Publish,
+    OpenPublishFolder,
+    OpenInIde,
+    ExportVsCode,
+    Docker,
+    Compose,
+    Migrations,
+    Secrets,
+    Packages,
+    Sdk,
+    Copy,
+    BuildSolution,
+    Restore,
+    Clean,
+    RunWithArgs,
+    BuildArgs,
+    ClearBuildArgs,
+    EditEnv,
+    CycleEnvironment,
+    OpenBrowser,
+    ToggleMark,
+    NextProcess,
+    PreviousProcess,
+    StopAll,
+    ChooseGroup,
+    ShowProcesses,
+    ClearExited,
+    OpenRecording,
+    ShowProblems,
+    ShowFailedTests,
+    RerunFailed,
+    ChooseProfile,
+    ChooseFramework,
+    ShowOutput,
+    CancelBuild,
+    PageUp,
+    PageDown,
+    Top,
+    Bottom,
+    Follow,
+    PauseOutput,
+    RawOutput,
+    OutputRules,
+    Stop,
+    Restart,
+    LogUp,
+    LogDown,
+    LogPageUp,
+    LogPageDown,
+    LogFollow,
+    ToggleTimestamps,
+    FocusLogs,
+    NextMatch,
+    PreviousMatch,
+    CycleLevel,
+    ExportLogs,
+    GrowLogs,
+    ShrinkLogs,
+    CollapseLogs,
+    ZoomLogs,
+}
+
+impl Action {
+    const ALL: &[Action] = &[
+        Self::Help,
+        Self::Palette,
+        Self::Quit,
+        Self::Back,
+        Self::Up,
+        Self::Down,
+        Self::Select,
+        Self::ForceRun,
+        Self::Expand,
+        Self::Collapse,
+        Self::ToggleTree,
+        Self::ToggleDetails,
+        Self::ShowGraph,
+        Self::ToggleReverse,
+        Self::BuildImpacted,
+        Self::Filter,
+        Self::ClearFilter,
+        Self::Rescan,
+        Self::CycleSort,
+        Self::TogglePin,
+        Self::Hide,
+        Self::ToggleHidden,
+        Self::EditRoot,
+        Self::CycleConfiguration,
+        Self::ToggleBuildStrategy,
+        Self::CycleVerbosity,
+        Self::ToggleBinlog,
+        Self::ToggleWatch,
+        Self::ToggleSpa,
+        Self::RunTests,
+        Self::ExploreTests,
+        Self::ToggleGroup,
+        Self::Publish,
+        Self::OpenPublishFolder,
+        Self::OpenInIde,
+        Self::ExportVsCode,
+        Self::Docker,
+        Self::Compose,
+        Self::Migrations,
+        Self::Secrets,
+        Self::Packages,
+        Self::Sdk,
+        Self::Copy,
+        Self::BuildSolution,
+        Self::Restore,
+        Self::Clean,
+        Self::RunWithArgs,
+        Self::BuildArgs,
+        Self::ClearBuildArgs,
+        Self::EditEnv,
+        Self::CycleEnvironment,
+        Self::OpenBrowser,
+        Self::ToggleMark,
+        Self::NextProcess,
+        Self::PreviousProcess,
+        Self::StopAll,
+        Self::ChooseGroup,
+        Self::ShowProcesses,
+        Self::ClearExited,
+        Self::OpenRecording,
+        Self::ShowProblems,
+        Self::ShowFailedTests,
+        Self::RerunFailed,
+        Self::ChooseProfile,
+        Self::ChooseFramework,
+        Self::ShowOutput,
+        Self::CancelBuild,
+        Self::PageUp,
+        Self::PageDown,
+        Self::Top,
+        Self::Bottom,
+        Self::Follow,
+        Self::PauseOutput,
+        Self::RawOutput,
+        Self::OutputRules,
+        Self::Stop,
+        Self::Restart,
+        Self::LogUp,
+        Self::LogDown,
+        Self::LogPageUp,
+        Self::LogPageDown,
+        Self::LogFollow,
+        Self::ToggleTimestamps,
+        Self::FocusLogs,
+        Self::NextMatch,
+        Self::PreviousMatch,
+        Self::CycleLevel,
+        Self::ExportLogs,
+        Self::GrowLogs,
+        Self::ShrinkLogs,
+        Self::CollapseLogs,
+        Self::ZoomLogs,
+    ];
+
+    /// The name used for the action in the `[keys]` table of the config file.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Help => "help",
+            Self::Palette => "palette",
+            Self::Quit => "quit",
+            Self::Back => "back",
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::Select => "select",
+            Self::ForceRun => "force_run",
+            Self::Expand => "expand",
+            Self::Collapse => "collapse",
+            Self::ToggleTree => "tree",
+            Self::ToggleDetails => "details",
+            Self::ShowGraph => "graph",
+            Self::ToggleReverse => "reverse",
+            Self::BuildImpacted => "build_impacted",
+            Self::Filter => "filter",
+            Self::ClearFilter => "clear_filter",
+            Self::Rescan => "rescan",
+            Self::CycleSort => "sort",
+            Self::TogglePin => "pin",
+            Self::Hide => "hide",
+            Self::ToggleHidden => "show_hidden",
+            Self::EditRoot => "edit_root",
+            Self::CycleConfiguration => "configuration",
+            Self::ToggleBuildStrategy => "build_strategy",
+            Self::CycleVerbosity => "verbosity",
+            Self::ToggleBinlog => "binlog",
+            Self::ToggleWatch => "watch",
+            Self::ToggleSpa => "spa",
+            Self::RunTests => "test",
+            Self::ExploreTests => "explore_tests",
+            Self::ToggleGroup => "fold",
+            Self::Publish => "publish",
+            Self::OpenPublishFolder => "open_publish",
+            Self::OpenInIde => "open_ide",
+            Self::ExportVsCode => "export_vscode",
+            Self::Docker => "docker",
+            Self::Compose => "compose",
+            Self::Migrations => "migrations",
+            Self::Secrets => "secrets",
+            Self::Packages => "packages",
+            Self::Sdk => "sdk",
+            Self::Copy => "copy",
+            Self::BuildSolution => "build_solution",
+            Self::Restore => "restore",
+            Self::Clean => "clean",
+            Self::RunWithArgs => "run_with_args",
+            Self::BuildArgs => "build_args",
+            Self::ClearBuildArgs => "clear_build_args",
+            Self::EditEnv => "environment",
+            Self::CycleEnvironment => "environment_name",
+            Self::OpenBrowser => "open_browser",
+            Self::ToggleMark => "mark",
+            Self::NextProcess => "next_process",
+            Self::PreviousProcess => "previous_process",
+            Self::StopAll => "stop_all",
+            Self::ChooseGroup => "groups",
+            Self::ShowProcesses => "processes",
+            Self::ClearExited => "clear_exited",
+            Self::OpenRecording => "open_recording",
+            Self::ShowProblems => "problems",
+            Self::ShowFailedTests => "failed_tests",
+            Self::RerunFailed => "rerun_failed",
+            Self::ChooseProfile => "profile",
+            Self::ChooseFramework => "framework",
+            Self::ShowOutput => "output",
+            Self::CancelBuild => "cancel",
+            Self::PageUp => "page_up",
+            Self::PageDown => "page_down",
+            Self::Top => "top",
+            Self::Bottom => "bottom",
+            Self::Follow => "follow",
+            Self::PauseOutput => "pause_output",
+            Self::RawOutput => "raw_output",
+            Self::OutputRules => "output_rules",
+            Self::Stop => "stop",
+            Self::Restart => "restart",
+            Self::LogUp => "log_up",
+            Self::LogDown => "log_down",
+            Self::LogPageUp => "log_page_up",
+            Self::LogPageDown => "log_page_down",
+            Self::LogFollow => "log_follow",
+            Self::ToggleTimestamps => "timestamps",
+            Self::FocusLogs => "focus_logs",
+            Self::NextMatch => "next_match",
+            Self::PreviousMatch => "previous_match",
+            Self::CycleLevel => "log_level",
+            Self::ExportLogs => "export_logs",
+            Self::GrowLogs => "grow_logs",
+            Self::ShrinkLogs => "shrink_logs",
+            Self::CollapseLogs => "collapse_logs",
+            Self::ZoomLogs => "zoom_logs",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|action| action.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    code: KeyCode,
+    ctrl: bool,
+    shift: bool,
+}
+
+const fn key(code: KeyCode) -> Key {
+    Key {
+        code,
+        ctrl: false,
+        shift: false,
+    }
+}
+
+const fn ch(c: char) -> Key {
+    key(KeyCode::Char(c))
+}
+
+const fn ctrl(c: char) -> Key {
+    Key {
+        code: KeyCode::Char(c),
+        ctrl: true,
+        shift: false,
+    }
+}
+
+const fn shift(code: KeyCode) -> Key {
+    Key {
+        code,
+        ctrl: false,
+        shift: true,
+    }
+}
+
+impl Key {
+    /// Parses a key specification such as `q`, `f5`, `pagedown` or `ctrl+c`.
+    fn parse(spec: &str) -> Option<Self> {
+        let (modifiers, name) = match spec.rsplit_once('+') {
+            // `ctrl++` binds the plus key
+            Some((modifiers, "")) => (modifiers.trim_end_matches('+'), "+"),
+            Some((modifiers, name)) => (modifiers, name),
+            None => ("", spec),
+        };
+        let (mut ctrl, mut shift) = (false, false);
+        for modifier in modifiers.split('+').filter(|m| !m.is_empty()) {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => ctrl = true,
+                "shift" => shift = true,
+                _ => return None,
+            }
+        }
+
+        let mut chars = name.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match name.to_ascii_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "backspace" => KeyCode::Backspace,
+                "tab" => KeyCode::Tab,
+                "space" => KeyCode::Char(' '),
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                f => KeyCode::F(
+                    f.strip_prefix('f')?
+                        .parse()
+                        .ok()
+                        .filter(|n| (1..=24).contains(n))?,
+                ),
+            },
+        };
+        // Shifted characters are written as the character itself, e.g. `G`
+        if shift && matches!(code, KeyCode::Char(_)) {
+            return None;
+        }
+        Some(Self { code, ctrl, shift })
+    }
+
+    // Plain characters are typed into the filter rather than treated as keys
+    fn is_text(&self) -> bool {
+        !self.ctrl && matches!(self.code, KeyCode::Char(_))
+    }
+
+    /// The key press this key is, as the terminal would report it.
+    fn event(&self) -> KeyEvent {
+        let mut modifiers = KeyModifiers::NONE;
+        if self.ctrl {
+            modifiers |= KeyModifiers::CONTROL;
+        }
+        if self.shift || matches!(self.code, KeyCode::Char(c) if c.is_uppercase()) {
+            modifiers |= KeyModifiers::SHIFT;
+        }
+        KeyEvent::new(self.code, modifiers)
+    }
+
+    fn matches(&self, event: &KeyEvent) -> bool {
+        // Shift is already part of a character, `G` arrives as Char('G') with SHIFT
+        let shift = event.modifiers.contains(KeyModifiers::SHIFT)
+            && !matches!(event.code, KeyCode::Char(_));
+        self.code == event.code
+            && self.ctrl == event.modifiers.contains(KeyModifiers::CONTROL)
+            && self.shift == shift
+    }
+
+    fn label(&self) -> String {
+        let code = match self.code {
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::Enter => "Enter".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Backspace => "Backspace".to_string(),
+            KeyCode::PageUp => "PgUp".to_string(),
+            KeyCode::PageDown => "PgDn".to_string(),
+            KeyCode::End => "End".to_string(),
+            KeyCode::F(n) => format!("F{}", n),
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            other => format!("{:?}", other),
+        };
+        let ctrl = if self.ctrl { "Ctrl+" } else { "" };
+        let shift = if self.shift { "Shift+" } else { "" };
+        format!("{}{}{}", ctrl, shift, code)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Binding {
+    pub context: Context,
+    pub keys: Cow<'static, [Key]>,
+    pub action: Action,
+    pub description: &'static str,
+}
+
+impl Binding {
+    /// A key press that triggers the binding, unless it has no keys left.
+    pub fn event(&self) -> Option<KeyEvent> {
+        self.keys.first().map(Key::event)
+    }
+
+    pub fn keys_label(&self) -> String {
+        self.keys
+            .iter()
+            .map(Key::label)
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+const fn bind(
+    context: Context,
+    keys: &'static [Key],
+    action: Action,
+    description: &'static str,
+) -> Binding {
+    Binding {
+        context,
+        keys: Cow::Borrowed(keys),
+        action,
+        description,
+    }
+}
+
+const UP: Key = key(KeyCode::Up);
+const DOWN: Key = key(KeyCode::Down);
+const LEFT: Key = key(KeyCode::Left);
+const RIGHT: Key = key(KeyCode::Right);
+const ENTER: Key = key(KeyCode::Enter);
+const ESC: Key = key(KeyCode::Esc);
+const BACKSPACE: Key = key(KeyCode::Backspace);
+const HOME: Key = key(KeyCode::Home);
+const END: Key = key(KeyCode::End);
+const PAGE_UP: Key = key(KeyCode::PageUp);
+const PAGE_DOWN: Key = key(KeyCode::PageDown);
+
+use Action::*;
+use Context::*;
+
+/// The default keybindings, in the order the help overlay lists them.
+/// Letter keys stay out of the filter context, where they are typed.
+#[rustfmt::skip]
+const DEFAULT_BINDINGS: &[Binding] = &[
+    bind(Global, &[ch('?')], Help, "Toggle this help"),
+    bind(Global, &[ctrl('p')], Palette, "Search the commands and run one"),
+    bind(Global, &[shift(KeyCode::Up)], LogUp, "Scroll the log back"),
+    bind(Global, &[shift(KeyCode::Down)], LogDown, "Scroll the log forward"),
+    bind(Global, &[shift(KeyCode::PageUp)], LogPageUp, "Scroll the log back a page"),
+    bind(Global, &[shift(KeyCode::PageDown)], LogPageDown, "Scroll the log forward a page"),
+    bind(Global, &[shift(KeyCode::End)], LogFollow, "Follow the log again"),
+    bind(Global, &[ch('T')], ToggleTimestamps, "Toggle log timestamps"),
+    bind(Global, &[key(KeyCode::Tab)], FocusLogs, "Focus the log panel and back"),
+    bind(Global, &[ctrl('s')], ExportLogs, "Save the log to a file"),
+    bind(Global, &[ch('+'), ch('=')], GrowLogs, "Grow the log panel"),
+    bind(Global, &[ch('-')], ShrinkLogs, "Shrink the log panel"),
+    bind(Global, &[ch('L')], CollapseLogs, "Collapse the log panel"),
+    bind(Global, &[ch('z')], ZoomLogs, "Zoom the log panel to full screen"),
+    bind(Global, &[ch('p')], ShowProcesses, "List running processes"),
+    bind(Global, &[ch('E')], ShowProblems, "List build errors and warnings"),
+    bind(Global, &[ch('F')], ShowFailedTests, "List the tests that failed"),
+    bind(Solutions, &[UP, ch('k')], Up, "Previous solution (wraps)"),
+    bind(Solutions, &[DOWN, ch('j')], Down, "Next solution (wraps)"),
+    bind(Solutions, &[PAGE_UP], PageUp, "Page up"),
+    bind(Solutions, &[PAGE_DOWN], PageDown, "Page down"),
+    bind(Solutions, &[HOME, ch('g')], Top, "First solution"),
+    bind(Solutions, &[END, ch('G')], Bottom, "Last solution"),
+    bind(Solutions, &[ENTER], Select, "Open solution"),
+    bind(Solutions, &[ch('/')], Action::Filter, "Filter the list"),
+    bind(Solutions, &[ch('r'), key(KeyCode::F(5))], Rescan, "Rescan"),
+    bind(Solutions, &[ch('o')], CycleSort, "Cycle the order of the solutions"),
+    bind(Solutions, &[ch('*')], TogglePin, "Pin the solution to the top, or unpin it"),
+    bind(Solutions, &[ch('h')], Hide, "Hide solutions like this one, or show a hidden one again"),
+    bind(Solutions, &[ch('H')], ToggleHidden, "Show or leave out the hidden solutions"),
+    bind(Solutions, &[ch('O')], OpenInIde, "Open the solution in an IDE"),
+    bind(Solutions, &[ch('y')], Copy, "Copy the solution's path"),
+    bind(Solutions, &[ch('e')], EditRoot, "Change the search root"),
+    bind(Solutions, &[ch('q'), ESC], Quit, "Quit"),
+    bind(Projects, &[UP, ch('k')], Up, "Previous project (wraps)"),
+    bind(Projects, &[DOWN, ch('j')], Down, "Next project (wraps)"),
+    bind(Projects, &[PAGE_UP], PageUp, "Page up"),
+    bind(Projects, &[PAGE_DOWN], PageDown, "Page down"),
+    bind(Projects, &[HOME, ch('g')], Top, "First project"),
+    bind(Projects, &[END], Bottom, "Last project"),
+    bind(Projects, &[ENTER], Select, "Run, build or test the project, or run all marked ones"),
+    bind(Projects, &[ch('R')], ForceRun, "Build and run the project, even a library"),
+    bind(Projects, &[RIGHT], Expand, "Unfold the solution folder"),
+    bind(Projects, &[LEFT], Collapse, "Fold the solution folder, or back to solutions"),
+    bind(Projects, &[ch('H')], ToggleTree, "Show the projects in their folders or flat"),
+    bind(Projects, &[ch(' ')], ToggleMark, "Mark the project to run together"),
+    bind(Projects, &[ch('G')], ChooseGroup, "Run a group of projects"),
+    bind(Projects, &[ch('/')], Action::Filter, "Filter the list"),
+    bind(Projects, &[ch('c')], CycleConfiguration, "Cycle configuration"),
+    bind(Projects, &[ch('b')], ToggleBuildStrategy, "Switch build strategy"),
+    bind(Projects, &[ch('V')], CycleVerbosity, "Cycle build verbosity"),
+    bind(Projects, &[ch('D')], ToggleBinlog, "Toggle the MSBuild binary log"),
+    bind(Projects, &[ch('w')], ToggleWatch, "Toggle dotnet watch for the project"),
+    bind(Projects, &[ch('n')], ToggleSpa, "Toggle starting the SPA dev server with the project"),
+    bind(Projects, &[ch('a'), shift(ENTER.code)], RunWithArgs, "Run with arguments"),
+    bind(Projects, &[ch('m')], BuildArgs, "Set MSBuild arguments for this session"),
+    bind(Projects, &[ch('M')], ClearBuildArgs, "Clear the session's MSBuild arguments"),
+    bind(Projects, &[ch('t')], RunTests, "Run the tests, or all of the solution's"),
+    bind(Projects, &[ch('X')], ExploreTests, "Explore the tests of the project"),
+    bind(Projects, &[ch('u')], Publish, "Publish the project"),
+    bind(Projects, &[ch('U')], OpenPublishFolder, "Open the last publish folder"),
+    bind(Projects, &[ch('O')], OpenInIde, "Open the project in an IDE"),
+    bind(Projects, &[ch('x')], ExportVsCode, "Write VS Code launch and build configurations"),
+    bind(Projects, &[ctrl('d')], Docker, "Build or run the project's Docker image"),
+    bind(Projects, &[ch('K')], Compose, "Open the solution's Docker Compose services"),
+    bind(Projects, &[ctrl('e')], Migrations, "Open the project's EF Core migrations"),
+    bind(Projects, &[ch('S')], Secrets, "Open the project's user secrets"),
+    bind(Projects, &[ch('N')], Packages, "List outdated and vulnerable NuGet packages"),
+    bind(Projects, &[ch('y')], Copy, "Copy the project's path"),
+    bind(Projects, &[ch('B')], BuildSolution, "Build the whole solution"),
+    bind(Projects, &[ctrl('r')], Restore, "Restore the project or the solution"),
+    bind(Projects, &[ch('C')], Clean, "Clean the project or the solution"),
+    bind(Projects, &[ch('d')], ShowGraph, "Show the project references"),
+    bind(Projects, &[ch('I')], BuildImpacted, "Build the project and what depends on it"),
+    bind(Projects, &[ch('i')], ToggleDetails, "Show or hide the project details"),
+    bind(Projects, &[ch('l')], ChooseProfile, "Choose launch profile"),
+    bind(Projects, &[ch('f')], ChooseFramework, "Choose target framework"),
+    bind(Projects, &[ctrl('k')], Sdk, "Choose the .NET SDK to run the project with"),
+    bind(Projects, &[ch('e')], EditEnv, "Edit environment variables"),
+    bind(Projects, &[ch('A')], CycleEnvironment, "Cycle ASPNETCORE_ENVIRONMENT"),
+    bind(Projects, &[ch('v')], ShowOutput, "Show process output"),
+    bind(Projects, &[ch('o')], OpenBrowser, "Open the app in the browser"),
+    bind(Projects, &[ESC, BACKSPACE], Back, "Back to solutions"),
+    bind(Projects, &[ch('q')], Quit, "Quit"),
+    bind(Context::Filter, &[UP], Up, "Previous match"),
+    bind(Context::Filter, &[DOWN], Down, "Next match"),
+    bind(Context::Filter, &[PAGE_UP], PageUp, "Page up"),
+    bind(Context::Filter, &[PAGE_DOWN], PageDown, "Page down"),
+    bind(Context::Filter, &[HOME], Top, "First match"),
+    bind(Context::Filter, &[END], Bottom, "Last match"),
+    bind(Context::Filter, &[ENTER], Select, "Pick the highlighted match"),
+    bind(Context::Filter, &[ESC], ClearFilter, "Clear the filter"),
+    bind(Building, &[ch('x'), ctrl('c')], CancelBuild, "Cancel the build or test run"),
+    bind(Output, &[UP, ch('k')], Up, "Scroll up"),
+    bind(Output, &[DOWN, ch('j')], Down, "Scroll down"),
+    bind(Output, &[PAGE_UP], PageUp, "Scroll up a page"),
+    bind(Output, &[PAGE_DOWN], PageDown, "Scroll down a page"),
+    bind(Output, &[HOME, ch('g')], Top, "Scroll to the top"),
+    bind(Output, &[END, ch('G')], Follow, "Follow new output"),
+    bind(Output, &[ch(' ')], PauseOutput, "Pause the output, or resume at the newest"),
+    bind(Output, &[ch('/')], Action::Filter, "Search the output"),
+    bind(Output, &[ch('n')], NextMatch, "Next match"),
+    bind(Output, &[ch('N')], PreviousMatch, "Previous match"),
+    bind(Output, &[ch('J')], RawOutput, "Show JSON log lines raw or prettified"),
+    bind(Output, &[ENTER], Select, "Show the whole JSON of the highlighted line"),
+    bind(Output, &[ch('m')], OutputRules, "Switch output rules on and off"),
+    bind(Output, &[ch('s')], Stop, "Stop the process"),
+    bind(Output, &[ch('R')], Restart, "Restart the process"),
+    bind(Output, &[ch('S')], StopAll, "Stop all processes"),
+    bind(Output, &[ch(']')], NextProcess, "Next process"),
+    bind(Output, &[ch('[')], PreviousProcess, "Previous process"),
+    bind(Output, &[ch('o')], OpenBrowser, "Open the app in the browser"),
+    bind(Output, &[ch('y')], Copy, "Copy the app's address"),
+    bind(Output, &[ESC, BACKSPACE, LEFT], Back, "Back to projects"),
+    bind(Output, &[ch('q')], Quit, "Quit"),
+    bind(Processes, &[UP, ch('k')], Up, "Previous process"),
+    bind(Processes, &[DOWN, ch('j')], Down, "Next process"),
+    bind(Processes, &[ENTER], Select, "Show the process output"),
+    bind(Processes, &[ch('s')], Stop, "Stop the process"),
+    bind(Processes, &[ch('R')], Restart, "Restart the process"),
+    bind(Processes, &[ch('S')], StopAll, "Stop all processes"),
+    bind(Processes, &[ch('c')], ClearExited, "Clear exited processes"),
+    bind(Processes, &[ch('v')], OpenRecording, "Open the recording of the process's output"),
+    bind(Processes, &[ch('o')], OpenBrowser, "Open the app in the browser"),
+    bind(Processes, &[ch('y')], Copy, "Copy the app's address"),
+    bind(Processes, &[ESC, BACKSPACE, LEFT], Back, "Back to projects"),
+    bind(Processes, &[ch('q')], Quit, "Quit"),
+    bind(Problems, &[UP, ch('k')], Up, "Previous problem"),
+    bind(Problems, &[DOWN, ch('j')], Down, "Next problem"),
+    bind(Problems, &[PAGE_UP], PageUp, "Page up"),
+    bind(Problems, &[PAGE_DOWN], PageDown, "Page down"),
+    bind(Problems, &[HOME, ch('g')], Top, "First problem"),
+    bind(Problems, &[END, ch('G')], Bottom, "Last problem"),
+    bind(Problems, &[ENTER], Select, "Open the file at the line in the editor"),
+    bind(Problems, &[ESC, BACKSPACE, LEFT], Back, "Back to projects"),
+    bind(Problems, &[ch('q')], Quit, "Quit"),
+    bind(Tests, &[UP, ch('k')], Up, "Previous failed test"),
+    bind(Tests, &[DOWN, ch('j')], Down, "Next failed test"),
+    bind(Tests, &[PAGE_UP], PageUp, "Page up"),
+    bind(Tests, &[PAGE_DOWN], PageDown, "Page down"),
+    bind(Tests, &[HOME, ch('g')], Top, "First failed test"),
+    bind(Tests, &[END, ch('G')], Bottom, "Last failed test"),
+    bind(Tests, &[ch('r')], RerunFailed, "Run only the failed tests again"),
+    bind(Tests, &[ESC, BACKSPACE, LEFT], Back, "Back to projects"),
+    bind(Tests, &[ch('q')], Quit, "Quit"),
+    bind(Explorer, &[UP, ch('k')], Up, "Previous row"),
+    bind(Explorer, &[DOWN, ch('j')], Down, "Next row"),
+    bind(Explorer, &[PAGE_UP], PageUp, "Page up"),
+    bind(Explorer, &[PAGE_DOWN], PageDown, "Page down"),
+    bind(Explorer, &[HOME, ch('g')], Top, "First row"),
+    bind(Explorer, &[END, ch('G')], Bottom, "Last row"),
+    bind(Explorer, &[ENTER], Select, "Run the test, class or namespace"),
+    bind(Explorer, &[ch(' ')], ToggleGroup, "Fold or unfold the namespace or class"),
+    bind(Explorer, &[ch('/')], Action::Filter, "Filter the tests"),
+    bind(Explorer, &[ch('r')], Rescan, "List the tests again"),
+    bind(Explorer, &[ESC, BACKSPACE, LEFT], Back, "Back to projects"),
+    bind(Explorer, &[ch('q')], Quit, "Quit"),
+    bind(Graph, &[UP, ch('k')], Up, "Previous project"),
+    bind(Graph, &[DOWN, ch('j')], Down, "Next project"),
+    bind(Graph, &[PAGE_UP], PageUp, "Page up"),
+    bind(Graph, &[PAGE_DOWN], PageDown, "Page down"),
+    bind(Graph, &[HOME, ch('g')], Top, "First project"),
+    bind(Graph, &[END, ch('G')], Bottom, "Last project"),
+    bind(Graph, &[ENTER], Select, "Show the references of the project"),
+    bind(Graph, &[ch('r')], ToggleReverse, "Switch between references and dependents"),
+    bind(Graph, &[ch('b')], BuildImpacted, "Build the project and what depends on it"),
+    bind(Graph, &[ESC, BACKSPACE, LEFT], Back, "Back to the previous project"),
+    bind(Graph, &[ch('q')], Quit, "Quit"),
+    bind(Logs, &[UP, ch('k')], Up, "Scroll back"),
+    bind(Logs, &[DOWN, ch('j')], Down, "Scroll forward"),
+    bind(Logs, &[PAGE_UP], PageUp, "Scroll back a page"),
+    bind(Logs, &[PAGE_DOWN], PageDown, "Scroll forward a page"),
+    bind(Logs, &[END, ch('G')], Follow, "Follow new lines"),
+    bind(Logs, &[ch('/')], Action::Filter, "Search the log"),
+    bind(Logs, &[ch('n')], NextMatch, "Next match"),
+    bind(Logs, &[ch('N')], PreviousMatch, "Previous match"),
+    bind(Logs, &[ch('f')], CycleLevel, "Cycle level filter (All, Warn+, Error)"),
+    bind(Logs, &[ESC], Back, "Back to the list"),
+    bind(Logs, &[ch('q')], Quit, "Quit"),
+];
+
+/// Key specifications for an action in the config file: one key or a list.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum KeySpecs {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeySpecs {
+    fn specs(&self) -> &[String] {
+        match self {
+            Self::One(spec) => std::slice::from_ref(spec),
+            Self::Many(specs) => specs,
+        }
+    }
+}
+
+/// The active keybindings. Key handlers look actions up here and the help
+/// overlay lists them, so the overlay always matches what the keys do.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<Binding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            bindings: DEFAULT_BINDINGS.to_vec(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Applies the `[keys]` table from the config file on top of the defaults.
+    /// Returns the keymap along with a warning for every entry that was ignored.
+    pub fn with_overrides(overrides: &BTreeMap<String, KeySpecs>) -> (Self, Vec<String>) {
+        let mut keymap = Self::default();
+        let mut warnings = Vec::new();
+
+        for (name, specs) in overrides {
+            let Some(action) = Action::from_name(name) else {
+                warnings.push(format!("Unknown action '{}' in [keys]", name));
+                continue;
+            };
+
+            let mut keys = Vec::new();
+            for spec in specs.specs() {
+                match Key::parse(spec.trim()) {
+                    Some(key) => keys.push(key),
+                    None => warnings.push(format!("Invalid key '{}' for action '{}'", spec, name)),
+                }
+            }
+            if keys.is_empty() {
+                continue;
+            }
+
+            for binding in keymap.bindings.iter_mut().filter(|b| b.action == action) {
+                let keys: Vec<Key> = keys
+                    .iter()
+                    .filter(|key| binding.context != Context::Filter || !key.is_text())
+                    .copied()
+                    .collect();
+                binding.keys = Cow::Owned(keys);
+            }
+        }
+
+        (keymap, warnings)
+    }
+
+    pub fn bindings(&self) -> &[Binding] {
+        &self.bindings
+    }
+
+    /// Looks up the action bound to `event` in `context`.
+    pub fn action(&self, context: Context, event: &KeyEvent) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|binding| {
+                binding.context == context && binding.keys.iter().any(|k| k.matches(event))
+            })
+            .map(|binding| binding.action)
+    }
+
+    /// The bindings for `actions` in `context`, in the order given.
+    pub fn find(&self, context: Context, actions: &[Action]) -> Vec<&Binding> {
+        actions
+            .iter()
+            .filter_map(|action| {
+                self.bindings
+                    .iter()
+                    .find(|binding| binding.context == context && binding.action == *action)
+            })
+            .collect()
+    }
+}
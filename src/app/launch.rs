@@ -0,0 +1,58 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LaunchProfile {
+    #[serde(skip)]
+    pub name: String,
+    pub command_name: Option<String>,
+    pub command_line_args: Option<String>,
+    pub application_url: Option<String>,
+    pub launch_browser: bool,
+    pub dotnet_run_messages: bool,
+    pub environment_variables: BTreeMap<String, String>,
+    // The host ports of a Docker profile
+    pub http_port: Option<u16>,
+    pub ssl_port: Option<u16>,
+}
+
+impl LaunchProfile {
+    // `dotnet run --launch-profile` only understands "Project" profiles;
+    // IIS Express, Docker etc. are launched by the IDE.
+    pub fn is_supported(&self) -> bool {
+        self.command_name.as_deref() == Some("Project")
+    }
+
+    pub fn is_docker(&self) -> bool {
+        self.command_name.as_deref() == Some("Docker")
+    }
+}
+
+pub fn launch_settings_path(project_dir: &Path) -> std::path::PathBuf {
+    project_dir.join("Properties").join("launchSettings.json")
+}
+
+/// Reads the profiles from `Properties/launchSettings.json`, in file order.
+pub fn read_launch_profiles(project_dir: &Path) -> Vec<LaunchProfile> {
+    let Ok(contents) = fs::read_to_string(launch_settings_path(project_dir)) else {
+        return Vec::new();
+    };
+    let Ok(json) = serde_json::from_str::<Value>(&contents) else {
+        return Vec::new();
+    };
+    let Some(profiles) = json.get("profiles").and_then(|p| p.as_object()) else {
+        return Vec::new();
+    };
+
+    profiles
+        .iter()
+        .filter_map(|(name, value)| {
+            let mut profile = LaunchProfile::deserialize(value).ok()?;
+            profile.name = name.clone();
+            Some(profile)
+        })
+        .collect()
+}
@@ -0,0 +1,352 @@
+use std::{
+    collections::BTreeSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Local};
+
+use crate::app::{
+    error::{AppError, Result},
+    input::TextInput,
+    scrollback::{Item, Scrollback},
+    secrets,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Info,
+    // Info that went well, shown in green
+    Success,
+    Warn,
+    Error,
+}
+
+impl Level {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Info => "INFO",
+            Self::Success => "OK",
+            Self::Warn => "WARN",
+            Self::Error => "ERROR",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub time: DateTime<Local>,
+    pub level: Level,
+    pub message: String,
+}
+
+impl LogEntry {
+    pub fn new(level: Level, message: impl Into<String>) -> Self {
+        Self {
+            time: Local::now(),
+            level,
+            message: message.into(),
+        }
+    }
+
+    pub fn timestamp(&self) -> String {
+        self.time.format("%H:%M:%S").to_string()
+    }
+}
+
+impl Item for LogEntry {
+    fn bytes(&self) -> usize {
+        self.message.len()
+    }
+
+    fn dropped_marker(count: usize) -> Self {
+        Self::new(Level::Info, String::dropped_marker(count))
+    }
+}
+
+/// The lowest level shown in the log panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelFilter {
+    All,
+    Warn,
+    Error,
+}
+
+impl LevelFilter {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::All => "All",
+            Self::Warn => "Warn+",
+            Self::Error => "Error",
+        }
+    }
+
+    fn allows(self, level: Level) -> bool {
+        match self {
+            Self::All => true,
+            Self::Warn => level >= Level::Warn,
+            Self::Error => level == Level::Error,
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            Self::All => Self::Warn,
+            Self::Warn => Self::Error,
+            Self::Error => Self::All,
+        }
+    }
+}
+
+/// The log buffer together with how it is being viewed. Level filtering and
+/// search happen when reading, so the buffer itself is never modified.
+pub struct LogPanel {
+    entries: Scrollback<LogEntry>,
+    // Lines scrolled back from the newest visible entry
+    pub scroll: usize,
+    // Entries kept of each level, to tell how many are visible without
+    // going over them
+    counts: [usize; 4],
+    pub timestamps: bool,
+    pub level: LevelFilter,
+    // Search input while it is being typed
+    pub search: Option<TextInput>,
+    pub query: String,
+    // Index into `visible()` of the match last jumped to
+    pub current_match: Option<usize>,
+    // Secret values to leave out of the export
+    masked: BTreeSet<String>,
+}
+
+impl LogPanel {
+    pub fn new(capacity: usize, max_bytes: usize) -> Self {
+        Self {
+            entries: Scrollback::new(capacity, max_bytes),
+            scroll: 0,
+            counts: [0; 4],
+            timestamps: true,
+            level: LevelFilter::All,
+            search: None,
+            query: String::new(),
+            current_match: None,
+            masked: BTreeSet::new(),
+        }
+    }
+
+    /// Keeps up to `capacity` entries and `max_bytes` of text from now on,
+    /// dropping the oldest ones over them.
+    pub fn set_capacity(&mut self, capacity: usize, max_bytes: usize) {
+        if self.entries.set_limits(capacity, max_bytes) > 0 {
+            self.counts = [0; 4];
+            // Past the marker, which is there now
+            for entry in self.entries.iter().skip(1) {
+                self.counts[entry.level as usize] += 1;
+            }
+            self.current_match = None;
+            self.scroll = self.scroll.min(self.visible_len());
+        }
+    }
+
+    pub fn push(&mut self, entry: LogEntry) {
+        // Stay on the same lines while scrolled back instead of following the tail
+        if self.scroll > 0 && self.level.allows(entry.level) {
+            self.scroll += 1;
+        }
+        self.counts[entry.level as usize] += 1;
+        let counts = &mut self.counts;
+        if self
+            .entries
+            .push_with(entry, |dropped| counts[dropped.level as usize] -= 1)
+            > 0
+        {
+            // Match indices shift with the buffer, so forget the position
+            self.current_match = None;
+        }
+        if self.scroll > 0 {
+            self.scroll = self.scroll.min(self.visible_len());
+        }
+    }
+
+    /// How many entries `visible()` returns.
+    fn visible_len(&self) -> usize {
+        let marker =
+            self.entries.first() < self.entries.dropped() && self.level == LevelFilter::All;
+        [Level::Info, Level::Success, Level::Warn, Level::Error]
+            .into_iter()
+            .filter(|&level| self.level.allows(level))
+            .map(|level| self.counts[level as usize])
+            .sum::<usize>()
+            + usize::from(marker)
+    }
+
+    /// Entries that pass the level filter, oldest first.
+    pub fn visible(&self) -> Vec<&LogEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| self.level.allows(entry.level))
+            .collect()
+    }
+
+    /// Indices into `visible()` of the entries containing the search query.
+    pub fn matches(&self) -> Vec<usize> {
+        let query = self.query.to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        self.visible()
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| strip_ansi(&entry.message).to_lowercase().contains(&query))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Scrolls by `delta` lines, positive values going back in history, keeping
+    /// at least a `page` of lines in view.
+    pub fn scroll_by(&mut self, delta: isize, page: usize) {
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(delta)
+            .min(self.visible_len().saturating_sub(page));
+    }
+
+    pub fn cycle_level(&mut self) {
+        self.level = self.level.next();
+        self.scroll = 0;
+        self.current_match = None;
+    }
+
+    /// Moves to the next (newer) or previous (older) match, wrapping around,
+    /// and scrolls so it is the bottom line of a `page`.
+    pub fn jump_to_match(&mut self, forward: bool, page: usize) {
+        let matches = self.matches();
+        let (Some(&first), Some(&last)) = (matches.first(), matches.last()) else {
+            return;
+        };
+        let target = match (self.current_match, forward) {
+            (None, _) => last,
+            (Some(current), true) => matches
+                .iter()
+                .copied()
+                .find(|&i| i > current)
+                .unwrap_or(first),
+            (Some(current), false) => matches
+                .iter()
+                .rev()
+                .copied()
+                .find(|&i| i < current)
+                .unwrap_or(last),
+        };
+        self.current_match = Some(target);
+        let len = self.visible_len();
+        self.scroll = (len - 1 - target).min(len.saturating_sub(page));
+    }
+
+    /// Masks the value wherever it shows up in the export from now on.
+    pub fn mask(&mut self, value: &str) {
+        if secrets::maskable(value) {
+            self.masked.insert(value.to_string());
+        }
+    }
+
+    /// Writes the whole buffer, regardless of filters and scrolling, to a
+    /// timestamped file in `dir` and returns its path. Masked values are
+    /// replaced.
+    pub fn export(&self, dir: &Path) -> Result<PathBuf> {
+        let path = dir.join(format!(
+            "sln-runner-{}.log",
+            Local::now().format("%Y-%m-%dT%H-%M-%S")
+        ));
+        let contents: String = self
+            .entries
+            .iter()
+            .map(|entry| {
+                format!(
+                    "{} {:<5} {}\n",
+                    entry.time.format("%Y-%m-%d %H:%M:%S"),
+                    entry.level.label(),
+                    secrets::mask(&strip_ansi(&entry.message), &self.masked)
+                )
+            })
+            .collect();
+
+        let write = || -> io::Result<()> {
+            fs::create_dir_all(dir)?;
+            fs::write(&path, contents)
+        };
+        write().map_err(|source| AppError::LogExport {
+            path: path.clone(),
+            source,
+        })?;
+        Ok(path)
+    }
+}
+
+/// Removes ANSI escape sequences, which tools like dotnet emit for colors.
+pub fn strip_ansi(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\u{1b}' {
+            plain.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters up to a final byte in @..~
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC: up to BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\u{7}' || (c == '\u{1b}' && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    plain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_the_visible_entries_as_old_ones_are_dropped() {
+        let mut panel = LogPanel::new(5, 1000);
+        let levels = [Level::Info, Level::Warn, Level::Error, Level::Success];
+        for i in 0..50 {
+            panel.push(LogEntry::new(levels[i % levels.len()], i.to_string()));
+            for _ in 0..3 {
+                assert_eq!(panel.visible_len(), panel.visible().len());
+                panel.cycle_level();
+            }
+        }
+
+        panel.set_capacity(2, 1000);
+        for _ in 0..3 {
+            assert_eq!(panel.visible_len(), panel.visible().len());
+            panel.cycle_level();
+        }
+    }
+
+    #[test]
+    fn scrolled_back_stays_within_the_entries() {
+        let mut panel = LogPanel::new(10, 1000);
+        for i in 0..10 {
+            panel.push(LogEntry::new(Level::Info, i.to_string()));
+        }
+        panel.scroll_by(5, 1);
+        for i in 10..1000 {
+            panel.push(LogEntry::new(Level::Info, i.to_string()));
+            assert!(panel.scroll <= panel.visible().len());
+        }
+    }
+}
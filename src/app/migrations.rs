@@ -0,0 +1,89 @@
+use std::{fs, path::Path, process::Command};
+
+/// The command line that installs the EF Core tools.
+pub const INSTALL_COMMAND: &str = "dotnet tool install --global dotnet-ef";
+
+/// Whether the project references the EF Core design package the `dotnet ef`
+/// commands need.
+pub fn uses_ef(project_path: &Path) -> bool {
+    fs::read_to_string(project_path)
+        .is_ok_and(|project| project.contains("\"Microsoft.EntityFrameworkCore.Design\""))
+}
+
+/// `dotnet ef` for the project, with the project the app starts from when
+/// that is another one. The output is prefixed by kind, so that the data
+/// lines can be told from the build's.
+pub fn ef_command(
+    project_path: &Path,
+    startup_project: Option<&Path>,
+    configuration: &str,
+    args: &[&str],
+) -> Command {
+    let mut command = Command::new("dotnet");
+    command
+        .arg("ef")
+        .args(args)
+        .arg("--project")
+        .arg(project_path);
+    if let Some(startup_project) = startup_project {
+        command.arg("--startup-project").arg(startup_project);
+    }
+    command
+        .arg("--configuration")
+        .arg(configuration)
+        .arg("--no-color")
+        .arg("--prefix-output");
+    if let Some(dir) = project_path.parent() {
+        command.current_dir(dir);
+    }
+    command
+}
+
+pub fn install_command() -> Command {
+    let mut command = Command::new("dotnet");
+    command.args(["tool", "install", "--global", "dotnet-ef"]);
+    command
+}
+
+/// Whether the output says that the `dotnet-ef` tool is not installed.
+pub fn tool_missing(output: &[String]) -> bool {
+    output.iter().any(|line| {
+        line.contains("dotnet-ef does not exist")
+            || line
+                .contains("Could not execute because the specified command or file was not found")
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration {
+    // Like 20240101120000_AddOrders
+    pub id: String,
+    // None when the database could not be asked
+    pub applied: Option<bool>,
+}
+
+/// The migrations in the output of `dotnet ef migrations list
+/// --prefix-output`, oldest first, marked pending as far as the database
+/// could be asked.
+pub fn parse_migrations(output: &[String]) -> Vec<Migration> {
+    // EF lists the migrations without marks when the database is unreachable
+    let unknown = output
+        .iter()
+        .any(|line| line.contains("Continuing without the information provided by the database"));
+    output
+        .iter()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("No migrations were found"))
+        .map(|line| {
+            let (id, pending) = match line.strip_suffix("(Pending)") {
+                Some(id) => (id.trim(), true),
+                None => (line, false),
+            };
+            Migration {
+                id: id.to_string(),
+                applied: (!unknown).then_some(!pending),
+            }
+        })
+        .collect()
+}
@@ -1,4 +1,55 @@
+mod ansi;
+#[allow(clippy::module_inception)]
 mod app;
+mod clean;
+mod clipboard;
+mod completions;
+mod compose;
+mod config;
+mod details;
+mod devcerts;
+mod diagnostics;
+mod docker;
+mod editor;
+mod env;
+mod error;
+mod events;
+mod explorer;
+mod filter;
+mod git;
+mod graph;
+mod group;
+mod headless;
+mod hooks;
+mod input;
+mod jsonlog;
+mod keymap;
+mod launch;
+mod logs;
+mod migrations;
+mod outputindex;
+mod packages;
+mod palette;
+mod ports;
+mod process;
+mod publish;
+mod ready;
+mod recording;
+mod restore;
+mod rules;
+mod scan;
+mod scrollback;
+mod sdk;
+mod secrets;
+mod spa;
+mod state;
+mod status;
+mod terminal;
+mod testing;
 mod util;
+mod vscode;
 
-pub use app::App;
+pub use app::{App, Pick};
+pub use completions::{complete_solutions, completion_script, COMPLETE_SLNS};
+pub use error::AppError;
+pub use headless::{list_projects, list_solutions, run_project, RunOptions};
@@ -0,0 +1,121 @@
+use std::{borrow::Cow, collections::VecDeque};
+
+use crate::app::{
+    logs::strip_ansi,
+    rules::{self, Rule, RuleAction},
+    scrollback::Scrollback,
+};
+
+/// The lines of a process's output the output view shows, that is all but
+/// those muted by a rule, and those of them matching the search, by the
+/// index `Scrollback::get` takes. It takes in new lines and forgets dropped
+/// ones as they come and go, rather than going over the whole output every
+/// frame.
+pub struct OutputIndex {
+    // The process it is for
+    pid: u32,
+    // How many lines the process had pushed when last updated
+    seen: usize,
+    pub lines: VecDeque<usize>,
+    // Lowercase
+    query: String,
+    pub matches: VecDeque<usize>,
+}
+
+impl OutputIndex {
+    pub fn new(pid: u32) -> Self {
+        Self {
+            pid,
+            seen: 0,
+            lines: VecDeque::new(),
+            query: String::new(),
+            matches: VecDeque::new(),
+        }
+    }
+
+    pub fn is_for(&self, pid: u32) -> bool {
+        self.pid == pid
+    }
+
+    pub fn update(&mut self, output: &Scrollback<String>, rules: &[Rule], project: &str) {
+        let first = output.first();
+        while self.lines.front().is_some_and(|&line| line < first) {
+            self.lines.pop_front();
+        }
+        while self.matches.front().is_some_and(|&line| line < first) {
+            self.matches.pop_front();
+        }
+        // The marker saying how many were dropped is never muted
+        if first < output.dropped() && self.lines.front() != Some(&first) {
+            self.lines.push_front(first);
+        }
+
+        let mutes = rules::mutes(rules, project);
+        for line in self.seen.max(output.dropped())..output.pushed() {
+            let muted = mutes
+                && output.get(line).is_some_and(|text| {
+                    rules::find(rules, project, text)
+                        .is_some_and(|rule| rule.config.action == RuleAction::Mute)
+                });
+            if muted {
+                continue;
+            }
+            self.lines.push_back(line);
+            if self.is_match(output, line) {
+                self.matches.push_back(line);
+            }
+        }
+        self.seen = output.pushed();
+    }
+
+    /// Finds the lines matching the search, ignoring case and colors. Typing
+    /// on only narrows down the lines already found.
+    pub fn search(&mut self, output: &Scrollback<String>, query: &str) {
+        let query = query.to_lowercase();
+        if query == self.query {
+            return;
+        }
+        let narrows = !self.query.is_empty() && query.contains(&self.query);
+        self.query = query;
+        if self.query.is_empty() {
+            self.matches.clear();
+            return;
+        }
+        let candidates = if narrows {
+            std::mem::take(&mut self.matches)
+        } else {
+            self.lines.clone()
+        };
+        self.matches = candidates
+            .into_iter()
+            .filter(|&line| self.is_match(output, line))
+            .collect();
+    }
+
+    fn is_match(&self, output: &Scrollback<String>, line: usize) -> bool {
+        if self.query.is_empty() {
+            return false;
+        }
+        let Some(text) = output.get(line) else {
+            return false;
+        };
+        let text = if text.contains('\u{1b}') {
+            Cow::Owned(strip_ansi(text))
+        } else {
+            Cow::Borrowed(text.as_str())
+        };
+        // Most searches are plain ASCII, which needs no lowercase copy of every line
+        if self.query.is_ascii() {
+            text.as_bytes()
+                .windows(self.query.len())
+                .any(|window| window.eq_ignore_ascii_case(self.query.as_bytes()))
+        } else {
+            text.to_lowercase().contains(&self.query)
+        }
+    }
+
+    /// The position in `lines` of the line, or of the first shown after it.
+    pub fn position(&self, line: usize) -> usize {
+        self.lines.partition_point(|&shown| shown < line)
+    }
+}
@@ -0,0 +1,278 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use ratatui::style::Color;
+use serde_json::Value;
+
+// Printed in the Latest column of the text output, the only value with spaces
+const NOT_FOUND: &str = "Not found at the sources";
+
+/// What `dotnet list package` is asked for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Report {
+    Outdated,
+    Vulnerable,
+}
+
+impl Report {
+    fn flag(self) -> &'static str {
+        match self {
+            Self::Outdated => "--outdated",
+            Self::Vulnerable => "--vulnerable",
+        }
+    }
+}
+
+/// `dotnet list package` of the project or solution, as JSON where the SDK
+/// supports it (7.0.200 and later).
+pub fn list_command(path: &Path, report: Report, json: bool) -> Command {
+    let mut command = Command::new("dotnet");
+    command
+        .arg("list")
+        .arg(path)
+        .arg("package")
+        .arg(report.flag());
+    if json {
+        command.args(["--format", "json"]);
+    }
+    command
+}
+
+/// Whether the output says that the SDK has no `--format` option.
+pub fn format_unsupported(output: &[String]) -> bool {
+    output.iter().any(|line| {
+        line.contains("--format")
+            && (line.contains("Unrecognized") || line.contains("not recognized"))
+    })
+}
+
+/// A top-level package of a project, with the newer version there is and the
+/// vulnerabilities it has.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Package {
+    pub project: String,
+    pub id: String,
+    pub requested: String,
+    pub resolved: String,
+    pub latest: Option<String>,
+    // The highest of its vulnerabilities, like High
+    pub severity: Option<String>,
+    pub advisory: Option<String>,
+}
+
+impl Package {
+    /// Whether the latest version is a major version ahead of the resolved one.
+    pub fn major_behind(&self) -> bool {
+        let major = |version: &str| version.split('.').next()?.parse::<u64>().ok();
+        match (
+            major(&self.resolved),
+            self.latest.as_deref().and_then(major),
+        ) {
+            (Some(resolved), Some(latest)) => latest > resolved,
+            _ => false,
+        }
+    }
+
+    /// The command that moves the project to the latest version.
+    pub fn add_command(&self) -> String {
+        match &self.latest {
+            Some(latest) => format!("dotnet add package {} --version {}", self.id, latest),
+            None => format!("dotnet add package {}", self.id),
+        }
+    }
+}
+
+pub fn severity_rank(severity: &str) -> u8 {
+    match severity.to_ascii_lowercase().as_str() {
+        "critical" => 4,
+        "high" => 3,
+        "moderate" => 2,
+        "low" => 1,
+        _ => 0,
+    }
+}
+
+pub fn severity_color(severity: &str) -> Color {
+    match severity_rank(severity) {
+        3.. => Color::Red,
+        2 => Color::Yellow,
+        _ => Color::DarkGray,
+    }
+}
+
+fn project_name(path: &str) -> String {
+    let path = PathBuf::from(path.replace('\\', "/"));
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+// Keeps one entry per project and package, which each target framework lists
+fn push(packages: &mut Vec<Package>, package: Package) {
+    if !packages
+        .iter()
+        .any(|p| p.project == package.project && p.id == package.id)
+    {
+        packages.push(package);
+    }
+}
+
+/// The packages in the output of `dotnet list package --format json`, or
+/// None when it holds no JSON.
+pub fn parse_json(output: &[String]) -> Option<Vec<Package>> {
+    let text = output.join("\n");
+    let report: Value = serde_json::from_str(&text[text.find('{')?..]).ok()?;
+    let mut packages = Vec::new();
+    for project in report["projects"].as_array()? {
+        let name = project_name(project["path"].as_str().unwrap_or_default());
+        let frameworks = project["frameworks"].as_array().into_iter().flatten();
+        for package in
+            frameworks.flat_map(|f| f["topLevelPackages"].as_array().into_iter().flatten())
+        {
+            let field = |name: &str| package[name].as_str().map(str::to_string);
+            let vulnerabilities = package["vulnerabilities"].as_array().into_iter().flatten();
+            let worst = vulnerabilities
+                .max_by_key(|v| severity_rank(v["severity"].as_str().unwrap_or_default()));
+            push(
+                &mut packages,
+                Package {
+                    project: name.clone(),
+                    id: field("id").unwrap_or_default(),
+                    requested: field("requestedVersion").unwrap_or_default(),
+                    resolved: field("resolvedVersion").unwrap_or_default(),
+                    latest: field("latestVersion").filter(|latest| latest != NOT_FOUND),
+                    severity: worst.and_then(|v| v["severity"].as_str().map(str::to_string)),
+                    advisory: worst.and_then(|v| v["advisoryurl"].as_str().map(str::to_string)),
+                },
+            );
+        }
+    }
+    Some(packages)
+}
+
+/// The packages in the text output of SDKs without `--format`, from the
+/// `> Package Requested Resolved Latest` rows of the top-level tables, or
+/// `> Package Requested Resolved Severity Advisory` for vulnerabilities.
+pub fn parse_text(output: &[String], report: Report) -> Vec<Package> {
+    let mut packages = Vec::new();
+    let mut project = String::new();
+    let mut top_level = false;
+    for line in output {
+        let line = line.trim();
+        if let Some(rest) = line
+            .strip_prefix("Project `")
+            .or(line.strip_prefix("Project '"))
+        {
+            project = rest
+                .split(['`', '\''])
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            continue;
+        }
+        if line.starts_with("Top-level Package") {
+            top_level = true;
+            continue;
+        }
+        if line.starts_with("Transitive Package") {
+            top_level = false;
+            continue;
+        }
+        let Some(row) = line.strip_prefix("> ").filter(|_| top_level) else {
+            continue;
+        };
+        let row = row.replace(NOT_FOUND, "-");
+        // Auto-referenced and deprecated packages are marked after the name
+        let columns: Vec<&str> = row
+            .split_whitespace()
+            .filter(|column| !matches!(*column, "(A)" | "(D)"))
+            .collect();
+        // Resolved and Latest, or Resolved, Severity and Advisory
+        let after = match report {
+            Report::Outdated => 2,
+            Report::Vulnerable => 3,
+        };
+        if columns.len() <= after {
+            continue;
+        }
+        let (head, tail) = columns.split_at(columns.len() - after);
+        let id = head[0];
+        // A version range like [1.0.0, ) has a space
+        let requested = head[1..].join(" ");
+        let resolved = tail[0];
+        let (latest, severity, advisory) = match report {
+            Report::Outdated => (Some(tail[1]), None, None),
+            Report::Vulnerable => (None, Some(tail[1]), Some(tail[2])),
+        };
+        push(
+            &mut packages,
+            Package {
+                project: project.clone(),
+                id: id.to_string(),
+                requested,
+                resolved: resolved.to_string(),
+                latest: latest.filter(|latest| *latest != "-").map(str::to_string),
+                severity: severity.map(str::to_string),
+                advisory: advisory.map(str::to_string),
+            },
+        );
+    }
+    packages
+}
+
+/// The outdated and the vulnerable packages as one list, by project and
+/// package.
+pub fn merge(outdated: &[Package], vulnerable: &[Package]) -> Vec<Package> {
+    let mut merged: BTreeMap<(String, String), Package> = BTreeMap::new();
+    for package in outdated.iter().chain(vulnerable) {
+        let key = (package.project.clone(), package.id.to_lowercase());
+        match merged.get_mut(&key) {
+            Some(existing) => {
+                existing.latest = existing.latest.take().or(package.latest.clone());
+                existing.severity = existing.severity.take().or(package.severity.clone());
+                existing.advisory = existing.advisory.take().or(package.advisory.clone());
+            }
+            None => {
+                merged.insert(key, package.clone());
+            }
+        }
+    }
+    merged.into_values().collect()
+}
+
+/// Which packages the view lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageFilter {
+    All,
+    Vulnerable,
+    MajorBehind,
+}
+
+impl PackageFilter {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Vulnerable => "vulnerable",
+            Self::MajorBehind => "a major version behind",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::All => Self::Vulnerable,
+            Self::Vulnerable => Self::MajorBehind,
+            Self::MajorBehind => Self::All,
+        }
+    }
+
+    pub fn allows(self, package: &Package) -> bool {
+        match self {
+            Self::All => true,
+            Self::Vulnerable => package.severity.is_some(),
+            Self::MajorBehind => package.major_behind(),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use ratatui::{crossterm::event::KeyEvent, widgets::ListState};
+
+use crate::app::{filter::fuzzy_match, input::TextInput, keymap::Binding};
+
+/// The command palette: the bindings that apply where it was opened, narrowed
+/// down by a fuzzy query on their descriptions.
+pub struct Palette {
+    pub input: TextInput,
+    bindings: Vec<Binding>,
+    // Indices into `bindings` with the matched characters, best first
+    matches: Vec<(usize, Vec<usize>)>,
+    pub state: ListState,
+}
+
+impl Palette {
+    pub fn new(bindings: Vec<Binding>) -> Self {
+        let mut palette = Self {
+            input: TextInput::default(),
+            bindings,
+            matches: Vec::new(),
+            state: ListState::default(),
+        };
+        palette.refilter();
+        palette
+    }
+
+    /// Applies an editing key to the query. Returns false for keys it does
+    /// not handle.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if !self.input.handle_key(key) {
+            return false;
+        }
+        self.refilter();
+        true
+    }
+
+    fn refilter(&mut self) {
+        let mut matches: Vec<(i32, usize, Vec<usize>)> = self
+            .bindings
+            .iter()
+            .enumerate()
+            .filter_map(|(i, binding)| {
+                fuzzy_match(&self.input.value, binding.description)
+                    .map(|m| (m.score, i, m.positions))
+            })
+            .collect();
+        // Stable, so equal scores keep the order of the help overlay
+        matches.sort_by_key(|(score, _, _)| -score);
+        self.matches = matches
+            .into_iter()
+            .map(|(_, i, positions)| (i, positions))
+            .collect();
+        self.state.select((!self.matches.is_empty()).then_some(0));
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        let Some(current) = self.state.selected() else {
+            return;
+        };
+        let last = self.matches.len().saturating_sub(1);
+        self.state
+            .select(Some(current.saturating_add_signed(delta).min(last)));
+    }
+
+    /// The matching bindings, with the characters of their descriptions that
+    /// matched the query.
+    pub fn rows(&self) -> impl Iterator<Item = (&Binding, &[usize])> {
+        self.matches
+            .iter()
+            .map(|(i, positions)| (&self.bindings[*i], positions.as_slice()))
+    }
+
+    pub fn selected(&self) -> Option<&Binding> {
+        let (i, _) = self.matches.get(self.state.selected()?)?;
+        self.bindings.get(*i)
+    }
+}
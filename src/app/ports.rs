@@ -0,0 +1,196 @@
+use std::{
+    io,
+    net::{Ipv4Addr, TcpListener},
+    process::{Command, Stdio},
+};
+
+/// A port from the launch profile that something else already listens on.
+#[derive(Debug, Clone)]
+pub struct PortConflict {
+    pub url: String,
+    pub port: u16,
+    pub owner: Option<PortOwner>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PortOwner {
+    pub pid: u32,
+    pub name: Option<String>,
+}
+
+impl PortConflict {
+    pub fn describe(&self) -> String {
+        match &self.owner {
+            Some(PortOwner {
+                pid,
+                name: Some(name),
+            }) => format!("{} is used by {} (PID {})", self.url, name, pid),
+            Some(PortOwner { pid, name: None }) => format!("{} is used by PID {}", self.url, pid),
+            None => format!("{} is already in use", self.url),
+        }
+    }
+}
+
+/// The port of a URL such as `https://localhost:5001`, with the scheme's
+/// default when none is given.
+pub fn url_port(url: &str) -> Option<u16> {
+    let (scheme, rest) = url.split_once("://")?;
+    let authority = rest.split('/').next()?;
+    match authority.rsplit_once(':') {
+        // An IPv6 host without a port, like `[::1]`
+        Some((_, port)) if !port.ends_with(']') => port.parse().ok(),
+        _ => match scheme {
+            "http" => Some(80),
+            "https" => Some(443),
+            _ => None,
+        },
+    }
+}
+
+/// Replaces the port of `url`, adding one if it had none.
+pub fn with_port(url: &str, port: u16) -> String {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return url.to_string();
+    };
+    let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let host = match authority.rsplit_once(':') {
+        Some((host, port)) if !port.ends_with(']') => host,
+        _ => authority,
+    };
+    format!("{}://{}:{}{}", scheme, host, port, path)
+}
+
+/// The URLs whose port cannot be bound on this machine.
+pub fn find_conflicts(urls: &[String]) -> Vec<PortConflict> {
+    urls.iter()
+        .filter_map(|url| {
+            let port = url_port(url)?;
+            let in_use = matches!(
+                TcpListener::bind((Ipv4Addr::LOCALHOST, port)),
+                Err(e) if e.kind() == io::ErrorKind::AddrInUse
+            );
+            in_use.then(|| PortConflict {
+                url: url.clone(),
+                port,
+                owner: port_owner(port),
+            })
+        })
+        .collect()
+}
+
+/// A port nobody listens on, picked by the OS.
+pub fn free_port() -> io::Result<u16> {
+    Ok(TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?
+        .local_addr()?
+        .port())
+}
+
+pub fn kill_process(pid: u32) -> io::Result<()> {
+    #[cfg(windows)]
+    let mut command = {
+        let mut command = Command::new("taskkill");
+        command.args(["/F", "/PID", &pid.to_string()]);
+        command
+    };
+    #[cfg(not(windows))]
+    let mut command = {
+        let mut command = Command::new("kill");
+        command.arg(pid.to_string());
+        command
+    };
+
+    let status = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!(
+            "could not kill PID {} ({})",
+            pid, status
+        )));
+    }
+    Ok(())
+}
+
+/// Finds the process listening on `port`, where the platform makes that cheap.
+#[cfg(target_os = "linux")]
+fn port_owner(port: u16) -> Option<PortOwner> {
+    use std::fs;
+
+    // Listening sockets in /proc/net/tcp{,6} lead to an inode, and the process
+    // holding that inode has it among its file descriptors
+    let inode = ["/proc/net/tcp", "/proc/net/tcp6"]
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .flat_map(|table| {
+            table
+                .lines()
+                .skip(1)
+                .filter_map(|line| {
+                    let fields: Vec<&str> = line.split_whitespace().collect();
+                    let local_port = fields.get(1)?.rsplit_once(':')?.1;
+                    let listening = *fields.get(3)? == "0A";
+                    (listening && u16::from_str_radix(local_port, 16).ok()? == port)
+                        .then(|| fields.get(9).map(|inode| inode.to_string()))
+                        .flatten()
+                })
+                .collect::<Vec<_>>()
+        })
+        .next()?;
+    let socket = format!("socket:[{}]", inode);
+
+    fs::read_dir("/proc").ok()?.flatten().find_map(|entry| {
+        let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+        let owns = fs::read_dir(entry.path().join("fd"))
+            .ok()?
+            .flatten()
+            .any(|fd| {
+                fs::read_link(fd.path()).is_ok_and(|target| target.as_os_str() == socket.as_str())
+            });
+        owns.then(|| PortOwner {
+            pid,
+            name: fs::read_to_string(entry.path().join("comm"))
+                .ok()
+                .map(|name| name.trim().to_string()),
+        })
+    })
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn port_owner(port: u16) -> Option<PortOwner> {
+    let output = Command::new("lsof")
+        .args(["-nP", &format!("-iTCP:{}", port), "-sTCP:LISTEN", "-Fpc"])
+        .stdin(Stdio::null())
+        .output()
+        .ok()?;
+    // Field output: `p<pid>` and `c<command>` lines
+    let text = String::from_utf8_lossy(&output.stdout);
+    let pid = text
+        .lines()
+        .find_map(|line| line.strip_prefix('p')?.parse().ok())?;
+    let name = text
+        .lines()
+        .find_map(|line| line.strip_prefix('c'))
+        .map(str::to_string);
+    Some(PortOwner { pid, name })
+}
+
+#[cfg(windows)]
+fn port_owner(port: u16) -> Option<PortOwner> {
+    let output = Command::new("netstat")
+        .args(["-ano", "-p", "TCP"])
+        .stdin(Stdio::null())
+        .output()
+        .ok()?;
+    // Rows look like `TCP  0.0.0.0:5000  0.0.0.0:0  LISTENING  1234`
+    let text = String::from_utf8_lossy(&output.stdout);
+    let pid = text.lines().find_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let local_port = fields.get(1)?.rsplit_once(':')?.1;
+        (fields.get(3) == Some(&"LISTENING") && local_port.parse() == Ok(port))
+            .then(|| fields.get(4)?.parse().ok())
+            .flatten()
+    })?;
+    Some(PortOwner { pid, name: None })
+}
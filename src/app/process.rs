@@ -0,0 +1,575 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::mpsc::{self, Receiver, SyncSender, TryRecvError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::app::{
+    diagnostics::DiagnosticParser,
+    docker::Container,
+    error::{dotnet_spawn_error, AppError, Result},
+    logs::strip_ansi,
+    recording::Recording,
+    scrollback::Scrollback,
+    spa::Spa,
+};
+
+// How long to keep waiting for buffered output after the process has exited.
+// Grandchildren that inherited the pipes can keep them open indefinitely.
+const OUTPUT_GRACE: Duration = Duration::from_secs(1);
+
+// Lines read ahead of the app, past which the readers wait, and so does the
+// process writing them, rather than the backlog growing without end. Also
+// the most lines a drain takes at once, so a flood can't stall the UI.
+const PENDING_LINES: usize = 10_000;
+
+pub struct OutputLine {
+    pub text: String,
+    pub is_stderr: bool,
+}
+
+impl OutputLine {
+    /// MSBuild reports compile errors on stdout, so look at the text as well.
+    pub fn is_error(&self) -> bool {
+        self.is_stderr || self.text.contains(": error ")
+    }
+}
+
+/// A child process whose stdout and stderr are read line by line on worker threads.
+pub struct Job {
+    child: Child,
+    rx: Receiver<OutputLine>,
+    disconnected: bool,
+    status: Option<ExitStatus>,
+    exited_at: Option<Instant>,
+    // Left running when sln-runner quits
+    detached: bool,
+    #[cfg(windows)]
+    tree: Option<ProcessTree>,
+    pub started: Instant,
+}
+
+impl Job {
+    pub fn spawn(command: &mut Command) -> io::Result<Self> {
+        // A process group of its own, so stopping it also reaches the app
+        // that `dotnet run` starts, and so does a kill of the group even after
+        // `dotnet run` itself is gone
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            command.creation_flags(windows_sys::Win32::System::Threading::CREATE_NEW_PROCESS_GROUP);
+        }
+
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        let (tx, rx) = mpsc::sync_channel(PENDING_LINES);
+        if let Some(stdout) = child.stdout.take() {
+            forward_lines(stdout, false, tx.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward_lines(stderr, true, tx);
+        }
+
+        Ok(Self {
+            // Without a job object, kill() falls back to taskkill
+            #[cfg(windows)]
+            tree: ProcessTree::assign(&child).ok(),
+            child,
+            rx,
+            disconnected: false,
+            status: None,
+            exited_at: None,
+            detached: false,
+            started: Instant::now(),
+        })
+    }
+
+    pub fn drain(&mut self) -> Vec<OutputLine> {
+        let mut lines = Vec::new();
+        while lines.len() < PENDING_LINES {
+            match self.rx.try_recv() {
+                Ok(line) => lines.push(line),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.disconnected = true;
+                    break;
+                }
+            }
+        }
+        lines
+    }
+
+    pub fn id(&self) -> u32 {
+        self.child.id()
+    }
+
+    pub fn has_exited(&self) -> bool {
+        self.exited_at.is_some()
+    }
+
+    /// How long the process has been running, or ran until it exited.
+    pub fn uptime(&self) -> Duration {
+        self.exited_at.unwrap_or_else(Instant::now) - self.started
+    }
+
+    /// Returns the exit status once the process has exited and its output has been drained.
+    pub fn poll_exit(&mut self) -> io::Result<Option<ExitStatus>> {
+        if self.status.is_none() {
+            self.status = self.child.try_wait()?;
+            if self.status.is_some() {
+                self.exited_at = Some(Instant::now());
+            }
+        }
+
+        let output_done = self.disconnected
+            || self
+                .exited_at
+                .is_some_and(|exited| exited.elapsed() >= OUTPUT_GRACE);
+
+        Ok(self.status.filter(|_| output_done))
+    }
+
+    /// Asks the process to shut down the way Ctrl+C would: SIGINT to its
+    /// process group, or Ctrl+Break on Windows. ASP.NET Core runs its
+    /// shutdown hooks for either.
+    pub fn interrupt(&mut self) -> io::Result<()> {
+        #[cfg(unix)]
+        {
+            // SAFETY: kill() only takes plain integers
+            if unsafe { libc::kill(-(self.child.id() as libc::pid_t), libc::SIGINT) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        #[cfg(windows)]
+        {
+            use windows_sys::Win32::System::Console::{GenerateConsoleCtrlEvent, CTRL_BREAK_EVENT};
+
+            // SAFETY: GenerateConsoleCtrlEvent only takes plain integers
+            if unsafe { GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, self.child.id()) } == 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+
+    /// Kills the process and everything it started, and waits for it to exit.
+    pub fn kill(&mut self) -> io::Result<ExitStatus> {
+        // The process group goes by the PID, which may belong to someone else
+        // once the process has been reaped
+        #[cfg(unix)]
+        if self.status.is_none() && matches!(self.child.try_wait(), Ok(None)) {
+            // SAFETY: kill() only takes plain integers
+            unsafe { libc::kill(-(self.child.id() as libc::pid_t), libc::SIGKILL) };
+        }
+        #[cfg(windows)]
+        match &self.tree {
+            Some(tree) => tree.terminate(),
+            // taskkill goes by PID, which may belong to someone else once
+            // the process has been reaped
+            None if matches!(self.child.try_wait(), Ok(None)) => {
+                let _ = Command::new("taskkill")
+                    .args(["/T", "/F", "/PID", &self.child.id().to_string()])
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .status();
+            }
+            None => {}
+        }
+
+        let _ = self.child.kill();
+        let status = self.child.wait()?;
+        self.exited_at.get_or_insert_with(Instant::now);
+        Ok(status)
+    }
+}
+
+impl Job {
+    /// Lets the process outlive sln-runner instead of being killed with it.
+    pub fn detach(&mut self) {
+        self.detached = true;
+        #[cfg(windows)]
+        if let Some(tree) = &self.tree {
+            tree.release();
+        }
+    }
+}
+
+impl Drop for Job {
+    /// However sln-runner exits, nothing it started outlives it unless
+    /// detached.
+    fn drop(&mut self) {
+        if !self.detached {
+            let _ = self.kill();
+        }
+    }
+}
+
+/// A job object holding the process and everything it starts. Windows kills
+/// them all when the last handle to it closes, which also happens when
+/// sln-runner itself dies.
+#[cfg(windows)]
+struct ProcessTree(windows_sys::Win32::Foundation::HANDLE);
+
+#[cfg(windows)]
+impl ProcessTree {
+    fn assign(child: &Child) -> io::Result<Self> {
+        use std::os::windows::io::AsRawHandle;
+        use windows_sys::Win32::System::JobObjects::{
+            AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+            SetInformationJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+            JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+        };
+
+        // SAFETY: the handles are valid for the duration of the calls, and
+        // `limits` is the structure the information class expects
+        unsafe {
+            let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if job.is_null() {
+                return Err(io::Error::last_os_error());
+            }
+            let tree = Self(job);
+
+            let mut limits = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
+            limits.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            let set = SetInformationJobObject(
+                job,
+                JobObjectExtendedLimitInformation,
+                &limits as *const _ as *const std::ffi::c_void,
+                std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            );
+            if set == 0 || AssignProcessToJobObject(job, child.as_raw_handle()) == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(tree)
+        }
+    }
+
+    /// Drops the kill-on-close limit, so the processes survive the handle.
+    fn release(&self) {
+        use windows_sys::Win32::System::JobObjects::{
+            JobObjectExtendedLimitInformation, SetInformationJobObject,
+            JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        };
+
+        let limits = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
+        // SAFETY: as in assign()
+        unsafe {
+            SetInformationJobObject(
+                self.0,
+                JobObjectExtendedLimitInformation,
+                &limits as *const _ as *const std::ffi::c_void,
+                std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            )
+        };
+    }
+
+    fn terminate(&self) {
+        // SAFETY: the handle stays open until drop
+        unsafe { windows_sys::Win32::System::JobObjects::TerminateJobObject(self.0, 1) };
+    }
+}
+
+#[cfg(windows)]
+impl Drop for ProcessTree {
+    fn drop(&mut self) {
+        // SAFETY: the handle is owned by this value and closed only here
+        unsafe { windows_sys::Win32::Foundation::CloseHandle(self.0) };
+    }
+}
+
+/// Runs `dotnet --version` and returns the SDK version it reports.
+pub fn dotnet_version() -> Result<String> {
+    let output = Command::new("dotnet")
+        .arg("--version")
+        .stdin(Stdio::null())
+        .output()
+        .map_err(dotnet_spawn_error)?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(AppError::Io(io::Error::other(format!(
+            "dotnet --version failed ({}): {}",
+            output.status,
+            stderr.lines().next().unwrap_or_default()
+        ))));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// How `dotnet` is invoked to run a project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunMode {
+    /// `dotnet run`, which builds the project first.
+    Run,
+    /// `dotnet run --no-build` after a separate build.
+    NoBuild,
+    /// `dotnet watch run`, rebuilding and hot reloading on changes.
+    Watch,
+}
+
+/// `dotnet build` of a project or solution with the MSBuild arguments, with
+/// node reuse off, since it would leave MSBuild worker processes behind when
+/// a build is cancelled.
+pub fn build_command(path: &Path, configuration: &str, build_args: &[String]) -> Command {
+    let mut command = Command::new("dotnet");
+    command
+        .arg("build")
+        .arg("--configuration")
+        .arg(configuration)
+        .arg("-nodeReuse:false")
+        .args(build_args)
+        .arg(path)
+        .env("MSBUILDDISABLENODEREUSE", "1");
+    command
+}
+
+/// `dotnet run` of the project in `project_dir`, or `dotnet watch run`.
+pub fn run_command(
+    project_dir: &Path,
+    launch_profile: Option<&str>,
+    configuration: &str,
+    mode: RunMode,
+    build_args: &[String],
+    args: &[String],
+    env: &BTreeMap<String, String>,
+) -> Command {
+    let mut command = Command::new("dotnet");
+    if mode == RunMode::Watch {
+        // Watch otherwise prompts on stdin, which belongs to the TUI
+        command.arg("watch").arg("--non-interactive");
+    }
+    command
+        .arg("run")
+        .arg("--configuration")
+        .arg(configuration)
+        .envs(env)
+        .current_dir(project_dir);
+    if mode == RunMode::NoBuild {
+        command.arg("--no-build");
+    }
+    if let Some(profile) = launch_profile {
+        command.arg("--launch-profile").arg(profile);
+    }
+    command.args(build_args);
+    if !args.is_empty() {
+        command.arg("--").args(args);
+    }
+    command
+}
+
+/// A process left running when sln-runner quit, for the summary printed after.
+pub struct DetachedProcess {
+    pub project: String,
+    pub pid: u32,
+    pub urls: Vec<String>,
+    // The name of the container it runs in, if any
+    pub container: Option<String>,
+}
+
+/// Lists detached processes and a command that stops them, for copying out
+/// of the terminal.
+pub fn detached_summary(processes: &[DetachedProcess]) -> String {
+    let width = processes.iter().map(|p| p.project.len()).max().unwrap_or(0);
+    let mut summary = String::from("Left running:\n");
+    for process in processes {
+        let line = match &process.container {
+            Some(container) => format!(
+                "  {:width$}  {} {}",
+                process.project,
+                container,
+                process.urls.join(" "),
+                width = width
+            ),
+            None => format!(
+                "  {:width$}  PID {:<7} {}",
+                process.project,
+                process.pid,
+                process.urls.join(" "),
+                width = width
+            ),
+        };
+        summary.push_str(line.trim_end());
+        summary.push('\n');
+    }
+
+    // Each process leads a process group (or job tree) with the app it started
+    let pids: Vec<u32> = processes
+        .iter()
+        .filter(|p| p.container.is_none())
+        .map(|p| p.pid)
+        .collect();
+    let containers: Vec<&str> = processes
+        .iter()
+        .filter_map(|p| p.container.as_deref())
+        .collect();
+    let mut commands = Vec::new();
+    if !pids.is_empty() {
+        commands.push(if cfg!(windows) {
+            let pids: Vec<String> = pids.iter().map(|pid| format!("/PID {}", pid)).collect();
+            format!("taskkill /T /F {}", pids.join(" "))
+        } else {
+            let pids: Vec<String> = pids.iter().map(|pid| format!("-{}", pid)).collect();
+            format!("kill -INT -- {}", pids.join(" "))
+        });
+    }
+    if !containers.is_empty() {
+        commands.push(format!("docker stop {}", containers.join(" ")));
+    }
+    summary.push_str("Stop them with:\n");
+    for command in commands {
+        summary.push_str("  ");
+        summary.push_str(&command);
+        summary.push('\n');
+    }
+    summary
+}
+
+/// A `dotnet run` process together with the output it has produced so far.
+pub struct RunningProcess {
+    pub job: Job,
+    pub project: String,
+    // Path of the solution it was started from
+    pub solution: String,
+    pub project_dir: PathBuf,
+    pub launch_profile: Option<String>,
+    pub configuration: String,
+    pub mode: RunMode,
+    // MSBuild arguments, given again on a restart
+    pub build_args: Vec<String>,
+    // Passed to the program after `--`
+    pub args: Vec<String>,
+    pub env: BTreeMap<String, String>,
+    // Compiler errors from `dotnet run` building the project itself, also
+    // picked out for the problems list
+    pub build_errors: Vec<String>,
+    pub diagnostics: DiagnosticParser,
+    // Addresses from the app's "Now listening on" lines
+    pub urls: Vec<String>,
+    // The launch profile's applicationUrl, until the app reports its own
+    pub profile_urls: Vec<String>,
+    pub output: Scrollback<String>,
+    pub status: Option<ExitStatus>,
+    // When a graceful stop was requested, and whether to start again after
+    pub stopping: Option<Instant>,
+    pub restarting: bool,
+    // Exited with an error without being stopped
+    pub crashed: bool,
+    // Whether a crash restarts it, how often it crashed in a row and when the
+    // next restart is due
+    pub auto_restart: bool,
+    pub crashes: u32,
+    pub restart_at: Option<Instant>,
+    // Set when it runs the project's image with `docker run`
+    pub container: Option<Container>,
+    // Set when it is the dev server of a project's single-page app
+    pub spa: Option<Spa>,
+    // The file the output of its current run is saved to, with record_output
+    pub recording: Option<Recording>,
+}
+
+impl RunningProcess {
+    pub fn state_label(&self) -> String {
+        match (self.status, self.stopping) {
+            (Some(_), _) if self.restart_at.is_some() => {
+                let due = self.restart_at.map_or(Duration::ZERO, |at| {
+                    at.saturating_duration_since(Instant::now())
+                });
+                format!("crashed, restart in {}s", due.as_secs_f32().ceil())
+            }
+            (Some(status), _) if self.crashed => format!("crashed ({})", status),
+            (Some(status), _) => format!("exited ({})", status),
+            (None, Some(_)) if self.restarting => "restarting…".to_string(),
+            (None, Some(_)) => "stopping…".to_string(),
+            (None, None) => "running".to_string(),
+        }
+    }
+
+    /// The addresses the app listens on, as far as they are known.
+    pub fn known_urls(&self) -> &[String] {
+        if self.urls.is_empty() {
+            &self.profile_urls
+        } else {
+            &self.urls
+        }
+    }
+}
+
+/// The address from an ASP.NET Core `Now listening on: <url>` line, with
+/// wildcard hosts replaced by localhost so it can be opened.
+pub fn listening_url(line: &str) -> Option<String> {
+    let line = strip_ansi(line);
+    let (_, url) = line.split_once("Now listening on:")?;
+    let url = url.trim();
+    let (scheme, rest) = url.split_once("://")?;
+    let (host, port) = match rest.rsplit_once(':') {
+        Some((host, port)) => (host, format!(":{}", port)),
+        None => (rest, String::new()),
+    };
+    let host = match host {
+        "0.0.0.0" | "[::]" | "*" | "+" => "localhost",
+        host => host,
+    };
+    Some(format!("{}://{}{}", scheme, host, port))
+}
+
+/// Opens `url` with the platform's default handler without waiting for it.
+pub fn open_url(url: &str) -> io::Result<()> {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else {
+        Command::new("xdg-open")
+    };
+    let mut child = command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => io::Error::new(
+                e.kind(),
+                format!("{} not found", command.get_program().to_string_lossy()),
+            ),
+            _ => e,
+        })?;
+    // Reap it in the background so it doesn't linger as a zombie
+    thread::spawn(move || child.wait());
+    Ok(())
+}
+
+fn forward_lines(stream: impl Read + Send + 'static, is_stderr: bool, tx: SyncSender<OutputLine>) {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(stream);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    let text = String::from_utf8_lossy(&buf)
+                        .trim_end_matches(['\r', '\n'])
+                        .to_string();
+                    if tx.send(OutputLine { text, is_stderr }).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,51 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use regex::Regex;
+
+use crate::app::{logs::strip_ansi, util::target_frameworks};
+
+/// Runtime identifiers the publish prompt offers besides none and a custom one.
+pub const RUNTIMES: &[&str] = &[
+    "win-x64",
+    "win-arm64",
+    "linux-x64",
+    "linux-arm64",
+    "linux-musl-x64",
+    "osx-x64",
+    "osx-arm64",
+];
+
+// `  Api -> /src/Api/bin/Release/net8.0/linux-x64/publish/`
+static PUBLISH_DIR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*\S+ -> (?P<path>.*[/\\]publish[/\\]?)\s*$").expect("valid publish regex")
+});
+
+/// The directory `dotnet publish` reports having published to, if the line
+/// is where it says so.
+pub fn publish_dir(line: &str) -> Option<PathBuf> {
+    PUBLISH_DIR
+        .captures(&strip_ansi(line))
+        .map(|captures| PathBuf::from(&captures["path"]))
+}
+
+/// Where `dotnet publish` puts the output by default, for when it didn't say:
+/// `bin/<configuration>/<framework>/[<runtime>/]publish` of the project.
+pub fn default_publish_dir(
+    project_path: &Path,
+    configuration: &str,
+    runtime: Option<&str>,
+) -> Option<PathBuf> {
+    let framework = target_frameworks(project_path).into_iter().next()?;
+    let mut dir = project_path
+        .parent()?
+        .join("bin")
+        .join(configuration)
+        .join(framework);
+    if let Some(runtime) = runtime {
+        dir.push(runtime);
+    }
+    Some(dir.join("publish"))
+}
@@ -0,0 +1,162 @@
+use std::{
+    io::{Read, Write},
+    net::{SocketAddr, TcpStream, ToSocketAddrs},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use regex::Regex;
+use serde::Deserialize;
+
+const PROBE_INTERVAL: Duration = Duration::from_millis(500);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// When a group member counts as started, so its dependents can follow.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Readiness {
+    /// A line of its output matches the regex.
+    Log(String),
+    /// The port on localhost accepts connections.
+    Port(u16),
+    /// A GET of the URL answers 200.
+    Http(String),
+}
+
+impl Readiness {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Log(pattern) => format!("a log line matching {}", pattern),
+            Self::Port(port) => format!("port {}", port),
+            Self::Http(url) => format!("{} to answer 200", url),
+        }
+    }
+
+    /// Checks the condition before anything is started, so a typo doesn't
+    /// surface halfway through a group.
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Log(pattern) => Regex::new(pattern).map(|_| ()).map_err(|e| e.to_string()),
+            Self::Port(_) => Ok(()),
+            Self::Http(url) => parse_http_url(url).map(|_| ()),
+        }
+    }
+
+    /// The compiled pattern of a log condition.
+    pub fn log_regex(&self) -> Option<Regex> {
+        match self {
+            Self::Log(pattern) => Regex::new(pattern).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Polls a port or health URL on a worker thread until it is up.
+pub struct Probe {
+    rx: Receiver<()>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Probe {
+    /// Starts probing, or returns None for conditions met by the output
+    /// instead.
+    pub fn start(readiness: &Readiness) -> Option<Self> {
+        let check: Box<dyn Fn() -> bool + Send> = match readiness.clone() {
+            Readiness::Log(_) => return None,
+            Readiness::Port(port) => Box::new(move || port_open(port)),
+            Readiness::Http(url) => Box::new(move || http_ok(&url)),
+        };
+        let (tx, rx) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&stop);
+        thread::spawn(move || {
+            while !stopped.load(Ordering::Relaxed) {
+                if check() {
+                    let _ = tx.send(());
+                    return;
+                }
+                thread::sleep(PROBE_INTERVAL);
+            }
+        });
+        Some(Self { rx, stop })
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.rx.try_recv().is_ok()
+    }
+}
+
+impl Drop for Probe {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+fn port_open(port: u16) -> bool {
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT).is_ok()
+}
+
+/// Splits `http://host:port/path` into the address to connect to, the host
+/// header and the path.
+fn parse_http_url(url: &str) -> Result<(String, String, String), String> {
+    let Some(rest) = url.strip_prefix("http://") else {
+        return Err(format!(
+            "{} is not an http:// URL; use a port check for HTTPS endpoints",
+            url
+        ));
+    };
+    let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    if authority.is_empty() {
+        return Err(format!("{} has no host", url));
+    }
+    let addr = if authority
+        .rsplit_once(':')
+        .is_some_and(|(_, port)| !port.ends_with(']'))
+    {
+        authority.to_string()
+    } else {
+        format!("{}:80", authority)
+    };
+    let path = if path.is_empty() { "/" } else { path };
+    Ok((addr, authority.to_string(), path.to_string()))
+}
+
+fn http_ok(url: &str) -> bool {
+    let Ok((addr, host, path)) = parse_http_url(url) else {
+        return false;
+    };
+    let Some(addr) = addr
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+    else {
+        return false;
+    };
+    let Ok(mut stream) = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) else {
+        return false;
+    };
+    let _ = stream.set_read_timeout(Some(CONNECT_TIMEOUT));
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        path, host
+    );
+    if stream.write_all(request.as_bytes()).is_err() {
+        return false;
+    }
+    // Only the status line matters
+    let mut head = [0; 32];
+    let Ok(read) = stream.read(&mut head) else {
+        return false;
+    };
+    let status = String::from_utf8_lossy(&head[..read]);
+    status
+        .split_whitespace()
+        .nth(1)
+        .is_some_and(|code| code == "200")
+}
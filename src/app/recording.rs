@@ -0,0 +1,114 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::Local;
+
+use crate::app::{error::Result, logs::strip_ansi};
+
+/// Where recordings go without `record_path`.
+pub fn default_template() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| {
+        dir.join("sln-runner")
+            .join("runs")
+            .join("{project}")
+            .join("{timestamp}.log")
+    })
+}
+
+/// The output of a run saved to a file as it streams, without its colors.
+/// Past `max_bytes` the file starts over, keeping what it had as `<file>.1`.
+pub struct Recording {
+    pub path: PathBuf,
+    file: BufWriter<File>,
+    size: u64,
+    max_bytes: u64,
+    // Lines in the file, to open it at the end
+    pub lines: u32,
+}
+
+impl Recording {
+    /// Creates the file for a run of the project from the path template,
+    /// with `{project}` and `{timestamp}` filled in.
+    pub fn create(template: &Path, project: &str, max_bytes: u64) -> Result<Self> {
+        let timestamp = Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
+        let template = template
+            .to_string_lossy()
+            .replace("{project}", &project.replace(['/', '\\', ':'], "_"))
+            .replace("{timestamp}", &timestamp);
+        let first = PathBuf::from(template);
+        if let Some(dir) = first.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        // Runs started within the same second get a number
+        let mut path = first.clone();
+        let mut number = 1;
+        let file = loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => break file,
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists && number < 100 => {
+                    number += 1;
+                    path = numbered(&first, number);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        };
+        Ok(Self {
+            path,
+            file: BufWriter::new(file),
+            size: 0,
+            max_bytes: max_bytes.max(1),
+            lines: 0,
+        })
+    }
+
+    pub fn write(&mut self, line: &str) -> Result<()> {
+        if self.size >= self.max_bytes {
+            self.rotate()?;
+        }
+        let line = strip_ansi(line);
+        writeln!(self.file, "{}", line)?;
+        self.size += line.len() as u64 + 1;
+        self.lines += 1;
+        Ok(())
+    }
+
+    /// Writes out what is buffered, so the file is up to date for reading.
+    pub fn flush(&mut self) -> Result<()> {
+        self.file.flush()?;
+        Ok(())
+    }
+
+    // Moves the file to <file>.1, replacing an earlier one, and starts it over
+    fn rotate(&mut self) -> Result<()> {
+        self.file.flush()?;
+        let mut previous = self.path.clone().into_os_string();
+        previous.push(".1");
+        let previous = PathBuf::from(previous);
+        fs::rename(&self.path, &previous)?;
+        self.file = BufWriter::new(File::create(&self.path)?);
+        self.size = 0;
+        self.lines = 0;
+        let name = previous
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.write(&format!("──── earlier output in {} ────", name))
+    }
+}
+
+// The path with -<number> before its extension
+fn numbered(path: &Path, number: u32) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = match path.extension() {
+        Some(extension) => format!("{}-{}.{}", stem, number, extension.to_string_lossy()),
+        None => format!("{}-{}", stem, number),
+    };
+    path.with_file_name(name)
+}
@@ -0,0 +1,66 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::app::logs::strip_ansi;
+
+// `error NU1301: Unable to load the service index for source https://.../index.json.`
+// followed by `Response status code does not indicate success: 401 (Unauthorized).`,
+// on the same line or the next
+static STATUS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?P<status>401|403)\b\s*\((?:unauthorized|forbidden)\)")
+        .expect("valid status regex")
+});
+
+static FEED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\bsource\s+'?(?P<feed>https?://[^\s']+?)'?\.?(?:\s|$)")
+        .expect("valid feed regex")
+});
+
+/// A NuGet feed turning the restore away for its credentials.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedAuthError {
+    pub feed: Option<String>,
+    pub status: u16,
+}
+
+impl FeedAuthError {
+    /// What to do about it, for the log.
+    pub fn hint(&self) -> String {
+        let feed = self.feed.as_deref().unwrap_or("A NuGet feed");
+        let reason = match self.status {
+            401 => "did not accept the credentials (401)",
+            _ => "denied access (403)",
+        };
+        format!(
+            "{} {}. Renew the feed's credentials, with `dotnet nuget update source` or the credential provider of the feed, then restore again with Ctrl+R",
+            feed, reason
+        )
+    }
+}
+
+/// Watches restore output for a feed refusing the credentials.
+#[derive(Debug, Default)]
+pub struct FeedAuthDetector {
+    // The feed the last line about a source named
+    feed: Option<String>,
+    pub error: Option<FeedAuthError>,
+}
+
+impl FeedAuthDetector {
+    pub fn feed(&mut self, line: &str) {
+        if self.error.is_some() {
+            return;
+        }
+        let line = strip_ansi(line);
+        if let Some(captures) = FEED.captures(&line) {
+            self.feed = Some(captures["feed"].to_string());
+        }
+        if let Some(captures) = STATUS.captures(&line) {
+            self.error = Some(FeedAuthError {
+                feed: self.feed.clone(),
+                status: captures["status"].parse().unwrap_or(401),
+            });
+        }
+    }
+}
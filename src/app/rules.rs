@@ -0,0 +1,106 @@
+use std::{borrow::Cow, str::FromStr};
+
+use ratatui::style::Color;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::app::logs::strip_ansi;
+
+/// What an output rule does with the lines it matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleAction {
+    /// Shows them in the rule's color.
+    Highlight,
+    /// Hides them from the output view, counting how many.
+    Mute,
+    /// Flashes the status bar and rings the bell as one arrives.
+    Alert,
+}
+
+impl RuleAction {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Highlight => "highlight",
+            Self::Mute => "mute",
+            Self::Alert => "alert",
+        }
+    }
+}
+
+/// A rule for the lines of process output, from `[[output_rules]]`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutputRule {
+    pub pattern: String,
+    pub action: RuleAction,
+    // A name like "yellow" or "#ffa500"
+    pub color: Option<String>,
+    // The project it applies to, by name, or all of them
+    pub project: Option<String>,
+}
+
+/// An output rule ready to match, and whether it is on for the session.
+pub struct Rule {
+    pub config: OutputRule,
+    pub color: Color,
+    pub enabled: bool,
+    regex: Regex,
+}
+
+impl Rule {
+    fn compile(config: OutputRule) -> Result<Self, String> {
+        let regex = Regex::new(&config.pattern).map_err(|e| e.to_string())?;
+        let color = match &config.color {
+            Some(name) => {
+                Color::from_str(name).map_err(|_| format!("unknown color \"{}\"", name))?
+            }
+            None if config.action == RuleAction::Alert => Color::Red,
+            None => Color::Yellow,
+        };
+        Ok(Self {
+            config,
+            color,
+            enabled: true,
+            regex,
+        })
+    }
+
+    /// Whether it is switched on for the project.
+    fn applies(&self, project: &str) -> bool {
+        self.enabled && self.config.project.as_deref().is_none_or(|p| p == project)
+    }
+}
+
+/// The rules of the config that compile, and a warning for each of the rest.
+pub fn compile(configs: &[OutputRule]) -> (Vec<Rule>, Vec<String>) {
+    let mut rules = Vec::new();
+    let mut warnings = Vec::new();
+    for config in configs {
+        match Rule::compile(config.clone()) {
+            Ok(rule) => rules.push(rule),
+            Err(e) => warnings.push(format!("Output rule {} ignored: {}", config.pattern, e)),
+        }
+    }
+    (rules, warnings)
+}
+
+/// The first rule switched on for the project that matches the line, which
+/// is matched without its colors.
+pub fn find<'a>(rules: &'a [Rule], project: &str, line: &str) -> Option<&'a Rule> {
+    let mut rules = rules.iter().filter(|rule| rule.applies(project)).peekable();
+    rules.peek()?;
+    let line = if line.contains('\u{1b}') {
+        Cow::Owned(strip_ansi(line))
+    } else {
+        Cow::Borrowed(line)
+    };
+    rules.find(|rule| rule.regex.is_match(&line))
+}
+
+/// Whether any rule switched on for the project mutes lines.
+pub fn mutes(rules: &[Rule], project: &str) -> bool {
+    rules
+        .iter()
+        .any(|rule| rule.config.action == RuleAction::Mute && rule.applies(project))
+}
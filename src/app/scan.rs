@@ -0,0 +1,80 @@
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, TryRecvError},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Instant,
+};
+
+use crate::app::util::{find_sln_files, IgnoreList, SolutionFile};
+
+pub enum ScanEvent {
+    Found(SolutionFile),
+    Done,
+}
+
+pub struct Scan {
+    rx: Receiver<ScanEvent>,
+    cancel: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+    pub started: Instant,
+    pub found: usize,
+}
+
+impl Scan {
+    pub fn start(roots: Vec<PathBuf>, excludes: Vec<String>, ignore: IgnoreList) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let worker_cancel = Arc::clone(&cancel);
+
+        let handle = thread::spawn(move || {
+            find_sln_files(&roots, &excludes, &ignore, &worker_cancel, |sln| {
+                let _ = tx.send(ScanEvent::Found(sln));
+            });
+            let _ = tx.send(ScanEvent::Done);
+        });
+
+        Self {
+            rx,
+            cancel,
+            handle: Some(handle),
+            started: Instant::now(),
+            found: 0,
+        }
+    }
+
+    /// Returns everything the worker has sent since the last call, without blocking.
+    pub fn drain(&mut self) -> Vec<ScanEvent> {
+        let mut events = Vec::new();
+        loop {
+            match self.rx.try_recv() {
+                Ok(ScanEvent::Found(sln)) => {
+                    self.found += 1;
+                    events.push(ScanEvent::Found(sln));
+                }
+                Ok(ScanEvent::Done) | Err(TryRecvError::Disconnected) => {
+                    events.push(ScanEvent::Done);
+                    break;
+                }
+                Err(TryRecvError::Empty) => break,
+            }
+        }
+        events
+    }
+
+    pub fn cancel(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for Scan {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
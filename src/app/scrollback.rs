@@ -0,0 +1,270 @@
+use std::collections::VecDeque;
+
+/// A line kept in a scrollback, which knows how much memory its text takes
+/// and how to say that earlier ones were dropped.
+pub trait Item {
+    fn bytes(&self) -> usize;
+    fn dropped_marker(count: usize) -> Self;
+}
+
+impl Item for String {
+    fn bytes(&self) -> usize {
+        self.len()
+    }
+
+    fn dropped_marker(count: usize) -> Self {
+        format!("… {} earlier lines dropped", count)
+    }
+}
+
+/// The newest lines of a stream, up to a number of lines and of bytes of
+/// text, dropping the oldest past either. Once any were dropped, a marker
+/// saying how many comes first.
+pub struct Scrollback<T> {
+    lines: VecDeque<T>,
+    bytes: usize,
+    max_lines: usize,
+    max_bytes: usize,
+    dropped: usize,
+    marker: Option<T>,
+}
+
+impl<T: Item> Scrollback<T> {
+    pub fn new(max_lines: usize, max_bytes: usize) -> Self {
+        Self {
+            lines: VecDeque::new(),
+            bytes: 0,
+            max_lines: max_lines.max(1),
+            max_bytes: max_bytes.max(1),
+            dropped: 0,
+            marker: None,
+        }
+    }
+
+    /// Adds the line, dropping the oldest ones over the limits, and returns
+    /// how many were dropped.
+    pub fn push(&mut self, line: T) -> usize {
+        self.push_with(line, |_| {})
+    }
+
+    /// Like `push`, showing each line dropped to `dropped` on its way out.
+    pub fn push_with(&mut self, line: T, dropped: impl FnMut(&T)) -> usize {
+        self.bytes += line.bytes();
+        self.lines.push_back(line);
+        self.trim(dropped)
+    }
+
+    /// Keeps within new limits from now on, and returns how many lines were
+    /// dropped to get there.
+    pub fn set_limits(&mut self, max_lines: usize, max_bytes: usize) -> usize {
+        self.max_lines = max_lines.max(1);
+        self.max_bytes = max_bytes.max(1);
+        self.trim(|_| {})
+    }
+
+    // The newest line stays even when it alone is over max_bytes
+    fn trim(&mut self, mut on_drop: impl FnMut(&T)) -> usize {
+        let mut dropped = 0;
+        while self.lines.len() > self.max_lines
+            || (self.bytes > self.max_bytes && self.lines.len() > 1)
+        {
+            let Some(line) = self.lines.pop_front() else {
+                break;
+            };
+            self.bytes -= line.bytes();
+            on_drop(&line);
+            dropped += 1;
+        }
+        if dropped > 0 {
+            self.dropped += dropped;
+            self.marker = Some(T::dropped_marker(self.dropped));
+        }
+        dropped
+    }
+
+    /// The lines, oldest first, after the marker if there is one.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        self.marker.iter().chain(self.lines.iter())
+    }
+
+    /// The number of lines `iter()` yields.
+    pub fn len(&self) -> usize {
+        self.lines.len() + usize::from(self.marker.is_some())
+    }
+
+    /// The lines pushed since `pushed()` returned `seen`, oldest first, as
+    /// far as they are still kept.
+    pub fn since(&self, seen: usize) -> impl Iterator<Item = &T> + '_ {
+        let new = self.pushed().saturating_sub(seen).min(self.lines.len());
+        self.lines.iter().skip(self.lines.len() - new)
+    }
+
+    /// The number of lines pushed over its lifetime, kept or not.
+    pub fn pushed(&self) -> usize {
+        self.dropped + self.lines.len()
+    }
+
+    /// The line pushed as the `index`th over its lifetime while it is kept,
+    /// or the marker as the one before the oldest kept.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        match index.checked_sub(self.dropped) {
+            Some(i) => self.lines.get(i),
+            None if index + 1 == self.dropped => self.marker.as_ref(),
+            None => None,
+        }
+    }
+
+    /// The index `get` takes of the first line `iter()` yields.
+    pub fn first(&self) -> usize {
+        self.dropped - usize::from(self.marker.is_some())
+    }
+
+    /// The index `get` takes of the oldest line kept, which is how many were
+    /// dropped.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// How many lines are kept, without the marker.
+    pub fn kept(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// The bytes of text the kept lines take.
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(scrollback: &Scrollback<String>) -> Vec<&str> {
+        scrollback.iter().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn drops_the_oldest_lines_past_the_line_limit() {
+        let mut scrollback = Scrollback::new(3, 1000);
+        for i in 0..3 {
+            assert_eq!(scrollback.push(format!("line {}", i)), 0);
+        }
+        assert_eq!(scrollback.push("line 3".to_string()), 1);
+        assert_eq!(scrollback.push("line 4".to_string()), 1);
+
+        assert_eq!(
+            lines(&scrollback),
+            ["… 2 earlier lines dropped", "line 2", "line 3", "line 4"]
+        );
+        assert_eq!(scrollback.kept(), 3);
+        assert_eq!(scrollback.len(), 4);
+    }
+
+    #[test]
+    fn drops_the_oldest_lines_past_the_byte_limit() {
+        let mut scrollback = Scrollback::new(100, 10);
+        scrollback.push("aaaa".to_string());
+        scrollback.push("bbbb".to_string());
+        assert_eq!(scrollback.bytes(), 8);
+
+        assert_eq!(scrollback.push("cccc".to_string()), 1);
+        assert_eq!(scrollback.bytes(), 8);
+        assert_eq!(
+            lines(&scrollback),
+            ["… 1 earlier lines dropped", "bbbb", "cccc"]
+        );
+    }
+
+    #[test]
+    fn keeps_the_newest_line_even_when_it_alone_is_over_the_byte_limit() {
+        let mut scrollback = Scrollback::new(100, 10);
+        scrollback.push("short".to_string());
+        let long = "x".repeat(50);
+
+        assert_eq!(scrollback.push(long.clone()), 1);
+        assert_eq!(scrollback.kept(), 1);
+        assert_eq!(scrollback.bytes(), 50);
+        assert_eq!(scrollback.iter().last(), Some(&long));
+    }
+
+    #[test]
+    fn the_marker_counts_every_line_dropped_so_far() {
+        let mut scrollback = Scrollback::new(2, 1000);
+        for i in 0..10 {
+            scrollback.push(i.to_string());
+        }
+        assert_eq!(
+            scrollback.iter().next().map(String::as_str),
+            Some("… 8 earlier lines dropped")
+        );
+
+        assert_eq!(scrollback.set_limits(1, 1000), 1);
+        assert_eq!(lines(&scrollback), ["… 9 earlier lines dropped", "9"]);
+    }
+
+    #[test]
+    fn since_and_pushed_count_dropped_lines() {
+        let mut scrollback = Scrollback::new(3, 1000);
+        for i in 0..2 {
+            scrollback.push(i.to_string());
+        }
+        let seen = scrollback.pushed();
+        for i in 2..6 {
+            scrollback.push(i.to_string());
+        }
+
+        assert_eq!(scrollback.pushed(), 6);
+        // Of the four pushed since, the oldest was dropped already
+        let new: Vec<&String> = scrollback.since(seen).collect();
+        assert_eq!(new, ["3", "4", "5"]);
+        assert_eq!(scrollback.since(scrollback.pushed()).count(), 0);
+    }
+
+    #[test]
+    fn get_takes_the_index_of_a_line_among_all_pushed() {
+        let mut scrollback = Scrollback::new(2, 1000);
+        for i in 0..5 {
+            scrollback.push(i.to_string());
+        }
+
+        assert_eq!(scrollback.dropped(), 3);
+        assert_eq!(scrollback.first(), 2);
+        assert_eq!(
+            scrollback.get(2).map(String::as_str),
+            Some("… 3 earlier lines dropped")
+        );
+        assert_eq!(scrollback.get(3).map(String::as_str), Some("3"));
+        assert_eq!(scrollback.get(4).map(String::as_str), Some("4"));
+        assert_eq!(scrollback.get(1), None);
+        assert_eq!(scrollback.get(5), None);
+    }
+
+    #[test]
+    fn sustained_output_keeps_memory_flat() {
+        let mut scrollback = Scrollback::new(1000, 64 * 1024);
+        let mut dropped = 0;
+        for i in 0..200_000 {
+            dropped += scrollback.push(format!("{:>40} some output of the app", i));
+            assert!(scrollback.kept() <= 1000);
+            assert!(scrollback.bytes() <= 64 * 1024);
+        }
+
+        assert_eq!(scrollback.pushed(), 200_000);
+        assert_eq!(dropped + scrollback.kept(), 200_000);
+        assert_eq!(
+            scrollback.bytes(),
+            scrollback.since(0).map(String::len).sum::<usize>()
+        );
+    }
+
+    #[test]
+    fn push_with_shows_each_dropped_line() {
+        let mut scrollback = Scrollback::new(2, 1000);
+        let mut gone = Vec::new();
+        for i in 0..4 {
+            scrollback.push_with(i.to_string(), |line| gone.push(line.clone()));
+        }
+        assert_eq!(gone, ["0", "1"]);
+    }
+}
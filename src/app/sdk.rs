@@ -0,0 +1,158 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use serde_json::{json, Value};
+
+use crate::app::error::{dotnet_spawn_error, Result};
+
+/// The installed SDK versions, oldest first, from `dotnet --list-sdks`.
+pub fn list_sdks() -> Result<Vec<String>> {
+    let output = Command::new("dotnet")
+        .arg("--list-sdks")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .map_err(dotnet_spawn_error)?;
+    // Lines like 8.0.100 [/usr/share/dotnet/sdk]
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .filter(|version| SdkVersion::parse(version).is_some())
+        .map(str::to_string)
+        .collect())
+}
+
+/// An SDK version like 8.0.204, whose feature band is 2 and patch 4.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SdkVersion {
+    major: u32,
+    minor: u32,
+    feature: u32,
+    patch: u32,
+    prerelease: bool,
+}
+
+impl SdkVersion {
+    fn parse(version: &str) -> Option<Self> {
+        let (numbers, prerelease) = match version.split_once('-') {
+            Some((numbers, _)) => (numbers, true),
+            None => (version, false),
+        };
+        let mut parts = numbers.split('.').map(|part| part.parse::<u32>().ok());
+        let (major, minor, patch) = (parts.next()??, parts.next()??, parts.next()??);
+        Some(Self {
+            major,
+            minor,
+            feature: patch / 100,
+            patch: patch % 100,
+            prerelease,
+        })
+    }
+
+    // In version order, where a release comes after its previews
+    fn key(&self) -> (u32, u32, u32, u32, bool) {
+        (
+            self.major,
+            self.minor,
+            self.feature,
+            self.patch,
+            !self.prerelease,
+        )
+    }
+}
+
+/// The `sdk` section of a global.json.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlobalJson {
+    pub path: PathBuf,
+    pub version: Option<String>,
+    // Like latestFeature, defaulting as the SDK does
+    pub roll_forward: String,
+    pub allow_prerelease: bool,
+}
+
+impl GlobalJson {
+    /// The global.json the SDK would use for a command run in `dir`: the
+    /// closest in it or a directory above, that has an `sdk` section.
+    pub fn find(dir: &Path) -> Option<Self> {
+        dir.ancestors()
+            .map(|dir| dir.join("global.json"))
+            .filter(|path| path.is_file())
+            .find_map(|path| Self::read(&path))
+    }
+
+    fn read(path: &Path) -> Option<Self> {
+        let contents = fs::read_to_string(path).ok()?;
+        // The SDK allows comments, which JSON does not
+        let contents: String = contents
+            .lines()
+            .filter(|line| !line.trim_start().starts_with("//"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let json: Value = serde_json::from_str(&contents).ok()?;
+        let sdk = json.get("sdk")?;
+        let version = sdk["version"].as_str().map(str::to_string);
+        let roll_forward = match sdk["rollForward"].as_str() {
+            Some(policy) => policy.to_string(),
+            None if version.is_some() => "patch".to_string(),
+            None => "latestMajor".to_string(),
+        };
+        Some(Self {
+            path: path.to_path_buf(),
+            version,
+            roll_forward,
+            allow_prerelease: sdk["allowPrerelease"].as_bool().unwrap_or(true),
+        })
+    }
+
+    /// What it asks for, like 8.0.100 (rollForward latestFeature).
+    pub fn requirement(&self) -> String {
+        match &self.version {
+            Some(version) => format!("{} (rollForward {})", version, self.roll_forward),
+            None => format!("any (rollForward {})", self.roll_forward),
+        }
+    }
+
+    /// Whether the installed SDK `version` satisfies it under its roll-forward
+    /// policy.
+    pub fn allows(&self, version: &str) -> bool {
+        let Some(sdk) = SdkVersion::parse(version) else {
+            return false;
+        };
+        if sdk.prerelease && !self.allow_prerelease {
+            return false;
+        }
+        let Some(wanted) = self.version.as_deref().and_then(SdkVersion::parse) else {
+            return true;
+        };
+        let same_feature =
+            sdk.major == wanted.major && sdk.minor == wanted.minor && sdk.feature == wanted.feature;
+        let same_minor = sdk.major == wanted.major && sdk.minor == wanted.minor;
+        let newer = sdk.key() >= wanted.key();
+        match self.roll_forward.to_ascii_lowercase().as_str() {
+            "disable" => sdk == wanted,
+            "patch" | "latestpatch" => same_feature && newer,
+            "feature" | "latestfeature" => same_minor && newer,
+            "minor" | "latestminor" => sdk.major == wanted.major && newer,
+            _ => newer,
+        }
+    }
+}
+
+/// Writes the global.json that holds the runs of the project in `dir` to the
+/// SDK `version`, unless one of its own is there.
+pub fn write_pin(dir: &Path, version: &str) -> io::Result<PathBuf> {
+    let path = dir.join("global.json");
+    if path.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} exists already", path.display()),
+        ));
+    }
+    let pin = json!({ "sdk": { "version": version, "rollForward": "disable" } });
+    fs::write(&path, format!("{:#}\n", pin))?;
+    Ok(path)
+}
@@ -0,0 +1,95 @@
+use std::{fs, path::Path, process::Command};
+
+use crate::app::util::property_values;
+
+// Values shorter than this are not masked in the log export, where they would
+// blank out every number and word they happen to match
+const MIN_MASKED_LEN: usize = 4;
+
+/// The `UserSecretsId` of the project, which names its secrets.json.
+pub fn user_secrets_id(project_path: &Path) -> Option<String> {
+    let project = fs::read_to_string(project_path).ok()?;
+    let id = property_values(&project, "UserSecretsId").next();
+    id
+}
+
+// `dotnet user-secrets` for the project
+fn user_secrets(project_path: &Path) -> Command {
+    let mut command = Command::new("dotnet");
+    command.arg("user-secrets");
+    if let Some(dir) = project_path.parent() {
+        command.current_dir(dir);
+    }
+    command
+}
+
+pub fn list_command(project_path: &Path) -> Command {
+    let mut command = user_secrets(project_path);
+    command.arg("list").arg("--project").arg(project_path);
+    command
+}
+
+pub fn set_command(project_path: &Path, key: &str, value: &str) -> Command {
+    let mut command = user_secrets(project_path);
+    command
+        .arg("set")
+        .arg(key)
+        .arg(value)
+        .arg("--project")
+        .arg(project_path);
+    command
+}
+
+pub fn remove_command(project_path: &Path, key: &str) -> Command {
+    let mut command = user_secrets(project_path);
+    command
+        .arg("remove")
+        .arg(key)
+        .arg("--project")
+        .arg(project_path);
+    command
+}
+
+/// `dotnet user-secrets init`, which adds a `UserSecretsId` to the project.
+pub fn init_command(project_path: &Path) -> Command {
+    let mut command = user_secrets(project_path);
+    command.arg("init").arg("--project").arg(project_path);
+    command
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Secret {
+    // Like ConnectionStrings:Default
+    pub key: String,
+    pub value: String,
+}
+
+/// The secrets in the output of `dotnet user-secrets list`, one
+/// `Key = Value` per line.
+pub fn parse_secrets(output: &[String]) -> Vec<Secret> {
+    output
+        .iter()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(" = ")?;
+            Some(Secret {
+                key: key.trim().to_string(),
+                value: value.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Whether the value is long enough to be masked in the log export.
+pub fn maskable(value: &str) -> bool {
+    value.chars().count() >= MIN_MASKED_LEN
+}
+
+/// The text with the values replaced by a mask, longest first so that a value
+/// inside another is not masked half.
+pub fn mask<'a>(text: &str, values: impl IntoIterator<Item = &'a String>) -> String {
+    let mut values: Vec<&String> = values.into_iter().collect();
+    values.sort_by_key(|value| std::cmp::Reverse(value.len()));
+    values.iter().fold(text.to_string(), |text, value| {
+        text.replace(value.as_str(), "••••••••")
+    })
+}
@@ -0,0 +1,127 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use serde_json::Value;
+
+use crate::app::{
+    logs::strip_ansi,
+    util::{property_values, shell_command},
+};
+
+/// The single-page app of an ASP.NET project, whose dev server the
+/// project's SPA proxy forwards to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spa {
+    pub project_dir: PathBuf,
+    // The directory of its package.json
+    pub root: PathBuf,
+    // The command line that starts the dev server
+    pub command: String,
+    // From SpaProxyServerUrl, else read from the dev server's output
+    pub url: Option<String>,
+}
+
+impl Spa {
+    /// The SPA of the project: the `SpaRoot` and `SpaProxy` properties of
+    /// the project file, else a `package.json` in the project directory or
+    /// in a directory of it like `ClientApp`, with a `dev` or `start` script.
+    pub fn detect(project_path: &Path) -> Option<Self> {
+        let project_dir = project_path.parent()?;
+        let project = fs::read_to_string(project_path).unwrap_or_default();
+        let property = |name| property_values(&project, name).next();
+        let root = match property("SpaRoot") {
+            Some(root) => project_dir.join(root.replace('\\', "/").trim_end_matches('/')),
+            None => package_dir(project_dir)?,
+        };
+        let command = match property("SpaProxyLaunchCommand") {
+            Some(command) => command,
+            None => dev_script(&root)?,
+        };
+        Some(Self {
+            project_dir: project_dir.to_path_buf(),
+            root,
+            command,
+            url: property("SpaProxyServerUrl"),
+        })
+    }
+
+    /// The program that starts the dev server, like npm.
+    pub fn tool(&self) -> &str {
+        self.command.split_whitespace().next().unwrap_or_default()
+    }
+
+    /// The port of `SpaProxyServerUrl`.
+    pub fn port(&self) -> Option<u16> {
+        let (_, rest) = self.url.as_deref()?.split_once("://")?;
+        let host = rest.split('/').next()?;
+        host.rsplit_once(':')?.1.parse().ok()
+    }
+
+    pub fn command(&self) -> Command {
+        let mut command = shell_command(&self.command);
+        // The app is opened through the project, which proxies to the dev server
+        command.current_dir(&self.root).env("BROWSER", "none");
+        command
+    }
+}
+
+// The project directory, or the first directory in it, with a package.json
+fn package_dir(project_dir: &Path) -> Option<PathBuf> {
+    if project_dir.join("package.json").is_file() {
+        return Some(project_dir.to_path_buf());
+    }
+    let mut dirs: Vec<PathBuf> = fs::read_dir(project_dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name != "node_modules" && !name.starts_with('.')
+        })
+        .map(|entry| entry.path())
+        .filter(|dir| dir.join("package.json").is_file())
+        .collect();
+    dirs.sort();
+    dirs.into_iter().next()
+}
+
+// The `dev` or else `start` script, run with the package manager whose lock
+// file is there
+fn dev_script(root: &Path) -> Option<String> {
+    let package: Value =
+        serde_json::from_str(&fs::read_to_string(root.join("package.json")).ok()?).ok()?;
+    let script = ["dev", "start"]
+        .into_iter()
+        .find(|script| package["scripts"][script].is_string())?;
+    let manager = if root.join("pnpm-lock.yaml").is_file() {
+        "pnpm"
+    } else if root.join("yarn.lock").is_file() {
+        "yarn"
+    } else {
+        "npm"
+    };
+    Some(format!("{} run {}", manager, script))
+}
+
+/// The local address in a line of the dev server's output, like Vite's
+/// `Local:   http://localhost:5173/` or Angular's `http://localhost:4200/`.
+pub fn dev_server_url(line: &str) -> Option<String> {
+    let line = strip_ansi(line);
+    let start = [
+        "http://localhost:",
+        "https://localhost:",
+        "http://127.0.0.1:",
+        "https://127.0.0.1:",
+    ]
+    .iter()
+    .filter_map(|prefix| line.find(prefix))
+    .min()?;
+    let url: String = line[start..]
+        .chars()
+        .take_while(|c| !c.is_whitespace())
+        .collect();
+    Some(url.trim_end_matches(['/', ',', '.']).to_string())
+}
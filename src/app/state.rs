@@ -0,0 +1,145 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::{
+    config::SolutionSort,
+    error::{AppError, Result},
+};
+
+/// Choices remembered between sessions, stored as JSON in the platform state directory.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct State {
+    /// Launch profile chosen per project file; `None` means run without a profile.
+    pub launch_profiles: HashMap<String, Option<String>>,
+    /// Build configuration chosen per solution file.
+    pub configurations: HashMap<String, String>,
+    pub last_solution: Option<String>,
+    /// Solution files pinned to the top of the solution list, in the order they were pinned.
+    pub pinned: Vec<String>,
+    /// Project file last run per solution file.
+    pub last_projects: HashMap<String, String>,
+    /// When a project of each solution file last ran, in seconds since the Unix epoch.
+    pub last_runs: HashMap<String, i64>,
+    /// Order of the solution list picked with `o`, over the configured one.
+    pub solution_sort: Option<SolutionSort>,
+    /// Project files run with `dotnet watch`.
+    pub watch: HashSet<String>,
+    /// Project files run with the dev server of their single-page app.
+    pub spa: HashSet<String>,
+    /// Arguments passed to `dotnet run` per project file, most recent last.
+    pub run_args: HashMap<String, Vec<String>>,
+    /// ASPNETCORE_ENVIRONMENT chosen per project file, overriding the launch profile.
+    pub environments: HashMap<String, String>,
+    /// Outcome of the last build per project file.
+    pub builds: HashMap<String, BuildRecord>,
+    /// Target framework chosen per multi-targeted project file.
+    pub frameworks: HashMap<String, String>,
+    /// Options of the last publish per project file.
+    pub publish: HashMap<String, PublishOptions>,
+    /// MSBuild verbosity of builds and publishes.
+    pub verbosity: Verbosity,
+    /// Height of the log pane as a percentage of the screen.
+    pub log_percent: Option<u16>,
+    pub logs_collapsed: bool,
+    /// Projects listed flat rather than in their solution folders.
+    pub flat_projects: bool,
+    /// Hooks confirmed to run, by their owner and commands.
+    pub confirmed_hooks: HashSet<String>,
+}
+
+/// How the last build of a project went.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildRecord {
+    pub succeeded: bool,
+    /// How long it took, in seconds.
+    pub duration: f32,
+    pub errors: usize,
+    pub warnings: usize,
+    /// When it finished, in seconds since the Unix epoch.
+    pub finished: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verbosity {
+    Quiet,
+    #[default]
+    Minimal,
+    Normal,
+    Detailed,
+}
+
+impl Verbosity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Quiet => "quiet",
+            Self::Minimal => "minimal",
+            Self::Normal => "normal",
+            Self::Detailed => "detailed",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Quiet => Self::Minimal,
+            Self::Minimal => Self::Normal,
+            Self::Normal => Self::Detailed,
+            Self::Detailed => Self::Quiet,
+        }
+    }
+
+    /// How many times the configured log lines to keep, so that a detailed
+    /// build doesn't push everything else out of the log.
+    pub fn log_factor(self) -> usize {
+        match self {
+            Self::Quiet | Self::Minimal => 1,
+            Self::Normal => 4,
+            Self::Detailed => 20,
+        }
+    }
+}
+
+/// What `dotnet publish` was asked for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublishOptions {
+    pub configuration: String,
+    pub runtime: Option<String>,
+    pub self_contained: bool,
+}
+
+impl State {
+    pub fn path() -> Option<PathBuf> {
+        dirs::state_dir()
+            .or_else(dirs::data_local_dir)
+            .map(|dir| dir.join("sln-runner").join("state.json"))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents).map_err(|source| AppError::State {
+                path: path.to_path_buf(),
+                source,
+            }),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|source| AppError::State {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use std::time::{Duration, Instant};
+
+// How long a flashed message replaces the key hints
+const FLASH_DURATION: Duration = Duration::from_secs(3);
+
+struct Flash {
+    message: String,
+    is_error: bool,
+    shown_at: Instant,
+}
+
+/// Transient messages for the status bar. Everything else in the bar is
+/// derived from the app state on each draw, so it never goes stale.
+#[derive(Default)]
+pub struct StatusLine {
+    flash: Option<Flash>,
+}
+
+impl StatusLine {
+    pub fn flash(&mut self, message: impl Into<String>) {
+        self.set(message.into(), false);
+    }
+
+    pub fn flash_error(&mut self, message: impl Into<String>) {
+        self.set(message.into(), true);
+    }
+
+    /// The flashed message and whether it is an error, until it expires.
+    pub fn current(&self) -> Option<(&str, bool)> {
+        self.flash
+            .as_ref()
+            .filter(|flash| flash.shown_at.elapsed() < FLASH_DURATION)
+            .map(|flash| (flash.message.as_str(), flash.is_error))
+    }
+
+    fn set(&mut self, message: String, is_error: bool) {
+        self.flash = Some(Flash {
+            message,
+            is_error,
+            shown_at: Instant::now(),
+        });
+    }
+}
@@ -0,0 +1,166 @@
+use std::{
+    io::{self, stderr, stdout, Write},
+    panic,
+    process::{Command, ExitStatus},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Once, OnceLock,
+    },
+};
+
+use ratatui::crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    ExecutableCommand,
+};
+
+static PANIC_HOOK: Once = Once::new();
+static CLOSED: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+static ON_STDERR: AtomicBool = AtomicBool::new(false);
+#[cfg(test)]
+static PANIC_HOOK_RAN: AtomicBool = AtomicBool::new(false);
+
+/// Draws the TUI on stderr from now on, leaving stdout to what `pick` prints,
+/// which a shell is capturing.
+pub fn draw_on_stderr() {
+    ON_STDERR.store(true, Ordering::Relaxed);
+}
+
+/// The stream the TUI is drawn on: stdout, or stderr while picking.
+pub struct Screen;
+
+pub fn screen() -> Screen {
+    Screen
+}
+
+impl Write for Screen {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Tests have no terminal to draw on, and would print escape codes
+        // between their results
+        if cfg!(test) {
+            return Ok(buf.len());
+        }
+        if ON_STDERR.load(Ordering::Relaxed) {
+            stderr().write(buf)
+        } else {
+            stdout().write(buf)
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if cfg!(test) {
+            return Ok(());
+        }
+        if ON_STDERR.load(Ordering::Relaxed) {
+            stderr().flush()
+        } else {
+            stdout().flush()
+        }
+    }
+}
+
+/// Puts the terminal into raw mode on the alternate screen and restores it when
+/// dropped, so early returns and panics never leave the shell unusable.
+pub struct TerminalGuard;
+
+impl TerminalGuard {
+    /// Hands the terminal to `program` until it exits, as a shell would.
+    pub fn run_in_foreground(&self, command: &mut Command, mouse: bool) -> io::Result<ExitStatus> {
+        restore()?;
+        let status = command.status();
+        enable_raw_mode()?;
+        screen().execute(EnterAlternateScreen)?;
+        if mouse {
+            screen().execute(EnableMouseCapture)?;
+        }
+        status
+    }
+
+    pub fn enter(mouse: bool) -> io::Result<Self> {
+        install_panic_hook();
+        watch_close_signals()?;
+        enable_raw_mode()?;
+        if let Err(e) = screen().execute(EnterAlternateScreen) {
+            let _ = disable_raw_mode();
+            return Err(e);
+        }
+        // From here on the guard exists, so a failure below still restores the terminal
+        let guard = Self;
+        if mouse {
+            screen().execute(EnableMouseCapture)?;
+        }
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = restore();
+    }
+}
+
+pub fn restore() -> io::Result<()> {
+    disable_raw_mode()?;
+    // Harmless when capture was never enabled
+    screen().execute(DisableMouseCapture)?;
+    screen().execute(LeaveAlternateScreen)?;
+    Ok(())
+}
+
+fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            // Restore first, otherwise the message is printed into the alternate screen
+            let _ = restore();
+            #[cfg(test)]
+            PANIC_HOOK_RAN.store(true, Ordering::Relaxed);
+            previous(info);
+        }));
+    });
+}
+
+/// Rings the terminal bell.
+pub fn bell() {
+    let mut out = screen();
+    let _ = out.write_all(b"\x07").and_then(|()| out.flush());
+}
+
+/// Whether the terminal was closed or sln-runner was told to terminate, so it
+/// can shut down as if quit instead of dying with its processes running.
+pub fn closed() -> bool {
+    CLOSED
+        .get()
+        .is_some_and(|flag| flag.load(Ordering::Relaxed))
+}
+
+// The processes run in groups of their own, which a closing terminal no longer
+// hangs up. Windows needs nothing here: their job objects close with us.
+fn watch_close_signals() -> io::Result<()> {
+    if CLOSED.get().is_some() {
+        return Ok(());
+    }
+    let flag = Arc::new(AtomicBool::new(false));
+    #[cfg(unix)]
+    for signal in [signal_hook::consts::SIGHUP, signal_hook::consts::SIGTERM] {
+        signal_hook::flag::register(signal, Arc::clone(&flag))?;
+    }
+    let _ = CLOSED.set(flag);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_panic_restores_the_terminal_before_unwinding() {
+        install_panic_hook();
+        PANIC_HOOK_RAN.store(false, Ordering::Relaxed);
+
+        let result = panic::catch_unwind(|| panic!("forced by the test"));
+
+        assert!(result.is_err());
+        assert!(PANIC_HOOK_RAN.load(Ordering::Relaxed));
+    }
+}
@@ -0,0 +1,244 @@
+use std::{collections::HashMap, fs, path::Path, sync::LazyLock, time::Duration};
+
+use regex::Regex;
+
+use crate::app::{
+    logs::strip_ansi,
+    util::{attribute, elements},
+};
+
+// `Passed!  - Failed: 0, Passed: 12, Skipped: 1, Total: 13, Duration: 45 ms - Api.Tests.dll (net8.0)`
+static VSTEST_SUMMARY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^\s*(?:passed|failed)!\s*-\s*failed:\s*(?P<failed>\d+),\s*passed:\s*(?P<passed>\d+),\s*skipped:\s*(?P<skipped>\d+),\s*total:\s*\d+(?:,\s*duration:\s*(?P<duration>[^-]*))?",
+    )
+    .expect("valid vstest summary regex")
+});
+
+// `Test summary: total: 13, failed: 0, succeeded: 12, skipped: 1, duration: 1.2s`
+static MTP_SUMMARY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^\s*test summary:\s*total:\s*\d+,\s*failed:\s*(?P<failed>\d+),\s*succeeded:\s*(?P<passed>\d+),\s*skipped:\s*(?P<skipped>\d+)(?:,\s*duration:\s*(?P<duration>.*))?",
+    )
+    .expect("valid test summary regex")
+});
+
+// One part of a duration like `2 m 3 s`, `1s 234ms` or `45 ms`
+static DURATION_PART: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?P<value>\d+(?:\.\d+)?)\s*(?P<unit>ms|s|m|h)\b").expect("valid duration regex")
+});
+
+/// The counts `dotnet test` reports at the end, added up over the test
+/// assemblies it ran.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TestSummary {
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    // The longest assembly, since they run side by side
+    pub duration: Option<Duration>,
+    pub assemblies: usize,
+}
+
+impl TestSummary {
+    /// Counts the line in if it is the summary of a test assembly.
+    pub fn feed(&mut self, line: &str) {
+        let line = strip_ansi(line);
+        let Some(captures) = VSTEST_SUMMARY
+            .captures(&line)
+            .or_else(|| MTP_SUMMARY.captures(&line))
+        else {
+            return;
+        };
+        let count = |name: &str| captures[name].parse::<usize>().unwrap_or_default();
+        self.passed += count("passed");
+        self.failed += count("failed");
+        self.skipped += count("skipped");
+        self.assemblies += 1;
+        if let Some(duration) = captures
+            .name("duration")
+            .and_then(|d| parse_duration(d.as_str()))
+        {
+            self.duration = Some(self.duration.map_or(duration, |d| d.max(duration)));
+        }
+    }
+
+    /// Counts like "12 passed, 0 failed, 1 skipped".
+    pub fn counts(&self) -> String {
+        format!(
+            "{} passed, {} failed, {} skipped",
+            self.passed, self.failed, self.skipped
+        )
+    }
+}
+
+fn parse_duration(text: &str) -> Option<Duration> {
+    let mut parts = DURATION_PART.captures_iter(text).peekable();
+    parts.peek()?;
+    Some(parts.fold(Duration::ZERO, |total, part| {
+        let value: f64 = part["value"].parse().unwrap_or_default();
+        let secs = match &part["unit"] {
+            "ms" => value / 1000.0,
+            "m" => value * 60.0,
+            "h" => value * 3600.0,
+            _ => value,
+        };
+        total + Duration::from_secs_f64(secs)
+    }))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Outcome {
+    Skipped,
+    Passed,
+    Failed,
+}
+
+/// How a test went, from the TRX file of the run.
+#[derive(Debug, Clone)]
+pub struct TestResult {
+    // The name the test reported, with the arguments of a theory
+    pub name: String,
+    // Namespace, class and method, for `--filter`
+    pub full_name: String,
+    pub outcome: Outcome,
+    pub duration: Option<Duration>,
+    pub message: String,
+    pub stack_trace: String,
+}
+
+/// The results in the TRX files `dotnet test` wrote to `dir`, one per test
+/// assembly.
+pub fn read_results(dir: &Path) -> Vec<TestResult> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("trx"))
+        })
+        .collect();
+    files.sort();
+    files
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .flat_map(|trx| parse_trx(&trx))
+        .collect()
+}
+
+/// `FullyQualifiedName=A|FullyQualifiedName=B` for running just these tests.
+pub fn rerun_filter(tests: &[TestResult]) -> String {
+    let mut names: Vec<&str> = tests.iter().map(|test| test.full_name.as_str()).collect();
+    // Every case of a theory has the same name
+    names.sort_unstable();
+    names.dedup();
+    names
+        .iter()
+        .map(|name| format!("FullyQualifiedName={}", escape_filter(name)))
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+fn parse_trx(trx: &str) -> Vec<TestResult> {
+    // Test IDs to `Namespace.Class.Method`
+    let full_names: HashMap<&str, String> = elements(trx, "UnitTest")
+        .filter_map(|(tag, body)| {
+            let (method, _) = elements(body, "TestMethod").next()?;
+            let class = attribute(method, "className")?;
+            // Older loggers add the assembly to the class: `Ns.Class, Api.Tests, ...`
+            let class = class.split(',').next().unwrap_or_default().trim();
+            Some((
+                attribute(tag, "id")?,
+                format!("{}.{}", class, unescape(attribute(method, "name")?)),
+            ))
+        })
+        .collect();
+
+    elements(trx, "UnitTestResult")
+        .map(|(tag, body)| {
+            let name = unescape(attribute(tag, "testName").unwrap_or_default());
+            let text = |element| {
+                elements(body, element)
+                    .next()
+                    .map(|(_, text)| unescape(text).trim_end().to_string())
+                    .unwrap_or_default()
+            };
+            TestResult {
+                full_name: attribute(tag, "testId")
+                    .and_then(|id| full_names.get(id).cloned())
+                    // The display name starts with it, up to the arguments
+                    .unwrap_or_else(|| name.split('(').next().unwrap_or_default().to_string()),
+                name,
+                outcome: match attribute(tag, "outcome") {
+                    Some("Passed") => Outcome::Passed,
+                    Some("Failed" | "Error" | "Timeout" | "Aborted") => Outcome::Failed,
+                    _ => Outcome::Skipped,
+                },
+                duration: attribute(tag, "duration").and_then(parse_timespan),
+                message: text("Message"),
+                stack_trace: text("StackTrace"),
+            }
+        })
+        .collect()
+}
+
+// `00:00:01.2345678`
+fn parse_timespan(text: &str) -> Option<Duration> {
+    let mut parts = text.split(':');
+    let hours: f64 = parts.next()?.parse().ok()?;
+    let minutes: f64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    Some(Duration::from_secs_f64(
+        hours * 3600.0 + minutes * 60.0 + seconds,
+    ))
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(|code| code.ok())
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            // Line endings come as `&#xD;&#xA;`
+            Some('\r') => {}
+            Some(c) => result.push(c),
+            None => result.push_str(&rest[..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    result.replace("\r\n", "\n")
+}
+
+/// Escapes the characters the `--filter` syntax gives a meaning.
+pub fn escape_filter(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        if matches!(c, '\\' | '(' | ')' | '&' | '|' | '=' | '!' | '~') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
@@ -1,25 +1,1107 @@
-use std::{io, path::Path};
-use walkdir::WalkDir;
+use regex::Regex;
+use serde::Deserialize;
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::atomic::{AtomicBool, Ordering},
+    time::SystemTime,
+};
+use walkdir::{DirEntry, WalkDir};
 
-pub fn find_sln_files() -> io::Result<Vec<String>> {
-    let dir = Path::new(r"C:\Users\Berat Hündürel\Desktop\Software\Personal");
-    Ok(WalkDir::new(dir)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().extension().map_or(false, |ext| ext == "sln"))
-        .map(|e| e.path().to_string_lossy().into_owned())
-        .collect())
+use crate::app::{config::expand_tilde, docker::has_docker};
+
+pub struct SolutionFile {
+    pub path: String,
+    pub root: PathBuf,
+    pub modified: Option<SystemTime>,
+}
+
+pub const DEFAULT_EXCLUDED_DIRS: &[&str] = &["bin", "obj", "node_modules", ".git"];
+
+/// Solutions hidden from the list, by path or glob pattern: `*` and `?` stay
+/// within a directory, `**` crosses them, and a pattern without a `/` matches
+/// a file or directory name anywhere. Matching a directory hides everything
+/// below it.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreList {
+    patterns: Vec<(String, Regex)>,
+}
+
+// Both separators match either way
+fn slash_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+impl IgnoreList {
+    pub fn new(patterns: &[String]) -> Self {
+        Self {
+            patterns: patterns
+                .iter()
+                .filter_map(|pattern| Some((pattern.clone(), glob_regex(pattern)?)))
+                .collect(),
+        }
+    }
+
+    /// The patterns hiding the solution, by matching it or a directory it is in.
+    pub fn matching(&self, path: &Path) -> Vec<&str> {
+        self.patterns
+            .iter()
+            .filter(|(_, regex)| {
+                path.ancestors().enumerate().any(|(depth, path)| {
+                    let path = slash_path(path);
+                    !path.is_empty()
+                        && (regex.is_match(&path)
+                            || depth > 0 && regex.is_match(&format!("{}/", path)))
+                })
+            })
+            .map(|(pattern, _)| pattern.as_str())
+            .collect()
+    }
+
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        !self.matching(path).is_empty()
+    }
+
+    // A directory the scan need not go into: `**/samples/**` hides
+    // `x/samples` as well as what is in it
+    fn hides_dir(&self, path: &Path) -> bool {
+        let path = slash_path(path);
+        let dir = format!("{}/", path);
+        self.patterns
+            .iter()
+            .any(|(_, regex)| regex.is_match(&path) || regex.is_match(&dir))
+    }
+
+    fn hides_file(&self, path: &Path) -> bool {
+        let path = slash_path(path);
+        self.patterns.iter().any(|(_, regex)| regex.is_match(&path))
+    }
+}
+
+fn glob_regex(pattern: &str) -> Option<Regex> {
+    let pattern = slash_path(&expand_tilde(Path::new(pattern.trim())));
+    let pattern = pattern.trim_end_matches('/');
+    if pattern.is_empty() {
+        return None;
+    }
+    let mut regex = String::from(if cfg!(windows) { "(?i)" } else { "" });
+    regex.push_str(if pattern.contains('/') {
+        "^"
+    } else {
+        "(?:^|/)"
+    });
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                regex.push_str(".*");
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex).ok()
 }
 
-pub fn parse_sln_for_projects(sln_path: &str) -> io::Result<Vec<String>> {
-    Ok(std::fs::read_to_string(sln_path)?
+pub fn find_sln_files(
+    roots: &[PathBuf],
+    extra_excludes: &[String],
+    ignore: &IgnoreList,
+    cancel: &AtomicBool,
+    mut on_found: impl FnMut(SolutionFile),
+) {
+    let mut seen = HashSet::new();
+
+    let is_excluded = |entry: &DirEntry| {
+        entry.depth() > 0
+            && entry.file_type().is_dir()
+            && (entry.file_name().to_str().is_some_and(|name| {
+                DEFAULT_EXCLUDED_DIRS.contains(&name) || extra_excludes.iter().any(|e| e == name)
+            }) || ignore.hides_dir(entry.path()))
+    };
+
+    for root in roots {
+        for entry in WalkDir::new(root)
+            .into_iter()
+            .filter_entry(|e| !is_excluded(e))
+            .take_while(|_| !cancel.load(Ordering::Relaxed))
+            .filter_map(|e| e.ok())
+            .filter(|e| {
+                e.path()
+                    .extension()
+                    .is_some_and(|ext| ext == "sln" || ext == "slnx" || ext == "slnf")
+                    && !ignore.hides_file(e.path())
+            })
+        {
+            // Overlapping roots would otherwise list the same solution twice
+            let key = entry
+                .path()
+                .canonicalize()
+                .unwrap_or_else(|_| entry.path().to_path_buf());
+            if seen.insert(key) {
+                on_found(SolutionFile {
+                    path: entry.path().to_string_lossy().into_owned(),
+                    root: root.clone(),
+                    modified: entry.metadata().ok().and_then(|meta| meta.modified().ok()),
+                });
+            }
+        }
+    }
+}
+
+const SOLUTION_FOLDER_GUID: &str = "2150E333-8FDC-42A3-9474-1A3956D46DE8";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectKind {
+    CSharp,
+    FSharp,
+    VisualBasic,
+}
+
+impl ProjectKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::CSharp => "C#",
+            Self::FSharp => "F#",
+            Self::VisualBasic => "VB",
+        }
+    }
+
+    fn from_path(path: &str) -> Option<Self> {
+        let extension = path.rsplit_once('.')?.1.to_ascii_lowercase();
+        match extension.as_str() {
+            "csproj" => Some(Self::CSharp),
+            "fsproj" => Some(Self::FSharp),
+            "vbproj" => Some(Self::VisualBasic),
+            _ => None,
+        }
+    }
+}
+
+/// What building the project gives, which decides what Enter does with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputKind {
+    Web,
+    Worker,
+    Console,
+    Library,
+    Test,
+}
+
+impl OutputKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Web => "web",
+            Self::Worker => "worker",
+            Self::Console => "console",
+            Self::Library => "lib",
+            Self::Test => "test",
+        }
+    }
+
+    /// Whether `dotnet run` has something to start.
+    pub fn is_executable(self) -> bool {
+        matches!(self, Self::Web | Self::Worker | Self::Console)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Project {
+    pub name: String,
+    pub rel_path: String,
+    pub path: PathBuf,
+    pub missing: bool,
+    pub guid: String,
+    pub kind: ProjectKind,
+    pub output: OutputKind,
+    // More than one needs `--framework` to run
+    pub frameworks: Vec<String>,
+    // The solution folders it is in, outermost first
+    pub folder: Vec<String>,
+    // Has a Dockerfile or a Docker launch profile
+    pub docker: bool,
+}
+
+/// Resolves a path stored in a solution file, which always uses `\` separators,
+/// against the solution directory using the host's separator.
+pub fn resolve_sln_relative(sln_dir: &Path, rel_path: &str) -> PathBuf {
+    rel_path
+        .split(['\\', '/'])
+        .filter(|part| !part.is_empty() && *part != ".")
+        .fold(sln_dir.to_path_buf(), |path, part| path.join(part))
+}
+
+/// Whether the path is a solution filter, a `.slnf` that picks some of the
+/// projects of a solution.
+pub fn is_solution_filter(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("slnf"))
+}
+
+#[derive(Deserialize)]
+struct SolutionFilterFile {
+    solution: SolutionFilter,
+}
+
+#[derive(Deserialize)]
+struct SolutionFilter {
+    path: String,
+    #[serde(default)]
+    projects: Vec<String>,
+}
+
+fn read_solution_filter(path: &str) -> io::Result<SolutionFilter> {
+    let contents = std::fs::read_to_string(path)?;
+    let file: SolutionFilterFile = serde_json::from_str(contents.trim_start_matches('\u{feff}'))
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    Ok(file.solution)
+}
+
+/// The `.sln` itself: the path for a solution, the one a filter names,
+/// relative to the filter, for a solution filter.
+pub fn underlying_sln(path: &str) -> io::Result<String> {
+    if !is_solution_filter(path) {
+        return Ok(path.to_string());
+    }
+    let filter = read_solution_filter(path)?;
+    let filter_dir = Path::new(path).parent().unwrap_or(Path::new(""));
+    Ok(resolve_sln_relative(filter_dir, &filter.path)
+        .to_string_lossy()
+        .into_owned())
+}
+
+fn is_slnx(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("slnx"))
+}
+
+// Visual Studio saves solutions with a byte order mark, which would stick to
+// the first line; `lines` already drops the `\r` of Windows line endings
+fn read_solution(path: &str) -> io::Result<String> {
+    let contents = std::fs::read_to_string(path)?;
+    Ok(match contents.strip_prefix('\u{feff}') {
+        Some(contents) => contents.to_string(),
+        None => contents,
+    })
+}
+
+/// The projects of a solution, in the classic text format or the XML one of
+/// `.slnx`, or those a solution filter picks from its solution, by name, along
+/// with the project lines that made no sense and were skipped.
+pub fn parse_sln_for_projects(path: &str) -> io::Result<(Vec<Project>, Vec<String>)> {
+    let sln_path = underlying_sln(path)?;
+    let sln_dir = Path::new(&sln_path).parent().unwrap_or(Path::new(""));
+    let contents = read_solution(&sln_path)?;
+    let (mut projects, skipped) = if is_slnx(&sln_path) {
+        (parse_slnx_projects(&contents, sln_dir), Vec::new())
+    } else {
+        parse_classic_projects(&contents, sln_dir)
+    };
+    if is_solution_filter(path) {
+        // Both are relative to the solution, but may differ in separators and case
+        let key = |rel_path: &str| rel_path.replace('/', "\\").to_ascii_lowercase();
+        let picked: HashSet<String> = read_solution_filter(path)?
+            .projects
+            .iter()
+            .map(|rel_path| key(rel_path))
+            .collect();
+        projects.retain(|project| picked.contains(&key(&project.rel_path)));
+    }
+    projects.sort_by_cached_key(|project| (project.name.to_lowercase(), project.rel_path.clone()));
+    Ok((projects, skipped))
+}
+
+fn parse_classic_projects(contents: &str, sln_dir: &Path) -> (Vec<Project>, Vec<String>) {
+    // Solution folders are entries too, which the projects are nested in
+    let mut folders: HashMap<String, String> = HashMap::new();
+    let mut projects = Vec::new();
+    let mut skipped = Vec::new();
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        // Not `ProjectSection(` or `EndProject`
+        let is_entry = line
+            .strip_prefix("Project")
+            .is_some_and(|rest| rest.trim_start().starts_with('('));
+        if !is_entry {
+            continue;
+        }
+        let Some(entry) = parse_entry_line(line) else {
+            skipped.push(format!("line {}: {}", number + 1, line));
+            continue;
+        };
+        if entry.type_guid.eq_ignore_ascii_case(SOLUTION_FOLDER_GUID) {
+            folders.insert(entry.guid, entry.name);
+            continue;
+        }
+        projects.extend(project(entry.name, entry.rel_path, &entry.guid, sln_dir));
+    }
+
+    // Entries look like `{child GUID} = {parent GUID}`
+    let parents: HashMap<String, String> = contents
         .lines()
+        .map(str::trim)
+        .skip_while(|line| !line.starts_with("GlobalSection(NestedProjects)"))
+        .skip(1)
+        .take_while(|line| !line.starts_with("EndGlobalSection"))
         .filter_map(|line| {
-            line.trim()
-                .starts_with("Project(")
-                .then(|| line.split(',').nth(1))
-                .flatten()
-                .map(|s| s.trim().trim_matches('"').to_string())
+            let (child, parent) = line.split_once('=')?;
+            Some((normalize_guid(child), normalize_guid(parent)))
+        })
+        .collect();
+    for project in &mut projects {
+        let mut guid = &project.guid;
+        // A broken file could nest folders in each other
+        for _ in 0..=parents.len() {
+            let Some(name) = parents.get(guid).and_then(|parent| {
+                guid = parent;
+                folders.get(parent)
+            }) else {
+                break;
+            };
+            project.folder.insert(0, name.clone());
+        }
+    }
+    (projects, skipped)
+}
+
+struct SlnEntry {
+    type_guid: String,
+    name: String,
+    rel_path: String,
+    guid: String,
+}
+
+// Project("{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}") = "Api", "src\Api\Api.csproj", "{GUID}"
+// with any spacing between the parts, and commas allowed within the quotes
+fn parse_entry_line(line: &str) -> Option<SlnEntry> {
+    let rest = line
+        .strip_prefix("Project")?
+        .trim_start()
+        .strip_prefix('(')?;
+    let (type_guid, rest) = quoted(rest)?;
+    let rest = rest
+        .trim_start()
+        .strip_prefix(')')?
+        .trim_start()
+        .strip_prefix('=')?;
+    let (name, rest) = quoted(rest)?;
+    let (rel_path, rest) = quoted(rest.trim_start().strip_prefix(',')?)?;
+    // Some old tools leave the project's own GUID out
+    let guid = match rest.trim_start().strip_prefix(',') {
+        Some(rest) => quoted(rest)?.0,
+        None => "",
+    };
+
+    let type_guid = normalize_guid(type_guid);
+    let (name, rel_path) = (name.trim(), rel_path.trim());
+    let plausible = |text: &str| !text.is_empty() && !text.contains(char::is_control);
+    let is_guid = type_guid.chars().filter(char::is_ascii_hexdigit).count() == 32
+        && type_guid.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+    if !is_guid || !plausible(name) || !plausible(rel_path) {
+        return None;
+    }
+    Some(SlnEntry {
+        type_guid,
+        name: name.to_string(),
+        rel_path: rel_path.to_string(),
+        guid: normalize_guid(guid),
+    })
+}
+
+// The text within the quotes `s` starts with, and what follows them
+fn quoted(s: &str) -> Option<(&str, &str)> {
+    let rest = s.trim_start().strip_prefix('"')?;
+    let end = rest.find('"')?;
+    Some((&rest[..end], &rest[end + 1..]))
+}
+
+// `{1b2c...}` and `1B2C...` are the same GUID
+fn normalize_guid(guid: &str) -> String {
+    guid.trim().trim_matches(['{', '}']).to_ascii_uppercase()
+}
+
+// <Folder Name="/src/">
+//   <Project Path="src/Api/Api.csproj" />
+// </Folder>
+// Folders don't nest: their names are paths from the root of the solution.
+fn parse_slnx_projects(contents: &str, sln_dir: &Path) -> Vec<Project> {
+    let folders: HashMap<&str, Vec<String>> = elements(contents, "Folder")
+        .filter_map(|(tag, body)| {
+            let folder: Vec<String> = attribute(tag, "Name")?
+                .split('/')
+                .filter(|part| !part.is_empty())
+                .map(str::to_string)
+                .collect();
+            Some(
+                elements(body, "Project")
+                    .filter_map(move |(tag, _)| Some((attribute(tag, "Path")?, folder.clone()))),
+            )
+        })
+        .flatten()
+        .collect();
+
+    elements(contents, "Project")
+        .filter_map(|(tag, _)| {
+            let rel_path = attribute(tag, "Path")?;
+            // The project file name is the name a classic solution would give it
+            let file_name = rel_path.rsplit(['\\', '/']).next().unwrap_or(rel_path);
+            let name = file_name
+                .rsplit_once('.')
+                .map_or(file_name, |(stem, _)| stem);
+            let guid = attribute(tag, "Id").unwrap_or_default();
+            // Shown the way a classic solution stores it, so either format of
+            // the same solution lists the same paths
+            let stored = rel_path.replace('/', "\\");
+            let mut project = project(name.to_string(), stored, guid, sln_dir)?;
+            project.folder = folders.get(rel_path).cloned().unwrap_or_default();
+            Some(project)
+        })
+        .collect()
+}
+
+fn project(name: String, rel_path: String, guid: &str, sln_dir: &Path) -> Option<Project> {
+    let kind = ProjectKind::from_path(&rel_path)?;
+    let path = resolve_sln_relative(sln_dir, &rel_path);
+    Some(Project {
+        output: output_kind(&name, &path),
+        frameworks: target_frameworks(&path),
+        docker: path.parent().is_some_and(has_docker),
+        name,
+        rel_path,
+        missing: !path.is_file(),
+        path,
+        guid: normalize_guid(guid),
+        kind,
+        folder: Vec::new(),
+    })
+}
+
+/// Whether `dotnet test` has anything to run in the project: it references the
+/// test SDK, says it is a test project, or is named like one.
+fn is_test_project(name: &str, path: &Path) -> bool {
+    if name.ends_with(".Tests") {
+        return true;
+    }
+    std::fs::read_to_string(path).is_ok_and(|contents| {
+        contents.contains("Microsoft.NET.Test.Sdk")
+            || contents.contains("<IsTestProject>true</IsTestProject>")
+    })
+}
+
+/// Tells web apps and workers by their SDK, other programs by `<OutputType>`.
+/// Test projects are programs too, but `dotnet test` is what runs them.
+pub fn output_kind(name: &str, path: &Path) -> OutputKind {
+    if is_test_project(name, path) {
+        return OutputKind::Test;
+    }
+    let contents = std::fs::read_to_string(path).unwrap_or_default();
+    if [
+        "Microsoft.NET.Sdk.Web",
+        "Microsoft.NET.Sdk.BlazorWebAssembly",
+    ]
+    .iter()
+    .any(|sdk| uses_sdk(&contents, sdk))
+    {
+        return OutputKind::Web;
+    }
+    if uses_sdk(&contents, "Microsoft.NET.Sdk.Worker") {
+        return OutputKind::Worker;
+    }
+    let output_type = from_project_or_props(path, |contents| {
+        property_values(contents, "OutputType").find(|value| !value.contains("$("))
+    });
+    match output_type
+        .as_deref()
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("exe" | "winexe") => OutputKind::Console,
+        _ => OutputKind::Library,
+    }
+}
+
+// `<Project Sdk="...">`, `<Sdk Name="..." />` or `<Import Sdk="..." />`, the
+// last two with a version that the name is followed by
+fn uses_sdk(contents: &str, sdk: &str) -> bool {
+    ["Sdk=\"", "Name=\""].iter().any(|attribute| {
+        contents.match_indices(attribute).any(|(start, _)| {
+            let value = &contents[start + attribute.len()..];
+            value
+                .strip_prefix(sdk)
+                .is_some_and(|rest| rest.starts_with(['"', '/']))
         })
-        .collect())
+    })
+}
+
+/// The target frameworks of the project, from the project file or else from
+/// the nearest `Directory.Build.props` above it, the one MSBuild imports.
+pub fn target_frameworks(project_path: &Path) -> Vec<String> {
+    from_project_or_props(project_path, frameworks_in).unwrap_or_default()
+}
+
+/// What `find` finds in the project file, or else in the nearest
+/// `Directory.Build.props` above it.
+pub fn from_project_or_props<T>(
+    project_path: &Path,
+    find: impl Fn(&str) -> Option<T>,
+) -> Option<T> {
+    let read = |path: &Path| {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| find(&contents))
+    };
+    read(project_path).or_else(|| {
+        project_path
+            .ancestors()
+            .skip(1)
+            .map(|dir| dir.join("Directory.Build.props"))
+            .find(|props| props.is_file())
+            .and_then(|props| read(&props))
+    })
+}
+
+// `<TargetFrameworks>` wins over `<TargetFramework>`, as in MSBuild. Values
+// built from other properties can't be known without evaluating the project.
+fn frameworks_in(contents: &str) -> Option<Vec<String>> {
+    ["TargetFrameworks", "TargetFramework"]
+        .into_iter()
+        .find_map(|element| {
+            property_values(contents, element).find_map(|value| {
+                let frameworks: Vec<String> = value
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|f| !f.is_empty() && !f.contains("$("))
+                    .map(str::to_string)
+                    .collect();
+                (!frameworks.is_empty()).then_some(frameworks)
+            })
+        })
+}
+
+/// The values given to the property, in order.
+pub fn property_values<'a>(contents: &'a str, element: &str) -> impl Iterator<Item = String> + 'a {
+    let open = format!("<{}", element);
+    let mut rest = contents;
+    std::iter::from_fn(move || loop {
+        let start = rest.find(&open)?;
+        rest = &rest[start + open.len()..];
+        // `<TargetFramework` is also the start of `<TargetFrameworks`
+        if !rest.starts_with(|c: char| c == '>' || c.is_whitespace()) {
+            continue;
+        }
+        let value = &rest[rest.find('>')? + 1..];
+        let value = value[..value.find('<')?].trim();
+        if !value.is_empty() {
+            return Some(value.to_string());
+        }
+    })
+}
+
+/// The start tags and contents of the elements called `name`, which must not
+/// nest in themselves.
+pub fn elements<'a>(xml: &'a str, name: &'a str) -> impl Iterator<Item = (&'a str, &'a str)> + 'a {
+    let open = format!("<{}", name);
+    let close = format!("</{}>", name);
+    let mut rest = xml;
+    std::iter::from_fn(move || loop {
+        let start = rest.find(&open)?;
+        let after = &rest[start + open.len()..];
+        // `<UnitTest` is also the start of `<UnitTestResult`
+        if !after.starts_with(|c: char| c.is_whitespace() || c == '>' || c == '/') {
+            rest = after;
+            continue;
+        }
+        let tag_end = after.find('>')?;
+        let tag = &after[..tag_end];
+        if tag.ends_with('/') {
+            rest = &after[tag_end + 1..];
+            return Some((tag, ""));
+        }
+        let body = &after[tag_end + 1..];
+        let end = body.find(&close)?;
+        rest = &body[end + close.len()..];
+        return Some((tag, &body[..end]));
+    })
+}
+
+/// The value of the attribute in a start tag from [`elements`].
+pub fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let pattern = format!("{}=\"", name);
+    // Attributes may be on lines of their own
+    let start = tag
+        .match_indices(&pattern)
+        .find(|(start, _)| tag[..*start].ends_with(char::is_whitespace))?
+        .0
+        + pattern.len();
+    let end = tag[start..].find('"')?;
+    Some(&tag[start..start + end])
+}
+
+pub fn parse_sln_configurations(path: &str) -> io::Result<Vec<String>> {
+    let sln_path = underlying_sln(path)?;
+    let contents = read_solution(&sln_path)?;
+    let mut configurations = vec!["Debug".to_string(), "Release".to_string()];
+
+    // `<Configurations><BuildType Name="Staging" /></Configurations>`
+    if is_slnx(&sln_path) {
+        for (tag, _) in elements(&contents, "BuildType") {
+            if let Some(name) = attribute(tag, "Name") {
+                if !configurations.iter().any(|c| c == name) {
+                    configurations.push(name.to_string());
+                }
+            }
+        }
+        return Ok(configurations);
+    }
+
+    let section = contents
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| !line.starts_with("GlobalSection(SolutionConfigurationPlatforms)"))
+        .skip(1)
+        .take_while(|line| !line.starts_with("EndGlobalSection"));
+
+    // Entries look like `Staging|Any CPU = Staging|Any CPU`
+    for line in section {
+        if let Some((name, _)) = line.split_once('|') {
+            let name = name.trim();
+            if !name.is_empty() && !configurations.iter().any(|c| c == name) {
+                configurations.push(name.to_string());
+            }
+        }
+    }
+
+    Ok(configurations)
+}
+
+/// Splits a command line into arguments the way a shell would, without running
+/// one. Single and double quotes group words and are removed; a backslash only
+/// escapes a quote, so Windows paths pass through unchanged. Returns `None` for
+/// an unterminated quote.
+pub fn split_args(line: &str) -> Option<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quote = None;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', _)
+                if chars
+                    .peek()
+                    .is_some_and(|&next| next == '"' || next == '\'') =>
+            {
+                current.get_or_insert_with(String::new).extend(chars.next());
+            }
+            ('"' | '\'', None) => {
+                quote = Some(c);
+                current.get_or_insert_with(String::new);
+            }
+            (_, Some(open)) if c == open => quote = None,
+            (_, None) if c.is_whitespace() => args.extend(current.take()),
+            _ => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quote.is_some() {
+        return None;
+    }
+    args.extend(current);
+    Some(args)
+}
+
+/// Runs a command line with the platform's shell, so that it can use pipes
+/// and quoting as typed.
+pub fn shell_command(line: &str) -> Command {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C");
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c");
+        command
+    };
+    command.arg(line);
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    // Creates the files, with their directories, under a new temporary one
+    fn tree(files: &[&str]) -> TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for file in files {
+            let path = dir.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+        dir
+    }
+
+    fn found(root: &Path, extra_excludes: &[String], ignore: &IgnoreList) -> Vec<String> {
+        let mut found = Vec::new();
+        find_sln_files(
+            &[root.to_path_buf()],
+            extra_excludes,
+            ignore,
+            &AtomicBool::new(false),
+            |file| {
+                let path = Path::new(&file.path).strip_prefix(root).unwrap();
+                found.push(slash_path(path));
+            },
+        );
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn find_sln_files_skips_the_excluded_directories() {
+        let dir = tree(&[
+            "App.sln",
+            "src/Api/Api.slnx",
+            "src/Api/bin/Debug/Copied.sln",
+            "obj/Generated.sln",
+            "web/node_modules/package/Sample.sln",
+            ".git/modules/Sub.sln",
+            "vendor/Vendored.sln",
+            "samples/Sample.sln",
+        ]);
+
+        assert_eq!(
+            found(dir.path(), &[], &IgnoreList::default()),
+            [
+                "App.sln",
+                "samples/Sample.sln",
+                "src/Api/Api.slnx",
+                "vendor/Vendored.sln"
+            ]
+        );
+        assert_eq!(
+            found(dir.path(), &["vendor".to_string()], &IgnoreList::default()),
+            ["App.sln", "samples/Sample.sln", "src/Api/Api.slnx"]
+        );
+    }
+
+    #[test]
+    fn find_sln_files_keeps_a_root_named_like_an_excluded_directory() {
+        let dir = tree(&["bin/Tool.sln"]);
+        let mut found = Vec::new();
+        find_sln_files(
+            &[dir.path().join("bin")],
+            &[],
+            &IgnoreList::default(),
+            &AtomicBool::new(false),
+            |file| found.push(file.path),
+        );
+        assert_eq!(found.len(), 1);
+    }
+
+    #[test]
+    fn resolves_backslash_paths_with_the_host_separator() {
+        let dir = Path::new("/repo");
+        assert_eq!(
+            resolve_sln_relative(dir, r"src\Api\Api.csproj"),
+            dir.join("src").join("Api").join("Api.csproj")
+        );
+        assert_eq!(
+            resolve_sln_relative(dir, r".\tools\\Tool.csproj"),
+            dir.join("tools").join("Tool.csproj")
+        );
+        assert_eq!(
+            resolve_sln_relative(dir, r"..\Shared\Shared.csproj"),
+            dir.join("..").join("Shared").join("Shared.csproj")
+        );
+        assert_eq!(
+            resolve_sln_relative(dir, "src/Web/Web.csproj"),
+            dir.join("src").join("Web").join("Web.csproj")
+        );
+    }
+
+    #[test]
+    fn projects_of_a_solution_with_backslash_paths_are_found() {
+        let dir = tree(&["src/Api/Api.csproj", "tests/Api.Tests/Api.Tests.csproj"]);
+        let sln = dir.path().join("App.sln");
+        fs::write(
+            &sln,
+            r#"Microsoft Visual Studio Solution File, Format Version 12.00
+Project("{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}") = "Api", "src\Api\Api.csproj", "{11111111-1111-1111-1111-111111111111}"
+EndProject
+Project("{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}") = "Api.Tests", "tests\Api.Tests\Api.Tests.csproj", "{22222222-2222-2222-2222-222222222222}"
+EndProject
+Project("{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}") = "Gone", "src\Gone\Gone.csproj", "{33333333-3333-3333-3333-333333333333}"
+EndProject
+"#,
+        )
+        .unwrap();
+
+        let (projects, skipped) = parse_sln_for_projects(&sln.to_string_lossy()).unwrap();
+        assert!(skipped.is_empty());
+        let paths: Vec<(&str, &Path, bool)> = projects
+            .iter()
+            .map(|p| (p.name.as_str(), p.path.as_path(), p.missing))
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                (
+                    "Api",
+                    dir.path().join("src/Api/Api.csproj").as_path(),
+                    false
+                ),
+                (
+                    "Api.Tests",
+                    dir.path()
+                        .join("tests/Api.Tests/Api.Tests.csproj")
+                        .as_path(),
+                    false
+                ),
+                (
+                    "Gone",
+                    dir.path().join("src/Gone/Gone.csproj").as_path(),
+                    true
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_solution_and_its_slnx_migration_list_the_same_projects() {
+        let dir = tree(&[
+            "src/Api/Api.csproj",
+            "src/Web/Web.csproj",
+            "tests/Api.Tests/Api.Tests.csproj",
+            "tools/Seed.fsproj",
+        ]);
+        let sln = dir.path().join("App.sln");
+        fs::write(
+            &sln,
+            r#"Microsoft Visual Studio Solution File, Format Version 12.00
+Project("{2150E333-8FDC-42A3-9474-1A3956D46DE8}") = "src", "src", "{AAAAAAAA-AAAA-AAAA-AAAA-AAAAAAAAAAAA}"
+EndProject
+Project("{2150E333-8FDC-42A3-9474-1A3956D46DE8}") = "web", "web", "{BBBBBBBB-BBBB-BBBB-BBBB-BBBBBBBBBBBB}"
+EndProject
+Project("{2150E333-8FDC-42A3-9474-1A3956D46DE8}") = "tests", "tests", "{CCCCCCCC-CCCC-CCCC-CCCC-CCCCCCCCCCCC}"
+EndProject
+Project("{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}") = "Api", "src\Api\Api.csproj", "{11111111-1111-1111-1111-111111111111}"
+EndProject
+Project("{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}") = "Api.Tests", "tests\Api.Tests\Api.Tests.csproj", "{33333333-3333-3333-3333-333333333333}"
+EndProject
+Project("{F2A71F9B-5D33-465A-A702-920D77279786}") = "Seed", "tools\Seed.fsproj", "{44444444-4444-4444-4444-444444444444}"
+EndProject
+Project("{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}") = "Web", "src\Web\Web.csproj", "{22222222-2222-2222-2222-222222222222}"
+EndProject
+Global
+	GlobalSection(NestedProjects) = preSolution
+		{BBBBBBBB-BBBB-BBBB-BBBB-BBBBBBBBBBBB} = {AAAAAAAA-AAAA-AAAA-AAAA-AAAAAAAAAAAA}
+		{11111111-1111-1111-1111-111111111111} = {AAAAAAAA-AAAA-AAAA-AAAA-AAAAAAAAAAAA}
+		{22222222-2222-2222-2222-222222222222} = {BBBBBBBB-BBBB-BBBB-BBBB-BBBBBBBBBBBB}
+		{33333333-3333-3333-3333-333333333333} = {CCCCCCCC-CCCC-CCCC-CCCC-CCCCCCCCCCCC}
+	EndGlobalSection
+EndGlobal
+"#,
+        )
+        .unwrap();
+        let slnx = dir.path().join("App.slnx");
+        fs::write(
+            &slnx,
+            r#"<Solution>
+  <Folder Name="/src/">
+    <Project Path="src/Api/Api.csproj" />
+  </Folder>
+  <Folder Name="/tests/">
+    <Project Path="tests/Api.Tests/Api.Tests.csproj" />
+  </Folder>
+  <Project Path="tools/Seed.fsproj" />
+  <Folder Name="/src/web/">
+    <Project Path="src/Web/Web.csproj" />
+  </Folder>
+</Solution>
+"#,
+        )
+        .unwrap();
+
+        let listed = |path: &Path| {
+            let (projects, skipped) = parse_sln_for_projects(&path.to_string_lossy()).unwrap();
+            assert!(skipped.is_empty());
+            projects
+                .into_iter()
+                .map(|p| (p.name, p.rel_path, p.folder, p.missing))
+                .collect::<Vec<_>>()
+        };
+        let classic = listed(&sln);
+        assert_eq!(classic, listed(&slnx));
+        assert_eq!(
+            classic,
+            [
+                (
+                    "Api".to_string(),
+                    r"src\Api\Api.csproj".to_string(),
+                    vec!["src".to_string()],
+                    false
+                ),
+                (
+                    "Api.Tests".to_string(),
+                    r"tests\Api.Tests\Api.Tests.csproj".to_string(),
+                    vec!["tests".to_string()],
+                    false
+                ),
+                (
+                    "Seed".to_string(),
+                    r"tools\Seed.fsproj".to_string(),
+                    vec![],
+                    false
+                ),
+                (
+                    "Web".to_string(),
+                    r"src\Web\Web.csproj".to_string(),
+                    vec!["src".to_string(), "web".to_string()],
+                    false
+                ),
+            ]
+        );
+    }
+
+    fn entry(line: &str) -> Option<(String, String, String, String)> {
+        parse_entry_line(line).map(|e| (e.type_guid, e.name, e.rel_path, e.guid))
+    }
+
+    #[test]
+    fn entry_lines_allow_any_spacing() {
+        let expected = Some((
+            "FAE04EC0-301F-11D3-BF4B-00C04F79EFBC".to_string(),
+            "Api".to_string(),
+            r"src\Api\Api.csproj".to_string(),
+            "11111111-1111-1111-1111-111111111111".to_string(),
+        ));
+        assert_eq!(
+            entry(
+                r#"Project("{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}") = "Api", "src\Api\Api.csproj", "{11111111-1111-1111-1111-111111111111}""#
+            ),
+            expected
+        );
+        assert_eq!(
+            entry(
+                r#"Project ( "{fae04ec0-301f-11d3-bf4b-00c04f79efbc}" )="Api" ,  "src\Api\Api.csproj",	"{11111111-1111-1111-1111-111111111111}""#
+            ),
+            expected
+        );
+        assert_eq!(
+            entry(
+                r#"Project("{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}") = " Api ", " src\Api\Api.csproj ", "{11111111-1111-1111-1111-111111111111}""#
+            ),
+            expected
+        );
+    }
+
+    #[test]
+    fn entry_lines_keep_a_comma_within_the_quotes() {
+        let (_, name, rel_path, guid) = entry(r#"Project("{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}") = "Api", "src\Api, v2\Api.csproj", "{11111111-1111-1111-1111-111111111111}""#).unwrap();
+        assert_eq!(name, "Api");
+        assert_eq!(rel_path, r"src\Api, v2\Api.csproj");
+        assert_eq!(guid, "11111111-1111-1111-1111-111111111111");
+    }
+
+    #[test]
+    fn entry_lines_may_leave_the_project_guid_out() {
+        let (_, name, _, guid) = entry(
+            r#"Project("{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}") = "Api", "src\Api\Api.csproj""#,
+        )
+        .unwrap();
+        assert_eq!(name, "Api");
+        assert_eq!(guid, "");
+    }
+
+    #[test]
+    fn malformed_entry_lines_are_rejected() {
+        for line in [
+            r#"Project("{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}") = "Api""#,
+            r#"Project("{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}") = "Api", "src\Api\Api.csproj"#,
+            r#"Project("not a guid") = "Api", "src\Api\Api.csproj", "{11111111-1111-1111-1111-111111111111}""#,
+            r#"Project("{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}") = "", "src\Api\Api.csproj", "{11111111-1111-1111-1111-111111111111}""#,
+            r#"Project("{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}") "Api", "src\Api\Api.csproj", "{11111111-1111-1111-1111-111111111111}""#,
+        ] {
+            assert!(entry(line).is_none(), "{line}");
+        }
+    }
+
+    #[test]
+    fn a_solution_saved_by_visual_studio_is_read_whole() {
+        let dir = tree(&["src/Api/Api.csproj", "src/Api, v2/Legacy.csproj"]);
+        let sln = dir.path().join("App.sln");
+        let contents = [
+            "Microsoft Visual Studio Solution File, Format Version 12.00",
+            r#"Project("{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}") = "Api", "src\Api\Api.csproj", "{11111111-1111-1111-1111-111111111111}""#,
+            "EndProject",
+            r#"  Project ( "{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}" ) =  "Legacy" , "src\Api, v2\Legacy.csproj" , "{22222222-2222-2222-2222-222222222222}"  "#,
+            "EndProject",
+            r#"Project("{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}") = "Broken", "src\Broken"#,
+            "EndProject",
+        ]
+        .join("\r\n");
+        fs::write(&sln, format!("\u{feff}{contents}\r\n")).unwrap();
+
+        let (projects, skipped) = parse_sln_for_projects(&sln.to_string_lossy()).unwrap();
+        let listed: Vec<(&str, &str, bool)> = projects
+            .iter()
+            .map(|p| (p.name.as_str(), p.rel_path.as_str(), p.missing))
+            .collect();
+        assert_eq!(
+            listed,
+            [
+                ("Api", r"src\Api\Api.csproj", false),
+                ("Legacy", r"src\Api, v2\Legacy.csproj", false),
+            ]
+        );
+        assert_eq!(
+            skipped,
+            [
+                r#"line 6: Project("{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}") = "Broken", "src\Broken"#
+            ]
+        );
+    }
+
+    #[test]
+    fn read_solution_drops_the_byte_order_mark() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("App.sln");
+        fs::write(&path, "\u{feff}Microsoft Visual Studio Solution File\r\n").unwrap();
+        let contents = read_solution(&path.to_string_lossy()).unwrap();
+        assert!(contents.starts_with("Microsoft"));
+        assert_eq!(
+            contents.lines().next(),
+            Some("Microsoft Visual Studio Solution File")
+        );
+    }
+
+    #[test]
+    fn entry_lines_keep_commas_parentheses_and_non_ascii_in_the_quotes() {
+        for (name, rel_path) in [
+            ("Api, Legacy", r"src\Api, Legacy\Api, Legacy.csproj"),
+            ("Api (old)", r"src\Api (old)\Api (old).csproj"),
+            ("Überweisung", r"src\Zahlungsverkehr\Überweisung.csproj"),
+            ("Платежи.Тесты", r"тесты\Платежи.Тесты\Платежи.Тесты.csproj"),
+            ("決済 (β), 日本", r"src\決済 (β), 日本\決済.csproj"),
+        ] {
+            let line = format!(
+                r#"Project("{{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}}") = "{name}", "{rel_path}", "{{11111111-1111-1111-1111-111111111111}}""#
+            );
+            let (type_guid, parsed_name, parsed_path, guid) = entry(&line).unwrap();
+            assert_eq!(type_guid, "FAE04EC0-301F-11D3-BF4B-00C04F79EFBC");
+            assert_eq!(parsed_name, name);
+            assert_eq!(parsed_path, rel_path);
+            assert_eq!(guid, "11111111-1111-1111-1111-111111111111");
+        }
+    }
 }
@@ -0,0 +1,350 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+use serde_json::{json, ser::PrettyFormatter, Map, Value};
+
+/// What the launch configuration and build task of a project are made of.
+pub struct Target {
+    // The folder VS Code opens, the solution's
+    pub workspace: PathBuf,
+    pub project: String,
+    pub project_path: PathBuf,
+    pub assembly: String,
+    pub configuration: String,
+    pub framework: String,
+    pub args: Vec<String>,
+    pub env: BTreeMap<String, String>,
+    pub launch_browser: bool,
+}
+
+/// The change to one of the files in `.vscode`.
+pub struct Merge {
+    pub path: PathBuf,
+    contents: Value,
+    // The name of the entry, and whether one of that name is replaced
+    pub name: String,
+    pub replaces: bool,
+    pub unchanged: bool,
+    // The existing file had comments, which are not written back
+    pub drops_comments: bool,
+}
+
+impl Merge {
+    pub fn write(&self) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        // Indented by four, as VS Code writes them
+        let mut json = Vec::new();
+        let mut serializer = serde_json::Serializer::with_formatter(
+            &mut json,
+            PrettyFormatter::with_indent(b"    "),
+        );
+        self.contents.serialize(&mut serializer)?;
+        json.push(b'\n');
+        fs::write(&self.path, json)
+    }
+}
+
+/// The launch configuration and build task of a project, merged into the
+/// `launch.json` and `tasks.json` of the workspace.
+pub struct Export {
+    pub launch: Merge,
+    pub tasks: Merge,
+}
+
+impl Export {
+    pub fn new(target: &Target) -> Result<Self, String> {
+        let dir = target.workspace.join(".vscode");
+        let task = build_task(target);
+        let configuration = launch_configuration(target);
+        let tasks = merge(&dir.join("tasks.json"), "tasks", "label", task)?;
+        let launch = merge(
+            &dir.join("launch.json"),
+            "configurations",
+            "name",
+            configuration,
+        )?;
+        validate_tasks(&tasks.contents, &tasks.name).map_err(|e| format!("tasks.json: {}", e))?;
+        validate_launch(&launch.contents, &launch.name, &tasks.contents)
+            .map_err(|e| format!("launch.json: {}", e))?;
+        Ok(Self { launch, tasks })
+    }
+
+    /// The names of the existing entries the export would replace.
+    pub fn replaced(&self) -> Vec<String> {
+        [&self.launch, &self.tasks]
+            .into_iter()
+            .filter(|merge| merge.replaces && !merge.unchanged)
+            .map(|merge| format!("'{}' in {}", merge.name, file_name(&merge.path)))
+            .collect()
+    }
+}
+
+fn launch_configuration(target: &Target) -> Value {
+    let project_dir = workspace_path(target, target.project_path.parent());
+    let mut configuration = json!({
+        "name": format!("Launch {}", target.project),
+        "type": "coreclr",
+        "request": "launch",
+        "preLaunchTask": task_label(target),
+        "program": format!(
+            "{}/bin/{}/{}/{}.dll",
+            project_dir, target.configuration, target.framework, target.assembly
+        ),
+        "args": target.args,
+        "cwd": project_dir,
+        "env": target.env,
+        "stopAtEntry": false,
+        "console": "internalConsole",
+    });
+    // As VS Code's own templates open the browser for web apps
+    if target.launch_browser {
+        configuration["serverReadyAction"] = json!({
+            "action": "openExternally",
+            "pattern": "\\bNow listening on:\\s+(https?://\\S+)",
+        });
+    }
+    configuration
+}
+
+fn build_task(target: &Target) -> Value {
+    json!({
+        "label": task_label(target),
+        "type": "process",
+        "command": "dotnet",
+        "args": [
+            "build",
+            workspace_path(target, Some(&target.project_path)),
+            "--configuration",
+            target.configuration,
+            "--framework",
+            target.framework,
+            "/property:GenerateFullPaths=true",
+            "/consoleloggerparameters:NoSummary",
+        ],
+        "problemMatcher": "$msCompile",
+        "group": "build",
+    })
+}
+
+fn task_label(target: &Target) -> String {
+    format!("build {}", target.project)
+}
+
+// Relative to `${workspaceFolder}` where it is inside, with forward slashes,
+// which VS Code takes on Windows too
+fn workspace_path(target: &Target, path: Option<&Path>) -> String {
+    let path = path.unwrap_or(Path::new(""));
+    match path.strip_prefix(&target.workspace) {
+        Ok(relative) if relative.as_os_str().is_empty() => "${workspaceFolder}".to_string(),
+        Ok(relative) => format!(
+            "${{workspaceFolder}}/{}",
+            relative.to_string_lossy().replace('\\', "/")
+        ),
+        Err(_) => path.to_string_lossy().replace('\\', "/"),
+    }
+}
+
+// Puts the entry in place of the one with its name, or after the others
+fn merge(path: &Path, list: &str, key: &str, entry: Value) -> Result<Merge, String> {
+    let name = entry[key].as_str().unwrap_or_default().to_string();
+    let (mut contents, drops_comments) = match fs::read_to_string(path) {
+        Ok(text) => {
+            let (json, comments) = strip_jsonc(&text);
+            let contents = serde_json::from_str::<Value>(&json)
+                .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+            (contents, comments)
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => (Value::Object(Map::new()), false),
+        Err(e) => return Err(format!("cannot read {}: {}", path.display(), e)),
+    };
+    let Some(document) = contents.as_object_mut() else {
+        return Err(format!("{} is not a JSON object", path.display()));
+    };
+    let version = if list == "tasks" { "2.0.0" } else { "0.2.0" };
+    document
+        .entry("version")
+        .or_insert_with(|| Value::from(version));
+    let Some(entries) = document
+        .entry(list)
+        .or_insert_with(|| Value::Array(Vec::new()))
+        .as_array_mut()
+    else {
+        return Err(format!("{} in {} is not a list", list, path.display()));
+    };
+
+    let existing = entries
+        .iter()
+        .position(|existing| existing[key].as_str() == Some(name.as_str()));
+    let unchanged = existing.is_some_and(|i| entries[i] == entry);
+    match existing {
+        Some(i) => entries[i] = entry,
+        None => entries.push(entry),
+    }
+    Ok(Merge {
+        path: path.to_path_buf(),
+        contents,
+        name,
+        replaces: existing.is_some(),
+        unchanged,
+        drops_comments,
+    })
+}
+
+// Checks the document and the configuration written into it against what
+// VS Code and its C# extension expect. The user's own entries are left to VS
+// Code, which knows more kinds than these.
+fn validate_launch(launch: &Value, name: &str, tasks: &Value) -> Result<(), String> {
+    require_string(launch, "version")?;
+    let configuration = find_entry(launch, "configurations", "name", name)?;
+    for key in ["type", "request", "program", "cwd", "preLaunchTask"] {
+        in_entry("configuration", name, require_string(configuration, key))?;
+    }
+    if configuration["type"] != "coreclr" || configuration["request"] != "launch" {
+        return Err(format!("configuration '{}': not a coreclr launch", name));
+    }
+    if !configuration["env"]
+        .as_object()
+        .is_some_and(|env| env.values().all(Value::is_string))
+    {
+        return Err(format!("configuration '{}': env is not all strings", name));
+    }
+    if !is_string_list(&configuration["args"]) {
+        return Err(format!(
+            "configuration '{}': args are not all strings",
+            name
+        ));
+    }
+    let task = configuration["preLaunchTask"].as_str().unwrap_or_default();
+    find_entry(tasks, "tasks", "label", task)
+        .map_err(|_| format!("configuration '{}': no task '{}'", name, task))?;
+    Ok(())
+}
+
+fn validate_tasks(tasks: &Value, label: &str) -> Result<(), String> {
+    if tasks["version"] != "2.0.0" {
+        return Err("version is not 2.0.0".to_string());
+    }
+    let task = find_entry(tasks, "tasks", "label", label)?;
+    for key in ["type", "command"] {
+        in_entry("task", label, require_string(task, key))?;
+    }
+    if !is_string_list(&task["args"]) {
+        return Err(format!("task '{}': args are not all strings", label));
+    }
+    Ok(())
+}
+
+// The one entry of the list with the name
+fn find_entry<'a>(
+    document: &'a Value,
+    list: &str,
+    key: &str,
+    name: &str,
+) -> Result<&'a Value, String> {
+    let entries = document[list]
+        .as_array()
+        .ok_or_else(|| format!("{} is not a list", list))?;
+    let mut named = entries.iter().filter(|entry| entry[key] == name);
+    match (named.next(), named.next()) {
+        (Some(entry), None) => Ok(entry),
+        (None, _) => Err(format!("no entry '{}' in {}", name, list)),
+        (Some(_), Some(_)) => Err(format!("more than one entry '{}' in {}", name, list)),
+    }
+}
+
+fn is_string_list(value: &Value) -> bool {
+    value
+        .as_array()
+        .is_some_and(|items| items.iter().all(Value::is_string))
+}
+
+fn in_entry<T>(kind: &str, name: &str, result: Result<T, String>) -> Result<T, String> {
+    result.map_err(|e| format!("{} '{}': {}", kind, name, e))
+}
+
+fn require_string<'a>(value: &'a Value, key: &str) -> Result<&'a str, String> {
+    value[key]
+        .as_str()
+        .ok_or_else(|| format!("{} is missing or not a string", key))
+}
+
+/// VS Code's JSON with comments and trailing commas, made plain JSON. Also
+/// tells whether there were comments.
+fn strip_jsonc(text: &str) -> (String, bool) {
+    let mut json = String::with_capacity(text.len());
+    let mut comments = false;
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            json.push(c);
+            match c {
+                '\\' => json.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                json.push(c);
+            }
+            ('/', Some('/')) => {
+                comments = true;
+                while chars.next_if(|&next| next != '\n').is_some() {}
+            }
+            ('/', Some('*')) => {
+                comments = true;
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            _ => json.push(c),
+        }
+    }
+    (drop_trailing_commas(&json), comments)
+}
+
+fn drop_trailing_commas(json: &str) -> String {
+    let chars: Vec<char> = json.chars().collect();
+    let mut out = String::with_capacity(json.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, &c) in chars.iter().enumerate() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+            if matches!(next, Some('}' | ']')) {
+                continue;
+            }
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
@@ -1,16 +1,189 @@
 mod app;
 
-use app::App;
-use std::io;
+use app::{App, AppError, Pick, RunOptions};
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use std::{path::PathBuf, process};
+
+/// Finds .NET solutions and builds and runs their projects from the terminal.
+#[derive(Parser)]
+#[command(version, args_conflicts_with_subcommands = true)]
+struct Cli {
+    /// Start without the remembered solution, project and settings
+    #[arg(long)]
+    fresh: bool,
+
+    /// Directory to search for solutions instead of the configured roots
+    path: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the solutions found, or the projects of one solution
+    List {
+        /// Print JSON instead of text
+        #[arg(long)]
+        json: bool,
+
+        /// List the projects of this solution
+        #[arg(long)]
+        sln: Option<PathBuf>,
+
+        /// Directory to search for solutions instead of the configured roots
+        path: Option<PathBuf>,
+    },
+    /// Pick a solution, or a project with --project, and print its path
+    Pick {
+        /// Pick a project of the solution rather than the solution
+        #[arg(long)]
+        project: bool,
+
+        /// Print the directory the pick is in instead of its path
+        #[arg(long)]
+        dir_of: bool,
+
+        /// Directory to search for solutions instead of the configured roots
+        path: Option<PathBuf>,
+    },
+    /// Build and run a project without the TUI, exiting with its exit code
+    Run {
+        /// The solution the project is in
+        #[arg(long)]
+        sln: PathBuf,
+
+        /// The project, by name or by the path of its project file
+        #[arg(long)]
+        project: String,
+
+        /// Build configuration [default: Debug]
+        #[arg(long, short)]
+        configuration: Option<String>,
+
+        /// Launch profile from launchSettings.json
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Target framework, for projects with several
+        #[arg(long, short)]
+        framework: Option<String>,
+
+        /// Print the build and the app's output as JSON events, one per line
+        #[arg(long)]
+        json_events: bool,
+
+        /// Arguments passed on to the app
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+    /// Print a completion script for the shell
+    Completions { shell: Shell },
+    /// Print the solutions to complete --sln with
+    #[command(name = app::COMPLETE_SLNS, hide = true)]
+    CompleteSlns,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Some(Command::List { json, sln, path }) => match sln {
+            Some(sln) => app::list_projects(&sln, json),
+            None => app::list_solutions(search_root(path), json),
+        },
+        Some(Command::Run {
+            sln,
+            project,
+            configuration,
+            profile,
+            framework,
+            json_events,
+            args,
+        }) => app::run_project(RunOptions {
+            sln,
+            project,
+            configuration,
+            profile,
+            framework,
+            args,
+            json_events,
+        })
+        .map(|code| process::exit(code)),
+        Some(Command::Pick {
+            project,
+            dir_of,
+            path,
+        }) => pick(search_root(path), project, dir_of),
+        Some(Command::Completions { shell }) => {
+            print!("{}", app::completion_script(shell, Cli::command()));
+            Ok(())
+        }
+        Some(Command::CompleteSlns) => app::complete_solutions(),
+        None => run(search_root(cli.path), cli.fresh),
+    };
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+fn search_root(path: Option<PathBuf>) -> Option<PathBuf> {
+    let path = path?;
+    let root = std::path::absolute(&path).unwrap_or(path);
+    if !root.is_dir() {
+        eprintln!(
+            "error: search root '{}' does not exist or is not a directory",
+            root.display()
+        );
+        process::exit(1);
+    }
+    Some(root)
+}
+
+// The TUI is drawn on stderr, so that only the path ends up on stdout
+fn pick(root: Option<PathBuf>, project: bool, dir_of: bool) -> Result<(), AppError> {
+    let mut app = App::new(root, false)?;
+    app.pick = Some(if project {
+        Pick::Project
+    } else {
+        Pick::Solution
+    });
 
-fn main() -> io::Result<()> {
-    let mut app = App::new()?;
     app.run()?;
 
+    if let Some(summary) = app.detached_summary() {
+        eprint!("\n{}", summary);
+    }
+
+    let Some(picked) = app.picked else {
+        process::exit(1);
+    };
+    let picked = match picked.parent().filter(|_| dir_of) {
+        Some(dir) => dir.to_path_buf(),
+        None => picked,
+    };
+    println!("{}", picked.display());
+    Ok(())
+}
+
+fn run(root: Option<PathBuf>, fresh: bool) -> Result<(), AppError> {
+    let mut app = App::new(root, fresh)?;
+
+    app.run()?;
+
+    if let Some(summary) = app.detached_summary() {
+        print!("\n{}", summary);
+    }
+
+    if app.selected_sln.is_empty() {
+        return Ok(());
+    }
+
     println!("\nSelected solution: {}", app.selected_sln);
     println!("Projects:");
     for project in &app.projects {
-        println!("  - {}", project);
+        println!("  - {}", project.rel_path);
     }
 
     Ok(())